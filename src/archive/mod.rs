@@ -0,0 +1,456 @@
+use std::{io::Write, path::{Path, PathBuf}};
+
+use chrono::{Datelike, Timelike};
+use clap::ValueEnum;
+use flate2::{Compression, Crc, GzBuilder, write::DeflateEncoder};
+
+use crate::{
+    Repository,
+    error::MinigitError,
+    objects::{MODE_EXECUTABLE, MODE_FILE, MODE_SYMLINK, MODE_TREE, RGitObjectTypes, TreeObject, get_tree_files, parse_mode},
+    utils::{dates::commit_datetime, pathspec::Pathspec, resolve_revision},
+};
+
+/// Tamanho de um bloco do tar: cabeçalhos e conteúdos ocupam múltiplos dele
+const TAR_BLOCK_SIZE: usize = 512;
+/// O tar termina em um múltiplo de 20 blocos, como no GNU tar e no git
+const TAR_RECORD_SIZE: u64 = 20 * TAR_BLOCK_SIZE as u64;
+/// Maior tamanho que cabe nos 11 dígitos octais do cabeçalho do tar
+const TAR_MAX_SIZE: u64 = 0o77777777777;
+
+const ZIP_LOCAL_HEADER: u32 = 0x04034b50;
+const ZIP_CENTRAL_HEADER: u32 = 0x02014b50;
+const ZIP_END_OF_CENTRAL_DIRECTORY: u32 = 0x06054b50;
+/// Versão 2.0 do formato, a primeira com diretórios e deflate
+const ZIP_VERSION: u16 = 20;
+/// "Criado em Unix": faz os leitores usarem o modo guardado nos atributos externos
+const ZIP_MADE_BY_UNIX: u16 = 3 << 8;
+/// Nomes dos arquivos em UTF-8
+const ZIP_FLAG_UTF8: u16 = 0x0800;
+const ZIP_METHOD_STORED: u16 = 0;
+const ZIP_METHOD_DEFLATED: u16 = 8;
+/// Atributo de diretório do MS-DOS
+const ZIP_DOS_DIRECTORY: u32 = 0x10;
+
+/// Formato do arquivo gerado pelo `archive`
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ArchiveFormat {
+    Tar,
+    #[value(name = "tar.gz", alias = "tgz")]
+    TarGz,
+    Zip,
+}
+
+impl ArchiveFormat {
+    /// Deduz o formato pela extensão do arquivo (`.tar`, `.tar.gz`, `.tgz` ou `.zip`)
+    pub fn from_path(path: &Path) -> Option<ArchiveFormat> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+
+        if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveFormat::TarGz)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveFormat::Tar)
+        } else if name.ends_with(".zip") {
+            Some(ArchiveFormat::Zip)
+        } else {
+            None
+        }
+    }
+}
+
+/// Opções do `archive`
+///
+/// ## Atributos
+/// - `format` - Formato do arquivo gerado
+/// - `prefix` - Texto adicionado na frente de cada caminho (ex: `projeto-1.0/`)
+/// - `revision` - Revisão cujos arquivos são arquivados
+/// - `paths` - Pathspec que limita os arquivos incluídos
+#[derive(Clone, Debug)]
+pub struct ArchiveOptions {
+    pub format: ArchiveFormat,
+    pub prefix: String,
+    pub revision: String,
+    pub paths: Vec<String>,
+}
+
+/// Destino das entradas do arquivo, em ordem: cada diretório antes do seu conteúdo
+trait ArchiveWriter {
+    /// Adiciona um diretório. `path` termina com `/`
+    fn directory(&mut self, path: &str) -> Result<(), MinigitError>;
+    /// Adiciona um arquivo com o modo da árvore (`MODE_FILE`, `MODE_EXECUTABLE` ou `MODE_SYMLINK`)
+    fn file(&mut self, path: &str, mode: u32, content: &[u8]) -> Result<(), MinigitError>;
+}
+
+/// Grava em `out` um arquivo com os arquivos da revisão, lidos direto do banco de objetos
+///
+/// Os blobs são lidos e gravados um de cada vez, na ordem da árvore, então só um arquivo fica em
+/// memória por vez. O hash do commit é gravado como comentário do arquivo (cabeçalho global pax no
+/// tar, comentário do arquivo no zip). Retorna o hash do commit arquivado.
+///
+/// Retorna `MinigitError::InvalidArgument` se o prefixo for inválido ou se algum caminho da
+/// pathspec não corresponder a nenhum arquivo da revisão. Os dois casos são verificados antes de
+/// gravar qualquer byte em `out`.
+pub fn archive(repo: &Repository, options: &ArchiveOptions, out: &mut dyn Write) -> Result<String, MinigitError> {
+    validate_prefix(&options.prefix)?;
+    let pathspec = Pathspec::parse(repo, &options.paths)?;
+
+    let commit_id = resolve_revision(repo, &options.revision)?;
+    let commit = repo.get_commit(&commit_id)?;
+    let tree = repo.get_tree(&commit.tree)?;
+
+    if !options.paths.is_empty() {
+        let selected: Vec<PathBuf> = get_tree_files(repo, &tree)?.into_keys()
+            .map(PathBuf::from)
+            .filter(|path| pathspec.matches(path))
+            .collect();
+        let unmatched = pathspec.unmatched(selected.iter());
+        if !unmatched.is_empty() {
+            return Err(MinigitError::InvalidArgument(format!("pathspec '{}' não corresponde a nenhum arquivo", unmatched.join("', '"))));
+        }
+    }
+
+    let mut walker = TreeWalker {
+        repo,
+        pathspec: &pathspec,
+        prefix: &options.prefix,
+        pending: prefix_directories(&options.prefix),
+    };
+
+    match options.format {
+        ArchiveFormat::Tar => {
+            let mut tar = TarWriter::new(&mut *out, &commit_id, commit.timestamp)?;
+            walker.walk(&tree, "", &mut tar)?;
+            tar.finish()?;
+        }
+        ArchiveFormat::TarGz => {
+            let gzip = GzBuilder::new().mtime(timestamp_seconds(commit.timestamp) as u32).write(&mut *out, Compression::default());
+            let mut tar = TarWriter::new(gzip, &commit_id, commit.timestamp)?;
+            walker.walk(&tree, "", &mut tar)?;
+            tar.finish()?.finish()?;
+        }
+        ArchiveFormat::Zip => {
+            let mut zip = ZipWriter::new(&mut *out, commit.timestamp);
+            walker.walk(&tree, "", &mut zip)?;
+            zip.finish(&commit_id)?;
+        }
+    }
+    out.flush()?;
+
+    Ok(commit_id)
+}
+
+/// Recusa prefixos que gerariam caminhos absolutos ou fora da pasta de extração
+fn validate_prefix(prefix: &str) -> Result<(), MinigitError> {
+    if prefix.starts_with('/') || prefix.split('/').any(|part| part == "..") {
+        return Err(MinigitError::InvalidArgument(format!("Prefixo inválido: '{}'", prefix)));
+    }
+    Ok(())
+}
+
+/// Diretórios criados pelo prefixo: `a/b/` cria `a/` e `a/b/`; `v1-` não cria nenhum
+fn prefix_directories(prefix: &str) -> Vec<String> {
+    prefix.match_indices('/')
+        .map(|(index, _)| prefix[..=index].to_string())
+        .filter(|directory| directory != "/" && !directory.ends_with("//"))
+        .collect()
+}
+
+/// Percorre a árvore gravando os arquivos selecionados pela pathspec
+///
+/// Diretórios ficam em `pending` até o primeiro arquivo dentro deles ser gravado, para que
+/// diretórios sem nenhum arquivo selecionado não apareçam no arquivo.
+struct TreeWalker<'a> {
+    repo: &'a Repository,
+    pathspec: &'a Pathspec,
+    prefix: &'a str,
+    pending: Vec<String>,
+}
+
+impl TreeWalker<'_> {
+    fn walk(&mut self, tree: &TreeObject, dir: &str, writer: &mut dyn ArchiveWriter) -> Result<(), MinigitError> {
+        for child in &tree.children {
+            let path = format!("{}{}", dir, child.name);
+
+            // Árvores antigas usavam `100644` para tudo, então o tipo vem do objeto e não do modo
+            match self.repo.get_object(&child.object_id)? {
+                RGitObjectTypes::Tree(subtree) => {
+                    let subdir = format!("{}/", path);
+                    let entry = format!("{}{}", self.prefix, subdir);
+                    self.pending.push(entry.clone());
+                    self.walk(&subtree, &subdir, writer)?;
+
+                    // Nenhum arquivo gravado: o diretório ainda está pendente
+                    if self.pending.last() == Some(&entry) {
+                        self.pending.pop();
+                    }
+                }
+                RGitObjectTypes::Blob(blob) => {
+                    if !self.pathspec.matches(Path::new(&path)) {
+                        continue;
+                    }
+
+                    for directory in self.pending.drain(..) {
+                        writer.directory(&directory)?;
+                    }
+                    let mode = match parse_mode(&child.mode)? {
+                        MODE_TREE => MODE_FILE,
+                        mode => mode,
+                    };
+                    writer.file(&format!("{}{}", self.prefix, path), mode, &blob.content)?;
+                }
+                _ => return Err(MinigitError::CorruptObject(format!("Objeto inválido na árvore do commit: {}", child.object_id))),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Tar no formato pax (ustar com cabeçalhos estendidos para caminhos longos)
+struct TarWriter<W: Write> {
+    out: W,
+    mtime: u64,
+    written: u64,
+}
+
+impl<W: Write> TarWriter<W> {
+    /// Começa o tar com o cabeçalho global que guarda o hash do commit, como o `git archive`
+    fn new(out: W, commit_id: &str, timestamp: u128) -> Result<Self, MinigitError> {
+        let mut tar = TarWriter { out, mtime: timestamp_seconds(timestamp), written: 0 };
+        tar.pax_header("pax_global_header", b'g', &pax_record("comment", commit_id))?;
+        Ok(tar)
+    }
+
+    /// Termina o tar com dois blocos vazios e completa o último registro. Retorna o destino
+    fn finish(mut self) -> Result<W, MinigitError> {
+        self.write(&[0; 2 * TAR_BLOCK_SIZE])?;
+        let padding = (TAR_RECORD_SIZE - self.written % TAR_RECORD_SIZE) % TAR_RECORD_SIZE;
+        self.write(&vec![0; padding as usize])?;
+        Ok(self.out)
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> Result<(), MinigitError> {
+        self.out.write_all(bytes)?;
+        self.written += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Grava o conteúdo seguido de zeros até completar o bloco
+    fn write_content(&mut self, content: &[u8]) -> Result<(), MinigitError> {
+        self.write(content)?;
+        let padding = (TAR_BLOCK_SIZE - content.len() % TAR_BLOCK_SIZE) % TAR_BLOCK_SIZE;
+        self.write(&vec![0; padding])
+    }
+
+    fn pax_header(&mut self, name: &str, kind: u8, records: &str) -> Result<(), MinigitError> {
+        let header = self.header(name, kind, 0o644, records.len() as u64, "");
+        self.write(&header)?;
+        self.write_content(records.as_bytes())
+    }
+
+    /// Grava a entrada, precedida de um cabeçalho pax se o caminho, o destino do link ou o tamanho
+    /// não couberem no cabeçalho ustar
+    fn entry(&mut self, path: &str, kind: u8, mode: u32, content: &[u8], link: &str) -> Result<(), MinigitError> {
+        let mut records = String::new();
+        if path.len() > 100 {
+            records.push_str(&pax_record("path", path));
+        }
+        if link.len() > 100 {
+            records.push_str(&pax_record("linkpath", link));
+        }
+        if content.len() as u64 > TAR_MAX_SIZE {
+            records.push_str(&pax_record("size", &content.len().to_string()));
+        }
+        if !records.is_empty() {
+            self.pax_header("././@PaxHeader", b'x', &records)?;
+        }
+
+        let header = self.header(path, kind, mode, content.len() as u64, link);
+        self.write(&header)?;
+        self.write_content(content)
+    }
+
+    fn header(&self, path: &str, kind: u8, mode: u32, size: u64, link: &str) -> [u8; TAR_BLOCK_SIZE] {
+        let mut header = [0u8; TAR_BLOCK_SIZE];
+        let mut field = |offset: usize, len: usize, value: &[u8]| {
+            let len = value.len().min(len);
+            header[offset..offset + len].copy_from_slice(&value[..len]);
+        };
+
+        field(0, 100, path.as_bytes());
+        field(100, 8, format!("{:07o}", mode).as_bytes());
+        field(108, 8, b"0000000");
+        field(116, 8, b"0000000");
+        field(124, 12, format!("{:011o}", size.min(TAR_MAX_SIZE)).as_bytes());
+        field(136, 12, format!("{:011o}", self.mtime).as_bytes());
+        field(148, 8, b"        ");
+        field(156, 1, &[kind]);
+        field(157, 100, link.as_bytes());
+        field(257, 8, b"ustar\x0000");
+        field(265, 32, b"root");
+        field(297, 32, b"root");
+
+        // O checksum é a soma dos bytes do cabeçalho, com o próprio campo preenchido com espaços
+        let checksum: u32 = header.iter().map(|&byte| byte as u32).sum();
+        header[148..156].copy_from_slice(format!("{:06o}\0 ", checksum).as_bytes());
+        header
+    }
+}
+
+impl<W: Write> ArchiveWriter for TarWriter<W> {
+    fn directory(&mut self, path: &str) -> Result<(), MinigitError> {
+        self.entry(path, b'5', 0o755, &[], "")
+    }
+
+    fn file(&mut self, path: &str, mode: u32, content: &[u8]) -> Result<(), MinigitError> {
+        match mode {
+            MODE_SYMLINK => self.entry(path, b'2', 0o777, &[], &String::from_utf8_lossy(content)),
+            MODE_EXECUTABLE => self.entry(path, b'0', 0o755, content, ""),
+            _ => self.entry(path, b'0', 0o644, content, ""),
+        }
+    }
+}
+
+/// Registro `<tamanho> <chave>=<valor>\n` de um cabeçalho pax, onde o tamanho inclui os próprios dígitos
+fn pax_record(key: &str, value: &str) -> String {
+    let base = key.len() + value.len() + 3;
+    let mut len = base;
+    while len != base + len.to_string().len() {
+        len = base + len.to_string().len();
+    }
+    format!("{} {}={}\n", len, key, value)
+}
+
+/// Zip com os arquivos comprimidos por deflate (ou guardados, quando isso não os deixa menores)
+struct ZipWriter<W: Write> {
+    out: W,
+    dos_time: u16,
+    dos_date: u16,
+    offset: u64,
+    entries: u64,
+    central_directory: Vec<u8>,
+}
+
+impl<W: Write> ZipWriter<W> {
+    fn new(out: W, timestamp: u128) -> Self {
+        let (dos_time, dos_date) = dos_datetime(timestamp);
+        ZipWriter { out, dos_time, dos_date, offset: 0, entries: 0, central_directory: Vec::new() }
+    }
+
+    fn entry(&mut self, path: &str, unix_mode: u32, content: &[u8]) -> Result<(), MinigitError> {
+        let mut crc = Crc::new();
+        crc.update(content);
+
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content)?;
+        let compressed = encoder.finish()?;
+        let (method, data) = if compressed.len() < content.len() {
+            (ZIP_METHOD_DEFLATED, compressed.as_slice())
+        } else {
+            (ZIP_METHOD_STORED, content)
+        };
+
+        let too_large = |what: &str| MinigitError::InvalidArgument(format!(
+            "'{}' não cabe no arquivo zip: {} passa de 4 GiB (zip64 não é suportado)", path, what,
+        ));
+        let size = u32::try_from(content.len()).map_err(|_| too_large("o arquivo"))?;
+        let compressed_size = u32::try_from(data.len()).map_err(|_| too_large("o arquivo"))?;
+        let offset = u32::try_from(self.offset).map_err(|_| too_large("o zip"))?;
+
+        let mut common = Vec::new();
+        put_u16(&mut common, ZIP_VERSION);
+        put_u16(&mut common, ZIP_FLAG_UTF8);
+        put_u16(&mut common, method);
+        put_u16(&mut common, self.dos_time);
+        put_u16(&mut common, self.dos_date);
+        put_u32(&mut common, crc.sum());
+        put_u32(&mut common, compressed_size);
+        put_u32(&mut common, size);
+        put_u16(&mut common, path.len() as u16);
+        put_u16(&mut common, 0);
+
+        let mut local = Vec::new();
+        put_u32(&mut local, ZIP_LOCAL_HEADER);
+        local.extend_from_slice(&common);
+        local.extend_from_slice(path.as_bytes());
+        self.out.write_all(&local)?;
+        self.out.write_all(data)?;
+        self.offset += (local.len() + data.len()) as u64;
+
+        let dos_attributes = if unix_mode & 0o170000 == MODE_TREE { ZIP_DOS_DIRECTORY } else { 0 };
+        let central = &mut self.central_directory;
+        put_u32(central, ZIP_CENTRAL_HEADER);
+        put_u16(central, ZIP_MADE_BY_UNIX | ZIP_VERSION);
+        central.extend_from_slice(&common);
+        put_u16(central, 0);
+        put_u16(central, 0);
+        put_u16(central, 0);
+        put_u32(central, (unix_mode << 16) | dos_attributes);
+        put_u32(central, offset);
+        central.extend_from_slice(path.as_bytes());
+
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Grava o diretório central e o registro final, com o hash do commit como comentário do arquivo
+    fn finish(mut self, comment: &str) -> Result<W, MinigitError> {
+        let too_large = || MinigitError::InvalidArgument("O arquivo zip ficou grande demais (zip64 não é suportado)".to_string());
+        let entries = u16::try_from(self.entries).map_err(|_| too_large())?;
+        let central_size = u32::try_from(self.central_directory.len()).map_err(|_| too_large())?;
+        let central_offset = u32::try_from(self.offset).map_err(|_| too_large())?;
+
+        let mut end = Vec::new();
+        put_u32(&mut end, ZIP_END_OF_CENTRAL_DIRECTORY);
+        put_u16(&mut end, 0);
+        put_u16(&mut end, 0);
+        put_u16(&mut end, entries);
+        put_u16(&mut end, entries);
+        put_u32(&mut end, central_size);
+        put_u32(&mut end, central_offset);
+        put_u16(&mut end, comment.len() as u16);
+        end.extend_from_slice(comment.as_bytes());
+
+        self.out.write_all(&self.central_directory)?;
+        self.out.write_all(&end)?;
+        Ok(self.out)
+    }
+}
+
+impl<W: Write> ArchiveWriter for ZipWriter<W> {
+    fn directory(&mut self, path: &str) -> Result<(), MinigitError> {
+        self.entry(path, MODE_TREE | 0o755, &[])
+    }
+
+    fn file(&mut self, path: &str, mode: u32, content: &[u8]) -> Result<(), MinigitError> {
+        let unix_mode = match mode {
+            MODE_SYMLINK => MODE_SYMLINK | 0o777,
+            mode => mode,
+        };
+        self.entry(path, unix_mode, content)
+    }
+}
+
+fn timestamp_seconds(timestamp: u128) -> u64 {
+    (timestamp / 1_000_000_000) as u64
+}
+
+/// Hora e data no formato do MS-DOS usado pelo zip, no horário local. Datas antes de 1980 viram 01/01/1980
+fn dos_datetime(timestamp: u128) -> (u16, u16) {
+    let datetime = commit_datetime(timestamp);
+    if datetime.year() < 1980 {
+        return (0, (1 << 5) | 1);
+    }
+
+    let time = (datetime.hour() << 11) | (datetime.minute() << 5) | (datetime.second() / 2);
+    let date = (((datetime.year() - 1980) as u32) << 9) | (datetime.month() << 5) | datetime.day();
+    (time as u16, date as u16)
+}
+
+fn put_u16(buffer: &mut Vec<u8>, value: u16) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
+
+fn put_u32(buffer: &mut Vec<u8>, value: u32) {
+    buffer.extend_from_slice(&value.to_le_bytes());
+}
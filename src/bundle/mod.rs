@@ -0,0 +1,508 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    io::{BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use flate2::{Compression, read::ZlibDecoder, write::ZlibEncoder};
+use serde::Serialize;
+
+use crate::{
+    Repository,
+    commands::{branch::validate_branch_name, checkout::instanciate_commit, log::subject, merge::is_ancestor},
+    error::MinigitError,
+    objects::{CommitObject, MODE_TREE, TreeObject, is_valid_tree_entry_name, parse_mode},
+    staging::rewrite_index_from_commit_id,
+    utils::{LockFile, is_valid_sha1, list_branches, refs, resolve_revision, sha1sum, write_locked},
+};
+
+/// Primeira linha de todo bundle
+const BUNDLE_SIGNATURE: &str = "# minigit bundle v1";
+
+/// Referência gravada no bundle: `HEAD` ou `refs/heads/<branch>`
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct BundleRef {
+    pub name: String,
+    pub commit: String,
+}
+
+/// Commit que não está no bundle, mas que o repositório de destino precisa ter para recebê-lo
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct Prerequisite {
+    pub commit: String,
+    pub subject: String,
+}
+
+/// Cabeçalho do bundle
+///
+/// O arquivo é formado pelo cabeçalho em texto e pelos objetos comprimidos com zlib:
+///
+/// ```text
+/// # minigit bundle v1
+/// -<commit exigido> <assunto>
+/// <commit> <referência>
+///
+/// <objetos no formato do banco de objetos, um após o outro>
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize)]
+pub struct BundleHeader {
+    pub prerequisites: Vec<Prerequisite>,
+    pub refs: Vec<BundleRef>,
+}
+
+impl BundleHeader {
+    /// Branch que fica no HEAD ao clonar o bundle: a que aponta para o mesmo commit do `HEAD` do
+    /// bundle (de preferência `master`), senão `master`, senão a primeira branch
+    pub fn head_branch(&self) -> Option<&str> {
+        let branches: Vec<(&str, &str)> = self.refs.iter()
+            .filter_map(|reference| Some((reference.name.strip_prefix("refs/heads/")?, reference.commit.as_str())))
+            .collect();
+        let head = self.refs.iter().find(|reference| reference.name == Repository::HEAD);
+
+        let mut candidates: Vec<&(&str, &str)> = branches.iter()
+            .filter(|(_, commit)| head.is_none_or(|head| head.commit == *commit))
+            .collect();
+        if candidates.is_empty() {
+            candidates = branches.iter().collect();
+        }
+
+        candidates.iter()
+            .find(|(name, _)| *name == "master")
+            .or_else(|| candidates.first())
+            .map(|(name, _)| *name)
+    }
+}
+
+/// Resumo de um bundle criado ou verificado
+///
+/// ## Atributos
+/// - `header` - Referências e commits exigidos
+/// - `commits` - Quantidade de commits no bundle
+/// - `objects` - Quantidade de objetos no bundle, incluindo os commits
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct BundleSummary {
+    pub header: BundleHeader,
+    pub commits: usize,
+    pub objects: usize,
+}
+
+/// O que aconteceu com uma branch do bundle ao importá-lo
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "snake_case", tag = "status", content = "reason")]
+pub enum RefUpdateStatus {
+    /// A branch não existia (ou não tinha commits) e foi criada
+    Created,
+    /// A branch avançou para um descendente do commit anterior
+    FastForward,
+    /// A branch foi movida para um commit que não descende do anterior (`--force`)
+    Forced,
+    /// A branch já estava no commit do bundle
+    UpToDate,
+    /// A branch não foi alterada, pelo motivo informado
+    Rejected(String),
+}
+
+/// Atualização de uma branch do bundle
+///
+/// ## Atributos
+/// - `branch` - Nome da branch (ex: `master`)
+/// - `old` - Commit anterior, vazio se a branch não existia ou não tinha commits
+/// - `new` - Commit do bundle
+/// - `status` - Resultado da atualização
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct RefUpdate {
+    pub branch: String,
+    pub old: String,
+    pub new: String,
+    pub status: RefUpdateStatus,
+}
+
+/// Grava em `path` um bundle com os commits das revisões e os objetos necessários para eles
+///
+/// Cada revisão pode ser uma branch ou `HEAD` (gravada como referência), `A..B` (B sem o que é
+/// alcançável de A) ou `^A` (exclui o que é alcançável de A); `all` inclui todas as branches. Os pais
+/// dos commits incluídos que ficaram de fora viram commits exigidos, e os objetos das árvores deles
+/// não são gravados.
+///
+/// Retorna `MinigitError::InvalidArgument` se nenhuma branch for informada, se uma revisão incluída
+/// não for uma branch nem `HEAD` ou se o bundle ficaria vazio.
+pub fn create_bundle(repo: &Repository, path: &Path, revisions: &[String], all: bool) -> Result<BundleSummary, MinigitError> {
+    let (bundle_refs, exclude) = parse_revisions(repo, revisions, all)?;
+    let excluded = reachable_commits(repo, &exclude)?;
+
+    let mut commits = Vec::new();
+    let mut seen = HashSet::new();
+    let mut stack: Vec<String> = bundle_refs.iter().map(|reference| reference.commit.clone()).collect();
+    while let Some(hash) = stack.pop() {
+        if excluded.contains(&hash) || !seen.insert(hash.clone()) {
+            continue;
+        }
+
+        let commit = repo.get_commit(&hash)?;
+        stack.extend(commit.parent.iter().cloned());
+        commits.push((hash, commit));
+    }
+
+    if commits.is_empty() {
+        return Err(MinigitError::InvalidArgument("O bundle ficaria vazio: todos os commits já são alcançáveis pelas revisões excluídas".to_string()));
+    }
+
+    let mut prerequisites: Vec<Prerequisite> = Vec::new();
+    let mut known_objects = HashSet::new();
+    for parent in commits.iter().flat_map(|(_, commit)| &commit.parent) {
+        if seen.contains(parent) || prerequisites.iter().any(|prerequisite| prerequisite.commit == *parent) {
+            continue;
+        }
+
+        let commit = repo.get_commit(parent)?;
+        collect_tree_objects(repo, &commit.tree, &mut known_objects)?;
+        prerequisites.push(Prerequisite { commit: parent.clone(), subject: subject(&commit.message).to_string() });
+    }
+
+    let header = BundleHeader { prerequisites, refs: bundle_refs };
+
+    // O bundle só aparece no caminho final se for gravado por inteiro
+    let mut out = BufWriter::new(LockFile::acquire(path)?);
+    out.write_all(format_header(&header).as_bytes())?;
+
+    let mut encoder = ZlibEncoder::new(out, Compression::default());
+    let mut objects = 0;
+    let mut stack: Vec<String> = commits.iter().rev().map(|(hash, _)| hash.clone()).collect();
+    while let Some(hash) = stack.pop() {
+        if !known_objects.insert(hash.clone()) {
+            continue;
+        }
+
+        let (object_type, content) = repo.get_raw_object(&hash)?;
+        encoder.write_all(format!("{} {}\0", object_type, content.len()).as_bytes())?;
+        encoder.write_all(&content)?;
+        objects += 1;
+
+        match object_type.as_str() {
+            "commit" => stack.push(CommitObject::new(content)?.tree),
+            "tree" => stack.extend(TreeObject::new(content)?.children.into_iter().rev().map(|child| child.object_id)),
+            _ => {}
+        }
+    }
+
+    encoder.finish()?.into_inner().map_err(|err| err.into_error())?.commit()?;
+
+    Ok(BundleSummary { header, commits: commits.len(), objects })
+}
+
+/// Separa as revisões em referências gravadas no bundle e commits excluídos
+fn parse_revisions(repo: &Repository, revisions: &[String], all: bool) -> Result<(Vec<BundleRef>, Vec<String>), MinigitError> {
+    let mut bundle_refs = Vec::new();
+    let mut exclude = Vec::new();
+
+    for revision in revisions {
+        if let Some((from, to)) = revision.split_once("..") {
+            exclude.push(resolve_revision(repo, if from.is_empty() { Repository::HEAD } else { from })?);
+            bundle_refs.push(bundle_ref(repo, if to.is_empty() { Repository::HEAD } else { to })?);
+        } else if let Some(excluded) = revision.strip_prefix('^') {
+            exclude.push(resolve_revision(repo, excluded)?);
+        } else {
+            bundle_refs.push(bundle_ref(repo, revision)?);
+        }
+    }
+
+    if all {
+        for branch in list_branches(repo)? {
+            if !branch.commit.is_empty() {
+                bundle_refs.push(BundleRef { name: format!("refs/heads/{}", branch.name), commit: branch.commit });
+            }
+        }
+    }
+
+    let mut names = HashSet::new();
+    bundle_refs.retain(|reference| names.insert(reference.name.clone()));
+
+    if bundle_refs.is_empty() {
+        return Err(MinigitError::InvalidArgument("Nenhuma branch informada: use 'bundle create <arquivo> <branch>' ou '--all'".to_string()));
+    }
+
+    Ok((bundle_refs, exclude))
+}
+
+fn bundle_ref(repo: &Repository, revision: &str) -> Result<BundleRef, MinigitError> {
+    let commit = resolve_revision(repo, revision)?;
+    if revision == Repository::HEAD {
+        return Ok(BundleRef { name: revision.to_string(), commit });
+    }
+
+    let name = format!("refs/heads/{}", revision);
+    if refs::resolve_ref_path(&name, repo)?.as_deref() != Some(commit.as_str()) {
+        return Err(MinigitError::InvalidArgument(format!(
+            "'{}' não é uma branch nem HEAD: o bundle precisa de referências para os commits incluídos", revision,
+        )));
+    }
+
+    Ok(BundleRef { name, commit })
+}
+
+fn reachable_commits(repo: &Repository, starts: &[String]) -> Result<HashSet<String>, MinigitError> {
+    let mut visited = HashSet::new();
+    let mut stack = starts.to_vec();
+
+    while let Some(hash) = stack.pop() {
+        if visited.insert(hash.clone()) {
+            stack.extend(repo.get_commit(&hash)?.parent);
+        }
+    }
+
+    Ok(visited)
+}
+
+/// Adiciona a `objects` a árvore e tudo o que ela referencia
+///
+/// Só desce nas subárvores com modo de árvore: em árvores antigas, que usavam `100644` para tudo,
+/// alguns objetos deixam de ser marcados e acabam gravados no bundle sem necessidade.
+fn collect_tree_objects(repo: &Repository, tree: &String, objects: &mut HashSet<String>) -> Result<(), MinigitError> {
+    if !objects.insert(tree.clone()) {
+        return Ok(());
+    }
+
+    for child in repo.get_tree(tree)?.children {
+        if parse_mode(&child.mode)? == MODE_TREE {
+            collect_tree_objects(repo, &child.object_id, objects)?;
+        } else {
+            objects.insert(child.object_id);
+        }
+    }
+
+    Ok(())
+}
+
+fn format_header(header: &BundleHeader) -> String {
+    let mut text = format!("{}\n", BUNDLE_SIGNATURE);
+    for prerequisite in &header.prerequisites {
+        text.push_str(&format!("-{} {}\n", prerequisite.commit, prerequisite.subject));
+    }
+    for reference in &header.refs {
+        text.push_str(&format!("{} {}\n", reference.commit, reference.name));
+    }
+    text.push('\n');
+    text
+}
+
+/// Bundle aberto para leitura: o cabeçalho e o leitor posicionado no início dos objetos
+struct BundleReader {
+    header: BundleHeader,
+    objects: BufReader<ZlibDecoder<BufReader<File>>>,
+}
+
+impl BundleReader {
+    /// Abre o bundle e lê o cabeçalho
+    ///
+    /// Retorna `MinigitError::InvalidArgument` se o arquivo não for um bundle e
+    /// `MinigitError::CorruptObject` se o cabeçalho estiver mal formatado.
+    fn open(path: &Path) -> Result<BundleReader, MinigitError> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let corrupt = |detail: String| MinigitError::CorruptObject(format!("Bundle '{}' corrompido: {}", path.display(), detail));
+
+        let mut line = String::new();
+        reader.read_line(&mut line).map_err(|_| corrupt("cabeçalho inválido".to_string()))?;
+        if line.trim_end() != BUNDLE_SIGNATURE {
+            return Err(MinigitError::InvalidArgument(format!("'{}' não é um bundle do minigit", path.display())));
+        }
+
+        let mut header = BundleHeader::default();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).map_err(|_| corrupt("cabeçalho inválido".to_string()))? == 0 {
+                return Err(corrupt("cabeçalho sem fim".to_string()));
+            }
+            let line = line.trim_end_matches('\n');
+            if line.is_empty() {
+                break;
+            }
+
+            if let Some(prerequisite) = line.strip_prefix('-') {
+                let (commit, subject) = prerequisite.split_once(' ').unwrap_or((prerequisite, ""));
+                if !is_valid_sha1(commit) {
+                    return Err(corrupt(format!("commit exigido inválido '{}'", commit)));
+                }
+                header.prerequisites.push(Prerequisite { commit: commit.to_string(), subject: subject.to_string() });
+            } else {
+                let (commit, name) = line.split_once(' ').ok_or_else(|| corrupt(format!("linha inválida '{}'", line)))?;
+                if !is_valid_sha1(commit) || (name != Repository::HEAD && !name.starts_with("refs/heads/")) {
+                    return Err(corrupt(format!("referência inválida '{}'", line)));
+                }
+                if let Some(branch) = name.strip_prefix("refs/heads/") {
+                    validate_branch_name(branch).map_err(|err| corrupt(format!("referência inválida '{}': {}", name.escape_debug(), err)))?;
+                }
+                header.refs.push(BundleRef { name: name.to_string(), commit: commit.to_string() });
+            }
+        }
+
+        Ok(BundleReader { header, objects: BufReader::new(ZlibDecoder::new(reader)) })
+    }
+
+    /// Lê o próximo objeto: o hash e os bytes no formato do banco de objetos (`tipo tamanho\0conteúdo`)
+    fn next_object(&mut self) -> Result<Option<(String, Vec<u8>)>, MinigitError> {
+        let corrupt = |detail: &str| MinigitError::CorruptObject(format!("Bundle corrompido: {}", detail));
+
+        let mut raw = Vec::new();
+        if self.objects.read_until(b'\0', &mut raw).map_err(|err| corrupt(&err.to_string()))? == 0 {
+            return Ok(None);
+        }
+        if raw.pop() != Some(b'\0') {
+            return Err(corrupt("objeto truncado"));
+        }
+
+        let header = std::str::from_utf8(&raw).map_err(|_| corrupt("cabeçalho de objeto inválido"))?;
+        let (object_type, size) = header.split_once(' ').ok_or_else(|| corrupt("cabeçalho de objeto inválido"))?;
+        let size: usize = size.parse().map_err(|_| corrupt("tamanho de objeto inválido"))?;
+        if !matches!(object_type, "blob" | "tree" | "commit") {
+            return Err(corrupt(&format!("tipo de objeto desconhecido '{}'", object_type)));
+        }
+
+        raw.push(b'\0');
+        let start = raw.len();
+        let end = start.checked_add(size).ok_or_else(|| corrupt("tamanho de objeto inválido"))?;
+
+        // Lê aos poucos, sem reservar de antemão o tamanho declarado no cabeçalho
+        (&mut self.objects).take(size as u64).read_to_end(&mut raw).map_err(|err| corrupt(&err.to_string()))?;
+        if raw.len() != end {
+            return Err(corrupt("objeto truncado"));
+        }
+
+        Ok(Some((sha1sum(&raw), raw)))
+    }
+}
+
+/// Lê o cabeçalho do bundle, sem verificar os objetos
+pub fn read_bundle_header(path: &Path) -> Result<BundleHeader, MinigitError> {
+    Ok(BundleReader::open(path)?.header)
+}
+
+fn object_exists(repo: &Repository, hash: &str) -> bool {
+    let (dir, file_name) = hash.split_at(2);
+    repo.get_repository_path(&["objects", dir, file_name]).is_file()
+}
+
+/// Verifica se o bundle pode ser importado no repositório, sem alterá-lo
+///
+/// O repositório precisa ter os commits exigidos, e todo objeto referenciado pelos objetos do bundle
+/// (pais, árvores e blobs) precisa estar no bundle ou no repositório.
+///
+/// Retorna `MinigitError::InvalidState` se faltar algum commit exigido e `MinigitError::CorruptObject`
+/// se o bundle estiver corrompido, incompleto ou tiver árvores com nomes de entrada inválidos.
+pub fn verify_bundle(repo: &Repository, path: &Path) -> Result<BundleSummary, MinigitError> {
+    let mut reader = BundleReader::open(path)?;
+
+    let missing: Vec<String> = reader.header.prerequisites.iter()
+        .filter(|prerequisite| repo.get_commit(&prerequisite.commit).is_err())
+        .map(|prerequisite| format!("{} {}", prerequisite.commit, prerequisite.subject))
+        .collect();
+    if !missing.is_empty() {
+        return Err(MinigitError::InvalidState(format!("O repositório não tem os commits exigidos pelo bundle:\n{}", missing.join("\n"))));
+    }
+
+    let mut types: HashMap<String, String> = HashMap::new();
+    let mut links: Vec<(String, String)> = Vec::new();
+    while let Some((hash, raw)) = reader.next_object()? {
+        let null = raw.iter().position(|&byte| byte == b'\0').unwrap_or_default();
+        let object_type = String::from_utf8_lossy(&raw[..null]).split(' ').next().unwrap_or_default().to_string();
+        let content = raw[null + 1..].to_vec();
+        let invalid = |err: MinigitError| MinigitError::CorruptObject(format!("Bundle corrompido: objeto {} inválido: {}", hash, err));
+
+        match object_type.as_str() {
+            "commit" => {
+                let commit = CommitObject::new(content).map_err(invalid)?;
+                links.push((hash.clone(), commit.tree));
+                links.extend(commit.parent.into_iter().map(|parent| (hash.clone(), parent)));
+            }
+            "tree" => {
+                let tree = TreeObject::new(content).map_err(invalid)?;
+                if let Some(child) = tree.children.iter().find(|child| !is_valid_tree_entry_name(&child.name)) {
+                    return Err(MinigitError::CorruptObject(format!(
+                        "Bundle corrompido: a árvore {} tem uma entrada com nome inválido '{}'", hash, child.name.escape_debug(),
+                    )));
+                }
+                links.extend(tree.children.into_iter().map(|child| (hash.clone(), child.object_id)));
+            }
+            _ => {}
+        }
+        types.insert(hash, object_type);
+    }
+
+    for (from, to) in &links {
+        if !types.contains_key(to) && !object_exists(repo, to) {
+            return Err(MinigitError::CorruptObject(format!(
+                "O bundle está incompleto: o objeto {} referenciado por {} não está no bundle nem no repositório", to, from,
+            )));
+        }
+    }
+
+    for reference in &reader.header.refs {
+        let in_bundle = types.get(&reference.commit).map(|object_type| object_type == "commit");
+        if !in_bundle.unwrap_or_else(|| repo.get_commit(&reference.commit).is_ok()) {
+            return Err(MinigitError::CorruptObject(format!(
+                "O bundle está incompleto: a referência {} aponta para {}, que não é um commit do bundle nem do repositório",
+                reference.name, reference.commit,
+            )));
+        }
+    }
+
+    let commits = types.values().filter(|object_type| *object_type == "commit").count();
+    Ok(BundleSummary { header: reader.header, commits, objects: types.len() })
+}
+
+/// Importa o bundle: grava os objetos que faltam e cria ou avança as branches dele
+///
+/// O bundle é verificado com [`verify_bundle`] antes de qualquer alteração. Uma branch só é movida se
+/// o novo commit descender do atual (ou com `force`). A branch do HEAD só é alterada se ainda não tiver
+/// commits, e nesse caso o commit é instanciado no índice e na worktree.
+pub fn unbundle(repo: &mut Repository, path: &Path, force: bool) -> Result<Vec<RefUpdate>, MinigitError> {
+    verify_bundle(repo, path)?;
+
+    let mut reader = BundleReader::open(path)?;
+    while let Some((hash, raw)) = reader.next_object()? {
+        if !object_exists(repo, &hash) {
+            let (dir, file_name) = hash.split_at(2);
+            repo.create_repository_dir(&["objects", dir])?;
+            write_locked(&repo.get_repository_path(&["objects", dir, file_name]), raw)?;
+        }
+    }
+
+    let head = repo.get_head()?;
+    let mut updates = Vec::new();
+    for reference in &reader.header.refs {
+        let Some(branch) = reference.name.strip_prefix("refs/heads/") else {
+            continue;
+        };
+        let old = refs::resolve_ref_path(&reference.name, repo)?.unwrap_or_default();
+        let new = reference.commit.clone();
+        let is_current = head == reference.name;
+
+        let status = if old == new {
+            RefUpdateStatus::UpToDate
+        } else if is_current && !old.is_empty() {
+            RefUpdateStatus::Rejected(format!("é a branch atual; use 'minigit merge {}' para trazer as mudanças", new))
+        } else if old.is_empty() {
+            RefUpdateStatus::Created
+        } else if is_ancestor(repo, &old, &new)? {
+            RefUpdateStatus::FastForward
+        } else if force {
+            RefUpdateStatus::Forced
+        } else {
+            RefUpdateStatus::Rejected("não é fast-forward; use --force para substituí-la".to_string())
+        };
+
+        if matches!(status, RefUpdateStatus::Created | RefUpdateStatus::FastForward | RefUpdateStatus::Forced) {
+            std::fs::create_dir_all(repo.minigitdir.join(&reference.name))?;
+            refs::update_ref(repo, &reference.name, &new, Some(&old))?;
+
+            if is_current {
+                rewrite_index_from_commit_id(repo, &new)?;
+                instanciate_commit(repo.get_commit(&new)?, repo)?;
+            }
+        }
+
+        updates.push(RefUpdate { branch: branch.to_string(), old, new, status });
+    }
+
+    Ok(updates)
+}
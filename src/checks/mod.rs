@@ -1,43 +1,42 @@
-use crate::{Repository, status::{self, non_staged_files}, utils::merge_rebase};
+use crate::{Repository, error::MinigitError, staging::StagingArea, status::{self, non_staged_files}, utils::merge_rebase::{self, OperationKind}};
 
 /// Garante de forma segura que não há arquivos não adicionados no repositório
 /// 
 /// ## Retorna
 /// - Ok(()): se não houver arquivos não adicionados
-/// - Err(String): se houver arquivos não adicionados, com uma mensagem detalhando os arquivos.
-pub fn ensure_no_non_staged_files(repo: &Repository) -> Result<(), String> {
-    let non_staged_files= non_staged_files(repo);
+/// - Err(MinigitError::UnstagedChanges): se houver arquivos não adicionados, com a lista dos arquivos.
+pub fn ensure_no_non_staged_files(repo: &Repository) -> Result<(), MinigitError> {
+    let non_staged_files = non_staged_files(repo)?;
 
     if !non_staged_files.is_empty() {
-        let file_list = non_staged_files.iter()
-            .map(|f| format!("\n- {}", f.to_str().unwrap()))
-            .collect::<String>();
+        return Err(MinigitError::UnstagedChanges(non_staged_files));
+    }
 
-        let message = String::from("Existem arquivos não adicionados no repositório:\n") +
-            "Adicione-os com 'minigit add <arquivos>' ou descarte as mudanças antes de continuar.\n" +
-            "\nArquivos não adicionados:" +
-            &file_list;
+    Ok(())
+}
 
-        return Err(message);
+/// Garante de forma segura que não há arquivos com conflitos não resolvidos no índice
+///
+/// ## Retorna
+/// - Ok(()): se nenhum arquivo estiver em conflito
+/// - Err(MinigitError::Conflict): se houver arquivos em conflito, com a lista dos arquivos.
+pub fn ensure_no_unresolved_conflicts(repo: &Repository) -> Result<(), MinigitError> {
+    let conflicted_paths = StagingArea::new(repo)?.conflicted_paths();
+
+    if !conflicted_paths.is_empty() {
+        return Err(MinigitError::Conflict(
+            conflicted_paths.iter().map(|path| path.to_string_lossy().to_string()).collect()
+        ));
     }
 
     Ok(())
 }
 
-pub fn ensure_no_uncommited_changes(repo: &Repository) -> Result<(), String> {
-    let uncommited_files = status::get_uncommited_files(repo);
+pub fn ensure_no_uncommited_changes(repo: &Repository) -> Result<(), MinigitError> {
+    let uncommited_files = status::get_uncommited_files(repo)?;
 
     if !uncommited_files.is_empty() {
-        let file_list = uncommited_files.iter()
-            .map(|f| format!("\n- {}", f.to_str().unwrap()))
-            .collect::<String>();
-
-        let message = String::from("Existem arquivos com mudanças não commitadas no repositório:\n") +
-            "Faça o commit dessas mudanças com 'minigit commit -m <mensagem>' ou descarte as mudanças antes de continuar.\n" +
-            "\nArquivos com mudanças não commitadas:" +
-            &file_list;
-
-        return Err(message);
+        return Err(MinigitError::DirtyWorktree(uncommited_files));
     }
     Ok(())
 }
@@ -46,10 +45,10 @@ pub fn ensure_no_uncommited_changes(repo: &Repository) -> Result<(), String> {
 /// 
 /// ## Retorna
 /// - Ok(()): se não houver merge em progresso
-/// - Err(String): se houver um merge em progresso, com uma mensagem detalhando o problema.
-pub fn ensure_no_merge_in_progress(repo: &Repository) -> Result<(), String> {
-    if merge_rebase::is_in_progress(repo, false) {
-        return Err("Há um merge em progresso. Finalize ou aborte antes de continuar.".to_string());
+/// - Err(MinigitError::InvalidState): se houver um merge em progresso, com uma mensagem detalhando o problema.
+pub fn ensure_no_merge_in_progress(repo: &Repository) -> Result<(), MinigitError> {
+    if merge_rebase::is_in_progress(repo, OperationKind::Merge) {
+        return Err(MinigitError::InvalidState("Há um merge em progresso. Finalize ou aborte antes de continuar.".to_string()));
     }
     Ok(())
 }
@@ -58,10 +57,10 @@ pub fn ensure_no_merge_in_progress(repo: &Repository) -> Result<(), String> {
 /// 
 /// ## Retorna
 /// - Ok(()): se houver um merge em progresso
-/// - Err(String): se não houver um merge em progresso, com uma mensagem
-pub fn ensure_merge_in_progress(repo: &Repository) -> Result<(), String> {
-    if !merge_rebase::is_in_progress(repo, false) {
-        return Err("Não há um merge em progresso para prosseguir.".to_string());
+/// - Err(MinigitError::InvalidState): se não houver um merge em progresso, com uma mensagem
+pub fn ensure_merge_in_progress(repo: &Repository) -> Result<(), MinigitError> {
+    if !merge_rebase::is_in_progress(repo, OperationKind::Merge) {
+        return Err(MinigitError::InvalidState("Não há um merge em progresso para prosseguir.".to_string()));
     }
     Ok(())
 }
@@ -70,10 +69,10 @@ pub fn ensure_merge_in_progress(repo: &Repository) -> Result<(), String> {
 /// 
 /// ## Retorna
 /// - Ok(()): se não houver merge em progresso
-/// - Err(String): se houver um merge em progresso, com uma mensagem detalhando o problema.
-pub fn ensure_no_rebase_in_progress(repo: &Repository) -> Result<(), String> {
-    if merge_rebase::is_in_progress(repo, true) {
-        return Err("Há um rebase em progresso. Finalize ou aborte antes de continuar.".to_string());
+/// - Err(MinigitError::InvalidState): se houver um merge em progresso, com uma mensagem detalhando o problema.
+pub fn ensure_no_rebase_in_progress(repo: &Repository) -> Result<(), MinigitError> {
+    if merge_rebase::is_in_progress(repo, OperationKind::Rebase) {
+        return Err(MinigitError::InvalidState("Há um rebase em progresso. Finalize ou aborte antes de continuar.".to_string()));
     }
     Ok(())
 }
@@ -82,10 +81,10 @@ pub fn ensure_no_rebase_in_progress(repo: &Repository) -> Result<(), String> {
 /// 
 /// ## Retorna
 /// - Ok(()): se houver um rebase em progresso
-/// - Err(String): se não houver um rebase em progresso, com uma mensagem
-pub fn ensure_rebase_in_progress(repo: &Repository) -> Result<(), String> {
-    if !merge_rebase::is_in_progress(repo, true) {
-        return Err("Não há um rebase em progresso para prosseguir.".to_string());
+/// - Err(MinigitError::InvalidState): se não houver um rebase em progresso, com uma mensagem
+pub fn ensure_rebase_in_progress(repo: &Repository) -> Result<(), MinigitError> {
+    if !merge_rebase::is_in_progress(repo, OperationKind::Rebase) {
+        return Err(MinigitError::InvalidState("Não há um rebase em progresso para prosseguir.".to_string()));
     }
     Ok(())
 }
@@ -94,10 +93,10 @@ pub fn ensure_rebase_in_progress(repo: &Repository) -> Result<(), String> {
 /// 
 /// ## Retorna
 /// - Ok(()): se o HEAD não estiver destacado
-/// - Err(String): se o HEAD estiver destacado, com uma mensagem detalhando o problema.
-pub fn ensure_no_detached_head(repo: &Repository) -> Result<(), String> {
-    if repo.head_detached() {
-        return Err("Não é possível realizar a operação com o HEAD destacado.".to_string());
+/// - Err(MinigitError::InvalidState): se o HEAD estiver destacado, com uma mensagem detalhando o problema.
+pub fn ensure_no_detached_head(repo: &Repository) -> Result<(), MinigitError> {
+    if repo.head_detached()? {
+        return Err(MinigitError::InvalidState("Não é possível realizar a operação com o HEAD destacado.".to_string()));
     }
     Ok(())
 }
\ No newline at end of file
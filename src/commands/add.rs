@@ -1,71 +1,230 @@
-use std::{path::PathBuf};
+use std::{collections::BTreeSet, io::Write, ops::Range, path::PathBuf};
 
-use crate::{Repository, checks::ensure_no_detached_head, config::RGitIgnore, utils::{find_current_repo, get_current_dir}};
+use crate::{
+    Repository,
+    checks::ensure_no_detached_head,
+    config::RGitIgnore,
+    diff::{FileDiff, apply_hunks, diff_lines, format_hunk, hunks},
+    error::MinigitError,
+    grep::BINARY_CHECK_LEN,
+    objects::BlobObject,
+    staging::{StagingArea, StagingEntry},
+    status::{non_staged_files, worktree_files},
+    utils::{LockFile, find_current_repo, get_current_dir, pathspec::Pathspec},
+};
+
+/// Quais arquivos o `add` deve considerar
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum AddMode {
+    /// Arquivos novos, modificados e removidos que correspondem à pathspec
+    Pathspec,
+    /// Todos os arquivos novos, modificados e removidos (`-A`)
+    All,
+    /// Apenas arquivos já rastreados que foram modificados ou removidos (`-u`)
+    Update,
+    /// Seleção interativa dos trechos modificados dos arquivos rastreados (`-p`)
+    Patch,
+}
 
 /// Adiciona um arquivo na área de staging
-pub fn cmd_add(files_to_add: Vec<String>) {
-    if let Err(err) = cmd_add_result(files_to_add) {
+pub fn cmd_add(files_to_add: Vec<String>, mode: AddMode) -> Result<(), MinigitError> {
+    let result = cmd_add_result(files_to_add, mode);
+    if let Err(err) = &result {
         eprintln!("Erro ao adicionar arquivo(s): {}", err);
     }
+    result
 }
 
-fn cmd_add_result(files: Vec<String>) -> Result<(), String> {
-    let mut repo = find_current_repo().ok_or("Não é um repositório minigit")?;
+fn cmd_add_result(files: Vec<String>, mode: AddMode) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
 
     ensure_no_detached_head(&repo)?;
 
-    let repository_paths = get_paths_relative_to_repository(&repo, &files);
-    let valid_paths = filter_invalid_paths(&repo, &repository_paths);
-    let paths_to_add = filter_ignored_files(&repo, &valid_paths);
-    
-    repo.add_files(paths_to_add);
+    if mode == AddMode::Pathspec && files.is_empty() {
+        return Err(MinigitError::InvalidArgument("Nenhum caminho fornecido. Use 'add .' para adicionar tudo".to_string()));
+    }
+
+    let pathspec = Pathspec::parse(&repo, &files)?;
+    warn_ignored_files(&repo, &files)?;
 
-    Ok(())
+    if mode == AddMode::Patch {
+        return add_patch(&mut repo, &pathspec);
+    }
+
+    let staging_area = StagingArea::new(&repo)?;
+    let ignore = RGitIgnore::new(&repo)?;
+
+    let tracked: BTreeSet<PathBuf> = staging_area.entries.iter().map(|entry| entry.path.clone()).collect();
+    let untracked: BTreeSet<PathBuf> = worktree_files(&repo, &ignore)
+        .into_iter()
+        .filter(|path| !tracked.contains(path))
+        .collect();
+
+    let unmatched = pathspec.unmatched(tracked.iter().chain(untracked.iter()));
+    if !unmatched.is_empty() {
+        return Err(MinigitError::InvalidArgument(format!("pathspec '{}' não corresponde a nenhum arquivo", unmatched.join("', '"))));
+    }
+
+    let mut paths_to_add: Vec<PathBuf> = tracked.into_iter()
+        .filter(|path| pathspec.matches(path))
+        .collect();
+
+    if mode != AddMode::Update {
+        paths_to_add.extend(untracked.into_iter().filter(|path| pathspec.matches(path)));
+    }
+
+    repo.add_files(paths_to_add)
 }
 
 /// Transforma paths relativos ao diretório atual em paths relativos à raiz do repositório
-pub fn get_paths_relative_to_repository(repo: &Repository, files: &Vec<String>) -> Vec<PathBuf> {
-    let current_dir = get_current_dir();
+///
+/// Retorna `MinigitError::InvalidArgument` se algum caminho estiver fora do repositório.
+pub fn get_paths_relative_to_repository(repo: &Repository, files: &[String]) -> Result<Vec<PathBuf>, MinigitError> {
+    let current_dir = get_current_dir()?;
 
-    files.into_iter().map(|f| {
+    files.iter().map(|f| {
         let blob_path = PathBuf::from(&f);
         current_dir
             .join(blob_path)
             .strip_prefix(&repo.worktree)
-            .unwrap()
-            .to_path_buf()
+            .map(|p| p.to_path_buf())
+            .map_err(|_| MinigitError::InvalidArgument(format!("{} está fora do repositório", f)))
     }).collect()
 }
 
-/// Filtra caminhos que são diretórios ou inexistentes
-fn filter_invalid_paths(repo: &Repository, paths: &Vec<PathBuf>) -> Vec<PathBuf> {
-    paths
-        .iter()
-        .filter(|path| {
-            let absolute_path = repo.worktree.join(path);
-            let is_valid = !absolute_path.exists() || absolute_path.is_file();
-            if !is_valid {
-                println!("Aviso: {:?} não é um arquivo regular e será ignorado", path);
+/// Avisa sobre arquivos fornecidos explicitamente que são ignorados pelo .gitignore
+fn warn_ignored_files(repo: &Repository, files: &[String]) -> Result<(), MinigitError> {
+    let repo_ignore = RGitIgnore::new(repo)?;
+
+    for path in get_paths_relative_to_repository(repo, files).unwrap_or_default() {
+        if repo.worktree.join(&path).is_file() && repo_ignore.check_ignore(&path) {
+            println!("Aviso: {:?} está sendo ignorado pelo .gitignore e não será adicionado", path);
+        }
+    }
+
+    Ok(())
+}
+
+/// Resposta do usuário para um trecho no `add -p`
+enum HunkAnswer {
+    Yes,
+    No,
+    All,
+    None,
+    Quit,
+}
+
+/// Pergunta, trecho a trecho, quais mudanças dos arquivos rastreados devem ser adicionadas
+fn add_patch(repo: &mut Repository, pathspec: &Pathspec) -> Result<(), MinigitError> {
+    let mut lock = LockFile::acquire(&repo.index_path)?;
+    let mut staging_area = StagingArea::new(repo)?;
+    let mut quit = false;
+
+    let candidates: Vec<PathBuf> = non_staged_files(repo)?
+        .into_iter()
+        .filter(|path| pathspec.matches(path) && staging_area.find_entry(path).is_some())
+        .collect();
+
+    if candidates.is_empty() {
+        println!("Nenhuma mudança encontrada.");
+        return Ok(());
+    }
+
+    for path in candidates {
+        if quit {
+            break;
+        }
+
+        let Some(entry) = staging_area.find_entry(&path) else {
+            continue;
+        };
+        let old_content = patch_text(repo.get_blob(&entry.object_hash)?.content);
+        let new_content = patch_text(std::fs::read(repo.worktree.join(&path))?);
+        let (Some(old_content), Some(new_content)) = (old_content, new_content) else {
+            println!("Aviso: {:?} é binário ou não está em UTF-8 e não pode ser adicionado por trechos; use 'add' sem -p", path);
+            continue;
+        };
+
+        let file_diff = FileDiff {
+            path: path.clone(),
+            old_hash: Some(entry.object_hash.clone()),
+            new_hash: None,
+            lines: diff_lines(&old_content, &new_content),
+            source: None,
+        };
+
+        let file_hunks = hunks(&file_diff, 3);
+        let mut selected: Vec<Range<usize>> = Vec::new();
+        let mut answer_all: Option<bool> = None;
+
+        println!("--- a/{}\n+++ b/{}", path.display(), path.display());
+        for (index, hunk) in file_hunks.iter().enumerate() {
+            let accept = match answer_all {
+                Some(accept) => accept,
+                None => {
+                    print!("{}", format_hunk(&file_diff, hunk.clone()));
+                    match prompt_hunk(index + 1, file_hunks.len())? {
+                        HunkAnswer::Yes => true,
+                        HunkAnswer::No => false,
+                        HunkAnswer::All => { answer_all = Some(true); true }
+                        HunkAnswer::None => { answer_all = Some(false); false }
+                        HunkAnswer::Quit => { quit = true; break; }
+                    }
+                }
+            };
+
+            if accept {
+                selected.push(hunk.clone());
             }
-            is_valid
-        })
-        .cloned()
-        .collect()
+        }
+
+        if selected.is_empty() {
+            continue;
+        }
+
+        let content = apply_hunks(&file_diff, &selected, old_content.ends_with('\n'), new_content.ends_with('\n'));
+        let hash = repo.create_object(&BlobObject::new(content.into_bytes()))?;
+        let mut new_entry = StagingEntry::new(hash, path);
+        new_entry.mode_type = entry.mode_type;
+        staging_area.update_or_create_entry(new_entry);
+    }
+
+    lock.write_all(&staging_area.serialize()?)?;
+    lock.commit()
+}
+
+/// Converte o conteúdo em texto, ou `None` se ele for binário ou não estiver em UTF-8
+fn patch_text(bytes: Vec<u8>) -> Option<String> {
+    if bytes.iter().take(BINARY_CHECK_LEN).any(|byte| *byte == 0) {
+        return None;
+    }
+
+    String::from_utf8(bytes).ok()
 }
 
-/// Filtra caminhos que são ignorados pelo .gitignore
-fn filter_ignored_files(repo: &Repository, paths: &Vec<PathBuf>) -> Vec<PathBuf> {
-    let repo_ignore = RGitIgnore::new(repo);
+fn prompt_hunk(index: usize, total: usize) -> Result<HunkAnswer, MinigitError> {
+    loop {
+        print!("({}/{}) Adicionar este trecho [y,n,a,d,q,?]? ", index, total);
+        std::io::stdout().flush()?;
+
+        let mut input = String::new();
+        if std::io::stdin().read_line(&mut input)? == 0 {
+            return Ok(HunkAnswer::Quit);
+        }
 
-    paths
-        .iter()
-        .filter(|path| {
-            let is_ignored = repo_ignore.check_ignore(path);
-            if is_ignored {
-                println!("Aviso: {:?} está sendo ignorado pelo .gitignore e não será adicionado", path);
+        match input.trim() {
+            "y" => return Ok(HunkAnswer::Yes),
+            "n" => return Ok(HunkAnswer::No),
+            "a" => return Ok(HunkAnswer::All),
+            "d" => return Ok(HunkAnswer::None),
+            "q" => return Ok(HunkAnswer::Quit),
+            _ => {
+                println!("y - adiciona este trecho");
+                println!("n - não adiciona este trecho");
+                println!("a - adiciona este e os demais trechos do arquivo");
+                println!("d - não adiciona este nem os demais trechos do arquivo");
+                println!("q - sai sem adicionar este nem os trechos restantes");
             }
-            !is_ignored
-        })
-        .cloned()
-        .collect()
-}
\ No newline at end of file
+        }
+    }
+}
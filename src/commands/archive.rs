@@ -0,0 +1,41 @@
+use std::{io::{BufWriter, Write}, path::Path};
+
+use crate::{
+    archive::{ArchiveFormat, ArchiveOptions},
+    error::MinigitError,
+    utils::{LockFile, find_current_repo},
+};
+
+/// Gera o arquivo da revisão na saída padrão ou em `output`
+///
+/// Sem `format`, o formato é deduzido da extensão de `output` (tar se não der para deduzir).
+pub fn cmd_archive(format: Option<ArchiveFormat>, prefix: String, revision: String, paths: Vec<String>, output: Option<String>) -> Result<(), MinigitError> {
+    let result = cmd_archive_result(format, prefix, revision, paths, output);
+    if let Err(e) = &result {
+        eprintln!("Erro no archive: {}", e);
+    }
+    result
+}
+
+fn cmd_archive_result(format: Option<ArchiveFormat>, prefix: String, revision: String, paths: Vec<String>, output: Option<String>) -> Result<(), MinigitError> {
+    let repo = find_current_repo()?;
+    let format = format
+        .or_else(|| output.as_deref().and_then(|output| ArchiveFormat::from_path(Path::new(output))))
+        .unwrap_or(ArchiveFormat::Tar);
+    let options = ArchiveOptions { format, prefix, revision, paths };
+
+    match output {
+        // O arquivo é gravado no lock e só aparece no lugar final se tudo der certo
+        Some(output) => {
+            let mut out = BufWriter::new(LockFile::acquire(Path::new(&output))?);
+            repo.archive(&options, &mut out)?;
+            out.into_inner().map_err(|err| err.into_error())?.commit()
+        }
+        None => {
+            let mut out = BufWriter::new(std::io::stdout().lock());
+            repo.archive(&options, &mut out)?;
+            out.flush()?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,485 @@
+use std::{collections::{HashMap, HashSet}, path::{Path, PathBuf}, process::Command};
+
+use crate::{
+    Repository,
+    commands::{checkout, log::{short_hash, subject}},
+    error::MinigitError,
+    objects::CommitObject,
+    utils::{LockFile, dates::format_commit_date, find_current_repo, resolve_revision, write_locked},
+};
+
+const BISECT_START: &str = "BISECT_START";
+const BISECT_BAD: &str = "BISECT_BAD";
+const BISECT_GOOD: &str = "BISECT_GOOD";
+const BISECT_SKIP: &str = "BISECT_SKIP";
+const BISECT_LOG: &str = "BISECT_LOG";
+
+/// Código de saída do script do `bisect run` que indica que o commit não pode ser testado
+const SKIP_EXIT_CODE: i32 = 125;
+
+/// Ações do comando `bisect`
+pub enum BisectAction {
+    /// Inicia a busca, opcionalmente já marcando um commit ruim e commits bons
+    Start { bad: Option<String>, good: Vec<String> },
+    /// Marca os commits (o HEAD por padrão) como bons
+    Good(Vec<String>),
+    /// Marca o commit (o HEAD por padrão) como ruim
+    Bad(Option<String>),
+    /// Marca os commits (o HEAD por padrão) como impossíveis de testar
+    Skip(Vec<String>),
+    /// Encerra a busca e volta para o commit original ou para o commit fornecido
+    Reset(Option<String>),
+    /// Exibe o registro da busca atual
+    Log,
+    /// Refaz a busca a partir de um registro salvo pelo `bisect log`
+    Replay(PathBuf),
+    /// Automatiza a busca com o código de saída do comando
+    Run(Vec<String>),
+}
+
+/// Como um commit foi marcado
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum BisectMark {
+    Good,
+    Bad,
+    Skip,
+}
+
+impl BisectMark {
+    fn name(&self) -> &'static str {
+        match self {
+            BisectMark::Good => "good",
+            BisectMark::Bad => "bad",
+            BisectMark::Skip => "skip",
+        }
+    }
+}
+
+/// Situação da busca depois de uma marcação
+pub enum BisectStep {
+    /// Ainda falta marcar um commit ruim e/ou um commit bom
+    Waiting { bad: bool, good: bool },
+    /// Próximo commit a ser testado
+    Next { hash: String, commit: CommitObject, remaining: usize, steps: u32 },
+    /// O primeiro commit ruim foi encontrado
+    Found { hash: String, commit: CommitObject },
+    /// Só sobraram commits pulados; o primeiro commit ruim é um deles
+    OnlySkipped(Vec<String>),
+}
+
+/// Commits marcados na busca, salvos nos arquivos `BISECT_*` da pasta `.minigit`
+#[derive(Default)]
+struct BisectState {
+    bad: Option<String>,
+    good: Vec<String>,
+    skip: Vec<String>,
+}
+
+impl BisectState {
+    fn load(repo: &Repository) -> Result<Self, MinigitError> {
+        Ok(BisectState {
+            bad: read_hashes(repo, BISECT_BAD)?.pop(),
+            good: read_hashes(repo, BISECT_GOOD)?,
+            skip: read_hashes(repo, BISECT_SKIP)?,
+        })
+    }
+
+    fn save(&self, repo: &Repository) -> Result<(), MinigitError> {
+        write_hashes(repo, BISECT_BAD, self.bad.iter())?;
+        write_hashes(repo, BISECT_GOOD, self.good.iter())?;
+        write_hashes(repo, BISECT_SKIP, self.skip.iter())
+    }
+
+    fn mark(&mut self, mark: BisectMark, hash: String) {
+        self.good.retain(|good| *good != hash);
+        self.skip.retain(|skip| *skip != hash);
+        match mark {
+            BisectMark::Good => self.good.push(hash),
+            BisectMark::Bad => self.bad = Some(hash),
+            BisectMark::Skip => self.skip.push(hash),
+        }
+    }
+}
+
+pub fn cmd_bisect(action: BisectAction) -> Result<(), MinigitError> {
+    let result = cmd_bisect_result(action);
+    if let Err(e) = &result {
+        eprintln!("Erro no bisect: {}", e);
+    }
+    result
+}
+
+fn cmd_bisect_result(action: BisectAction) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
+
+    match action {
+        BisectAction::Start { bad, good } => {
+            let step = start(&mut repo, bad.as_deref(), &good)?;
+            print_step(&step);
+        }
+        BisectAction::Good(revisions) => print_step(&mark(&mut repo, BisectMark::Good, &revisions)?),
+        BisectAction::Bad(revision) => print_step(&mark(&mut repo, BisectMark::Bad, &Vec::from_iter(revision))?),
+        BisectAction::Skip(revisions) => print_step(&mark(&mut repo, BisectMark::Skip, &revisions)?),
+        BisectAction::Reset(commit) => {
+            let target = reset(&mut repo, commit.as_deref())?;
+            println!("Bisect encerrado. HEAD agora em {}", target);
+        }
+        BisectAction::Log => {
+            ensure_bisect_in_progress(&repo)?;
+            print!("{}", std::fs::read_to_string(repo.minigitdir.join(BISECT_LOG))?);
+        }
+        BisectAction::Replay(log_path) => print_step(&replay(&mut repo, &log_path)?),
+        BisectAction::Run(command) => {
+            run(&mut repo, &command)?;
+            println!("bisect run concluído com sucesso");
+        }
+    }
+
+    Ok(())
+}
+
+/// Verifica se há uma busca em andamento
+pub fn is_in_progress(repo: &Repository) -> bool {
+    repo.minigitdir.join(BISECT_START).exists()
+}
+
+fn ensure_bisect_in_progress(repo: &Repository) -> Result<(), MinigitError> {
+    if !is_in_progress(repo) {
+        return Err(MinigitError::InvalidState("Não há um bisect em andamento. Use 'bisect start'".to_string()));
+    }
+    Ok(())
+}
+
+/// Inicia a busca, guardando onde o HEAD estava para que o `bisect reset` possa voltar para lá.
+/// Se um commit ruim e ao menos um bom forem fornecidos, já muda para o primeiro commit a ser testado.
+pub fn start(repo: &mut Repository, bad: Option<&str>, good: &[String]) -> Result<BisectStep, MinigitError> {
+    if is_in_progress(repo) {
+        return Err(MinigitError::InvalidState("Já há um bisect em andamento. Use 'bisect reset' para encerrá-lo".to_string()));
+    }
+
+    let mut state = BisectState::default();
+    let mut marks = Vec::new();
+    if let Some(bad) = bad {
+        marks.push((BisectMark::Bad, resolve_revision(repo, bad)?));
+    }
+    for good in good {
+        marks.push((BisectMark::Good, resolve_revision(repo, good)?));
+    }
+
+    let head = repo.get_head()?;
+    let original = head.strip_prefix("refs/heads/").unwrap_or(&head).to_string();
+    write_locked(&repo.minigitdir.join(BISECT_START), format!("{}\n", original))?;
+    append_log(repo, "minigit bisect start\n")?;
+
+    for (mark, hash) in marks {
+        record_mark(repo, &mut state, mark, hash)?;
+    }
+    state.save(repo)?;
+
+    advance(repo, &state)
+}
+
+/// Marca os commits e muda para o próximo commit a ser testado
+pub fn mark(repo: &mut Repository, mark: BisectMark, revisions: &[String]) -> Result<BisectStep, MinigitError> {
+    ensure_bisect_in_progress(repo)?;
+
+    let hashes = if revisions.is_empty() {
+        vec![repo.resolve_head()?]
+    } else {
+        revisions.iter().map(|revision| resolve_revision(repo, revision)).collect::<Result<Vec<_>, _>>()?
+    };
+
+    let mut state = BisectState::load(repo)?;
+    for hash in hashes {
+        record_mark(repo, &mut state, mark, hash)?;
+    }
+    state.save(repo)?;
+
+    advance(repo, &state)
+}
+
+/// Encerra a busca, voltando para o ponto de partida (ou para `commit`) e apagando os arquivos `BISECT_*`.
+/// Retorna a referência para a qual o HEAD foi mudado.
+pub fn reset(repo: &mut Repository, commit: Option<&str>) -> Result<String, MinigitError> {
+    ensure_bisect_in_progress(repo)?;
+
+    let target = match commit {
+        Some(commit) => resolve_revision(repo, commit)?,
+        None => std::fs::read_to_string(repo.minigitdir.join(BISECT_START))?.trim().to_string(),
+    };
+
+    checkout::checkout(repo, &target, false)?;
+    remove_state(repo)?;
+    Ok(target)
+}
+
+/// Refaz a busca registrada em `log_path`, executando suas linhas `minigit bisect ...`.
+/// Só muda de commit uma vez, ao final.
+pub fn replay(repo: &mut Repository, log_path: &Path) -> Result<BisectStep, MinigitError> {
+    let log = std::fs::read_to_string(log_path)?;
+
+    if is_in_progress(repo) {
+        reset(repo, None)?;
+    }
+
+    let mut state = BisectState::default();
+    let mut started = false;
+
+    for line in log.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#')) {
+        let invalid = || MinigitError::InvalidArgument(format!("Linha inválida no registro do bisect: '{}'", line));
+        let mut words = line.strip_prefix("minigit bisect ").ok_or_else(invalid)?.split_whitespace();
+
+        let mark = match words.next() {
+            Some("start") if !started => {
+                let head = repo.get_head()?;
+                let original = head.strip_prefix("refs/heads/").unwrap_or(&head).to_string();
+                write_locked(&repo.minigitdir.join(BISECT_START), format!("{}\n", original))?;
+                append_log(repo, "minigit bisect start\n")?;
+                started = true;
+                continue;
+            }
+            Some("good") if started => BisectMark::Good,
+            Some("bad") if started => BisectMark::Bad,
+            Some("skip") if started => BisectMark::Skip,
+            _ => return Err(invalid()),
+        };
+
+        for revision in words {
+            let hash = resolve_revision(repo, revision)?;
+            record_mark(repo, &mut state, mark, hash)?;
+        }
+    }
+
+    if !started {
+        return Err(MinigitError::InvalidArgument("O registro não contém 'minigit bisect start'".to_string()));
+    }
+
+    state.save(repo)?;
+    advance(repo, &state)
+}
+
+/// Executa `command` em cada commit escolhido até encontrar o primeiro commit ruim: código 0 marca o
+/// commit como bom, 125 como impossível de testar e de 1 a 127 como ruim. Outros códigos abortam a busca.
+///
+/// Um único argumento é interpretado pelo `sh`; vários são executados diretamente, como programa e argumentos.
+pub fn run(repo: &mut Repository, command: &[String]) -> Result<(String, CommitObject), MinigitError> {
+    ensure_bisect_in_progress(repo)?;
+
+    let mut step = next_step(repo, &BisectState::load(repo)?)?;
+
+    loop {
+        match step {
+            BisectStep::Found { hash, commit } => return Ok((hash, commit)),
+            BisectStep::Waiting { .. } => {
+                return Err(MinigitError::InvalidState("Marque um commit ruim e um bom antes do 'bisect run'".to_string()));
+            }
+            BisectStep::OnlySkipped(hashes) => {
+                return Err(MinigitError::InvalidState(format!(
+                    "Só sobraram commits pulados; o primeiro commit ruim pode ser qualquer um de: {}",
+                    hashes.join(" ")
+                )));
+            }
+            BisectStep::Next { .. } => {}
+        }
+
+        println!("executando '{}'", command.join(" "));
+        let status = match command {
+            [script] => Command::new("sh").arg("-c").arg(script).status()?,
+            [program, args @ ..] => Command::new(program).args(args).status()?,
+            [] => return Err(MinigitError::InvalidArgument("Forneça o comando do 'bisect run'".to_string())),
+        };
+
+        let bisect_mark = match status.code() {
+            Some(0) => BisectMark::Good,
+            Some(SKIP_EXIT_CODE) => BisectMark::Skip,
+            Some(1..=127) => BisectMark::Bad,
+            code => {
+                let code = code.map_or("sinal".to_string(), |code| code.to_string());
+                return Err(MinigitError::InvalidState(format!("O comando saiu com código {}; bisect run abortado", code)));
+            }
+        };
+
+        step = mark(repo, bisect_mark, &[])?;
+        print_step(&step);
+    }
+}
+
+/// Marca o commit no estado e registra a marcação no `BISECT_LOG`
+fn record_mark(repo: &Repository, state: &mut BisectState, mark: BisectMark, hash: String) -> Result<(), MinigitError> {
+    let commit = repo.get_commit(&hash)?;
+    append_log(repo, &format!(
+        "# {}: [{}] {}\nminigit bisect {} {}\n",
+        mark.name(), hash, subject(&commit.message), mark.name(), hash
+    ))?;
+    state.mark(mark, hash);
+    Ok(())
+}
+
+/// Calcula o próximo passo e, se houver um commit a ser testado, muda para ele com o HEAD destacado
+fn advance(repo: &mut Repository, state: &BisectState) -> Result<BisectStep, MinigitError> {
+    let step = next_step(repo, state)?;
+
+    match &step {
+        BisectStep::Next { hash, .. } => {
+            checkout::checkout(repo, hash, false)?;
+        }
+        BisectStep::Found { hash, commit } => {
+            append_log(repo, &format!("# first bad commit: [{}] {}\n", hash, subject(&commit.message)))?;
+        }
+        _ => {}
+    }
+
+    Ok(step)
+}
+
+/// Escolhe o próximo commit a ser testado entre os que ainda podem ser o primeiro commit ruim:
+/// os alcançáveis a partir do ruim que não são alcançáveis a partir de nenhum bom.
+///
+/// O escolhido é o que divide esse conjunto mais ao meio, considerando quantos candidatos são
+/// seus ancestrais. Commits pulados não são escolhidos.
+fn next_step(repo: &Repository, state: &BisectState) -> Result<BisectStep, MinigitError> {
+    let Some(bad) = &state.bad else {
+        return Ok(BisectStep::Waiting { bad: true, good: state.good.is_empty() });
+    };
+    if state.good.is_empty() {
+        return Ok(BisectStep::Waiting { bad: false, good: true });
+    }
+
+    let mut commits = HashMap::new();
+    let excluded = ancestors(repo, &state.good, &HashSet::new(), &mut commits)?;
+    let candidates = ancestors(repo, std::slice::from_ref(bad), &excluded, &mut commits)?;
+
+    if candidates.is_empty() {
+        return Err(MinigitError::InvalidState(format!(
+            "Os commits bons e ruins são inconsistentes: o commit ruim {} é um commit bom ou ancestral de um deles",
+            short_hash(bad)
+        )));
+    }
+
+    if candidates.len() == 1 {
+        return Ok(BisectStep::Found { hash: bad.clone(), commit: commits[bad].clone() });
+    }
+
+    let skipped: HashSet<&String> = state.skip.iter().collect();
+    let mut testable: Vec<&String> = candidates.iter()
+        .filter(|hash| *hash != bad && !skipped.contains(hash))
+        .collect();
+
+    if testable.is_empty() {
+        let mut remaining: Vec<&String> = candidates.iter().collect();
+        remaining.sort_by_key(|hash| (std::cmp::Reverse(commits[*hash].timestamp), hash.to_string()));
+        return Ok(BisectStep::OnlySkipped(remaining.into_iter().cloned().collect()));
+    }
+
+    // Ordem determinística para desempatar: do mais recente para o mais antigo
+    testable.sort_by_key(|hash| (std::cmp::Reverse(commits[*hash].timestamp), hash.to_string()));
+
+    let total = candidates.len();
+    let mut best: Option<(&String, usize, usize)> = None;
+    for hash in testable {
+        let weight = ancestors(repo, std::slice::from_ref(hash), &excluded, &mut commits)?.len();
+        let distance = weight.min(total - weight);
+        if best.is_none_or(|(_, _, best_distance)| distance > best_distance) {
+            best = Some((hash, weight, distance));
+        }
+    }
+
+    let (hash, weight, _) = best.expect("há ao menos um commit testável");
+    // Se o escolhido for ruim, sobram seus ancestrais além dele; se for bom, o resto menos o commit ruim
+    let remaining = (weight - 1).max(total - weight - 1);
+    let steps = usize::BITS - remaining.leading_zeros();
+
+    Ok(BisectStep::Next { hash: hash.clone(), commit: commits[hash].clone(), remaining, steps })
+}
+
+/// Commits alcançáveis a partir de `starts` (incluindo eles) sem passar pelos commits de `excluded`
+fn ancestors(
+    repo: &Repository,
+    starts: &[String],
+    excluded: &HashSet<String>,
+    commits: &mut HashMap<String, CommitObject>,
+) -> Result<HashSet<String>, MinigitError> {
+    let mut visited = HashSet::new();
+    let mut queue: Vec<String> = starts.to_vec();
+
+    while let Some(hash) = queue.pop() {
+        if excluded.contains(&hash) || !visited.insert(hash.clone()) {
+            continue;
+        }
+        if !commits.contains_key(&hash) {
+            commits.insert(hash.clone(), repo.get_commit(&hash)?);
+        }
+        queue.extend(commits[&hash].parent.iter().cloned());
+    }
+
+    Ok(visited)
+}
+
+fn print_step(step: &BisectStep) {
+    match step {
+        BisectStep::Waiting { bad: true, good: true } => println!("Aguardando um commit ruim e um bom"),
+        BisectStep::Waiting { bad: true, .. } => println!("Aguardando um commit ruim"),
+        BisectStep::Waiting { .. } => println!("Aguardando um commit bom"),
+        BisectStep::Next { hash, commit, remaining, steps } => {
+            println!("Bisect: restam {} revisões para testar depois desta (aproximadamente {} passos)", remaining, steps);
+            println!("[{}] {}", hash, subject(&commit.message));
+        }
+        BisectStep::Found { hash, commit } => print_found(hash, commit),
+        BisectStep::OnlySkipped(hashes) => {
+            println!("Só sobraram commits pulados. O primeiro commit ruim pode ser qualquer um destes:");
+            for hash in hashes {
+                println!("{}", hash);
+            }
+        }
+    }
+}
+
+fn print_found(hash: &str, commit: &CommitObject) {
+    println!("{} é o primeiro commit ruim", hash);
+    println!("commit {}", hash);
+    println!("Autor: {}", commit.author);
+    println!("Data:  {}", format_commit_date(commit.timestamp));
+    println!();
+    println!("    {}", subject(&commit.message));
+}
+
+/// Acrescenta `text` ao log do bisect, lendo o conteúdo atual com o lock já adquirido
+fn append_log(repo: &Repository, text: &str) -> Result<(), MinigitError> {
+    let path = repo.minigitdir.join(BISECT_LOG);
+    let mut lock = LockFile::acquire(&path)?;
+
+    if path.exists() {
+        lock.write_all(&std::fs::read(&path)?)?;
+    }
+    lock.write_all(text.as_bytes())?;
+    lock.commit()
+}
+
+fn read_hashes(repo: &Repository, file_name: &str) -> Result<Vec<String>, MinigitError> {
+    let path = repo.minigitdir.join(file_name);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect())
+}
+
+fn write_hashes<'a>(repo: &Repository, file_name: &str, hashes: impl Iterator<Item = &'a String>) -> Result<(), MinigitError> {
+    let text: String = hashes.map(|hash| format!("{}\n", hash)).collect();
+    write_locked(&repo.minigitdir.join(file_name), text)
+}
+
+fn remove_state(repo: &Repository) -> Result<(), MinigitError> {
+    for file_name in [BISECT_START, BISECT_BAD, BISECT_GOOD, BISECT_SKIP, BISECT_LOG] {
+        let path = repo.minigitdir.join(file_name);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
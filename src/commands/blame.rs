@@ -0,0 +1,134 @@
+use std::{collections::HashSet, ops::RangeInclusive};
+
+use crate::{
+    commands::log::{split_author, subject},
+    error::MinigitError,
+    history::{BlameLine, BlameOptions},
+    utils::{dates::{commit_datetime, format_commit_date}, find_current_repo, pathspec::relative_to_repository},
+};
+
+/// Largura da coluna do hash abreviado
+const HASH_WIDTH: usize = 8;
+
+/// Argumentos do comando `blame`
+///
+/// ## Atributos
+/// - `file` - Arquivo anotado, relativo ao diretório atual
+/// - `options` - Revisão e se mudanças só de espaços são ignoradas
+/// - `lines` - Intervalo `-L` ainda não interpretado (`início,fim`, `início,+quantidade`, `início,` ou `,fim`)
+/// - `porcelain` - Exibe as linhas no formato estável para scripts
+pub struct BlameArgs {
+    pub file: String,
+    pub options: BlameOptions,
+    pub lines: Option<String>,
+    pub porcelain: bool,
+}
+
+pub fn cmd_blame(args: BlameArgs) -> Result<(), MinigitError> {
+    let result = cmd_blame_result(args);
+    if let Err(e) = &result {
+        eprintln!("Erro no blame: {}", e);
+    }
+    result
+}
+
+fn cmd_blame_result(args: BlameArgs) -> Result<(), MinigitError> {
+    let repo = find_current_repo()?;
+    let path = relative_to_repository(&repo, &args.file)?.to_string_lossy().to_string();
+
+    let mut lines = repo.blame(&path, &args.options)?;
+
+    if let Some(range) = &args.lines {
+        let range = parse_line_range(range, lines.len())?;
+        lines.retain(|line| range.contains(&line.final_line));
+    }
+
+    if args.porcelain {
+        print!("{}", format_porcelain(&lines));
+    } else {
+        print!("{}", format_human(&lines, &path));
+    }
+
+    Ok(())
+}
+
+/// Interpreta o intervalo do `-L`, validando que ele está dentro do arquivo
+fn parse_line_range(range: &str, line_count: usize) -> Result<RangeInclusive<usize>, MinigitError> {
+    let invalid = || MinigitError::InvalidArgument(format!("Intervalo de linhas inválido: '{}'", range));
+    let parse = |value: &str| value.trim().parse::<usize>().map_err(|_| invalid());
+
+    let (start, end) = range.split_once(',').unwrap_or((range, ""));
+    let start = if start.trim().is_empty() { 1 } else { parse(start)? };
+    let end = match end.trim() {
+        "" => line_count,
+        count if count.starts_with('+') => (start + parse(&count[1..])?).saturating_sub(1),
+        end => parse(end)?,
+    };
+
+    if start == 0 || start > end {
+        return Err(invalid());
+    }
+    if start > line_count {
+        return Err(MinigitError::InvalidArgument(format!("O arquivo tem só {} linhas", line_count)));
+    }
+
+    Ok(start..=end.min(line_count))
+}
+
+/// Formato `<hash> [caminho] (<autor> <data> <linha>) <conteúdo>`. Commits sem pais são marcados com `^`
+/// e o caminho só aparece se alguma linha veio do arquivo com outro nome
+fn format_human(lines: &[BlameLine], path: &str) -> String {
+    let show_path = lines.iter().any(|line| line.path != path);
+    let path_width = lines.iter().map(|line| line.path.chars().count()).max().unwrap_or(0);
+    let author_width = lines.iter().map(|line| split_author(&line.commit.author).0.chars().count()).max().unwrap_or(0);
+    let line_width = lines.iter().map(|line| line.final_line.to_string().len()).max().unwrap_or(0);
+
+    let mut text = String::new();
+    for line in lines {
+        // Commits sem pais perdem um caractere do hash para o '^', mantendo a coluna alinhada
+        if line.boundary {
+            text.push_str(&format!("^{}", &line.hash[..HASH_WIDTH - 1]));
+        } else {
+            text.push_str(&line.hash[..HASH_WIDTH]);
+        }
+        if show_path {
+            text.push_str(&format!(" {:<width$}", line.path, width = path_width));
+        }
+        text.push_str(&format!(
+            " ({:<author_width$} {} {:>line_width$}) {}\n",
+            split_author(&line.commit.author).0,
+            format_commit_date(line.commit.timestamp),
+            line.final_line,
+            line.content,
+        ));
+    }
+
+    text
+}
+
+/// Formato para scripts: para cada linha, `<hash> <linha original> <linha final>`, os dados do commit
+/// na primeira vez em que ele aparece, `filename <caminho>` e o conteúdo precedido de um tab
+fn format_porcelain(lines: &[BlameLine]) -> String {
+    let mut seen = HashSet::new();
+    let mut text = String::new();
+
+    for line in lines {
+        text.push_str(&format!("{} {} {}\n", line.hash, line.original_line, line.final_line));
+
+        if seen.insert(&line.hash) {
+            let (name, email) = split_author(&line.commit.author);
+            text.push_str(&format!("author {}\n", name));
+            text.push_str(&format!("author-mail <{}>\n", email));
+            text.push_str(&format!("author-time {}\n", commit_datetime(line.commit.timestamp).timestamp()));
+            text.push_str(&format!("summary {}\n", subject(&line.commit.message)));
+            if line.boundary {
+                text.push_str("boundary\n");
+            }
+        }
+
+        text.push_str(&format!("filename {}\n", line.path));
+        text.push_str(&format!("\t{}\n", line.content));
+    }
+
+    text
+}
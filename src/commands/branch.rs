@@ -1,64 +1,346 @@
-use crate::{Repository, checks::{ensure_no_merge_in_progress, ensure_no_rebase_in_progress}, utils::{find_current_repo, is_valid_sha1}};
+use crate::{
+    Repository,
+    checks::{ensure_no_merge_in_progress, ensure_no_rebase_in_progress},
+    commands::{log::{short_hash, subject}, merge::is_ancestor},
+    error::MinigitError,
+    utils::{BranchInfo, find_current_repo, is_valid_sha1, list_branches, resolve_head_or_branch_name, resolve_revision, terminal::{self, CYAN, GREEN}, validate_ref_components, write_locked},
+};
 
-pub fn cmd_branch(branch_name: String, delete: bool) {
-    match cmd_branch_result(branch_name, delete) {
-        Ok(_) => {},
+/// Ação do comando `branch`
+pub enum BranchAction {
+    /// Lista as branches, opcionalmente só as mescladas (`merged`) ou não mescladas (`no_merged`) em uma revisão
+    List { verbose: bool, porcelain: bool, merged: Option<String>, no_merged: Option<String> },
+    /// Cria a branch no ponto de partida (HEAD por padrão)
+    Create { name: String, start_point: Option<String> },
+    /// Deleta a branch. Sem `force`, ela precisa estar mesclada no HEAD
+    Delete { name: String, force: bool },
+    /// Renomeia a branch `old` (a atual por padrão) para `new`
+    Rename { old: Option<String>, new: String, force: bool },
+    /// Copia a branch `old` (a atual por padrão) para `new`
+    Copy { old: Option<String>, new: String },
+    /// Define a branch `upstream` como upstream de `branch` (a atual por padrão)
+    SetUpstream { branch: Option<String>, upstream: String },
+}
+
+pub fn cmd_branch(action: BranchAction) -> Result<(), MinigitError> {
+    match cmd_branch_result(action) {
+        Ok(_) => Ok(()),
         Err(err) => {
             println!("{}", err);
+            Err(err)
         }
     }
 }
 
-fn cmd_branch_result(branch_name: String, delete: bool) -> Result<(), String> {
-    if branch_name == Repository::HEAD || is_valid_sha1(&branch_name) || branch_name.ends_with("index") {
-        return Err("Nome de branch não pode ser 'HEAD', um hash SHA-1 válido ou terminar com 'index'".to_string());
-    }
+fn cmd_branch_result(action: BranchAction) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
 
-    let mut repo = find_current_repo()
-        .ok_or("Diretório não está dentro um repositório minigit")?;
+    if let BranchAction::List { verbose, porcelain, merged, no_merged } = action {
+        return list_branches_command(&repo, verbose, porcelain, merged, no_merged);
+    }
 
     ensure_no_merge_in_progress(&repo)?;
     ensure_no_rebase_in_progress(&repo)?;
 
-    if delete {
-        delete_branch(&branch_name, &mut repo)?;
+    match action {
+        BranchAction::Create { name, start_point } => {
+            create_branch(&mut repo, &name, start_point.as_deref())?;
+        }
+        BranchAction::Delete { name, force } => {
+            let commit = delete_branch(&mut repo, &name, force)?;
+            println!("Branch '{}' deletada (era {}).", name, short_hash(&commit));
+        }
+        BranchAction::Rename { old, new, force } => {
+            rename_branch(&mut repo, old.as_deref(), &new, force)?;
+        }
+        BranchAction::Copy { old, new } => {
+            copy_branch(&mut repo, old.as_deref(), &new)?;
+        }
+        BranchAction::SetUpstream { branch, upstream } => {
+            let branch = set_upstream(&mut repo, branch.as_deref(), &upstream)?;
+            println!("A branch '{}' agora segue '{}'.", branch, upstream);
+        }
+        BranchAction::List { .. } => {}
+    }
+
+    Ok(())
+}
+
+/// Lista as branches. No formato `--porcelain` cada linha é `<*| > <nome> <hash>`,
+/// onde `*` marca a branch atual e o hash fica vazio para branches sem commits.
+///
+/// Com `verbose`, cada linha também exibe o hash abreviado, a upstream e o assunto do último commit.
+fn list_branches_command(
+    repo: &Repository,
+    verbose: bool,
+    porcelain: bool,
+    merged: Option<String>,
+    no_merged: Option<String>,
+) -> Result<(), MinigitError> {
+    let mut branches = list_branches(repo)?;
+
+    if let Some(revision) = merged {
+        let target = resolve_revision(repo, &revision)?;
+        branches = filter_merged(repo, branches, &target, true)?;
+    }
+    if let Some(revision) = no_merged {
+        let target = resolve_revision(repo, &revision)?;
+        branches = filter_merged(repo, branches, &target, false)?;
+    }
+
+    let name_width = branches.iter().map(|branch| branch.name.len()).max().unwrap_or_default();
+
+    for branch in branches {
+        let marker = if branch.current { "*" } else { " " };
+
+        if porcelain {
+            println!("{} {} {}", marker, branch.name, branch.commit);
+            continue;
+        }
+
+        let padded_name = format!("{:width$}", branch.name, width = if verbose { name_width } else { 0 });
+        let name = if branch.current { terminal::paint(&padded_name, GREEN) } else { padded_name };
+
+        if !verbose {
+            println!("{} {}", marker, name);
+            continue;
+        }
+
+        let upstream = match upstream_of(repo, &branch.name) {
+            Some(upstream) => format!("[{}] ", terminal::paint(&upstream, CYAN)),
+            None => String::new(),
+        };
+        let summary = if branch.commit.is_empty() {
+            "(sem commits)".to_string()
+        } else {
+            subject(&repo.get_commit(&branch.commit)?.message).to_string()
+        };
+
+        println!("{} {} {} {}{}", marker, name, short_hash(&branch.commit), upstream, summary);
+    }
+
+    Ok(())
+}
+
+/// Mantém só as branches mescladas (`merged`) ou não mescladas (`!merged`) no commit `target`
+fn filter_merged(repo: &Repository, branches: Vec<BranchInfo>, target: &str, merged: bool) -> Result<Vec<BranchInfo>, MinigitError> {
+    let mut result = Vec::new();
+
+    for branch in branches {
+        if is_merged(repo, &branch.commit, target)? == merged {
+            result.push(branch);
+        }
+    }
+
+    Ok(result)
+}
+
+/// Verifica se o commit já faz parte da história de `target`. Branches sem commits sempre estão mescladas
+fn is_merged(repo: &Repository, commit: &str, target: &str) -> Result<bool, MinigitError> {
+    if commit.is_empty() {
+        return Ok(true);
+    }
+    if target.is_empty() {
+        return Ok(false);
+    }
+
+    is_ancestor(repo, commit, target)
+}
+
+/// Retorna o nome da branch atual, ou `MinigitError::InvalidState` se o HEAD estiver destacado
+fn current_branch(repo: &Repository) -> Result<String, MinigitError> {
+    repo.get_head()?
+        .strip_prefix("refs/heads/")
+        .map(|name| name.to_string())
+        .ok_or_else(|| MinigitError::InvalidState("HEAD destacado não aponta para nenhuma branch".to_string()))
+}
+
+/// Retorna o hash do commit da branch, ou `MinigitError::InvalidRef` se ela não existir
+fn branch_commit(repo: &Repository, branch_name: &str) -> Result<String, MinigitError> {
+    resolve_head_or_branch_name(branch_name, repo)?
+        .filter(|_| branch_name != Repository::HEAD)
+        .ok_or_else(|| MinigitError::InvalidRef(format!("A branch '{}' não existe", branch_name)))
+}
+
+/// Cria a branch a partir da revisão `start_point` (o HEAD por padrão)
+pub fn create_branch(repo: &mut Repository, branch_name: &str, start_point: Option<&str>) -> Result<(), MinigitError> {
+    validate_branch_name(branch_name)?;
+
+    let commit = match start_point {
+        Some(revision) => resolve_revision(repo, revision)?,
+        None => repo.resolve_head()?,
+    };
+    create_branch_at(repo, branch_name, &commit)
+}
+
+/// Deleta a branch, retornando o hash do commit para o qual ela apontava
+///
+/// Sem `force`, retorna `MinigitError::InvalidState` se a branch tiver commits que não estão no HEAD.
+pub fn delete_branch(repo: &mut Repository, branch_name: &str, force: bool) -> Result<String, MinigitError> {
+    let commit = branch_commit(repo, branch_name)?;
+
+    if current_branch(repo).ok().as_deref() == Some(branch_name) {
+        return Err(MinigitError::InvalidState("Não é possível deletar a branch atualmente ativa".to_string()));
+    }
+
+    if !force && !is_merged(repo, &commit, &repo.resolve_head()?)? {
+        return Err(MinigitError::InvalidState(format!("A branch '{}' não foi totalmente mesclada. Use -D para deletá-la mesmo assim", branch_name)));
+    }
+
+    remove_branch_ref(repo, branch_name)?;
+    remove_upstream(repo, branch_name)?;
+    Ok(commit)
+}
+
+/// Renomeia a branch `old_name` (a atual por padrão), levando a sua upstream
+///
+/// Sem `force`, retorna `MinigitError::AlreadyExists` se já existir uma branch com o novo nome.
+/// Se a branch renomeada for a atual, o HEAD passa a apontar para o novo nome.
+pub fn rename_branch(repo: &mut Repository, old_name: Option<&str>, new_name: &str, force: bool) -> Result<(), MinigitError> {
+    let current = current_branch(repo).ok();
+    let old_name = match old_name {
+        Some(name) => name.to_string(),
+        None => current_branch(repo)?,
+    };
+
+    validate_branch_name(new_name)?;
+    let commit = branch_commit(repo, &old_name)?;
+
+    if old_name == new_name {
         return Ok(());
     }
 
-    create_branch(&branch_name, &mut repo)?;
+    replace_branch(repo, new_name, &commit, force, current.as_deref())?;
+    copy_upstream(repo, &old_name, new_name)?;
+    remove_branch_ref(repo, &old_name)?;
+    remove_upstream(repo, &old_name)?;
+
+    if current.as_deref() == Some(old_name.as_str()) {
+        write_locked(&repo.head_path, format!("ref: refs/heads/{}", new_name))?;
+    }
 
     Ok(())
 }
 
-fn delete_branch(branch_name: &String, repo: &mut Repository) -> Result<(), String> {
-    let branch_index = repo.refs_heads_path.join(branch_name).join(Repository::INDEX);
+/// Copia a branch `old_name` (a atual por padrão) e a sua upstream para `new_name`
+pub fn copy_branch(repo: &mut Repository, old_name: Option<&str>, new_name: &str) -> Result<(), MinigitError> {
+    let old_name = match old_name {
+        Some(name) => name.to_string(),
+        None => current_branch(repo)?,
+    };
+
+    validate_branch_name(new_name)?;
+    let commit = branch_commit(repo, &old_name)?;
+
+    create_branch_at(repo, new_name, &commit)?;
+    copy_upstream(repo, &old_name, new_name)
+}
+
+/// Faz `branch_name` (a atual por padrão) seguir a branch `upstream`, retornando o nome da branch
+///
+/// A upstream é guardada na configuração como `branch.<nome>.remote` (`.`, o próprio repositório)
+/// e `branch.<nome>.merge` (a referência da upstream).
+pub fn set_upstream(repo: &mut Repository, branch_name: Option<&str>, upstream: &str) -> Result<String, MinigitError> {
+    let branch_name = match branch_name {
+        Some(name) => name.to_string(),
+        None => current_branch(repo)?,
+    };
+
+    branch_commit(repo, &branch_name)?;
+    branch_commit(repo, upstream)?;
+
+    if branch_name == upstream {
+        return Err(MinigitError::InvalidArgument(format!("A branch '{}' não pode seguir ela mesma", branch_name)));
+    }
+
+    repo.update_config(format!("branch.{}.remote", branch_name), ".".to_string())?;
+    repo.update_config(format!("branch.{}.merge", branch_name), format!("refs/heads/{}", upstream))?;
+    Ok(branch_name)
+}
 
-    if !branch_index.exists() {
-        return Err("Branch não existe".to_string());
+/// Retorna o nome da branch upstream de `branch_name`, se houver
+pub fn upstream_of(repo: &Repository, branch_name: &str) -> Option<String> {
+    repo.config.get(&format!("branch.{}.merge", branch_name))
+        .and_then(|reference| reference.strip_prefix("refs/heads/"))
+        .map(|name| name.to_string())
+}
+
+fn copy_upstream(repo: &mut Repository, from: &str, to: &str) -> Result<(), MinigitError> {
+    for key in ["remote", "merge"] {
+        if let Some(value) = repo.config.get(&format!("branch.{}.{}", from, key)).cloned() {
+            repo.update_config(format!("branch.{}.{}", to, key), value)?;
+        }
     }
+    Ok(())
+}
+
+/// Remove a seção `[branch "<nome>"]` inteira da configuração local
+fn remove_upstream(repo: &mut Repository, branch_name: &str) -> Result<(), MinigitError> {
+    repo.remove_config_section(&format!("branch.{}", branch_name))
+}
 
-    let head_ref = repo.get_head();
-    let head_branch_index = repo.minigitdir.join(head_ref).join(Repository::INDEX);
+/// Cria `branch_name` no commit, sobrescrevendo a branch existente com `force` (exceto a atual)
+fn replace_branch(repo: &mut Repository, branch_name: &str, commit: &str, force: bool, current: Option<&str>) -> Result<(), MinigitError> {
+    let exists = resolve_head_or_branch_name(branch_name, repo)?.is_some();
 
-    if head_branch_index == branch_index {
-        return Err("Não é possível deletar a branch atualmente ativa".to_string());
+    if exists && force {
+        if current == Some(branch_name) {
+            return Err(MinigitError::InvalidState("Não é possível sobrescrever a branch atualmente ativa".to_string()));
+        }
+        remove_branch_ref(repo, branch_name)?;
+    }
+
+    create_branch_at(repo, branch_name, commit)
+}
+
+/// Apaga o arquivo da referência da branch e as pastas que ficarem vazias em `refs/heads`
+fn remove_branch_ref(repo: &Repository, branch_name: &str) -> Result<(), MinigitError> {
+    let branch_index = repo.refs_heads_path.join(branch_name).join(Repository::INDEX);
+    std::fs::remove_file(&branch_index)?;
+
+    let mut parent = branch_index.parent();
+    while let Some(dir) = parent {
+        if dir == repo.refs_heads_path.as_path() || !dir.starts_with(&repo.refs_heads_path) || std::fs::remove_dir(dir).is_err() {
+            break;
+        }
+        parent = dir.parent();
     }
 
-    std::fs::remove_file(branch_index).map_err(|_| "Erro ao deletar a branch".to_string())?;
     Ok(())
 }
 
-fn create_branch(branch_name: &String, repo: &mut Repository) -> Result<(), String> {
+/// Cria a branch apontando para o commit fornecido (vazio para uma branch sem commits)
+///
+/// Retorna `MinigitError::AlreadyExists` se a branch já existir.
+pub fn create_branch_at(repo: &mut Repository, branch_name: &str, commit_id: &str) -> Result<(), MinigitError> {
     let branch_index = repo.refs_heads_path.join(branch_name).join(Repository::INDEX);
 
     if branch_index.exists() {
-        return Err("Branch já existe".to_string());
+        return Err(MinigitError::AlreadyExists(format!("A branch '{}' já existe", branch_name)));
     }
 
-    let head_commit = repo.resolve_head();
+    if let Some(parent) = branch_index.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    write_locked(&branch_index, commit_id)?;
 
-    std::fs::create_dir_all(branch_index.parent().unwrap()).unwrap();
-    std::fs::write(branch_index, head_commit).unwrap();
+    Ok(())
+}
+
+/// Garante que o nome pode ser usado como branch
+///
+/// Retorna `MinigitError::InvalidArgument` se o nome for 'HEAD', um hash SHA-1 válido, terminar com 'index',
+/// começar com '-', tiver espaços, '..', barras invertidas, caracteres de controle ou componentes vazios,
+/// '.' ou 'index'.
+pub fn validate_branch_name(branch_name: &str) -> Result<(), MinigitError> {
+    if branch_name == Repository::HEAD || is_valid_sha1(branch_name) || branch_name.ends_with("index") {
+        return Err(MinigitError::InvalidArgument("Nome de branch não pode ser 'HEAD', um hash SHA-1 válido ou terminar com 'index'".to_string()));
+    }
+
+    let has_invalid_component = validate_ref_components(branch_name).is_err();
+    if branch_name.starts_with('-') || branch_name.contains(char::is_whitespace) || branch_name.contains("..") || has_invalid_component {
+        return Err(MinigitError::InvalidArgument(format!("'{}' não é um nome de branch válido", branch_name.escape_debug())));
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
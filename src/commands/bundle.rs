@@ -0,0 +1,77 @@
+use std::path::Path;
+
+use crate::{
+    bundle::RefUpdateStatus,
+    commands::log::short_hash,
+    error::MinigitError,
+    utils::find_current_repo,
+};
+
+/// Ações do comando `bundle`
+pub enum BundleAction {
+    /// Grava no arquivo os commits das revisões; com `all`, de todas as branches
+    Create { file: String, revisions: Vec<String>, all: bool },
+    /// Verifica o bundle sem alterar o repositório
+    Verify(String),
+    /// Importa o bundle; com `force`, também move branches que não avançam
+    Unbundle { file: String, force: bool },
+}
+
+pub fn cmd_bundle(action: BundleAction) -> Result<(), MinigitError> {
+    let result = cmd_bundle_result(action);
+    if let Err(e) = &result {
+        eprintln!("Erro no bundle: {}", e);
+    }
+    result
+}
+
+fn cmd_bundle_result(action: BundleAction) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
+
+    match action {
+        BundleAction::Create { file, revisions, all } => {
+            let summary = repo.create_bundle(Path::new(&file), &revisions, all)?;
+            println!("Bundle '{}' criado com {} commit(s) e {} objeto(s).", file, summary.commits, summary.objects);
+        }
+        BundleAction::Verify(file) => {
+            let summary = repo.verify_bundle(Path::new(&file))?;
+
+            println!("O bundle contém {} referência(s):", summary.header.refs.len());
+            for reference in &summary.header.refs {
+                println!("{} {}", reference.commit, reference.name);
+            }
+            if summary.header.prerequisites.is_empty() {
+                println!("O bundle tem o histórico completo e não exige nenhum commit.");
+            } else {
+                println!("O bundle exige {} commit(s):", summary.header.prerequisites.len());
+                for prerequisite in &summary.header.prerequisites {
+                    println!("{} {}", prerequisite.commit, prerequisite.subject);
+                }
+            }
+            println!("'{}' está ok ({} commit(s), {} objeto(s)).", file, summary.commits, summary.objects);
+        }
+        BundleAction::Unbundle { file, force } => {
+            let updates = repo.unbundle(Path::new(&file), force)?;
+
+            let mut rejected = 0;
+            for update in &updates {
+                match &update.status {
+                    RefUpdateStatus::Created => println!(" * [nova branch]     {} -> {}", update.branch, short_hash(&update.new)),
+                    RefUpdateStatus::FastForward => println!("   {}..{}  {}", short_hash(&update.old), short_hash(&update.new), update.branch),
+                    RefUpdateStatus::Forced => println!(" + {}...{} {} (forçada)", short_hash(&update.old), short_hash(&update.new), update.branch),
+                    RefUpdateStatus::UpToDate => println!(" = [sem alterações] {}", update.branch),
+                    RefUpdateStatus::Rejected(reason) => {
+                        rejected += 1;
+                        println!(" ! [recusada]        {} ({})", update.branch, reason);
+                    }
+                }
+            }
+
+            if rejected > 0 {
+                return Err(MinigitError::InvalidState(format!("{} branch(es) do bundle não foram atualizadas", rejected)));
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -1,43 +1,129 @@
-use std::fs;
-use crate::utils::{find_current_repo, is_valid_sha1};
+use std::io::{BufRead, Write};
 
-pub fn cmd_cat_file(hash: &String){
-    match cat_file_command(hash) {
-        Ok(_) => {},
+use serde_json::json;
+
+use crate::{Repository, error::MinigitError, objects::{MODE_TREE, RGitObjectTypes, parse_mode}, utils::{find_current_repo, resolve_object, terminal}};
+
+/// O que o `cat-file` exibe sobre o objeto
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum CatFileMode {
+    /// Tipo, tamanho e conteúdo sem interpretação
+    #[default]
+    Dump,
+    /// Tipo, tamanho e conteúdo interpretado, em JSON
+    Json,
+    /// Só o tipo (`-t`)
+    Type,
+    /// Só o tamanho do conteúdo em bytes (`-s`)
+    Size,
+    /// O conteúdo formatado de acordo com o tipo (`-p`)
+    Pretty,
+    /// Nada; só o código de saída indica se o objeto existe (`-e`)
+    Exists,
+    /// Lê especificações de objetos da entrada padrão e exibe o cabeçalho e o conteúdo de cada um (`--batch`)
+    Batch,
+}
+
+pub fn cmd_cat_file(object: Option<String>, mode: CatFileMode) -> Result<(), MinigitError> {
+    match cat_file_command(object.as_deref().unwrap_or_default(), mode) {
+        Ok(_) => Ok(()),
+        // Como no git, o -e não exibe nada: o código de saída 1 indica que o objeto não existe
+        Err(_) if mode == CatFileMode::Exists => std::process::exit(1),
         Err(err) => {
             println!("{}", err);
+            Err(err)
         }
     }
 }
 
-fn cat_file_command(hash: &String) -> Result<(), String> {
-    let repo = find_current_repo().ok_or("Não é um repositório minigit")?;
+fn cat_file_command(spec: &str, mode: CatFileMode) -> Result<(), MinigitError> {
+    let repo = find_current_repo()?;
 
-    if !is_valid_sha1(hash) {
-        return Err("Hash SHA-1 inválido".to_string());
+    if mode == CatFileMode::Batch {
+        return print_batch(&repo);
     }
 
-    let (dir, file_name) = hash.split_at(2);
-    let path = repo.minigitdir.join("objects").join(dir).join(file_name);
+    let hash = resolve_object(&repo, spec)?;
+    let (object_type, object_content) = repo.get_raw_object(&hash)?;
 
-    if !path.exists() {
-        return Err("Objeto não encontrado".to_string());
+    match mode {
+        CatFileMode::Dump => {
+            println!("Tipo de objeto: {}", object_type);
+            println!("Tamanho do conteúdo: {} bytes", object_content.len());
+            println!("Conteúdo: \n{}", String::from_utf8_lossy(&object_content));
+        }
+        CatFileMode::Json => print_object_json(&repo, &hash, object_content.len())?,
+        CatFileMode::Type => println!("{}", object_type),
+        CatFileMode::Size => println!("{}", object_content.len()),
+        CatFileMode::Pretty => print_pretty(&repo, &hash)?,
+        CatFileMode::Exists | CatFileMode::Batch => {}
     }
 
-    let raw_object = fs::read(&path).unwrap();
-    let space_pos = raw_object.iter().position(|b| *b == b' ').unwrap();
-    let (object_type, remainder) = raw_object.split_at(space_pos);
+    Ok(())
+}
 
-    let null_pos = remainder.iter().position(|b| *b == b'\0').unwrap();
-    let (_size_bytes, object_content) = remainder.split_at(null_pos + 1);
+/// Para cada linha da entrada padrão, exibe `<hash> <tipo> <tamanho>`, o conteúdo e uma quebra de linha,
+/// ou `<especificação> missing` se o objeto não existir
+fn print_batch(repo: &Repository) -> Result<(), MinigitError> {
+    let mut stdout = std::io::stdout().lock();
 
-    let object_type = String::from_utf8_lossy(object_type).to_string();
+    for line in std::io::stdin().lock().lines() {
+        let spec = line?;
+        let spec = spec.trim();
+        if spec.is_empty() {
+            continue;
+        }
 
-    println!("Tipo de objeto: {}", object_type);
-    println!("Tamanho do conteúdo: {} bytes", object_content.len());
-    println!("Conteúdo: \n{}", String::from_utf8_lossy(object_content));
+        let object = resolve_object(repo, spec).and_then(|hash| Ok((repo.get_raw_object(&hash)?, hash)));
+        match object {
+            Ok(((object_type, content), hash)) => {
+                writeln!(stdout, "{} {} {}", hash, object_type, content.len())?;
+                stdout.write_all(&content)?;
+                writeln!(stdout)?;
+            }
+            Err(_) => writeln!(stdout, "{} missing", spec)?,
+        }
+    }
 
     Ok(())
+}
+
+/// Exibe o conteúdo do blob, os filhos da árvore (`<modo> <tipo> <hash>\t<nome>`) ou o commit sem interpretação
+fn print_pretty(repo: &Repository, hash: &String) -> Result<(), MinigitError> {
+    match repo.get_object(hash)? {
+        RGitObjectTypes::Blob(blob) => std::io::stdout().write_all(&blob.content)?,
+        RGitObjectTypes::Tree(tree) => {
+            for child in &tree.children {
+                let child_type = if parse_mode(&child.mode).is_ok_and(|mode| mode == MODE_TREE) { "tree" } else { "blob" };
+                println!("{} {} {}\t{}", child.mode, child_type, child.object_id, child.name);
+            }
+        }
+        RGitObjectTypes::Commit(_) => print!("{}", String::from_utf8_lossy(&repo.get_raw_object(hash)?.1)),
+    }
 
+    Ok(())
 }
 
+/// Imprime o objeto como JSON com o tipo, o tamanho e o conteúdo interpretado.
+/// Blobs têm o conteúdo como texto, árvores a lista de filhos e commits os seus campos.
+fn print_object_json(repo: &Repository, hash: &String, size: usize) -> Result<(), MinigitError> {
+    let value = match repo.get_object(hash)? {
+        RGitObjectTypes::Blob(blob) => json!({
+            "type": "blob",
+            "size": size,
+            "content": String::from_utf8_lossy(&blob.content),
+        }),
+        RGitObjectTypes::Tree(tree) => json!({
+            "type": "tree",
+            "size": size,
+            "content": tree.children,
+        }),
+        RGitObjectTypes::Commit(commit) => json!({
+            "type": "commit",
+            "size": size,
+            "content": commit,
+        }),
+    };
+
+    terminal::print_json(&value)
+}
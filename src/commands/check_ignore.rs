@@ -0,0 +1,51 @@
+use crate::{
+    commands::add::get_paths_relative_to_repository,
+    config::RGitIgnore,
+    error::MinigitError,
+    staging::StagingArea,
+    utils::find_current_repo,
+};
+
+/// Exibe quais dos caminhos são ignorados e, com `verbose`, a regra e o arquivo de origem que decidiram.
+///
+/// Como no git, sai com código 1 se nenhum caminho for ignorado.
+///
+/// ## Argumentos
+/// - `paths` - Caminhos relativos ao diretório atual
+/// - `verbose` - Exibe `<origem>:<linha>:<padrão>` antes de cada caminho
+/// - `non_matching` - Com `verbose`, também exibe os caminhos que não casaram com nenhuma regra
+/// - `no_index` - Verifica também arquivos rastreados, que normalmente nunca são ignorados
+pub fn cmd_check_ignore(paths: Vec<String>, verbose: bool, non_matching: bool, no_index: bool) -> Result<(), MinigitError> {
+    match cmd_check_ignore_result(paths, verbose, non_matching, no_index) {
+        Ok(true) => Ok(()),
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("Erro ao verificar caminhos ignorados: {}", e);
+            Err(e)
+        }
+    }
+}
+
+fn cmd_check_ignore_result(paths: Vec<String>, verbose: bool, non_matching: bool, no_index: bool) -> Result<bool, MinigitError> {
+    let repo = find_current_repo()?;
+    let ignore = RGitIgnore::new(&repo)?;
+    let staging_area = StagingArea::new(&repo)?;
+    let relative_paths = get_paths_relative_to_repository(&repo, &paths)?;
+    let mut any_ignored = false;
+
+    for (path, relative_path) in paths.iter().zip(relative_paths) {
+        let tracked = !no_index && staging_area.find_entry(&relative_path).is_some();
+        let rule = if tracked { None } else { ignore.matching_rule(&relative_path) };
+        let ignored = rule.is_some_and(|rule| !rule.negated);
+        any_ignored |= ignored;
+
+        match rule {
+            Some(rule) if verbose => println!("{}\t{}", rule, path),
+            _ if ignored => println!("{}", path),
+            None if verbose && non_matching => println!("::\t{}", path),
+            _ => {}
+        }
+    }
+
+    Ok(any_ignored)
+}
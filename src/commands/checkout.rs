@@ -1,100 +1,231 @@
-use crate::{Repository, checks::{ensure_no_merge_in_progress, ensure_no_rebase_in_progress}, objects::{CommitObject, RGitObjectTypes, tree}, staging::{self}, status::get_uncommited_files, utils::{find_current_repo, is_valid_sha1, resolve_head_or_branch_name}};
+use std::{collections::{BTreeSet, HashMap}, path::{Path, PathBuf}};
 
-pub fn cmd_checkout(reference_to_commit: &String) {
-    match execute_checkout(reference_to_commit) {
+use walkdir::WalkDir;
+
+use crate::{Repository, checks::{ensure_no_merge_in_progress, ensure_no_rebase_in_progress}, error::MinigitError, hooks::{Hook, run_post_hook}, objects::{BlobObject, CommitObject, RGitObject, TreeFile, get_commit_files, tree}, staging::{self, StagingArea, StagingEntry}, utils::{LockFile, find_current_repo, is_valid_sha1, is_worktree_file, remove_worktree_file, resolve_head_or_branch_name, write_worktree_file}};
+
+pub fn cmd_checkout(reference_to_commit: &String, force: bool) -> Result<(), MinigitError> {
+    match execute_checkout(reference_to_commit, force) {
         Ok(..) => {
-            println!("Indo para o commit {}", reference_to_commit)
+            println!("Indo para o commit {}", reference_to_commit);
+            Ok(())
         },
         Err(err) => {
             println!("Erro: {}.", err);
+            if matches!(err, MinigitError::DirtyWorktree(_)) {
+                println!("Esses arquivos seriam sobrescritos pelo checkout. Use -f para descartar as mudanças locais");
+            }
+            Err(err)
         }
     }
 }
 
-fn execute_checkout(reference_to_commit: &String) -> Result<(), String> {
-    let mut repository = find_current_repo()
-        .ok_or("Diretório não está dentro um repositório minigit")?;
-
-    ensure_no_rebase_in_progress(&repository)?;
-    ensure_no_merge_in_progress(&repository)?;
-    prompt_uncommited_changes(&repository)?;
-    
-    let is_commit_id = is_valid_sha1(&reference_to_commit);
-
-    if is_commit_id {
-        repository.clear_worktree();
+fn execute_checkout(reference_to_commit: &String, force: bool) -> Result<(), MinigitError> {
+    let mut repository = find_current_repo()?;
 
-        let RGitObjectTypes::Commit(object) = repository
-            .get_object(&reference_to_commit)
-            .ok_or("Não é um commit reconhecido pelo minigit")?
-            else {
-                return Err("Não é um commit reconhecido pelo minigit".to_string());
-            };
+    checkout(&mut repository, reference_to_commit, force)?;
+    Ok(())
+}
 
-        instanciate_commit(object, &mut repository);
-        repository.change_head(reference_to_commit);
+/// Muda o HEAD para `reference_to_commit` (branch ou hash de commit) e atualiza a worktree
+///
+/// Só os arquivos que diferem entre o HEAD atual e o destino são alterados; arquivos não rastreados
+/// e mudanças locais que não conflitam com o destino são mantidos. Se `force` for falso e algum
+/// arquivo que difere tiver mudanças locais, retorna `MinigitError::DirtyWorktree` com esses arquivos
+/// sem alterar nada. Com `force`, as mudanças locais nos arquivos rastreados são descartadas.
+///
+/// Retorna o hash do commit apontado pelo novo HEAD, vazio se a branch ainda não tiver commits.
+pub fn checkout(repository: &mut Repository, reference_to_commit: &String, force: bool) -> Result<String, MinigitError> {
+    ensure_no_rebase_in_progress(repository)?;
+    ensure_no_merge_in_progress(repository)?;
+
+    let previous_head = repository.resolve_head()?;
+
+    let commit_id = if is_valid_sha1(reference_to_commit) {
+        repository
+            .get_commit(reference_to_commit)
+            .map_err(|_| MinigitError::InvalidRef("Não é um commit reconhecido pelo minigit".to_string()))?;
+        reference_to_commit.clone()
     } else {
-        let commit_id = resolve_head_or_branch_name(&reference_to_commit, &repository)
-            .ok_or("Referência não existe")?;
+        resolve_head_or_branch_name(reference_to_commit, repository)?
+            .ok_or_else(|| MinigitError::InvalidRef("Referência não existe".to_string()))?
+    };
 
-        repository.clear_worktree();
-        repository.change_head(reference_to_commit);
+    update_worktree(repository, &previous_head, &commit_id, force)?;
+    repository.change_head(reference_to_commit)?;
 
-        if commit_id.is_empty() {
-            return Ok(());
-        }
+    run_post_checkout_hook(repository, &previous_head)?;
 
-        let RGitObjectTypes::Commit(object) = repository
-            .get_object(&commit_id)
-            .expect("Branch aponta para referência inválida") else {
-                panic!("Branch aponta para referência que não é um commit");
-            };
+    Ok(commit_id)
+}
 
-        instanciate_commit(object, &mut repository);
-    }
+/// Executa o gancho `post-checkout` com o commit anterior, o novo commit e a flag `1` (checkout de commit)
+pub fn run_post_checkout_hook(repository: &Repository, previous_head: &str) -> Result<(), MinigitError> {
+    let new_head = repository.resolve_head()?;
 
+    run_post_hook(repository, Hook::PostCheckout, &[previous_head, &new_head, "1"]);
     Ok(())
 }
 
+/// Instancia o commit ou a tree na worktree do repositório
+/// 
+/// Retorna um erro se a árvore do commit estiver corrompida.
+pub fn instanciate_commit(object: CommitObject, repository: &mut Repository) -> Result<(), MinigitError> {
+    let mut new_staging_area = staging::staging_area_from_commit(repository, &object)?;
+    let tree_object = repository.get_tree(&object.tree)?;
+
+    tree::instanciate_tree_files(repository, &tree_object)?;
+    new_staging_area.refresh(repository)?;
 
-fn prompt_uncommited_changes(repo: &Repository) -> Result<(), String> {
-    let uncommited_files = get_uncommited_files(repo);
+    staging::rewrite_index(repository, &new_staging_area)
+}
 
-    if !uncommited_files.is_empty() {
-        let file_list = uncommited_files.iter()
-            .map(|f| format!("- {}\n", f.to_str().unwrap()))
-            .collect::<String>();
-        
-        println!("As mudanças nos seguintes arquivos serão perdidas:");
-        println!();
-        println!("{}", file_list);
-        println!("Deseja continuar? (y/n): ");
 
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input).unwrap();
+/// Atualiza o índice e a worktree do commit `from` para o commit `to`, mantendo as mudanças locais
+///
+/// Só os arquivos que diferem (no conteúdo ou no modo) entre os dois commits são alterados; arquivos não rastreados e mudanças
+/// em arquivos que não diferem são mantidos. Se algum arquivo que difere tiver mudanças locais (no índice
+/// ou na worktree), ou se um arquivo não rastreado ocupar o lugar de um arquivo de `to`, retorna
+/// `MinigitError::DirtyWorktree` com esses arquivos sem alterar nada. Todos os arquivos são removidos
+/// antes de qualquer escrita, para que um caminho possa trocar entre arquivo e diretório.
+///
+/// Com `force`, esses arquivos são sobrescritos e as mudanças locais de todos os arquivos rastreados
+/// são descartadas, deixando o índice igual a `to`. Arquivos não rastreados continuam sendo mantidos.
+///
+/// Hashes vazios representam uma branch sem commits.
+pub fn update_worktree(repository: &mut Repository, from: &str, to: &str, force: bool) -> Result<(), MinigitError> {
+    let from_files = get_commit_files(repository, from)?;
+    let to_files = get_commit_files(repository, to)?;
+    update_worktree_files(repository, &from_files, &to_files, force)
+}
 
-        if input.trim().to_lowercase() != "y" {
-            return Err("Operação de checkout abortada pelo usuário.".to_string());
+/// Como [`update_worktree`], a partir dos arquivos (caminho -> arquivo) das duas versões, que não
+/// precisam ser de um commit (ex: a árvore de um merge ainda sem commit)
+pub fn update_worktree_files(
+    repository: &mut Repository,
+    from_files: &HashMap<String, TreeFile>,
+    to_files: &HashMap<String, TreeFile>,
+    force: bool,
+) -> Result<(), MinigitError> {
+    let mut lock = LockFile::acquire(&repository.index_path)?;
+    let mut staging_area = StagingArea::new(repository)?;
+
+    let conflicted = staging_area.conflicted_paths();
+    let mut to_update = Vec::new();
+    let mut blocked = Vec::new();
+
+    let candidates: BTreeSet<String> = if force {
+        from_files.keys()
+            .chain(to_files.keys())
+            .cloned()
+            .chain(staging_area.entries.iter().map(|entry| entry.path.to_string_lossy().to_string()))
+            .collect()
+    } else {
+        from_files.keys()
+            .chain(to_files.keys())
+            .filter(|path| from_files.get(*path) != to_files.get(*path))
+            .cloned()
+            .collect()
+    };
+
+    for path in candidates {
+        let relative_path = PathBuf::from(&path);
+        let target = to_files.get(&path);
+        let index_file = staging_area.find_entry(&relative_path).map(StagingEntry::tree_file);
+        let is_conflicted = conflicted.contains(&relative_path);
+
+        if force {
+            let up_to_date = !is_conflicted && index_file.as_ref() == target
+                && is_worktree_clean(repository, &staging_area, &relative_path, target, to_files)?;
+            if !up_to_date {
+                to_update.push((relative_path, target.cloned()));
+            }
+        } else if is_conflicted {
+            blocked.push(relative_path);
+        } else if index_file.as_ref() == target {
+            // O índice já está como no destino; as mudanças da worktree são mantidas
+        } else if index_file.as_ref() == from_files.get(&path) && is_worktree_clean(repository, &staging_area, &relative_path, target, to_files)? {
+            to_update.push((relative_path, target.cloned()));
+        } else {
+            blocked.push(relative_path);
         }
     }
 
-    Ok(())
-}
+    if !blocked.is_empty() {
+        return Err(MinigitError::DirtyWorktree(blocked));
+    }
 
-/// Instancia o commit ou a tree na worktree do repositório
-/// 
-/// Essa função deve dar pânico se algum erro ocorrer, pois isso indica que o repositório está corrompido.
-pub fn instanciate_commit(object: CommitObject, repository: &mut Repository) {
-    let new_staging_area = staging::staging_area_from_commit(repository, &object);
-    let commit_tree_hash = &object.tree;
+    let (to_write, to_remove): (Vec<_>, Vec<_>) = to_update.into_iter().partition(|(_, target)| target.is_some());
 
-    let RGitObjectTypes::Tree(tree_object) = repository
-        .get_object(commit_tree_hash)
-        .unwrap()
-        else { panic!("Commit aponta para árvore inválida"); };
+    for (path, _) in to_remove {
+        staging_area.remove_entry_with_path(&path);
+        remove_worktree_file(repository, &path)?;
+    }
+
+    for (path, target) in to_write {
+        let Some(file) = target else {
+            continue;
+        };
+        staging_area.remove_entry_with_path(&path);
+        write_worktree_file(repository, &path, &repository.get_blob(&file.hash)?.content, file.mode)?;
+        staging_area.update_or_create_entry(StagingEntry::from_tree_file(&file, path));
+    }
 
-    tree::instanciate_tree_files(repository, &tree_object);
+    staging_area.refresh(repository)?;
+    lock.write_all(&staging_area.serialize()?)?;
+    lock.commit()
+}
 
-    staging::rewrite_index(repository, &new_staging_area);
+/// Verifica se o arquivo da worktree pode ser substituído pela versão `target` sem perder dados
+///
+/// Arquivos rastreados precisam estar iguais ao índice. Arquivos não rastreados só podem existir se
+/// o destino também não tiver o arquivo ou se o conteúdo for igual ao do destino. Um diretório no caminho
+/// de um arquivo do destino só é aceito se tiver apenas arquivos rastreados, sem mudanças, que `to_files`
+/// não tem (e que portanto serão removidos antes da escrita).
+fn is_worktree_clean(
+    repository: &Repository,
+    staging_area: &StagingArea,
+    path: &Path,
+    target: Option<&TreeFile>,
+    to_files: &HashMap<String, TreeFile>,
+) -> Result<bool, MinigitError> {
+    let absolute_path = repository.worktree.join(path);
+
+    match staging_area.find_entry(path) {
+        Some(entry) => Ok(is_worktree_file(&absolute_path) && !staging_area.is_modified(repository, entry)?),
+        None if !absolute_path.exists() && !absolute_path.is_symlink() => Ok(true),
+        None => match target {
+            Some(_) if !absolute_path.is_symlink() && absolute_path.is_dir() => {
+                is_removable_dir(repository, staging_area, &absolute_path, to_files)
+            }
+            Some(file) => Ok(is_worktree_file(&absolute_path) && BlobObject::try_from(&absolute_path)?.hash() == file.hash),
+            None => Ok(true),
+        },
+    }
 }
 
+/// Verifica se o diretório só tem arquivos rastreados, sem mudanças, que não existem em `to_files`
+fn is_removable_dir(
+    repository: &Repository,
+    staging_area: &StagingArea,
+    absolute_dir: &Path,
+    to_files: &HashMap<String, TreeFile>,
+) -> Result<bool, MinigitError> {
+    for entry in WalkDir::new(absolute_dir).into_iter().filter_map(|entry| entry.ok()) {
+        if entry.file_type().is_dir() {
+            continue;
+        }
+
+        let Ok(relative_path) = entry.path().strip_prefix(&repository.worktree) else {
+            return Ok(false);
+        };
+        let Some(index_entry) = staging_area.find_entry(relative_path) else {
+            return Ok(false);
+        };
+        if to_files.contains_key(relative_path.to_string_lossy().as_ref()) || staging_area.is_modified(repository, index_entry)? {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,238 @@
+use std::{collections::BTreeSet, io::Write, path::{Path, PathBuf}};
+
+use crate::{
+    Repository,
+    config::RGitIgnore,
+    error::MinigitError,
+    staging::StagingArea,
+    utils::{find_current_repo, pathspec::Pathspec},
+};
+
+/// Quais arquivos ignorados o `clean` remove
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Default)]
+pub enum IgnoredFiles {
+    /// Mantém os arquivos ignorados (padrão)
+    #[default]
+    Keep,
+    /// Remove também os arquivos ignorados (`-x`)
+    Include,
+    /// Remove só os arquivos ignorados (`-X`)
+    Only,
+}
+
+/// Opções do `clean`
+///
+/// ## Atributos
+/// - `dry_run` - Só exibe o que seria removido
+/// - `force` - Remove de fato os arquivos; exigido quando `clean.requireForce` não for `false`
+/// - `directories` - Remove também pastas não rastreadas inteiras
+/// - `ignored` - Se os arquivos ignorados são mantidos, também removidos ou os únicos removidos
+/// - `excludes` - Padrões de gitignore adicionais. Com `IgnoredFiles::Include` continuam protegendo os arquivos
+/// - `interactive` - Pergunta, caminho a caminho, o que deve ser removido
+/// - `paths` - Pathspec que limita os caminhos removidos
+#[derive(Clone, Debug, Default)]
+pub struct CleanOptions {
+    pub dry_run: bool,
+    pub force: bool,
+    pub directories: bool,
+    pub ignored: IgnoredFiles,
+    pub excludes: Vec<String>,
+    pub interactive: bool,
+    pub paths: Vec<String>,
+}
+
+pub fn cmd_clean(options: CleanOptions) -> Result<(), MinigitError> {
+    let result = cmd_clean_result(options);
+    if let Err(e) = &result {
+        eprintln!("Erro ao limpar a worktree: {}", e);
+    }
+    result
+}
+
+fn cmd_clean_result(options: CleanOptions) -> Result<(), MinigitError> {
+    let repo = find_current_repo()?;
+
+    let require_force = repo.config.get_bool("clean.requireforce")?.unwrap_or(true);
+    if require_force && !options.force && !options.dry_run && !options.interactive {
+        return Err(MinigitError::InvalidArgument("clean.requireForce é true; use -f, -n ou -i".to_string()));
+    }
+
+    let mut candidates = clean_candidates(&repo, &options)?;
+    if options.interactive && !options.dry_run {
+        candidates = select_interactively(candidates)?;
+    }
+
+    for path in candidates {
+        let display = display_path(&path, repo.worktree.join(&path).is_dir());
+
+        if options.dry_run {
+            println!("Removeria {}", display);
+            continue;
+        }
+
+        let absolute_path = repo.worktree.join(&path);
+        if absolute_path.is_dir() && !absolute_path.is_symlink() {
+            std::fs::remove_dir_all(&absolute_path)?;
+        } else {
+            std::fs::remove_file(&absolute_path)?;
+        }
+        println!("Removendo {}", display);
+    }
+
+    Ok(())
+}
+
+/// Retorna os caminhos, relativos à raíz do repositório, que o `clean` removeria.
+///
+/// Pastas só aparecem com `directories` e quando tudo dentro delas seria removido; nesse caso os arquivos
+/// de dentro não aparecem. A pasta `.minigit` e repositórios aninhados nunca são incluídos.
+pub fn clean_candidates(repo: &Repository, options: &CleanOptions) -> Result<Vec<PathBuf>, MinigitError> {
+    let staging_area = StagingArea::new(repo)?;
+    let tracked: BTreeSet<PathBuf> = staging_area.entries.iter().map(|entry| entry.path.clone()).collect();
+
+    let context = CleanContext {
+        repo,
+        options,
+        tracked_dirs: tracked.iter().flat_map(|path| path.ancestors().skip(1)).map(Path::to_path_buf).collect(),
+        tracked,
+        ignore: RGitIgnore::new(repo)?,
+        excludes: RGitIgnore::from_patterns(repo, &options.excludes),
+        pathspec: Pathspec::parse(repo, &options.paths)?,
+    };
+
+    let mut candidates = Vec::new();
+    context.collect(Path::new(""), &mut candidates)?;
+    Ok(candidates)
+}
+
+struct CleanContext<'a> {
+    repo: &'a Repository,
+    options: &'a CleanOptions,
+    tracked: BTreeSet<PathBuf>,
+    /// Pastas que contêm algum arquivo rastreado, incluindo a raíz
+    tracked_dirs: BTreeSet<PathBuf>,
+    ignore: RGitIgnore,
+    excludes: RGitIgnore,
+    pathspec: Pathspec,
+}
+
+impl CleanContext<'_> {
+    /// Adiciona os candidatos de dentro da pasta, retornando se todo o conteúdo dela seria removido
+    fn collect(&self, relative_dir: &Path, candidates: &mut Vec<PathBuf>) -> Result<bool, MinigitError> {
+        let mut entries: Vec<_> = std::fs::read_dir(self.repo.worktree.join(relative_dir))?
+            .filter_map(|entry| entry.ok())
+            .collect();
+        entries.sort_by_key(|entry| entry.file_name());
+
+        let mut removes_everything = true;
+
+        for entry in entries {
+            let relative_path = relative_dir.join(entry.file_name());
+            let is_dir = entry.file_type().is_ok_and(|file_type| file_type.is_dir());
+
+            if relative_path == Path::new(Repository::MINIGITDIR) || (is_dir && entry.path().join(Repository::MINIGITDIR).exists()) {
+                removes_everything = false;
+                continue;
+            }
+
+            let selected = if is_dir {
+                self.collect_directory(&relative_path, candidates)?
+            } else if self.is_candidate(&relative_path) {
+                candidates.push(relative_path);
+                true
+            } else {
+                false
+            };
+
+            removes_everything &= selected;
+        }
+
+        Ok(removes_everything)
+    }
+
+    /// Trata uma subpasta, retornando se ela seria removida inteira
+    fn collect_directory(&self, relative_path: &Path, candidates: &mut Vec<PathBuf>) -> Result<bool, MinigitError> {
+        let untracked = !self.tracked_dirs.contains(relative_path);
+
+        // Sem -d e sem pathspec, pastas não rastreadas não são visitadas, como no git
+        if untracked && !self.options.directories && self.pathspec.is_empty() {
+            return Ok(false);
+        }
+
+        let start = candidates.len();
+        let removes_everything = self.collect(relative_path, candidates)?;
+
+        if untracked && removes_everything && self.options.directories && self.selects(relative_path) {
+            candidates.truncate(start);
+            candidates.push(relative_path.to_path_buf());
+            return Ok(true);
+        }
+
+        Ok(false)
+    }
+
+    fn is_candidate(&self, relative_path: &Path) -> bool {
+        !self.tracked.contains(relative_path) && self.selects(relative_path)
+    }
+
+    /// Verifica se o caminho não rastreado é selecionado pelas regras de ignore, pelos `-e` e pela pathspec
+    fn selects(&self, relative_path: &Path) -> bool {
+        let ignored = self.ignore.check_ignore(relative_path);
+        let excluded = self.excludes.check_ignore(relative_path);
+
+        let selected = match self.options.ignored {
+            IgnoredFiles::Keep => !ignored && !excluded,
+            IgnoredFiles::Include => !excluded,
+            IgnoredFiles::Only => ignored || excluded,
+        };
+
+        selected && self.pathspec.matches(relative_path)
+    }
+}
+
+/// Pergunta, caminho a caminho, quais candidatos devem ser removidos
+fn select_interactively(candidates: Vec<PathBuf>) -> Result<Vec<PathBuf>, MinigitError> {
+    let mut selected = Vec::new();
+    let mut remaining = candidates.into_iter();
+
+    while let Some(path) = remaining.next() {
+        loop {
+            print!("Remover {} [y,n,a,q,?]? ", path.display());
+            std::io::stdout().flush()?;
+
+            let mut input = String::new();
+            if std::io::stdin().read_line(&mut input)? == 0 {
+                return Ok(selected);
+            }
+
+            match input.trim() {
+                "y" => selected.push(path),
+                "n" => {}
+                "a" => {
+                    selected.push(path);
+                    selected.extend(remaining);
+                    return Ok(selected);
+                }
+                "q" => return Ok(selected),
+                _ => {
+                    println!("y - remove este caminho");
+                    println!("n - mantém este caminho");
+                    println!("a - remove este e todos os caminhos restantes");
+                    println!("q - sai sem remover este nem os caminhos restantes");
+                    continue;
+                }
+            }
+            break;
+        }
+    }
+
+    Ok(selected)
+}
+
+fn display_path(path: &Path, is_dir: bool) -> String {
+    if is_dir {
+        format!("{}/", path.display())
+    } else {
+        path.display().to_string()
+    }
+}
@@ -1,45 +1,55 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use fs_extra::dir::{copy, CopyOptions};
+use crate::bundle::read_bundle_header;
 use crate::commands::checkout::{instanciate_commit};
+use crate::commands::init::create_repo;
 use crate::Repository;
-use crate::objects::RGitObjectTypes;
-use crate::utils::{find_repo};
+use crate::error::MinigitError;
+use crate::utils::{find_repo, write_locked};
 
-pub fn cmd_clone(repository_path: &str, destination_path: &str) {
+pub fn cmd_clone(repository_path: &str, destination_path: &str) -> Result<(), MinigitError> {
     match execute_clone(repository_path, destination_path) {
         Ok(..) => {
-            println!("Repositório clonado com sucesso!")
+            println!("Repositório clonado com sucesso!");
+            Ok(())
         },
         Err(err) => {
             println!("Erro: {}.", err);
+            Err(err)
         }
     }
 }
 
-pub fn execute_clone(repository_path: &str, destination_path: &str) -> Result<(), String> {
+pub fn execute_clone(repository_path: &str, destination_path: &str) -> Result<(), MinigitError> {
     let source = PathBuf::from(repository_path);
     let destination = PathBuf::from(destination_path);
 
-    let source_repository = find_repo(&source)
-        .ok_or("Não há um repositório minigit nesse caminho de origem. Use o comando init para inicializar um novo repositório.")?;
     if destination.exists() {
-        return Err(String::from("Já existe um diretório com esse nome nesse mesmo local."));
+        return Err(MinigitError::AlreadyExists(String::from("Já existe um diretório com esse nome nesse mesmo local.")));
     }
 
-    std::fs::create_dir_all(&destination).map_err(|e| e.to_string())?;
+    if source.is_file() {
+        return clone_from_bundle(&source, &destination);
+    }
+
+    let source_repository = find_repo(&source)?;
 
-    let mut new_repository = Repository::new(destination.as_path());
+    std::fs::create_dir_all(&destination)?;
+
+    let new_repository = Repository::new(destination.as_path())?;
     copy_minigit_dir(&source_repository, &new_repository);
 
-    let head_commit = new_repository.resolve_head();
+    // Recarrega o repositório para ler a configuração copiada
+    let mut new_repository = Repository::new(destination.as_path())?;
+    let head_commit = new_repository.resolve_head()?;
+
+    if head_commit.is_empty() {
+        return Ok(());
+    }
 
-    let RGitObjectTypes::Commit(object) = new_repository
-        .get_object(&head_commit)
-        .ok_or("Erro ao copiar diretório. Head commit do repositório não encontrado.")?
-        else { panic!("Objeto do head commit não é um commit."); };
+    let object = new_repository.get_commit(&head_commit)?;
 
-    instanciate_commit(object, &mut new_repository);
-    Ok(())
+    instanciate_commit(object, &mut new_repository)
 }
 
 fn copy_minigit_dir(source: &Repository, new_repository: &Repository) {
@@ -51,4 +61,27 @@ fn copy_minigit_dir(source: &Repository, new_repository: &Repository) {
         Ok(_) => println!("Pasta minigit copiada com sucesso"),
         Err(e) => println!("Erro ao copiar a pasta minigit: {}", e),
     }
-}
\ No newline at end of file
+}
+
+/// Cria o repositório a partir de um bundle com o histórico completo, com o HEAD na branch do HEAD do bundle
+fn clone_from_bundle(bundle_path: &Path, destination: &Path) -> Result<(), MinigitError> {
+    let header = read_bundle_header(bundle_path)?;
+    if !header.prerequisites.is_empty() {
+        return Err(MinigitError::InvalidArgument(String::from(
+            "O bundle depende de commits que não estão nele. Use 'bundle unbundle' em um repositório que já tenha esses commits",
+        )));
+    }
+
+    std::fs::create_dir_all(destination)?;
+    let mut repository = Repository::new(destination)?;
+    create_repo(&mut repository)?;
+
+    // A branch do HEAD ainda não tem commits, então o unbundle a cria e faz o checkout dela
+    if let Some(branch) = header.head_branch().filter(|branch| *branch != "master") {
+        write_locked(&repository.head_path, format!("ref: refs/heads/{}", branch))?;
+        std::fs::remove_dir_all(repository.refs_heads_path.join("master"))?;
+    }
+
+    repository.unbundle(bundle_path, false)?;
+    Ok(())
+}
@@ -1,32 +1,51 @@
-use crate::{checks::{ensure_no_detached_head, ensure_no_merge_in_progress, ensure_no_rebase_in_progress}, objects::create_commit_object_from_index, utils::{find_current_repo, merge_rebase::finish}};
+use crate::{Repository, checks::{ensure_no_detached_head, ensure_no_merge_in_progress, ensure_no_rebase_in_progress}, error::MinigitError, hooks::{Hook, run_post_hook}, objects::create_commit_object_from_index, utils::{find_current_repo, merge_rebase::finish}};
 
-pub fn cmd_commit(message: String) {
-    match cmd_commit_result(message) {
+pub fn cmd_commit(message: String, no_verify: bool) -> Result<(), MinigitError> {
+    match cmd_commit_result(message, no_verify) {
         Ok(hash) => {
             println!("Commit criado com o hash {}", hash);
+            Ok(())
         },
         Err(err) => {
             println!("{}", err);
+            Err(err)
         }
     }
 }
 
-fn cmd_commit_result(message: String) -> Result<String, String> {
-    let mut repo = find_current_repo()
-        .ok_or("Diretório não está dentro um repositório minigit")?;
+fn cmd_commit_result(message: String, no_verify: bool) -> Result<String, MinigitError> {
+    let mut repo = find_current_repo()?;
+    let finishes_merge = repo.merge_head_path.exists();
 
-    ensure_no_detached_head(&repo)?;
-    ensure_no_merge_in_progress(&repo)?;
-    ensure_no_rebase_in_progress(&repo)?;
+    let commit_hash = commit(&mut repo, message, !no_verify)?;
 
-    let commit_hash = create_commit_object_from_index(&mut repo, message);
+    if finishes_merge {
+        println!("Estado de merge finalizado e limpo");
+    }
+
+    Ok(commit_hash)
+}
 
-    repo.update_curr_branch(&commit_hash);
+/// Cria um commit com o conteúdo do índice e avança a branch atual até ele
+///
+/// Se `run_hooks` for falso, os ganchos `pre-commit` e `commit-msg` não são executados.
+/// Retorna o hash do commit criado.
+pub fn commit(repo: &mut Repository, message: String, run_hooks: bool) -> Result<String, MinigitError> {
+    ensure_no_detached_head(repo)?;
+    ensure_no_merge_in_progress(repo)?;
+    ensure_no_rebase_in_progress(repo)?;
+
+    // A branch só avança se nenhum outro processo a tiver movido enquanto o commit era criado
+    let previous_head = repo.resolve_head()?;
+    let commit_hash = create_commit_object_from_index(repo, message, run_hooks)?;
+
+    repo.compare_and_swap_curr_branch(&previous_head, &commit_hash)?;
 
     if repo.merge_head_path.exists() {
-        finish(&mut repo, false);
-        println!("Estado de merge finalizado e limpo");
+        finish(repo)?;
     }
 
+    run_post_hook(repo, Hook::PostCommit, &[]);
+
     Ok(commit_hash)
 }
@@ -1,16 +1,92 @@
-use crate::utils::find_current_repo;
+use clap::ValueEnum;
 
-pub fn cmd_config(key: String, value: String) {
-    match cmd_config_result(key.clone(), value.clone()) {
-        Ok(_) => {
-            println!("Chave '{}' atualizada para '{}' com sucesso.", &key, &value);
-        }
-        Err(e) => println!("Erro ao atualizar configuração: {}", e),
+use crate::{config::{expand_path, parse_bool, parse_int, ConfigKey, ConfigScope, GitConfig}, error::MinigitError, utils::find_current_repo};
+
+/// Tipo esperado do valor, usado para validar e normalizar com `--type`
+#[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
+pub enum ConfigType {
+    Bool,
+    Int,
+    Path,
+}
+
+pub enum ConfigAction {
+    /// Exibe o valor efetivo da chave
+    Get { key: String },
+    /// Define o valor da chave
+    Set { key: String, value: String },
+    /// Remove a chave
+    Unset { key: String },
+    /// Lista todos os valores, no formato `chave=valor`
+    List,
+}
+
+/// - `scope` - Arquivo lido ou alterado. Sem escopo, a leitura combina todos os arquivos e a escrita usa o local
+pub fn cmd_config(action: ConfigAction, scope: Option<ConfigScope>, value_type: Option<ConfigType>) -> Result<(), MinigitError> {
+    let result = cmd_config_result(action, scope, value_type);
+    if let Err(err) = &result {
+        println!("{}", err);
     }
+    result
 }
 
-fn cmd_config_result(key: String, value: String) -> Result<(), String> {
-    let mut repo = find_current_repo().ok_or("Não é um repositório minigit")?;
-    repo.update_config(key, value);
+fn cmd_config_result(action: ConfigAction, scope: Option<ConfigScope>, value_type: Option<ConfigType>) -> Result<(), MinigitError> {
+    let mut config = load_config(scope)?;
+
+    match action {
+        ConfigAction::Get { key } => {
+            let parsed = ConfigKey::parse(&key)?;
+            let value = config.values().iter().rev()
+                .find(|value| value.key == parsed && scope.is_none_or(|scope| value.scope == scope))
+                .ok_or_else(|| MinigitError::InvalidConfig(format!("Chave '{}' não encontrada", key)))?;
+
+            match value_type {
+                Some(ConfigType::Path) => println!("{}", expand_path(&value.value).display()),
+                _ => println!("{}", normalize(&key, &value.value, value_type)?),
+            }
+        }
+        ConfigAction::Set { key, value } => {
+            let value = normalize(&key, &value, value_type)?;
+            config.set(scope.unwrap_or(ConfigScope::Local), &key, &value)?;
+            println!("Chave '{}' atualizada para '{}' com sucesso.", key, value);
+        }
+        ConfigAction::Unset { key } => {
+            if !config.unset(scope.unwrap_or(ConfigScope::Local), &key)? {
+                return Err(MinigitError::InvalidConfig(format!("Chave '{}' não encontrada", key)));
+            }
+            println!("Chave '{}' removida com sucesso.", key);
+        }
+        ConfigAction::List => {
+            for value in config.values().iter().filter(|value| scope.is_none_or(|scope| value.scope == scope)) {
+                println!("{}={}", value.key, value.value);
+            }
+        }
+    }
+
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Carrega a configuração do repositório atual. Fora de um repositório, só os escopos
+/// do sistema e global estão disponíveis
+fn load_config(scope: Option<ConfigScope>) -> Result<GitConfig, MinigitError> {
+    match find_current_repo() {
+        Ok(repo) => Ok(repo.config),
+        Err(MinigitError::NotARepository) if matches!(scope, Some(ConfigScope::System | ConfigScope::Global) | None) => {
+            GitConfig::load(None)
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Valida o valor de acordo com `value_type`, retornando a sua forma canônica.
+/// Caminhos são mantidos como escritos e só expandidos na leitura
+fn normalize(key: &str, value: &str, value_type: Option<ConfigType>) -> Result<String, MinigitError> {
+    let invalid = |expected: &str| MinigitError::InvalidConfig(format!("Valor '{}' da chave '{}' não é um {} válido", value, key, expected));
+
+    match value_type {
+        None => Ok(value.to_string()),
+        Some(ConfigType::Bool) => parse_bool(value).map(|value| value.to_string()).ok_or_else(|| invalid("booleano")),
+        Some(ConfigType::Int) => parse_int(value).map(|value| value.to_string()).ok_or_else(|| invalid("inteiro")),
+        Some(ConfigType::Path) => Ok(value.to_string()),
+    }
+}
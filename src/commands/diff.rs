@@ -1,62 +1,65 @@
-use std::fs::read_to_string;
-use crate::objects::{get_tree_as_map, RGitObjectTypes};
-use crate::status::{non_staged_files};
-use crate::utils::find_current_repo;
-
-pub fn cmd_diff() {
-    let repository = find_current_repo().unwrap();
-    let non_staged = non_staged_files(&repository);
-
-    let head_commit = repository.resolve_head();
-    let RGitObjectTypes::Commit(commit) = repository.get_object(&head_commit).unwrap()
-    else { panic!("Objeto do head commit não é um commit."); };
-    let RGitObjectTypes::Tree(tree_object) = repository.get_object(&commit.tree).unwrap()
-    else { panic!("Objeto da tree do head commit não é um commit."); };
-
-    let tree_files = get_tree_as_map(&repository, &tree_object);
-
-    for path in non_staged {
-        let path_string = path.to_str().unwrap();
-        let current_content = match tree_files.get(path_string) {
-            Some(blob_hash_string) => {
-                let RGitObjectTypes::Blob(blob) = repository.get_object(blob_hash_string).unwrap()
-                else { panic!("O objeto não é um blob!"); };
-
-                String::from_utf8(blob.content).unwrap()
-            }
-            None => String::new(),
-        };
+use crate::diff::{FileDiff, LineChange, RenameKind, diff_worktree};
+use crate::error::MinigitError;
+use crate::utils::{find_current_repo, terminal::{self, GREEN, RED}};
 
-        let new_content = read_to_string(repository.worktree.join(path_string)).unwrap_or_default();
-        println!("{}:", path_string);
-        find_content_differences(&current_content, &new_content);
+pub fn cmd_diff(json: bool) -> Result<(), MinigitError> {
+    let result = cmd_diff_result(json);
+    if let Err(err) = &result {
+        println!("{}", err);
     }
+    result
 }
 
+fn cmd_diff_result(json: bool) -> Result<(), MinigitError> {
+    let repository = find_current_repo()?;
+    let diffs = diff_worktree(&repository)?;
 
-fn find_content_differences(old_text: &str, new_text: &str) {
-    let old: Vec<&str> = old_text.lines().collect();
-    let new: Vec<&str> = new_text.lines().collect();
-    let bigger_len: usize;
+    if json {
+        return terminal::print_json(&diffs);
+    }
 
-    if old.len() > new.len() {
-        bigger_len = old.len();
-    } else {
-        bigger_len = new.len();
+    for file_diff in diffs {
+        match &file_diff.source {
+            Some(source) => println!("{} ({}, {}% semelhante):", file_diff.display_path(), rename_description(source.kind), source.similarity),
+            None => println!("{}:", file_diff.path.display()),
+        }
+        print_content_differences(&file_diff);
     }
 
-    for i in 0..bigger_len {
-        let old_line = old.get(i).unwrap_or(&"");
-        let new_line = new.get(i).unwrap_or(&"");
+    Ok(())
+}
 
-        if old_line != new_line {
-            println!("  Linha {}:", i + 1);
-            if old_line.len() != 0 {
-                println!("      - {}", old_line);
-            }
-            if new_line.len() != 0{
-                println!("      + {}", new_line);
+/// Imprime cada trecho de linhas alteradas, identificado pela linha da versão nova em que começa
+fn print_content_differences(file_diff: &FileDiff) {
+    let mut in_block = false;
+    let mut new_lines_seen = 0;
+
+    for line in &file_diff.lines {
+        if line.change == LineChange::Unchanged {
+            in_block = false;
+            new_lines_seen += 1;
+            continue;
+        }
+
+        if !in_block {
+            println!("  Linha {}:", new_lines_seen + 1);
+            in_block = true;
+        }
+
+        match line.change {
+            LineChange::Removed => println!("      {}", terminal::paint(&format!("- {}", line.content), RED)),
+            LineChange::Added => {
+                println!("      {}", terminal::paint(&format!("+ {}", line.content), GREEN));
+                new_lines_seen += 1;
             }
+            LineChange::Unchanged => {}
         }
     }
-}
\ No newline at end of file
+}
+
+fn rename_description(kind: RenameKind) -> &'static str {
+    match kind {
+        RenameKind::Renamed => "renomeado",
+        RenameKind::Copied => "copiado",
+    }
+}
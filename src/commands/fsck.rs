@@ -0,0 +1,36 @@
+use crate::{
+    error::MinigitError,
+    fsck::FsckOptions,
+    utils::{find_current_repo, terminal},
+};
+
+pub fn cmd_fsck(options: FsckOptions, json: bool) -> Result<(), MinigitError> {
+    let result = cmd_fsck_result(&options, json);
+    if let Err(e) = &result {
+        eprintln!("Erro no fsck: {}", e);
+    }
+    result
+}
+
+fn cmd_fsck_result(options: &FsckOptions, json: bool) -> Result<(), MinigitError> {
+    let repo = find_current_repo()?;
+    let report = repo.fsck(options)?;
+
+    if json {
+        terminal::print_json(&report)?;
+    } else {
+        for issue in &report.issues {
+            println!("{}", issue);
+        }
+    }
+
+    let error_count = report.errors().count();
+    if error_count > 0 {
+        return Err(MinigitError::CorruptObject(format!("{} problemas encontrados em {} objetos", error_count, report.object_count)));
+    }
+
+    if !json {
+        println!("Nenhum problema encontrado em {} objetos", report.object_count);
+    }
+    Ok(())
+}
@@ -0,0 +1,74 @@
+use crate::{
+    error::MinigitError,
+    grep::{GrepFile, GrepOptions, GrepSource},
+    utils::find_current_repo,
+};
+
+/// Como os arquivos encontrados são exibidos
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum GrepOutput {
+    /// As linhas encontradas, com o caminho (e a revisão) na frente
+    Lines { line_numbers: bool },
+    /// Só os caminhos dos arquivos (`-l`)
+    FileNames,
+    /// Os caminhos e a quantidade de linhas encontradas (`-c`)
+    Count,
+}
+
+pub fn cmd_grep(options: GrepOptions, output: GrepOutput) -> Result<(), MinigitError> {
+    match cmd_grep_result(&options, output) {
+        Ok(true) => Ok(()),
+        // Como no git, nenhuma linha encontrada sai com código 1
+        Ok(false) => std::process::exit(1),
+        Err(e) => {
+            eprintln!("Erro no grep: {}", e);
+            Err(e)
+        }
+    }
+}
+
+fn cmd_grep_result(options: &GrepOptions, output: GrepOutput) -> Result<bool, MinigitError> {
+    let repo = find_current_repo()?;
+    let files = repo.grep(options)?;
+
+    let prefix = match &options.source {
+        GrepSource::Revision(revision) => format!("{}:", revision),
+        _ => String::new(),
+    };
+    let has_context = options.before_context > 0 || options.after_context > 0;
+
+    for (index, file) in files.iter().enumerate() {
+        match output {
+            GrepOutput::FileNames => println!("{}{}", prefix, file.path),
+            GrepOutput::Count => println!("{}{}:{}", prefix, file.path, file.match_count),
+            GrepOutput::Lines { line_numbers } => {
+                if has_context && index > 0 {
+                    println!("--");
+                }
+                print_lines(file, &prefix, line_numbers, has_context);
+            }
+        }
+    }
+
+    Ok(!files.is_empty())
+}
+
+/// Exibe as linhas do arquivo: `:` separa as linhas encontradas e `-` as de contexto.
+/// Com contexto, `--` separa grupos de linhas que não são vizinhos
+fn print_lines(file: &GrepFile, prefix: &str, line_numbers: bool, has_context: bool) {
+    let mut previous = None;
+
+    for line in &file.lines {
+        if has_context && previous.is_some_and(|previous| previous + 1 != line.number) {
+            println!("--");
+        }
+        previous = Some(line.number);
+
+        let separator = if line.is_match { ':' } else { '-' };
+        if line_numbers {
+            println!("{}{}{}{}{}{}", prefix, file.path, separator, line.number, separator, line.content);
+        } else {
+            println!("{}{}{}{}", prefix, file.path, separator, line.content);
+        }
+    }
+}
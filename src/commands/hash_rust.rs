@@ -1,34 +1,36 @@
 use std::fs;
 use std::path::PathBuf;
+use crate::error::MinigitError;
 use crate::objects::{BlobObject, RGitObject};
 use crate::utils::find_current_repo;
 
-pub fn cmd_hash_object(path: &str, write:bool){
+pub fn cmd_hash_object(path: &str, write:bool) -> Result<(), MinigitError> {
     match hash_object_command(path,write) {
-        Ok(_) => {},
+        Ok(_) => Ok(()),
         Err(err) => {
             println!("{}", err);
+            Err(err)
         }
     }
 }
 
-pub fn hash_object_command(path: &str, write: bool) -> Result<(), String> {
-    let mut repo = find_current_repo().ok_or("Não é um repositório minigit")?;
+pub fn hash_object_command(path: &str, write: bool) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
     let file_path = PathBuf::from(path);
 
     if !file_path.exists() || !file_path.is_file() {
-        return Err("Caminho do arquivo inválido".to_string());
+        return Err(MinigitError::InvalidArgument("Caminho do arquivo inválido".to_string()));
     }
 
-    let bytes = fs::read(file_path).map_err(|_| "Não foi possível ler o arquivo".to_string())?;
+    let bytes = fs::read(file_path)?;
     let blob =  BlobObject { content: bytes } ;
 
     println!("Hash SHA-1 do arquivo: {}", blob.hash());
 
     if write {
-        repo.create_object(&blob);
+        repo.create_object(&blob)?;
         println!("Objeto blob escrito no repositório.");
     }
 
     Ok(())
-}
\ No newline at end of file
+}
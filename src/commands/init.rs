@@ -1,42 +1,76 @@
 use std::fs::File;
 use std::io::Write;
+use std::path::Path;
 use crate::Repository;
+use crate::error::MinigitError;
+use crate::hooks::Hook;
 use crate::utils::find_repo;
 
-pub fn cmd_init() {
-    let current = std::env::current_dir().expect("Deveria acessar o diretório atual");
-    let repo = find_repo(&current);
-
-    match repo {
-        Some(_repository) => {
-            println!("Este diretório já faz parte de um repostitório. Ainda não suportamos repositórios dentro de outros repositórios");
-            return;
+pub fn cmd_init() -> Result<(), MinigitError> {
+    match cmd_init_result() {
+        Ok(repo) => {
+            println!("Repositório Minigit inicializado em {:?}", repo.worktree);
+            Ok(())
+        }
+        Err(err) => {
+            println!("{}", err);
+            Err(err)
         }
-        None => {}
+    }
+}
+
+fn cmd_init_result() -> Result<Repository, MinigitError> {
+    let current = std::env::current_dir()?;
+    init(&current)
+}
+
+/// Cria um novo repositório com a worktree em `path`
+///
+/// Retorna `MinigitError::AlreadyExists` se `path` já fizer parte de um repositório.
+pub fn init(path: &Path) -> Result<Repository, MinigitError> {
+    if find_repo(path).is_ok() {
+        return Err(MinigitError::AlreadyExists("Este diretório já faz parte de um repostitório. Ainda não suportamos repositórios dentro de outros repositórios".to_string()));
     }
 
-    let mut repo = Repository::new(&current);
-    create_repo(&mut repo);
-    println!("Repositório Minigit inicializado em {:?}", repo.worktree);
+    let mut repo = Repository::new(path)?;
+    create_repo(&mut repo)?;
+    Ok(repo)
 }
 
-fn create_repo(repo: &mut Repository) {
-    repo.create_repository_dir(&[]);
-    repo.create_repository_dir(&["objects"]);
-    repo.create_repository_dir(&["refs"]);
-    repo.create_repository_dir(&["refs", "tags"]);
-    repo.create_repository_dir(&["refs", "heads"]);
-    repo.create_repository_file(&["index"]);
-    repo.create_repository_file(&["refs", "heads", "master", "index"]);
-    repo.create_repository_file(&["config"]);
+/// Cria a pasta `.minigit` do repositório, sem verificar se ele está dentro de outro repositório
+pub fn create_repo(repo: &mut Repository) -> Result<(), MinigitError> {
+    repo.create_repository_dir(&[])?;
+    repo.create_repository_dir(&["objects"])?;
+    repo.create_repository_dir(&["refs"])?;
+    repo.create_repository_dir(&["refs", "tags"])?;
+    repo.create_repository_dir(&["refs", "heads"])?;
+    repo.create_repository_file(&["index"])?;
+    repo.create_repository_file(&["refs", "heads", "master", "index"])?;
+    repo.create_repository_file(&["config"])?;
     
-    let mut description_file = repo.create_repository_file(&["description"]);
-    let mut head_file = repo.create_repository_file(&["HEAD"]);
+    let mut description_file = repo.create_repository_file(&["description"])?;
+    let mut head_file = repo.create_repository_file(&["HEAD"])?;
+
+    write_file(&mut description_file, "Repositório sem nome. Edite este arquivo para nomear.\n")?;
+    write_file(&mut head_file, "ref: refs/heads/master")?;
+
+    install_sample_hooks(repo)
+}
+
+/// Instala scripts de exemplo em `.minigit/hooks`. Eles só são executados se forem renomeados sem o `.sample`.
+fn install_sample_hooks(repo: &mut Repository) -> Result<(), MinigitError> {
+    repo.create_repository_dir(&[Repository::HOOKS])?;
+
+    for hook in Hook::ALL {
+        let sample_name = format!("{}.sample", hook.name());
+        let mut sample_file = repo.create_repository_file(&[Repository::HOOKS, &sample_name])?;
+        write_file(&mut sample_file, hook.sample())?;
+    }
 
-    write_file(&mut description_file, "Repositório sem nome. Edite este arquivo para nomear.\n");
-    write_file(&mut head_file, "ref: refs/heads/master");
+    Ok(())
 }
 
-fn write_file(file: &mut File, content: &str) {
-    file.write_all(content.as_bytes()).expect("Erro escrevendo arquivo!");
+fn write_file(file: &mut File, content: &str) -> Result<(), MinigitError> {
+    file.write_all(content.as_bytes())?;
+    Ok(())
 }
@@ -1,54 +1,422 @@
-use chrono::{DateTime, Local, TimeZone};
-use std::process::{Command, Stdio};
-use std::io::Write;
+use std::collections::HashMap;
 
-use crate::{objects::RGitObject, utils::find_current_repo};
+use crate::{
+    Repository,
+    diff::{FileDiff, diff_commit, format_unified},
+    error::MinigitError,
+    history::{LogEntry, LogOptions},
+    notes::{DEFAULT_NOTES_REF, list_notes},
+    utils::{dates::{commit_datetime, format_commit_date, parse_date}, find_current_repo, terminal::{self, CYAN, GREEN, RED, YELLOW}},
+};
 
-pub fn cmd_log() {
-    match cmd_log_result() {
-        Ok(()) => {}
-        Err(e) => println!("Error: {}", e),
+/// Largura máxima da barra de `+` e `-` no `--stat`
+const STAT_BAR_WIDTH: usize = 40;
+
+/// Tamanho do hash abreviado exibido no `--oneline` e nos marcadores `%h`, `%t` e `%p`
+const SHORT_HASH_LEN: usize = 7;
+
+/// Argumentos do comando `log`
+///
+/// ## Atributos
+/// - `options` - Filtros do histórico (revisões, autor, mensagem, caminhos...)
+/// - `since` / `until` - Datas ainda não interpretadas do intervalo de commits
+/// - `oneline` - Exibe um commit por linha
+/// - `format` - Template de formatação de cada commit
+/// - `graph` - Desenha o grafo de commits à esquerda
+/// - `patch` - Exibe as diferenças introduzidas por cada commit
+/// - `stat` - Exibe o resumo das diferenças introduzidas por cada commit
+/// - `notes` - Namespaces de notas exibidos abaixo de cada commit no formato padrão
+/// - `json` - Exibe os commits em JSON
+pub struct LogArgs {
+    pub options: LogOptions,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    pub oneline: bool,
+    pub format: Option<String>,
+    pub graph: bool,
+    pub patch: bool,
+    pub stat: bool,
+    pub notes: Vec<String>,
+    pub json: bool,
+}
+
+/// Notas de cada namespace exibido: o nome do namespace e o mapa commit -> blob da nota
+pub type LogNotes = Vec<(String, HashMap<String, String>)>;
+
+pub fn cmd_log(args: LogArgs) -> Result<(), MinigitError> {
+    match cmd_log_result(args) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            println!("Error: {}", e);
+            Err(e)
+        }
     }
 }
 
-fn cmd_log_result() -> Result<(), String> {
-    let repo = find_current_repo().ok_or("Não está dentro de um repositório")?;
-    let commits = repo.get_commit_history();
-    let mut output = String::new();
+fn cmd_log_result(mut args: LogArgs) -> Result<(), MinigitError> {
+    let repo = find_current_repo()?;
 
-    if commits.is_empty() {
+    if let Some(since) = &args.since {
+        args.options.since = Some(parse_date(since)?);
+    }
+    if let Some(until) = &args.until {
+        args.options.until = Some(parse_date(until)?);
+    }
+
+    let entries = repo.log(&args.options)?;
+
+    if args.json {
+        return terminal::print_json(&entries);
+    }
+
+    if entries.is_empty() {
         println!("Nenhum commit encontrado no repositório.");
         return Ok(());
     }
 
-    for commit in commits {
-        let dt: DateTime<Local> = Local.timestamp_opt((commit.timestamp / 1_000_000_000) as i64, 0).unwrap();
-        let formatted_date = dt.format("%d/%m/%Y %H:%M:%S").to_string();
+    let notes = load_notes(&repo, &args.notes)?;
+    let mut graph = Graph::default();
+    let mut output = String::new();
+
+    for entry in &entries {
+        let text = format_entry(&repo, entry, &args, &notes)?;
+
+        if !args.graph {
+            output.push_str(&text);
+            continue;
+        }
+
+        let lines = graph.next(entry);
+        let mut text_lines = text.lines();
+        output.push_str(&format!("{} {}\n", lines.commit_line, text_lines.next().unwrap_or_default()));
+        for text_line in text_lines {
+            output.push_str(format!("{} {}", lines.padding, text_line).trim_end());
+            output.push('\n');
+        }
+        for edge_line in lines.edge_lines {
+            output.push_str(&edge_line);
+            output.push('\n');
+        }
+    }
+
+    terminal::page(&output)
+}
+
+/// Formata o texto de um commit de acordo com os argumentos (padrão, `--oneline` ou `--format`),
+/// seguido das diferenças se `--stat` ou `--patch` foram pedidos
+fn format_entry(repo: &Repository, entry: &LogEntry, args: &LogArgs, notes: &LogNotes) -> Result<String, MinigitError> {
+    let mut text = if let Some(template) = &args.format {
+        format!("{}\n", expand_template(template, entry))
+    } else if args.oneline {
+        format!("{} {}\n", terminal::paint(short_hash(&entry.hash), YELLOW), subject(&entry.commit.message))
+    } else {
+        format_full(entry) + &format_notes(repo, &entry.hash, notes)?
+    };
+
+    if !args.stat && !args.patch {
+        return Ok(text);
+    }
+
+    let diffs = diff_commit(repo, &entry.commit)?;
+    if args.oneline || args.format.is_some() {
+        text.push('\n');
+    }
+    if args.stat {
+        text.push_str(&format_stat(&diffs));
+        text.push('\n');
+    }
+    if args.patch {
+        for file_diff in &diffs {
+            text.push_str(&color_patch(&format_unified(file_diff, 3)));
+        }
+        text.push('\n');
+    }
+
+    Ok(text)
+}
+
+pub fn format_full(entry: &LogEntry) -> String {
+    let commit = &entry.commit;
+    let mut text = String::new();
+
+    let commit_line = format!("commit {}", entry.hash);
+    text.push_str(&format!("{}\n", terminal::paint(&commit_line, YELLOW)));
+    if commit.parent.len() > 1 {
+        let parents: Vec<&str> = commit.parent.iter().map(|parent| short_hash(parent)).collect();
+        text.push_str(&format!("Merge: {}\n", parents.join(" ")));
+    }
+    text.push_str(&format!("Autor: {}\n", commit.author));
+    text.push_str(&format!("Data:  {}\n", format_commit_date(commit.timestamp)));
+    text.push('\n');
+    for line in commit.message.lines() {
+        text.push_str(&format!("\t{}\n", line));
+    }
+    text.push('\n');
+
+    text
+}
+
+/// Carrega as notas dos namespaces uma única vez para todo o log
+pub fn load_notes(repo: &Repository, namespaces: &[String]) -> Result<LogNotes, MinigitError> {
+    let mut notes = Vec::new();
+    for namespace in namespaces {
+        let entries = list_notes(repo, namespace)?;
+        notes.push((namespace.clone(), entries.into_iter().map(|entry| (entry.commit, entry.note)).collect()));
+    }
+    Ok(notes)
+}
+
+/// Bloco `Notas:` (ou `Notas (<namespace>):` fora do namespace padrão) de cada namespace em que o commit tem nota
+pub fn format_notes(repo: &Repository, hash: &str, notes: &LogNotes) -> Result<String, MinigitError> {
+    let mut text = String::new();
+
+    for (namespace, commits) in notes {
+        let Some(blob) = commits.get(hash) else {
+            continue;
+        };
+
+        let name = namespace.strip_prefix("refs/notes/").unwrap_or(namespace);
+        if name == DEFAULT_NOTES_REF {
+            text.push_str("Notas:\n");
+        } else {
+            text.push_str(&format!("Notas ({}):\n", name));
+        }
+        for line in String::from_utf8_lossy(&repo.get_blob(blob)?.content).lines() {
+            text.push_str(&format!("\t{}\n", line));
+        }
+        text.push('\n');
+    }
+
+    Ok(text)
+}
+
+/// Substitui os marcadores do template de `--format`
+///
+/// Marcadores suportados: `%H` (hash), `%h` (hash abreviado), `%T`/`%t` (árvore), `%P`/`%p` (pais),
+/// `%an` (nome do autor), `%ae` (email do autor), `%ad` (data), `%at` (data em segundos),
+/// `%s` (assunto), `%b` (corpo da mensagem), `%n` (quebra de linha) e `%%`.
+fn expand_template(template: &str, entry: &LogEntry) -> String {
+    let commit = &entry.commit;
+    let (author_name, author_email) = split_author(&commit.author);
+    let mut result = String::new();
+    let mut chars = template.chars().peekable();
 
-        output.push_str(&format!("commit {}\n", commit.hash()));
-        output.push_str(&format!("Autor: {}\n", commit.author));
-        output.push_str(&format!("Data:  {}\n", formatted_date));
-        output.push_str("\n");
-        output.push_str(&format!("\t{}\n", commit.message));
-        output.push_str("\n");
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            result.push(c);
+            continue;
+        }
+
+        let Some(placeholder) = chars.next() else {
+            result.push('%');
+            break;
+        };
+
+        match placeholder {
+            'H' => result.push_str(&entry.hash),
+            'h' => result.push_str(short_hash(&entry.hash)),
+            'T' => result.push_str(&commit.tree),
+            't' => result.push_str(short_hash(&commit.tree)),
+            'P' => result.push_str(&commit.parent.join(" ")),
+            'p' => result.push_str(&commit.parent.iter().map(|parent| short_hash(parent)).collect::<Vec<&str>>().join(" ")),
+            's' => result.push_str(subject(&commit.message)),
+            'b' => result.push_str(body(&commit.message)),
+            'n' => result.push('\n'),
+            '%' => result.push('%'),
+            'a' => match chars.peek() {
+                Some('n') => { chars.next(); result.push_str(author_name); }
+                Some('e') => { chars.next(); result.push_str(author_email); }
+                Some('d') => { chars.next(); result.push_str(&format_commit_date(commit.timestamp)); }
+                Some('t') => { chars.next(); result.push_str(&commit_datetime(commit.timestamp).timestamp().to_string()); }
+                _ => result.push_str("%a"),
+            },
+            other => {
+                result.push('%');
+                result.push(other);
+            }
+        }
     }
 
-    run_with_pager(&output);
-    Ok(())
+    result
 }
 
-/// Executa o comando 'less' para paginar a saída
-/// 
-/// Depende que o `less` esteja instalado no sistema
-/// 
-/// ## Arguments
-/// * `output` - A string que será passada para o pager
-fn run_with_pager(output: &str) {
-    let mut child = Command::new("less")
-        .stdin(Stdio::piped())
-        .spawn()
-        .unwrap();
+/// Separa o autor no formato `Nome <email>` em nome e email
+pub fn split_author(author: &str) -> (&str, &str) {
+    match author.split_once(" <") {
+        Some((name, email)) => (name, email.trim_end_matches('>')),
+        None => (author, ""),
+    }
+}
+
+pub fn short_hash(hash: &str) -> &str {
+    &hash[..hash.len().min(SHORT_HASH_LEN)]
+}
+
+/// Primeira linha da mensagem do commit
+pub fn subject(message: &str) -> &str {
+    message.lines().next().unwrap_or_default()
+}
+
+/// Mensagem do commit sem a primeira linha e a linha em branco seguinte
+fn body(message: &str) -> &str {
+    match message.split_once('\n') {
+        Some((_, rest)) => rest.trim_start_matches('\n').trim_end(),
+        None => "",
+    }
+}
+
+/// Resumo das diferenças no formato `arquivo | N ++--`
+fn format_stat(diffs: &[FileDiff]) -> String {
+    let name_width = diffs.iter().map(|file_diff| file_diff.display_path().len()).max().unwrap_or(0);
+    let max_changes = diffs.iter().map(|file_diff| file_diff.insertions() + file_diff.deletions()).max().unwrap_or(0);
+
+    let mut text = String::new();
+    let (mut insertions, mut deletions) = (0, 0);
+
+    for file_diff in diffs {
+        let (added, removed) = (file_diff.insertions(), file_diff.deletions());
+        insertions += added;
+        deletions += removed;
+
+        // Reduz as barras proporcionalmente quando alguma mudança passa da largura máxima
+        let (plus, minus) = if max_changes > STAT_BAR_WIDTH {
+            (added * STAT_BAR_WIDTH / max_changes, removed * STAT_BAR_WIDTH / max_changes)
+        } else {
+            (added, removed)
+        };
+
+        text.push_str(&format!(
+            " {:<width$} | {} {}{}\n",
+            file_diff.display_path(),
+            added + removed,
+            terminal::paint(&"+".repeat(plus), GREEN),
+            terminal::paint(&"-".repeat(minus), RED),
+            width = name_width,
+        ));
+    }
+
+    text.push_str(&format!(
+        " {} arquivo(s) alterado(s), {} inserção(ões)(+), {} remoção(ões)(-)\n",
+        diffs.len(), insertions, deletions,
+    ));
 
-    child.stdin.as_mut().unwrap().write_all(output.as_bytes()).unwrap();
-    child.wait().unwrap();
-}
\ No newline at end of file
+    text
+}
+
+pub fn color_patch(patch: &str) -> String {
+    let mut text = String::new();
+    for line in patch.lines() {
+        let colored = if line.starts_with("@@") {
+            terminal::paint(line, CYAN)
+        } else if line.starts_with('+') && !line.starts_with("+++") {
+            terminal::paint(line, GREEN)
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            terminal::paint(line, RED)
+        } else {
+            line.to_string()
+        };
+        text.push_str(&colored);
+        text.push('\n');
+    }
+    text
+}
+
+/// Linhas do grafo desenhadas para um commit
+///
+/// ## Atributos
+/// - `commit_line` - Colunas na linha do commit, com `*` na coluna do commit
+/// - `padding` - Colunas exibidas antes das demais linhas do texto do commit
+/// - `edge_lines` - Linhas com `\` (novos ramos de um merge) e `/` (ramos que se juntam)
+struct GraphLines {
+    commit_line: String,
+    padding: String,
+    edge_lines: Vec<String>,
+}
+
+/// Estado do grafo: cada coluna guarda o hash do próximo commit esperado nela
+#[derive(Default)]
+struct Graph {
+    columns: Vec<String>,
+}
+
+impl Graph {
+    fn next(&mut self, entry: &LogEntry) -> GraphLines {
+        let column = match self.columns.iter().position(|hash| *hash == entry.hash) {
+            Some(column) => column,
+            None => {
+                self.columns.push(entry.hash.clone());
+                self.columns.len() - 1
+            }
+        };
+
+        let commit_line = self.columns.iter().enumerate()
+            .map(|(i, _)| if i == column { "*" } else { "|" })
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        let parents = &entry.commit.parent;
+        let padding = self.columns.iter().enumerate()
+            .map(|(i, _)| if i == column && parents.is_empty() { " " } else { "|" })
+            .collect::<Vec<&str>>()
+            .join(" ");
+
+        // Substitui o commit pelo primeiro pai e abre colunas para os outros pais
+        let mut expanded: Vec<Option<String>> = self.columns.iter().cloned().map(Some).collect();
+        expanded[column] = parents.first().cloned();
+        let new_parents: Vec<String> = parents.iter().skip(1)
+            .filter(|parent| !self.columns.contains(parent))
+            .cloned()
+            .collect();
+        for (offset, parent) in new_parents.iter().enumerate() {
+            expanded.insert(column + 1 + offset, Some(parent.clone()));
+        }
+
+        let mut edge_lines = Vec::new();
+        if !new_parents.is_empty() {
+            let mut line = vec![' '; expanded.len() * 2];
+            for i in 0..expanded.len() {
+                if i <= column {
+                    line[i * 2] = '|';
+                } else {
+                    line[i * 2 - 1] = '\\';
+                }
+            }
+            edge_lines.push(line.into_iter().collect::<String>().trim_end().to_string());
+        }
+
+        // Remove colunas vazias e colunas que esperam um commit já esperado por outra coluna,
+        // desenhando `/` para as colunas que se juntam ou se deslocam para a esquerda
+        let mut kept: Vec<(usize, String)> = Vec::new();
+        let mut line = vec![' '; expanded.len() * 2];
+        let mut collapsed = false;
+        for (i, hash) in expanded.into_iter().enumerate() {
+            let Some(hash) = hash else {
+                continue;
+            };
+
+            match kept.iter().position(|(_, kept_hash)| *kept_hash == hash) {
+                Some(_) => {
+                    line[i * 2 - 1] = '/';
+                    collapsed = true;
+                }
+                None if kept.len() == i => {
+                    line[i * 2] = '|';
+                    kept.push((i, hash));
+                }
+                None => {
+                    line[kept.len() * 2 + 1] = '/';
+                    collapsed = true;
+                    kept.push((i, hash));
+                }
+            }
+        }
+
+        if collapsed {
+            edge_lines.push(line.into_iter().collect::<String>().trim_end().to_string());
+        }
+
+        self.columns = kept.into_iter().map(|(_, hash)| hash).collect();
+
+        GraphLines { commit_line, padding, edge_lines }
+    }
+}
@@ -1,58 +1,78 @@
-use core::panic;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use crate::{Repository, objects::{RGitObjectTypes, TreeObject}, utils::find_current_repo};
+use serde::Serialize;
 
-pub fn cmd_ls_tree(tree_id: String) {
-    match cmd_ls_tree_result(tree_id) {
-        Ok(_) => {},
+use crate::{Repository, error::MinigitError, objects::{RGitObjectTypes, TreeObject}, utils::{find_current_repo, terminal}};
+
+/// Arquivo listado pelo `ls-tree`, com o caminho relativo à raíz da árvore
+#[derive(Serialize)]
+struct LsTreeEntry {
+    mode: String,
+    object_id: String,
+    path: PathBuf,
+}
+
+pub fn cmd_ls_tree(tree_id: String, json: bool) -> Result<(), MinigitError> {
+    match cmd_ls_tree_result(tree_id, json) {
+        Ok(_) => Ok(()),
         Err(err) => {
             println!("{}", err);
+            Err(err)
         }
     }
 }
 
-fn cmd_ls_tree_result(tree_id: String) -> Result<(), String> {
-    let repo = find_current_repo().ok_or("Não está dentro de um repositório")?;
+fn cmd_ls_tree_result(tree_id: String, json: bool) -> Result<(), MinigitError> {
+    let repo = find_current_repo()?;
     
-    let object = repo.get_object(&tree_id)
-        .ok_or("Objeto não encontrado no repositório")?;
+    let object = repo.get_object(&tree_id)?;
 
-    println!("mode object_id\tpath");
+    let mut entries = Vec::new();
     match object {
         RGitObjectTypes::Tree(tree) => {
-            print_tree(&tree, &PathBuf::new(), &repo);
+            collect_tree(&tree, &PathBuf::new(), &repo, &mut entries)?;
         }
         RGitObjectTypes::Commit(commit) => {
-            let tree = repo.get_object(&commit.tree).unwrap();
-
-            match tree {
-                RGitObjectTypes::Tree(tree) => print_tree(&tree, &PathBuf::new(), &repo),
-                _ => panic!("A tree referenciada pelo commit não é válida."),
-            }
+            let tree = repo.get_tree(&commit.tree)?;
+            collect_tree(&tree, &PathBuf::new(), &repo, &mut entries)?;
         }
         _ => {
-            return Err("O objeto fornecido não é uma árvore".to_string());
+            return Err(MinigitError::InvalidArgument("O objeto fornecido não é uma árvore".to_string()));
         }
     }
+
+    if json {
+        return terminal::print_json(&entries);
+    }
+
+    println!("mode object_id\tpath");
+    for entry in entries {
+        println!("{} {} {}", entry.mode, entry.object_id, entry.path.display());
+    }
     Ok(())
 }
 
-fn print_tree(tree: &TreeObject, prefix: &PathBuf, repo: &Repository) {
+fn collect_tree(tree: &TreeObject, prefix: &Path, repo: &Repository, entries: &mut Vec<LsTreeEntry>) -> Result<(), MinigitError> {
     for child in &tree.children {
-        let child_object = repo.get_object(&child.object_id).unwrap();
+        let child_object = repo.get_object(&child.object_id)?;
 
         match child_object {
             RGitObjectTypes::Blob(_) => {
-                println!("{} {} {}", child.mode, child.object_id, prefix.join(&child.name).display());
+                entries.push(LsTreeEntry {
+                    mode: child.mode.clone(),
+                    object_id: child.object_id.clone(),
+                    path: prefix.join(&child.name),
+                });
             },
             RGitObjectTypes::Tree(child_tree) => {
-                let mut new_prefix = prefix.clone();
+                let mut new_prefix = prefix.to_path_buf();
                 new_prefix.push(&child.name);
 
-                print_tree(&child_tree, &new_prefix, repo);
+                collect_tree(&child_tree, &new_prefix, repo, entries)?;
             },
             _ => {}
         }
     }
-}
\ No newline at end of file
+
+    Ok(())
+}
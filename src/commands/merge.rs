@@ -1,124 +1,199 @@
 use std::{
     collections::{HashMap, HashSet},
     path::PathBuf,
-    str::FromStr,
 };
 
 use crate::{
     Repository,
     checks::{
         ensure_merge_in_progress, ensure_no_detached_head, ensure_no_merge_in_progress,
-        ensure_no_rebase_in_progress, ensure_no_uncommited_changes,
+        ensure_no_rebase_in_progress, ensure_no_uncommited_changes, ensure_no_unresolved_conflicts,
     },
     commands::{checkout, rebase::create_conflict_blob},
+    error::MinigitError,
+    hooks::{Hook, run_hook, run_post_hook},
     objects::{
-        CommitObject, RGitObject, RGitObjectTypes, create_commit_object_from_index,
-        create_tree_object_from_staging_tree, get_commit_tree_as_map, get_tree_as_map,
-        instanciate_tree_files,
+        CommitObject, RGitObject, TreeFile, create_commit_object_from_index,
+        create_tree_object_from_staging_tree, get_commit_files, get_commit_tree_as_map, get_commit_tree_files,
+        get_tree_as_map, get_tree_files, pick_mode,
     },
-    staging::StagingTree,
+    staging::{StagingTree, record_conflicts},
     status::non_staged_files,
     utils::{
         find_current_repo, is_valid_sha1,
-        merge_rebase::{abort as abort_merge, finish, start},
+        merge_rebase::{self, OperationKind, abort as abort_merge, finish, start},
+        write_locked,
     },
 };
 
-pub fn cmd_merge(branch_name: Option<&String>, abort: bool, continue_: bool) {
+pub fn cmd_merge(branch_name: Option<&String>, abort: bool, continue_: bool) -> Result<(), MinigitError> {
     let mut repo = match find_current_repo() {
-        Some(r) => r,
-        None => {
-            println!("Diretório não está dentro um repositório minigit");
-            return;
+        Ok(r) => r,
+        Err(err) => {
+            println!("{}", err);
+            return Err(err);
         }
     };
 
     if abort {
-        abort_merge(&mut repo, false);
-        return;
+        return match abort_merge(&mut repo, OperationKind::Merge) {
+            Ok(_) => {
+                println!("Merge abortado com sucesso.");
+                Ok(())
+            }
+            Err(err) => {
+                println!("Erro ao abortar merge: {}", err);
+                Err(err)
+            }
+        };
     }
 
     if continue_ {
-        match continue_merge(&mut repo) {
-            Ok(_) => println!("Merge continuado e finalizado com sucesso."),
-            Err(e) => println!("Erro ao continuar merge: {}", e),
-        }
-        return;
+        return match continue_merge(&mut repo) {
+            Ok(commit_hash) => {
+                println!("Merge commit criado: {}", commit_hash);
+                println!("Merge continuado e finalizado com sucesso.");
+                Ok(())
+            }
+            Err(e) => {
+                println!("Erro ao continuar merge: {}", e);
+                Err(e)
+            }
+        };
     }
 
     if let Some(name) = branch_name {
-        match execute_merge(&mut repo, name) {
-            Ok(_) => {}
+        match merge(&mut repo, name) {
+            Ok(outcome) => print_merge_outcome(&repo, &outcome)?,
             Err(err) => {
                 println!("{}", err);
+                return Err(err);
             }
         }
     }
+
+    Ok(())
+}
+
+/// Resultado de um merge
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum MergeOutcome {
+    /// O commit alvo já faz parte da história da branch atual
+    UpToDate,
+    /// A branch atual foi avançada até o commit alvo
+    FastForward { commit: String },
+    /// Um commit de merge foi criado
+    Merged { commit: String },
+    /// O merge foi interrompido por conflitos. Os arquivos listados contêm marcadores de conflito
+    /// e o merge deve ser finalizado com `merge --continue` ou cancelado com `merge --abort`.
+    Conflicts { files: Vec<String> },
 }
 
-fn continue_merge(repo: &mut Repository) -> Result<(), String> {
+fn print_merge_outcome(repo: &Repository, outcome: &MergeOutcome) -> Result<(), MinigitError> {
+    match outcome {
+        MergeOutcome::UpToDate => println!("Branch já atualizada."),
+        MergeOutcome::FastForward { commit } => println!(
+            "Merge concluído. Branch {} atualizada para {}.",
+            repo.get_head()?,
+            commit
+        ),
+        MergeOutcome::Merged { commit } => println!("Merge commit criado: {}", commit),
+        MergeOutcome::Conflicts { files } => {
+            println!("CONFLITOS DETECTADOS!");
+            for file in files {
+                println!("CONFLICT (content): Merge conflict in {}", file);
+            }
+            println!(
+                "\nMerge automático falhou; conserte os conflitos e faça o commit do resultado."
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// Finaliza um merge interrompido por conflitos, criando o commit de merge a partir do índice
+///
+/// Se o processo tiver morrido depois de criar o commit de merge, mas antes de mover a branch,
+/// só completa a atualização da branch, do índice e da worktree a partir do diário.
+/// Retorna o hash do commit de merge.
+pub fn continue_merge(repo: &mut Repository) -> Result<String, MinigitError> {
     ensure_merge_in_progress(repo)?;
+    let mut state = merge_rebase::load(repo)?
+        .ok_or_else(|| MinigitError::InvalidState("Não há um merge em progresso para prosseguir.".to_string()))?;
+    merge_rebase::sync_branch(repo, &state)?;
+
+    if state.todo.is_empty() {
+        let commit_hash = state.current().to_string();
+        checkout::update_worktree(repo, &state.orig_head, &commit_hash, true)?;
+        finish(repo)?;
+        return Ok(commit_hash);
+    }
 
-    let unstaged = non_staged_files(repo);
-    if !unstaged.is_empty() {
-        return Err(format!(
-            "Você ainda tem mudanças não adicionadas (unstaged). \nResolva os conflitos nos arquivos e rode 'minigit add <arquivos>' antes de continuar.\nArquivos pendentes: {:?}",
-            unstaged
+    // Sem conflitos registrados, o merge parou antes de preparar o índice e a worktree
+    let Some(target_hash) = state.stopped_sha.clone() else {
+        return Err(MinigitError::InvalidState(
+            "O merge foi interrompido antes de terminar. Use 'merge --abort' e faça o merge novamente.".to_string(),
         ));
+    };
+
+    ensure_no_unresolved_conflicts(repo)?;
+
+    let unstaged = non_staged_files(repo)?;
+    if !unstaged.is_empty() {
+        return Err(MinigitError::UnstagedChanges(unstaged));
     }
 
+    // O MERGE_HEAD dá o segundo pai do commit de merge
     if !repo.merge_head_path.exists() {
-        return Err(
-            "Arquivo de controle MERGE_HEAD não encontrado. Estado inconsistente.".to_string(),
-        );
+        write_locked(&repo.merge_head_path, &target_hash)?;
     }
 
-    let target_hash = std::fs::read_to_string(&repo.merge_head_path)
-        .unwrap_or_default()
-        .trim()
-        .to_string();
-
     let message = format!(
         "Merge commit (resolving conflicts from branch hash {})",
-        &target_hash[0..7]
+        &target_hash[0..7.min(target_hash.len())]
     );
 
-    let commit_hash = create_commit_object_from_index(repo, message);
+    let previous_head = state.current().to_string();
+    let commit_hash = create_commit_object_from_index(repo, message, true)?;
 
-    repo.update_curr_branch(&commit_hash);
+    state.record_step(repo, &commit_hash)?;
+    repo.compare_and_swap_curr_branch(&previous_head, &commit_hash)?;
 
-    finish(repo, false);
+    finish(repo)?;
+    run_post_hook(repo, Hook::PostCommit, &[]);
+    run_post_hook(repo, Hook::PostMerge, &["0"]);
 
-    println!("Merge commit criado: {}", commit_hash);
-    Ok(())
+    Ok(commit_hash)
 }
 
-fn execute_merge(repo: &mut Repository, target_ref: &String) -> Result<(), String> {
-    ensure_no_detached_head(&repo)?;
-    ensure_no_merge_in_progress(&repo)?;
-    ensure_no_rebase_in_progress(&repo)?;
-    ensure_no_uncommited_changes(&repo)?;
-
-    let current_head_hash = repo.resolve_head();
+/// Faz o merge da referência `target_ref` (branch ou hash de commit) na branch atual
+///
+/// Tenta primeiro um fast-forward. Caso as histórias tenham divergido, faz um three-way merge
+/// a partir do ancestral comum mais recente.
+pub fn merge(repo: &mut Repository, target_ref: &String) -> Result<MergeOutcome, MinigitError> {
+    ensure_no_detached_head(repo)?;
+    ensure_no_merge_in_progress(repo)?;
+    ensure_no_rebase_in_progress(repo)?;
+    ensure_no_uncommited_changes(repo)?;
+    run_hook(repo, Hook::PreMerge, &[target_ref])?;
+
+    let current_head_hash = repo.resolve_head()?;
     if current_head_hash.is_empty() {
-        return Err("Nada para fazer merge, repositório vazio.".to_string());
+        return Err(MinigitError::InvalidState("Nada para fazer merge, repositório vazio.".to_string()));
     }
 
     let target_hash = if is_valid_sha1(target_ref) {
-        let object = repo.get_object(target_ref).ok_or(format!(
-            "Objeto com hash '{}' não encontrado na pasta .minigit.",
-            target_ref
-        ))?;
-
-        match object {
-            RGitObjectTypes::Commit(_) => target_ref.clone(),
-            _ => {
-                return Err(format!(
-                    "O hash {} não se refere a um objeto do tipo commit. Só é possível fazer merge de commits.",
-                    target_ref,
-                ));
-            }
-        }
+        repo.get_commit(target_ref).map_err(|err| match err {
+            MinigitError::ObjectNotFound(_) => MinigitError::ObjectNotFound(target_ref.clone()),
+            MinigitError::CorruptObject(_) => MinigitError::InvalidRef(format!(
+                "O hash {} não se refere a um objeto do tipo commit. Só é possível fazer merge de commits.",
+                target_ref,
+            )),
+            err => err,
+        })?;
+
+        target_ref.clone()
     } else {
         let target_branch_path = repo
             .minigitdir
@@ -128,104 +203,83 @@ fn execute_merge(repo: &mut Repository, target_ref: &String) -> Result<(), Strin
             .join("index");
 
         if !target_branch_path.exists() {
-            return Err(format!(
+            return Err(MinigitError::InvalidRef(format!(
                 "Referência '{}' não encontrada (não é branch nem hash válido).",
                 target_ref
-            ));
+            )));
         }
 
-        std::fs::read_to_string(target_branch_path)
-            .map_err(|_| "Não foi possível ler a referência da branch alvo.")?
+        std::fs::read_to_string(target_branch_path)?
             .trim()
             .to_string()
     };
 
     // Branches iguais
     if current_head_hash == target_hash {
-        println!("Branch já atualizada.");
-        return Ok(());
+        return Ok(MergeOutcome::UpToDate);
     }
 
     // Target já está no passado de HEAD
-    if is_ancestor(repo, &target_hash, &current_head_hash) {
-        println!("Branch já atualizada.");
-        return Ok(());
+    if is_ancestor(repo, &target_hash, &current_head_hash)? {
+        return Ok(MergeOutcome::UpToDate);
     }
 
     // Tentar realizar o fast-forward merge
-    if is_ancestor(&repo, &current_head_hash, &target_hash) {
-        repo.update_curr_branch(&target_hash);
-        repo.clear_worktree();
-
-        let RGitObjectTypes::Commit(target_object) = repo
-            .get_object(&target_hash)
-            .ok_or("Objeto da branch alvo não encontrado.")?
-        else {
-            panic!("Objeto da branch alvo não é um commit.");
-        };
-        checkout::instanciate_commit(target_object, repo);
+    if is_ancestor(repo, &current_head_hash, &target_hash)? {
+        checkout::update_worktree(repo, &current_head_hash, &target_hash, false)?;
+        if let Err(err) = repo.compare_and_swap_curr_branch(&current_head_hash, &target_hash) {
+            checkout::update_worktree(repo, &target_hash, &current_head_hash, false)?;
+            return Err(err);
+        }
+        run_post_hook(repo, Hook::PostMerge, &["1"]);
 
-        println!(
-            "Merge concluído. Branch {} atualizada para {}.",
-            repo.get_head(),
-            target_hash
-        );
-        return Ok(());
+        return Ok(MergeOutcome::FastForward { commit: target_hash });
     }
 
     // Tentar realizar o three-way merge
-    start(repo, false);
-    std::fs::write(&repo.merge_head_path, &target_hash).expect("Erro ao escrever MERGE_HEAD");
-
-    let common_ancestor_hash = find_common_ancestor(repo, &current_head_hash, &target_hash)
-        .ok_or("Erro: Sem ancestral comum entre branches, histórias desconexas.")?;
+    let mut state = start(repo, OperationKind::Merge, &current_head_hash, vec![target_hash.clone()])?;
+    write_locked(&repo.merge_head_path, &target_hash)?;
 
-    println!("Ancestral comum encontrado: {:?}", common_ancestor_hash);
-
-    let head_commit_obj: CommitObject = match repo.get_object(&current_head_hash) {
-        Some(RGitObjectTypes::Commit(c)) => c,
-        _ => return Err("HEAD atual não aponta para um commit válido".to_string()),
-    };
+    let common_ancestor_hash = find_common_ancestor(repo, &current_head_hash, &target_hash)?
+        .ok_or_else(|| MinigitError::InvalidState("Erro: Sem ancestral comum entre branches, histórias desconexas.".to_string()))?;
 
-    let target_commit_obj: CommitObject = match repo.get_object(&target_hash) {
-        Some(RGitObjectTypes::Commit(c)) => c,
-        _ => return Err("Branch alvo não aponta para um commit válido".to_string()),
-    };
-
-    let base_commit_obj: CommitObject = match repo.get_object(&common_ancestor_hash) {
-        Some(RGitObjectTypes::Commit(c)) => c,
-        _ => return Err("Ancestral comum inválido".to_string()),
-    };
+    let head_commit_obj: CommitObject = repo.get_commit(&current_head_hash)?;
+    let target_commit_obj: CommitObject = repo.get_commit(&target_hash)?;
+    let base_commit_obj: CommitObject = repo.get_commit(&common_ancestor_hash)?;
 
     let (merge_tree_id, conflicts) =
-        create_three_way_merge_tree(repo, &base_commit_obj, &head_commit_obj, &target_commit_obj);
+        create_three_way_merge_tree(repo, &base_commit_obj, &head_commit_obj, &target_commit_obj)?;
 
-    let merge_tree_obj = repo.get_object(&merge_tree_id).expect("Tree criada sumiu");
+    let tree = repo.get_tree(&merge_tree_id)?;
 
-    if let RGitObjectTypes::Tree(tree) = merge_tree_obj {
-        repo.clear_worktree();
-        instanciate_tree_files(repo, &tree);
-
-        if !conflicts.is_empty() {
-            println!("CONFLITOS DETECTADOS!");
-            for file in &conflicts {
-                println!("CONFLICT (content): Merge conflict in {}", file);
-            }
-
-            let all_files = get_tree_as_map(repo, &tree);
-            let safe_files: Vec<PathBuf> = all_files
-                .keys()
-                .filter(|path| !conflicts.contains(*path))
-                .map(|p| PathBuf::from(p))
-                .collect();
-
-            repo.add_files(safe_files);
+    let head_files = get_commit_files(repo, &current_head_hash)?;
+    if let Err(err) = checkout::update_worktree_files(repo, &head_files, &get_tree_files(repo, &tree)?, false) {
+        finish(repo)?;
+        return Err(err);
+    }
 
-            println!(
-                "\nMerge automático falhou; conserte os conflitos e faça o commit do resultado."
-            );
-            return Ok(());
-        }
+    if !conflicts.is_empty() {
+        let all_files = get_tree_as_map(repo, &tree)?;
+        let safe_files: Vec<PathBuf> = all_files
+            .keys()
+            .filter(|path| !conflicts.contains(*path))
+            .map(PathBuf::from)
+            .collect();
+
+        repo.add_files(safe_files)?;
+        record_conflicts(
+            repo,
+            &conflicts,
+            &get_commit_tree_as_map(repo, &base_commit_obj)?,
+            &get_commit_tree_as_map(repo, &head_commit_obj)?,
+            &get_commit_tree_as_map(repo, &target_commit_obj)?,
+        )?;
+
+        state.record_stop(repo, &target_hash)?;
+
+        let mut files: Vec<String> = conflicts.into_iter().collect();
+        files.sort();
+        return Ok(MergeOutcome::Conflicts { files });
     }
 
     let msg = format!("Merge branch '{}' into HEAD", target_ref);
@@ -236,7 +290,7 @@ fn execute_merge(repo: &mut Repository, target_ref: &String) -> Result<(), Strin
     );
     let now = std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
-        .unwrap()
+        .unwrap_or_default()
         .as_nanos();
 
     let merge_commit = CommitObject {
@@ -247,48 +301,48 @@ fn execute_merge(repo: &mut Repository, target_ref: &String) -> Result<(), Strin
         timestamp: now,
     };
 
-    repo.create_object(&merge_commit);
-    repo.update_curr_branch(&merge_commit.hash());
+    repo.create_object(&merge_commit)?;
+    state.record_step(repo, &merge_commit.hash())?;
+    repo.compare_and_swap_curr_branch(&current_head_hash, &merge_commit.hash())?;
 
-    finish(repo, false);
+    finish(repo)?;
+    run_post_hook(repo, Hook::PostMerge, &["0"]);
 
-    println!("Merge commit criado: {}", merge_commit.hash());
-    return Ok(());
+    Ok(MergeOutcome::Merged { commit: merge_commit.hash() })
 }
 
 /// Verifica se 'possible_ancestor' está na história de 'descendant'
-fn is_ancestor(repo: &Repository, possible_ancestor: &String, descendant: &String) -> bool {
-    let mut fila = vec![descendant.clone()];
+pub fn is_ancestor(repo: &Repository, possible_ancestor: &str, descendant: &str) -> Result<bool, MinigitError> {
+    let mut fila = vec![descendant.to_string()];
 
     while let Some(current_hash) = fila.pop() {
-        if &current_hash == possible_ancestor {
-            return true;
+        if current_hash == possible_ancestor {
+            return Ok(true);
         }
 
-        if let Some(RGitObjectTypes::Commit(commit)) = repo.get_object(&current_hash) {
-            for parent in commit.parent {
-                fila.push(parent);
-            }
+        let commit = repo.get_commit(&current_hash)?;
+        for parent in commit.parent {
+            fila.push(parent);
         }
     }
 
-    return false;
+    Ok(false)
 }
 
 /// Encontra o ancestral comum mais recente entre dois commits
-fn find_common_ancestor(repo: &Repository, commit_a: &String, commit_b: &String) -> Option<String> {
-    let history_a = repo.get_commit_history_from_commit(commit_a);
-    let history_b = repo.get_commit_history_from_commit(commit_b);
+fn find_common_ancestor(repo: &Repository, commit_a: &str, commit_b: &str) -> Result<Option<String>, MinigitError> {
+    let history_a = repo.get_commit_history_from_commit(commit_a)?;
+    let history_b = repo.get_commit_history_from_commit(commit_b)?;
 
     for ca in &history_a {
         for cb in &history_b {
             if ca.hash() == cb.hash() {
-                return Some(ca.hash());
+                return Ok(Some(ca.hash()));
             }
         }
     }
 
-    None
+    Ok(None)
 }
 
 fn create_three_way_merge_tree(
@@ -296,10 +350,13 @@ fn create_three_way_merge_tree(
     base: &CommitObject,
     ours: &CommitObject,
     theirs: &CommitObject,
-) -> (String, HashSet<String>) {
-    let map_base = get_commit_tree_as_map(repo, base);
-    let map_ours = get_commit_tree_as_map(repo, ours);
-    let map_theirs = get_commit_tree_as_map(repo, theirs);
+) -> Result<(String, HashSet<String>), MinigitError> {
+    let files_base = get_commit_tree_files(repo, base)?;
+    let files_ours = get_commit_tree_files(repo, ours)?;
+    let files_theirs = get_commit_tree_files(repo, theirs)?;
+    let map_base = get_commit_tree_as_map(repo, base)?;
+    let map_ours = get_commit_tree_as_map(repo, ours)?;
+    let map_theirs = get_commit_tree_as_map(repo, theirs)?;
 
     let mut all_paths: HashSet<&String> = HashSet::new();
     all_paths.extend(map_base.keys());
@@ -334,38 +391,34 @@ fn create_three_way_merge_tree(
             conflicts.insert(path.clone());
 
             let content_ours = if let Some(hash) = h_ours {
-                match repo
-                    .get_object(hash)
-                    .expect("Objeto blob não encontrado no banco")
-                {
-                    RGitObjectTypes::Blob(blob) => blob.content,
-                    _ => panic!("Objeto esperado é um blob"),
-                }
+                repo.get_blob(hash)?.content
             } else {
                 Vec::new() // Arquivo deletado
             };
 
             let content_theirs = if let Some(hash) = h_theirs {
-                match repo
-                    .get_object(hash)
-                    .expect("Objeto blob não encontrado no banco")
-                {
-                    RGitObjectTypes::Blob(blob) => blob.content,
-                    _ => panic!("Objeto esperado é um blob"),
-                }
+                repo.get_blob(hash)?.content
             } else {
                 Vec::new() // Arquivo deletado
             };
 
-            let conflict_hash = create_conflict_blob(repo, content_ours, content_theirs);
+            let conflict_hash = create_conflict_blob(repo, content_ours, content_theirs)?;
             final_map.insert(path.clone(), conflict_hash);
         }
     }
     let mut staging_tree = StagingTree::Fork(HashMap::new());
     for (path_str, hash) in final_map {
-        staging_tree.insert(hash, PathBuf::from_str(&path_str).unwrap());
+        // Se só eles mudaram o modo do arquivo, a mudança deles é mantida
+        let mode_of = |files: &HashMap<String, TreeFile>| files.get(&path_str).map(|file| file.mode);
+        let sides = if mode_of(&files_theirs) != mode_of(&files_base) && mode_of(&files_ours) == mode_of(&files_base) {
+            [&files_theirs, &files_ours]
+        } else {
+            [&files_ours, &files_theirs]
+        };
+        let mode = pick_mode(&path_str, &hash, &sides);
+        staging_tree.insert(hash, mode, PathBuf::from(&path_str));
     }
 
-    let tree_id = create_tree_object_from_staging_tree(&staging_tree, repo);
-    return (tree_id, conflicts);
+    let tree_id = create_tree_object_from_staging_tree(&staging_tree, repo)?;
+    Ok((tree_id, conflicts))
 }
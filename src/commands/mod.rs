@@ -15,10 +15,23 @@ pub mod status;
 pub mod rebase;
 pub mod diff;
 pub mod rm;
+pub mod mv;
+pub mod restore;
+pub mod switch;
+pub mod check_ignore;
+pub mod clean;
+pub mod blame;
+pub mod bisect;
+pub mod grep;
+pub mod show;
+pub mod fsck;
+pub mod notes;
+pub mod archive;
+pub mod bundle;
 
 use clap::{Parser, Subcommand};
 
-use crate::commands::reset::ResetTypes;
+use crate::{archive::ArchiveFormat, commands::reset::ResetTypes, error::MinigitError, history::{BlameOptions, LogOptions}};
 
 
 #[derive(Parser)]
@@ -32,23 +45,168 @@ pub struct CliArgs {
 pub enum Commands {
     /// Inicializa um novo repositório Minigit
     Init,
-    /// Clona um repositório Minigit existente no caminho especificado
+    /// Clona um repositório Minigit existente, ou um bundle, no caminho especificado
     Clone {
-        /// Caminho do repositório a ser clonado
+        /// Caminho do repositório ou do bundle a ser clonado
         repository_path: String,
         /// Caminho de destino para o repositório clonado
         destination_path: String,
     },
-    Diff,
-    /// Exibe o histórico a partir do commit atual
-    Log,
-    /// Cria uma nova branch ou deleta uma branch existente
-    Branch {
-        /// Deleta a branch especificada
+    /// Exibe as diferenças entre a worktree e o último commit
+    Diff {
+        /// Exibe as diferenças em JSON
+        #[arg(long)]
+        json: bool
+    },
+    /// Exibe o histórico a partir do commit atual ou das revisões fornecidas
+    Log {
+        /// Revisões de partida (ex: 'master', 'HEAD~2', 'a1b2..c3d4', '^develop')
+        revisions: Vec<String>,
+        /// Só exibe commits que alteram esses caminhos (após '--')
+        #[arg(last = true)]
+        paths: Vec<String>,
+        /// Quantidade máxima de commits exibidos
+        #[arg(short = 'n', long = "max-count")]
+        max_count: Option<usize>,
+        /// Exibe um commit por linha: '<hash abreviado> <assunto>'
+        #[arg(long, conflicts_with = "format")]
+        oneline: bool,
+        /// Template de cada commit (%H, %h, %T, %t, %P, %p, %an, %ae, %ad, %at, %s, %b, %n, %%)
+        #[arg(long)]
+        format: Option<String>,
+        /// Desenha o grafo de commits
+        #[arg(long)]
+        graph: bool,
+        /// Só exibe commits cujo autor contém o texto
+        #[arg(long)]
+        author: Option<String>,
+        /// Só exibe commits a partir da data (ex: '2024-01-31', '2 weeks ago')
+        #[arg(long)]
+        since: Option<String>,
+        /// Só exibe commits até a data (ex: '2024-01-31 18:00', 'yesterday')
+        #[arg(long)]
+        until: Option<String>,
+        /// Só exibe commits cuja mensagem contém o texto
+        #[arg(long)]
+        grep: Option<String>,
+        /// Segue as renomeações do arquivo fornecido após '--'
+        #[arg(long)]
+        follow: bool,
+        /// Exibe as diferenças introduzidas por cada commit
+        #[arg(short, long)]
+        patch: bool,
+        /// Exibe o resumo das diferenças introduzidas por cada commit
+        #[arg(long)]
+        stat: bool,
+        /// Parte de todas as branches
+        #[arg(long)]
+        all: bool,
+        /// Namespaces de notas exibidos abaixo de cada commit, no lugar de 'commits'
+        #[arg(long = "notes", value_name = "REF")]
+        notes: Vec<String>,
+        /// Não exibe as notas dos commits
+        #[arg(long, conflicts_with = "notes")]
+        no_notes: bool,
+        /// Exibe os commits em JSON
+        #[arg(long, conflicts_with_all = ["oneline", "format", "graph", "patch", "stat"])]
+        json: bool
+    },
+    /// Exibe o commit, o autor e a data que introduziram cada linha do arquivo
+    #[command(visible_alias = "annotate")]
+    Blame {
+        /// Arquivo anotado
+        file: String,
+        /// Revisão cujo arquivo é anotado (HEAD por padrão)
+        revision: Option<String>,
+        /// Só exibe as linhas do intervalo ('10,20', '10,+5', '10,' ou ',20')
+        #[arg(short = 'L', value_name = "INÍCIO,FIM")]
+        lines: Option<String>,
+        /// Exibe as linhas no formato estável para scripts
+        #[arg(long)]
+        porcelain: bool,
+        /// Ignora mudanças só de espaços ao procurar a origem das linhas
+        #[arg(short = 'w')]
+        ignore_whitespace: bool,
+    },
+    /// Procura uma expressão regular nos arquivos rastreados, no índice ou em uma revisão
+    Grep {
+        /// Expressão regular procurada
+        pattern: String,
+        /// Revisão onde procurar, lida direto do banco de objetos (a worktree por padrão)
+        #[arg(conflicts_with = "cached")]
+        revision: Option<String>,
+        /// Só procura nesses caminhos (após '--')
+        #[arg(last = true)]
+        paths: Vec<String>,
+        /// Procura no conteúdo do índice em vez da worktree
+        #[arg(long)]
+        cached: bool,
+        /// Ignora a diferença entre maiúsculas e minúsculas
+        #[arg(short, long)]
+        ignore_case: bool,
+        /// Exibe o número de cada linha
+        #[arg(short = 'n', long)]
+        line_number: bool,
+        /// Só exibe o nome dos arquivos com ocorrências
+        #[arg(short = 'l', long, conflicts_with = "count")]
+        files_with_matches: bool,
+        /// Exibe a quantidade de linhas encontradas em cada arquivo
+        #[arg(short, long)]
+        count: bool,
+        /// Só aceita ocorrências que formam palavras inteiras
         #[arg(short, long)]
+        word_regexp: bool,
+        /// Linhas de contexto depois de cada ocorrência
+        #[arg(short = 'A', long, value_name = "N")]
+        after_context: Option<usize>,
+        /// Linhas de contexto antes de cada ocorrência
+        #[arg(short = 'B', long, value_name = "N")]
+        before_context: Option<usize>,
+        /// Linhas de contexto antes e depois de cada ocorrência
+        #[arg(short = 'C', long, value_name = "N")]
+        context: Option<usize>,
+    },
+    /// Busca, por divisão do histórico, o commit que introduziu um problema
+    Bisect {
+        #[command(subcommand)]
+        action: BisectCommand,
+    },
+    /// Lista, cria, deleta, renomeia ou copia branches
+    Branch {
+        /// Deleta a branch especificada, se ela já tiver sido mesclada no HEAD
+        #[arg(short, long, group = "action", requires = "branch_name")]
         delete: bool,
-        /// Nome da branch
-        branch_name: String
+        /// Deleta a branch especificada mesmo que ela não tenha sido mesclada
+        #[arg(short = 'D', group = "action", requires = "branch_name")]
+        force_delete: bool,
+        /// Renomeia a branch: '-m <novo nome>' (branch atual) ou '-m <branch> <novo nome>'
+        #[arg(short = 'm', long = "move", group = "action", requires = "branch_name")]
+        rename: bool,
+        /// Renomeia a branch mesmo que já exista uma branch com o novo nome
+        #[arg(short = 'M', group = "action", requires = "branch_name")]
+        force_rename: bool,
+        /// Copia a branch: '-c <novo nome>' (branch atual) ou '-c <branch> <novo nome>'
+        #[arg(short, long, group = "action", requires = "branch_name")]
+        copy: bool,
+        /// Faz a branch fornecida (ou a atual) seguir a branch UPSTREAM
+        #[arg(short = 'u', long, value_name = "UPSTREAM", group = "action", conflicts_with = "start_point")]
+        set_upstream_to: Option<String>,
+        /// Exibe o hash, a upstream e o assunto do último commit de cada branch
+        #[arg(short, long, conflicts_with = "branch_name")]
+        verbose: bool,
+        /// Lista só as branches já mescladas no commit (HEAD por padrão)
+        #[arg(long, value_name = "COMMIT", num_args = 0..=1, default_missing_value = "HEAD", conflicts_with = "branch_name")]
+        merged: Option<String>,
+        /// Lista só as branches ainda não mescladas no commit (HEAD por padrão)
+        #[arg(long, value_name = "COMMIT", num_args = 0..=1, default_missing_value = "HEAD", conflicts_with = "branch_name")]
+        no_merged: Option<String>,
+        /// Lista as branches no formato estável '<*| > <nome> <hash>'
+        #[arg(long, conflicts_with_all = ["branch_name", "verbose"])]
+        porcelain: bool,
+        /// Nome da branch. Sem nome, lista as branches existentes
+        branch_name: Option<String>,
+        /// Revisão onde a nova branch começa (HEAD por padrão) ou, com -m/-c, o novo nome
+        start_point: Option<String>,
     },
     /// Realiza o merge da branch especificada na branch atual
     Merge {
@@ -71,35 +229,161 @@ pub enum Commands {
     },
     /// Adiciona arquivos ao índice para o próximo commit
     Add {
-        /// Lista de arquivos a serem adicionados
-        files: Vec<String>
+        /// Arquivos, diretórios ou padrões (ex: '.', 'src', '*.rs', ':(exclude)testes')
+        files: Vec<String>,
+        /// Adiciona todos os arquivos novos, modificados e removidos
+        #[arg(short = 'A', long, conflicts_with_all = ["update", "patch"])]
+        all: bool,
+        /// Adiciona apenas modificações e remoções de arquivos já rastreados
+        #[arg(short, long, conflicts_with = "patch")]
+        update: bool,
+        /// Escolhe interativamente quais trechos modificados adicionar
+        #[arg(short, long)]
+        patch: bool,
+    },
+    /// Remove arquivos da worktree e do índice
+    /// Exibe quais caminhos são ignorados pelo .gitignore, .minigit/info/exclude ou core.excludesFile
+    CheckIgnore {
+        /// Caminhos a verificar
+        #[arg(required = true)]
+        paths: Vec<String>,
+        /// Exibe a regra e o arquivo de origem no formato '<origem>:<linha>:<padrão>\t<caminho>'
+        #[arg(short, long)]
+        verbose: bool,
+        /// Com -v, também exibe os caminhos que não casam com nenhuma regra
+        #[arg(short, long, requires = "verbose")]
+        non_matching: bool,
+        /// Não consulta o índice, verificando também arquivos rastreados
+        #[arg(long)]
+        no_index: bool,
+    },
+    /// Remove arquivos não rastreados da worktree
+    Clean {
+        /// Só exibe o que seria removido
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+        /// Remove de fato os arquivos (exigido a menos que clean.requireForce seja false)
+        #[arg(short, long)]
+        force: bool,
+        /// Remove também pastas não rastreadas
+        #[arg(short)]
+        d: bool,
+        /// Remove também os arquivos ignorados
+        #[arg(short, conflicts_with = "only_ignored")]
+        x: bool,
+        /// Remove só os arquivos ignorados
+        #[arg(short = 'X')]
+        only_ignored: bool,
+        /// Padrão de ignore adicional, no formato do .gitignore
+        #[arg(short, long = "exclude", value_name = "PADRÃO")]
+        e: Vec<String>,
+        /// Pergunta, caminho a caminho, o que deve ser removido
+        #[arg(short, long)]
+        interactive: bool,
+        /// Limita a limpeza a esses caminhos
+        paths: Vec<String>,
     },
-    /// Tira arquivos do índice para o próximo commit
     Rm {
-        /// Lista de arquivos a serem removidos
-        files: Vec<String>
+        /// Arquivos, diretórios ou padrões a serem removidos
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Remove apenas do índice, mantendo os arquivos na worktree
+        #[arg(long)]
+        cached: bool,
+        /// Permite remover diretórios recursivamente
+        #[arg(short)]
+        r: bool,
+        /// Remove mesmo que as mudanças nos arquivos sejam perdidas
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Move ou renomeia arquivos e diretórios rastreados na worktree e no índice
+    Mv {
+        /// Arquivos ou diretórios de origem seguidos do destino
+        #[arg(required = true, num_args = 2..)]
+        paths: Vec<String>,
+        /// Sobrescreve o arquivo de destino se ele já existir
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Restaura arquivos da worktree a partir do índice, ou do índice a partir do HEAD
+    Restore {
+        /// Arquivos, diretórios ou padrões a serem restaurados
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Restaura o índice (por padrão a partir do HEAD)
+        #[arg(short = 'S', long)]
+        staged: bool,
+        /// Restaura a worktree (padrão se --staged não for usado)
+        #[arg(short = 'W', long)]
+        worktree: bool,
+        /// Revisão de onde os arquivos são lidos (ex: 'HEAD~1', 'master')
+        #[arg(short, long)]
+        source: Option<String>,
+    },
+    /// Muda para outra branch, mantendo as mudanças locais que não conflitam
+    Switch {
+        /// Branch de destino ou, com -c, o ponto de partida da nova branch
+        branch: Option<String>,
+        /// Cria uma nova branch e muda para ela
+        #[arg(short, long, value_name = "NOVA_BRANCH")]
+        create: Option<String>,
     },
-    /// Muda para o commit especificado
+    /// Muda para o commit especificado, mantendo as mudanças locais que não conflitam
     Checkout {
         /// ID do commit para o qual mudar
-        commit_reference: String
+        commit_reference: String,
+        /// Descarta as mudanças locais dos arquivos rastreados
+        #[arg(short, long)]
+        force: bool
     },
     /// Cria um novo commit com as mudanças no índice
     Commit {
         /// Mensagem do commit
-        message: String
+        message: String,
+        /// Não executa os ganchos pre-commit e commit-msg
+        #[arg(long)]
+        no_verify: bool
     },
     /// Lista o conteúdo de uma árvore especificada
     LsTree {
         /// Hash da árvore a ser listada
-        tree_id: String
+        tree_id: String,
+        /// Exibe os arquivos em JSON
+        #[arg(long)]
+        json: bool
     },
-    /// Configura uma chave e valor no arquivo de configuração do Minigit
+    /// Lê e altera a configuração do Minigit (sistema, global, local e da worktree)
     Config {
-        /// Chave de configuração
-        key: String,
-        /// Valor de configuração
-        value: String,
+        /// Exibe o valor efetivo da chave
+        #[arg(long, group = "action", requires = "key", conflicts_with = "value")]
+        get: bool,
+        /// Remove a chave do arquivo de configuração
+        #[arg(long, group = "action", requires = "key", conflicts_with = "value")]
+        unset: bool,
+        /// Lista todos os valores no formato 'chave=valor'
+        #[arg(short, long, group = "action", conflicts_with = "key")]
+        list: bool,
+        /// Usa o arquivo global (~/.minigitconfig ou $MINIGIT_CONFIG_GLOBAL)
+        #[arg(long, group = "scope")]
+        global: bool,
+        /// Usa o arquivo do sistema (/etc/minigitconfig ou $MINIGIT_CONFIG_SYSTEM)
+        #[arg(long, group = "scope")]
+        system: bool,
+        /// Usa o arquivo do repositório (.minigit/config)
+        #[arg(long, group = "scope")]
+        local: bool,
+        /// Usa o arquivo da worktree (.minigit/config.worktree)
+        #[arg(long, group = "scope")]
+        worktree: bool,
+        /// Valida e normaliza o valor como booleano, inteiro ou caminho
+        #[arg(long = "type", value_name = "TIPO")]
+        value_type: Option<config::ConfigType>,
+        /// Chave de configuração, como 'user.name' ou 'branch.main.merge'
+        #[arg(required_unless_present = "list")]
+        key: Option<String>,
+        /// Valor de configuração. Sem valor, exibe o valor atual da chave
+        value: Option<String>,
     },
     /// Gera o hash SHA-1 de um arquivo e opcionalmente o armazena no repositório
     HashObject {
@@ -111,8 +395,73 @@ pub enum Commands {
     },
     /// Exibe o conteúdo de um objeto armazenado no repositório
     CatFile{ 
-        /// Hash SHA-1 do objeto a ser exibido
-        hash: String 
+        /// Objeto a ser exibido: hash (completo ou abreviado), revisão, 'rev:caminho' ou ':caminho'
+        #[arg(required_unless_present = "batch", conflicts_with = "batch")]
+        hash: Option<String>,
+        /// Exibe o objeto em JSON
+        #[arg(long, group = "mode")]
+        json: bool,
+        /// Exibe só o tipo do objeto
+        #[arg(short = 't', group = "mode")]
+        show_type: bool,
+        /// Exibe só o tamanho do conteúdo em bytes
+        #[arg(short = 's', group = "mode")]
+        size: bool,
+        /// Exibe o conteúdo formatado de acordo com o tipo
+        #[arg(short = 'p', group = "mode")]
+        pretty: bool,
+        /// Não exibe nada; sai com código 0 se o objeto existir e 1 se não existir
+        #[arg(short = 'e', group = "mode")]
+        exists: bool,
+        /// Lê um objeto por linha da entrada padrão e exibe '<hash> <tipo> <tamanho>' e o conteúdo de cada um
+        #[arg(long, group = "mode")]
+        batch: bool,
+    },
+    /// Verifica a integridade dos objetos, das referências e do índice
+    Fsck {
+        /// Lista todos os objetos inalcançáveis, não só os soltos
+        #[arg(long)]
+        unreachable: bool,
+        /// Não lista os objetos soltos
+        #[arg(long)]
+        no_dangling: bool,
+        /// Exibe o resultado em JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Anexa, exibe e remove notas de commits sem reescrevê-los
+    Notes {
+        /// Namespace das notas (ex: 'ci' para 'refs/notes/ci')
+        #[arg(long = "ref", global = true)]
+        notes_ref: Option<String>,
+        #[command(subcommand)]
+        action: NotesCommand,
+    },
+    /// Gera um arquivo tar, tar.gz ou zip com os arquivos de uma revisão, sem a pasta .minigit
+    Archive {
+        /// Revisão arquivada
+        revision: String,
+        /// Só inclui esses caminhos
+        paths: Vec<String>,
+        /// Formato do arquivo (deduzido da extensão de --output; tar por padrão)
+        #[arg(long)]
+        format: Option<ArchiveFormat>,
+        /// Prefixo adicionado ao caminho de cada arquivo (ex: 'projeto-1.0/')
+        #[arg(long, default_value = "")]
+        prefix: String,
+        /// Grava o arquivo no caminho fornecido em vez da saída padrão
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+    /// Cria, verifica e importa bundles: arquivos com commits e objetos para transferir histórico sem rede
+    Bundle {
+        #[command(subcommand)]
+        action: BundleCommand,
+    },
+    /// Exibe um commit com suas mudanças, a listagem de uma árvore ou o conteúdo de um blob
+    Show {
+        /// Objeto exibido: revisão, hash, 'rev:caminho' ou ':caminho' (HEAD por padrão)
+        object: Option<String>,
     },
     /// Muda o HEAD para apontar para um commit específico
     Reset {
@@ -123,45 +472,317 @@ pub enum Commands {
         commit_reference: String
     },
     /// Exibe o estado atual do diretório de trabalho e da staging area
-    Status
+    Status {
+        /// Exibe uma linha 'XY caminho' por arquivo, em formato estável para scripts
+        #[arg(long, conflicts_with = "json")]
+        porcelain: bool,
+        /// Exibe o status em JSON
+        #[arg(long)]
+        json: bool
+    }
 }
 
+/// Subcomandos do `bisect`
+#[derive(Subcommand)]
+pub enum BisectCommand {
+    /// Inicia a busca, opcionalmente com o commit ruim seguido dos commits bons
+    Start {
+        /// Commit em que o problema acontece
+        bad: Option<String>,
+        /// Commits em que o problema não acontece
+        good: Vec<String>,
+    },
+    /// Marca os commits (o HEAD por padrão) como bons
+    Good {
+        revisions: Vec<String>,
+    },
+    /// Marca o commit (o HEAD por padrão) como ruim
+    Bad {
+        revision: Option<String>,
+    },
+    /// Marca os commits (o HEAD por padrão) como impossíveis de testar
+    Skip {
+        revisions: Vec<String>,
+    },
+    /// Encerra a busca e volta para onde o HEAD estava, ou para o commit fornecido
+    Reset {
+        commit: Option<String>,
+    },
+    /// Exibe o registro da busca atual
+    Log,
+    /// Refaz a busca a partir de um registro salvo pelo 'bisect log'
+    Replay {
+        /// Arquivo com o registro
+        file: std::path::PathBuf,
+    },
+    /// Testa cada commit com o comando: 0 é bom, 125 pula e de 1 a 127 é ruim
+    Run {
+        /// Comando e seus argumentos
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+}
+
+/// Subcomandos do `bundle`
+#[derive(Subcommand)]
+pub enum BundleCommand {
+    /// Grava no arquivo os commits das revisões (ex: 'master', 'v1..master', '^antigo master')
+    Create {
+        /// Caminho do bundle
+        file: String,
+        /// Branches incluídas, intervalos 'A..B' e revisões excluídas '^A'
+        revisions: Vec<String>,
+        /// Inclui todas as branches
+        #[arg(long)]
+        all: bool,
+    },
+    /// Verifica se o bundle está íntegro e se o repositório tem os commits que ele exige
+    Verify {
+        file: String,
+    },
+    /// Importa os objetos do bundle e cria ou avança (fast-forward) as branches dele
+    Unbundle {
+        file: String,
+        /// Também move branches para commits que não descendem do atual
+        #[arg(short, long)]
+        force: bool,
+    },
+}
+
+/// Subcomandos do `notes`. Os commits são o HEAD quando não informados
+#[derive(Subcommand)]
+pub enum NotesCommand {
+    /// Cria a nota do commit
+    Add {
+        commit: Option<String>,
+        /// Conteúdo da nota
+        #[arg(short, long)]
+        message: String,
+        /// Substitui a nota existente
+        #[arg(short, long)]
+        force: bool,
+    },
+    /// Acrescenta o texto à nota do commit, separado por uma linha em branco
+    Append {
+        commit: Option<String>,
+        /// Texto acrescentado
+        #[arg(short, long)]
+        message: String,
+    },
+    /// Exibe a nota do commit
+    Show {
+        commit: Option<String>,
+    },
+    /// Lista '<nota> <commit>' de todas as notas, ou o hash da nota do commit fornecido
+    List {
+        commit: Option<String>,
+    },
+    /// Remove a nota dos commits
+    Remove {
+        commits: Vec<String>,
+    },
+}
 
 pub fn cli_main() {
     use Commands::*;
     let args = CliArgs::parse();
 
-    match args.command {
+    let result = match args.command {
         Init => init::cmd_init(),
         Clone { 
             repository_path, 
             destination_path 
         } => clone::cmd_clone(&repository_path, &destination_path),
-        Diff => diff::cmd_diff(),
-        Log => log::cmd_log(),
-        Branch { branch_name, delete } => branch::cmd_branch(branch_name, delete),
+        Diff { json } => diff::cmd_diff(json),
+        Blame { file, revision, lines, porcelain, ignore_whitespace } => {
+            let options = BlameOptions { revision, ignore_whitespace };
+            blame::cmd_blame(blame::BlameArgs { file, options, lines, porcelain })
+        },
+        Log { revisions, paths, max_count, oneline, format, graph, author, since, until, grep, follow, patch, stat, all, notes, no_notes, json } => {
+            let options = LogOptions { revisions, all, max_count, author, grep, since: None, until: None, paths, follow };
+            let notes = if no_notes {
+                Vec::new()
+            } else if notes.is_empty() {
+                vec![crate::notes::DEFAULT_NOTES_REF.to_string()]
+            } else {
+                notes
+            };
+            log::cmd_log(log::LogArgs { options, since, until, oneline, format, graph, patch, stat, notes, json })
+        },
+        Grep { pattern, revision, paths, cached, ignore_case, line_number, files_with_matches, count, word_regexp, after_context, before_context, context } => {
+            let source = match revision {
+                Some(revision) => crate::grep::GrepSource::Revision(revision),
+                None if cached => crate::grep::GrepSource::Index,
+                None => crate::grep::GrepSource::Worktree,
+            };
+            let options = crate::grep::GrepOptions {
+                pattern,
+                source,
+                paths,
+                ignore_case,
+                word: word_regexp,
+                before_context: before_context.or(context).unwrap_or(0),
+                after_context: after_context.or(context).unwrap_or(0),
+            };
+            let output = if files_with_matches {
+                grep::GrepOutput::FileNames
+            } else if count {
+                grep::GrepOutput::Count
+            } else {
+                grep::GrepOutput::Lines { line_numbers: line_number }
+            };
+            grep::cmd_grep(options, output)
+        },
+        Bisect { action } => {
+            use bisect::BisectAction;
+            let action = match action {
+                BisectCommand::Start { bad, good } => BisectAction::Start { bad, good },
+                BisectCommand::Good { revisions } => BisectAction::Good(revisions),
+                BisectCommand::Bad { revision } => BisectAction::Bad(revision),
+                BisectCommand::Skip { revisions } => BisectAction::Skip(revisions),
+                BisectCommand::Reset { commit } => BisectAction::Reset(commit),
+                BisectCommand::Log => BisectAction::Log,
+                BisectCommand::Replay { file } => BisectAction::Replay(file),
+                BisectCommand::Run { command } => BisectAction::Run(command),
+            };
+            bisect::cmd_bisect(action)
+        },
+        Branch { delete, force_delete, rename, force_rename, copy, set_upstream_to, verbose, merged, no_merged, porcelain, branch_name, start_point } => {
+            use branch::BranchAction;
+            let action = match (set_upstream_to, branch_name, start_point) {
+                (Some(upstream), branch, _) => Ok(BranchAction::SetUpstream { branch, upstream }),
+                (None, None, _) if delete || force_delete || rename || force_rename || copy => Err(
+                    MinigitError::InvalidArgument("Forneça o nome da branch para deletar, renomear ou copiar.".to_string())
+                ),
+                (None, None, _) => Ok(BranchAction::List { verbose, porcelain, merged, no_merged }),
+                (None, Some(name), _) if delete || force_delete => Ok(BranchAction::Delete { name, force: force_delete }),
+                (None, Some(new), None) if rename || force_rename => Ok(BranchAction::Rename { old: None, new, force: force_rename }),
+                (None, Some(old), Some(new)) if rename || force_rename => Ok(BranchAction::Rename { old: Some(old), new, force: force_rename }),
+                (None, Some(new), None) if copy => Ok(BranchAction::Copy { old: None, new }),
+                (None, Some(old), Some(new)) if copy => Ok(BranchAction::Copy { old: Some(old), new }),
+                (None, Some(name), start_point) => Ok(BranchAction::Create { name, start_point }),
+            };
+            match action {
+                Ok(action) => branch::cmd_branch(action),
+                Err(err) => {
+                    println!("{}", err);
+                    Err(err)
+                }
+            }
+        },
         Merge {branch_name, abort, continue_ } => {
             if continue_ || abort {
-                merge::cmd_merge(None, abort, continue_);
+                merge::cmd_merge(None, abort, continue_)
             } else {
                 if branch_name.is_none() {
                     println!("Erro: Forneça o nome da branch, --abort ou --continue");
-                    return;
+                    std::process::exit(MinigitError::InvalidArgument(String::new()).exit_code());
                 }
-                merge::cmd_merge(branch_name.as_ref(), abort, continue_);
+                merge::cmd_merge(branch_name.as_ref(), abort, continue_)
             }
 
         },
         Rebase { continue_, abort, new_base_branch } => {rebase::cmd_rebase(continue_, abort, new_base_branch)},
-        Add { files } => add::cmd_add(files),
-        Rm { files } => rm::cmd_rm(files),
-        Checkout { commit_reference } => checkout::cmd_checkout(&commit_reference),
-        Commit { message } => commit::cmd_commit(message),
-        LsTree { tree_id } => ls_tree::cmd_ls_tree(tree_id),
-        Config { key, value } => config::cmd_config(key, value),
+        Add { files, all, update, patch } => {
+            let mode = if all {
+                add::AddMode::All
+            } else if update {
+                add::AddMode::Update
+            } else if patch {
+                add::AddMode::Patch
+            } else {
+                add::AddMode::Pathspec
+            };
+            add::cmd_add(files, mode)
+        },
+        CheckIgnore { paths, verbose, non_matching, no_index } => check_ignore::cmd_check_ignore(paths, verbose, non_matching, no_index),
+        Clean { dry_run, force, d, x, only_ignored, e, interactive, paths } => {
+            let ignored = if x {
+                clean::IgnoredFiles::Include
+            } else if only_ignored {
+                clean::IgnoredFiles::Only
+            } else {
+                clean::IgnoredFiles::Keep
+            };
+            clean::cmd_clean(clean::CleanOptions { dry_run, force, directories: d, ignored, excludes: e, interactive, paths })
+        },
+        Rm { files, cached, r, force } => rm::cmd_rm(files, cached, r, force),
+        Mv { paths, force } => mv::cmd_mv(paths, force),
+        Restore { files, staged, worktree, source } => restore::cmd_restore(files, staged, worktree, source),
+        Switch { branch, create } => switch::cmd_switch(branch, create),
+        Checkout { commit_reference, force } => checkout::cmd_checkout(&commit_reference, force),
+        Commit { message, no_verify } => commit::cmd_commit(message, no_verify),
+        LsTree { tree_id, json } => ls_tree::cmd_ls_tree(tree_id, json),
+        Config { get: _, unset, list, global, system, local, worktree, value_type, key, value } => {
+            use config::ConfigAction;
+            use crate::config::ConfigScope;
+            let scope = [(global, ConfigScope::Global), (system, ConfigScope::System), (local, ConfigScope::Local), (worktree, ConfigScope::Worktree)]
+                .into_iter()
+                .find_map(|(selected, scope)| selected.then_some(scope));
+            let action = match (key, value) {
+                _ if list => ConfigAction::List,
+                (Some(key), _) if unset => ConfigAction::Unset { key },
+                (Some(key), Some(value)) => ConfigAction::Set { key, value },
+                (Some(key), None) => ConfigAction::Get { key },
+                (None, _) => ConfigAction::List,
+            };
+            config::cmd_config(action, scope, value_type)
+        },
         HashObject { write, file } => hash_rust::cmd_hash_object(&file, write),
-        CatFile { hash } => cat_file::cmd_cat_file(&hash),
+        CatFile { hash, json, show_type, size, pretty, exists, batch } => {
+            use cat_file::CatFileMode;
+            let mode = [
+                (json, CatFileMode::Json),
+                (show_type, CatFileMode::Type),
+                (size, CatFileMode::Size),
+                (pretty, CatFileMode::Pretty),
+                (exists, CatFileMode::Exists),
+                (batch, CatFileMode::Batch),
+            ]
+                .into_iter()
+                .find_map(|(selected, mode)| selected.then_some(mode))
+                .unwrap_or_default();
+            cat_file::cmd_cat_file(hash, mode)
+        },
+        Show { object } => show::cmd_show(object),
+        Notes { notes_ref, action } => {
+            use notes::NotesAction;
+            let action = match action {
+                NotesCommand::Add { commit, message, force } => NotesAction::Add { commit, message, force },
+                NotesCommand::Append { commit, message } => NotesAction::Append { commit, message },
+                NotesCommand::Show { commit } => NotesAction::Show(commit),
+                NotesCommand::List { commit } => NotesAction::List(commit),
+                NotesCommand::Remove { commits } => NotesAction::Remove(commits),
+            };
+            notes::cmd_notes(notes_ref, action)
+        },
+        Archive { revision, paths, format, prefix, output } => archive::cmd_archive(format, prefix, revision, paths, output),
+        Bundle { action } => {
+            use bundle::BundleAction;
+            let action = match action {
+                BundleCommand::Create { file, revisions, all } => BundleAction::Create { file, revisions, all },
+                BundleCommand::Verify { file } => BundleAction::Verify(file),
+                BundleCommand::Unbundle { file, force } => BundleAction::Unbundle { file, force },
+            };
+            bundle::cmd_bundle(action)
+        },
+        Fsck { unreachable, no_dangling, json } => {
+            fsck::cmd_fsck(crate::fsck::FsckOptions { unreachable, dangling: !no_dangling }, json)
+        },
         Reset { mode, commit_reference } => reset::cmd_reset(mode, &commit_reference),
-        Status => status::cmd_status(),
+        Status { porcelain, json } => {
+            let format = if json {
+                status::StatusFormat::Json
+            } else if porcelain {
+                status::StatusFormat::Porcelain
+            } else {
+                status::StatusFormat::Human
+            };
+            status::cmd_status(format)
+        },
+    };
+
+    if let Err(err) = result {
+        std::process::exit(err.exit_code());
     }
 }
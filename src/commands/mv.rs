@@ -0,0 +1,202 @@
+use std::{collections::HashSet, path::{Path, PathBuf}};
+
+use crate::{
+    Repository,
+    checks::ensure_no_detached_head,
+    error::MinigitError,
+    staging::{STAGE_NORMAL, StagingArea},
+    utils::{LockFile, find_current_repo, pathspec::relative_to_repository},
+};
+
+/// Move ou renomeia arquivos e diretórios rastreados
+///
+/// ## Argumentos
+/// - `paths` - Caminhos de origem seguidos do destino, relativos ao diretório atual
+/// - `force` - Sobrescreve o arquivo de destino se ele já existir
+pub fn cmd_mv(paths: Vec<String>, force: bool) -> Result<(), MinigitError> {
+    let result = cmd_mv_result(paths, force);
+    if let Err(e) = &result {
+        eprintln!("Erro ao mover arquivos: {}", e);
+        if matches!(e, MinigitError::AlreadyExists(_)) {
+            eprintln!("Use -f para sobrescrever o destino");
+        }
+    }
+    result
+}
+
+fn cmd_mv_result(paths: Vec<String>, force: bool) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
+
+    ensure_no_detached_head(&repo)?;
+
+    let Some((destination, sources)) = paths.split_last() else {
+        return Err(MinigitError::InvalidArgument("Forneça a origem e o destino".to_string()));
+    };
+
+    let sources = sources.iter()
+        .map(|source| relative_to_repository(&repo, source))
+        .collect::<Result<Vec<PathBuf>, MinigitError>>()?;
+    let destination = relative_to_repository(&repo, destination)?;
+
+    move_paths(&mut repo, &sources, &destination, force)?;
+    Ok(())
+}
+
+/// Move os arquivos e diretórios `sources` para `destination` na worktree e no índice
+///
+/// Se `destination` for um diretório existente, cada origem é movida para dentro dele; caso contrário,
+/// deve haver uma única origem, que é renomeada para `destination`. Todas as origens são validadas antes
+/// de qualquer mudança, e os arquivos já movidos voltam ao lugar se alguma operação falhar. Destinos
+/// sobrescritos com `force` são guardados numa cópia temporária até o índice ser gravado, para que também
+/// possam ser devolvidos.
+///
+/// Retorna os pares (origem, destino) movidos, relativos à raíz do repositório.
+pub fn move_paths(
+    repo: &mut Repository,
+    sources: &[PathBuf],
+    destination: &Path,
+    force: bool,
+) -> Result<Vec<(PathBuf, PathBuf)>, MinigitError> {
+    let mut lock = LockFile::acquire(&repo.index_path)?;
+    let mut staging_area = StagingArea::new(repo)?;
+    let destination_is_dir = repo.worktree.join(destination).is_dir();
+
+    if sources.is_empty() {
+        return Err(MinigitError::InvalidArgument("Forneça a origem e o destino".to_string()));
+    }
+    if sources.len() > 1 && !destination_is_dir {
+        return Err(MinigitError::InvalidArgument(format!("o destino '{}' não é um diretório", destination.display())));
+    }
+
+    let mut moves = Vec::new();
+    let mut targets = HashSet::new();
+    for source in sources {
+        let target = match (destination_is_dir, source.file_name()) {
+            (true, Some(name)) => destination.join(name),
+            _ => destination.to_path_buf(),
+        };
+
+        validate_move(repo, &staging_area, source, &target, force)?;
+
+        if !targets.insert(target.clone()) {
+            return Err(MinigitError::InvalidArgument(format!("mais de um arquivo seria movido para '{}'", target.display())));
+        }
+        moves.push((source.clone(), target));
+    }
+
+    let mut done: Vec<&(PathBuf, PathBuf)> = Vec::new();
+    let mut backups: Vec<(PathBuf, PathBuf)> = Vec::new();
+    for movement in &moves {
+        let absolute_target = repo.worktree.join(&movement.1);
+        if absolute_target.symlink_metadata().is_ok() {
+            let backup = backup_path(&absolute_target);
+            if let Err(err) = std::fs::rename(&absolute_target, &backup) {
+                undo_moves(repo, &done, &backups);
+                return Err(err.into());
+            }
+            backups.push((absolute_target.clone(), backup));
+        }
+
+        if let Err(err) = std::fs::rename(repo.worktree.join(&movement.0), &absolute_target) {
+            undo_moves(repo, &done, &backups);
+            return Err(err.into());
+        }
+        done.push(movement);
+    }
+
+    for (source, target) in &moves {
+        let entries: Vec<_> = staging_area.entries.iter()
+            .filter(|entry| entry.path.starts_with(source))
+            .cloned()
+            .collect();
+
+        staging_area.remove_entry_with_path(target);
+        for mut entry in entries {
+            staging_area.remove_entry_with_path(&entry.path);
+            entry.path = match entry.path.strip_prefix(source) {
+                Ok(rest) if !rest.as_os_str().is_empty() => target.join(rest),
+                _ => target.clone(),
+            };
+            staging_area.update_or_create_entry(entry);
+        }
+    }
+
+    let written = staging_area.serialize().and_then(|content| {
+        lock.write_all(&content)?;
+        lock.commit()
+    });
+    if let Err(err) = written {
+        undo_moves(repo, &done, &backups);
+        return Err(err);
+    }
+
+    for (_, backup) in &backups {
+        if let Err(err) = std::fs::remove_file(backup) {
+            eprintln!("Aviso: não foi possível apagar a cópia temporária '{}': {}", backup.display(), err);
+        }
+    }
+
+    Ok(moves)
+}
+
+/// Verifica se a origem pode ser movida para o destino sem perder dados
+fn validate_move(repo: &Repository, staging_area: &StagingArea, source: &Path, target: &Path, force: bool) -> Result<(), MinigitError> {
+    let absolute_source = repo.worktree.join(source);
+    let absolute_target = repo.worktree.join(target);
+
+    if source.as_os_str().is_empty() {
+        return Err(MinigitError::InvalidArgument("não é possível mover a raíz do repositório".to_string()));
+    }
+    if absolute_source.symlink_metadata().is_err() {
+        return Err(MinigitError::InvalidArgument(format!("'{}' não existe", source.display())));
+    }
+    if target == source {
+        return Err(MinigitError::InvalidArgument(format!("'{}' já está nesse caminho", source.display())));
+    }
+    if target.starts_with(source) {
+        return Err(MinigitError::InvalidArgument(format!("não é possível mover '{}' para dentro dele mesmo", source.display())));
+    }
+
+    let tracked: Vec<_> = staging_area.entries.iter().filter(|entry| entry.path.starts_with(source)).collect();
+    if tracked.is_empty() {
+        return Err(MinigitError::InvalidArgument(format!("'{}' não está sendo rastreado", source.display())));
+    }
+    if tracked.iter().any(|entry| entry.stage != STAGE_NORMAL) {
+        return Err(MinigitError::InvalidState(format!("'{}' tem conflitos não resolvidos", source.display())));
+    }
+
+    if !absolute_target.parent().is_some_and(|parent| parent.is_dir()) {
+        return Err(MinigitError::InvalidArgument(format!("o diretório de destino de '{}' não existe", target.display())));
+    }
+
+    let target_exists = absolute_target.symlink_metadata().is_ok();
+    if target_exists && (absolute_source.is_dir() || absolute_target.is_dir() || !force) {
+        return Err(MinigitError::AlreadyExists(format!("'{}' já existe", target.display())));
+    }
+
+    Ok(())
+}
+
+/// Caminho livre ao lado de `target` para guardar o arquivo sobrescrito enquanto o `mv` não termina
+fn backup_path(target: &Path) -> PathBuf {
+    let name = target.file_name().unwrap_or_default().to_string_lossy();
+    (0..)
+        .map(|attempt| target.with_file_name(format!(".{}.mv-backup{}", name, attempt)))
+        .find(|backup| backup.symlink_metadata().is_err())
+        .expect("há um nome livre para a cópia temporária")
+}
+
+/// Desfaz os movimentos já realizados, na ordem inversa, e devolve os destinos sobrescritos
+fn undo_moves(repo: &Repository, done: &[&(PathBuf, PathBuf)], backups: &[(PathBuf, PathBuf)]) {
+    for (source, target) in done.iter().rev() {
+        if let Err(err) = std::fs::rename(repo.worktree.join(target), repo.worktree.join(source)) {
+            eprintln!("Aviso: não foi possível devolver '{}' para '{}': {}", target.display(), source.display(), err);
+        }
+    }
+
+    for (target, backup) in backups.iter().rev() {
+        if let Err(err) = std::fs::rename(backup, target) {
+            eprintln!("Aviso: não foi possível restaurar '{}' a partir de '{}': {}", target.display(), backup.display(), err);
+        }
+    }
+}
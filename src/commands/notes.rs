@@ -0,0 +1,100 @@
+use crate::{
+    Repository,
+    error::MinigitError,
+    notes::{DEFAULT_NOTES_REF, get_note, list_notes, notes_ref, set_note},
+    utils::{find_current_repo, resolve_revision},
+};
+
+/// Ações do comando `notes`. Os commits são o HEAD quando não informados
+pub enum NotesAction {
+    /// Cria a nota do commit; com `force`, substitui a nota existente
+    Add { commit: Option<String>, message: String, force: bool },
+    /// Acrescenta o texto à nota do commit, separado por uma linha em branco, criando a nota se preciso
+    Append { commit: Option<String>, message: String },
+    /// Exibe a nota do commit
+    Show(Option<String>),
+    /// Lista `<nota> <commit>` de todas as notas, ou só o hash da nota do commit fornecido
+    List(Option<String>),
+    /// Remove a nota dos commits
+    Remove(Vec<String>),
+}
+
+pub fn cmd_notes(namespace: Option<String>, action: NotesAction) -> Result<(), MinigitError> {
+    let result = cmd_notes_result(namespace.as_deref().unwrap_or(DEFAULT_NOTES_REF), action);
+    if let Err(e) = &result {
+        eprintln!("Erro no notes: {}", e);
+    }
+    result
+}
+
+fn cmd_notes_result(namespace: &str, action: NotesAction) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
+    notes_ref(namespace)?;
+
+    match action {
+        NotesAction::Add { commit, message, force } => {
+            let commit = resolve_commit(&repo, commit.as_deref())?;
+            if !force && get_note(&repo, namespace, &commit)?.is_some() {
+                return Err(MinigitError::AlreadyExists(format!(
+                    "O commit {} já tem uma nota. Use -f para substituí-la ou 'notes append' para acrescentar a ela.",
+                    commit,
+                )));
+            }
+
+            set_note(&mut repo, namespace, &commit, Some(&note_text(&message)), "Notas adicionadas por 'minigit notes add'")?;
+        }
+        NotesAction::Append { commit, message } => {
+            let commit = resolve_commit(&repo, commit.as_deref())?;
+            let content = match get_note(&repo, namespace, &commit)? {
+                Some(existing) => format!("{}\n{}", note_text(&existing), note_text(&message)),
+                None => note_text(&message),
+            };
+
+            set_note(&mut repo, namespace, &commit, Some(&content), "Notas adicionadas por 'minigit notes append'")?;
+        }
+        NotesAction::Show(commit) => {
+            let commit = resolve_commit(&repo, commit.as_deref())?;
+            let note = get_note(&repo, namespace, &commit)?.ok_or_else(|| missing_note(&commit))?;
+            print!("{}", note);
+        }
+        NotesAction::List(Some(commit)) => {
+            let commit = resolve_commit(&repo, Some(&commit))?;
+            let entry = list_notes(&repo, namespace)?.into_iter()
+                .find(|entry| entry.commit == commit)
+                .ok_or_else(|| missing_note(&commit))?;
+            println!("{}", entry.note);
+        }
+        NotesAction::List(None) => {
+            for entry in list_notes(&repo, namespace)? {
+                println!("{} {}", entry.note, entry.commit);
+            }
+        }
+        NotesAction::Remove(commits) => {
+            let commits = if commits.is_empty() { vec![Repository::HEAD.to_string()] } else { commits };
+            for commit in commits {
+                let commit = resolve_commit(&repo, Some(&commit))?;
+                if get_note(&repo, namespace, &commit)?.is_none() {
+                    return Err(missing_note(&commit));
+                }
+
+                set_note(&mut repo, namespace, &commit, None, "Notas removidas por 'minigit notes remove'")?;
+                println!("Nota do commit {} removida", commit);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve_commit(repo: &Repository, commit: Option<&str>) -> Result<String, MinigitError> {
+    resolve_revision(repo, commit.unwrap_or(Repository::HEAD))
+}
+
+/// Texto da nota terminado por exatamente uma quebra de linha
+fn note_text(message: &str) -> String {
+    format!("{}\n", message.trim_end_matches('\n'))
+}
+
+fn missing_note(commit: &str) -> MinigitError {
+    MinigitError::InvalidArgument(format!("Nenhuma nota encontrada para o commit {}", commit))
+}
@@ -1,20 +1,17 @@
-use core::panic;
-use std::{collections::{HashMap, HashSet}, path::PathBuf, str::FromStr};
+use std::{collections::{HashMap, HashSet}, path::PathBuf};
 
-use crate::{Repository, checks::{ensure_no_detached_head, ensure_no_merge_in_progress, ensure_no_non_staged_files, ensure_no_rebase_in_progress, ensure_no_uncommited_changes, ensure_rebase_in_progress}, objects::{BlobObject, CommitObject, RGitObject, RGitObjectTypes, create_commit_object_from_index, create_tree_object_from_staging_tree, get_commit_tree_as_map, get_tree_as_map, instanciate_tree_files}, staging::{StagingTree, rewrite_index_from_commit_id}, utils::{find_current_repo, merge_rebase}};
+use crate::{Repository, commands::checkout, error::MinigitError, hooks::{Hook, run_hook}, checks::{ensure_no_detached_head, ensure_no_merge_in_progress, ensure_no_non_staged_files, ensure_no_rebase_in_progress, ensure_no_uncommited_changes, ensure_no_unresolved_conflicts, ensure_rebase_in_progress}, objects::{BlobObject, CommitObject, RGitObject, create_commit_object_from_index, create_tree_object_from_staging_tree, get_commit_files, get_commit_tree_as_map, get_commit_tree_files, get_tree_as_map, get_tree_files, pick_mode}, staging::{StagingTree, record_conflicts, rewrite_index_from_commit_id}, utils::{find_current_repo, merge_rebase::{self, OperationKind, OperationState}}};
 
-pub fn cmd_rebase(continue_: bool, abort: bool, new_base_reference: Option<String>) {
-    match cmd_rebase_result(continue_, abort, new_base_reference) {
-        Ok(_) => {},
-        Err(err) => {
-            println!("{}", err);
-        }
+pub fn cmd_rebase(continue_: bool, abort: bool, new_base_reference: Option<String>) -> Result<(), MinigitError> {
+    let result = cmd_rebase_result(continue_, abort, new_base_reference);
+    if let Err(err) = &result {
+        println!("{}", err);
     }
+    result
 }
 
-fn cmd_rebase_result(continue_: bool, abort: bool, new_base_reference: Option<String>) -> Result<(), String> {
-    let mut repo = find_current_repo()
-        .ok_or("Diretório não está dentro um repositório minigit")?;
+fn cmd_rebase_result(continue_: bool, abort: bool, new_base_reference: Option<String>) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
 
     if continue_ {
         continue_rebase(&mut repo)?;
@@ -23,68 +20,75 @@ fn cmd_rebase_result(continue_: bool, abort: bool, new_base_reference: Option<St
     } else if let Some(new_base_reference) = new_base_reference.clone() {
         initialize_rebase_command(&mut repo, new_base_reference)?;
     } else {
-        return Err("Você deve fornecer nova base de branch para rebase.".to_string());
+        return Err(MinigitError::InvalidArgument("Você deve fornecer nova base de branch para rebase.".to_string()));
     }
 
-    merge_rebase::finish(&mut repo, true);
     Ok(())
 }
 
-fn abort_rebase(repo: &mut Repository) -> Result<(), String> {
+fn abort_rebase(repo: &mut Repository) -> Result<(), MinigitError> {
     ensure_rebase_in_progress(repo)?;
-    merge_rebase::abort(repo, true);
-    
+    merge_rebase::abort(repo, OperationKind::Rebase)?;
+    println!("Rebase abortado com sucesso.");
+
     Ok(())
 }
 
 /// Inicia o processo de rebase no repositório atual
-fn initialize_rebase_command(repo: &mut Repository, new_base_reference: String) -> Result<(), String> {
+fn initialize_rebase_command(repo: &mut Repository, new_base_reference: String) -> Result<(), MinigitError> {
     ensure_no_uncommited_changes(repo)?;
     ensure_no_detached_head(repo)?;
     ensure_no_merge_in_progress(repo)?;
     ensure_no_rebase_in_progress(repo)?;
 
-    let exists = repo.reference_exists(&new_base_reference);
+    let exists = repo.reference_exists(&new_base_reference)?;
     if !exists {
-        return Err("A referência fornecida não existe.".to_string());
+        return Err(MinigitError::InvalidRef("A referência fornecida não existe.".to_string()));
     }
 
-    merge_rebase::start(repo, true);
-    start_rebase(new_base_reference, repo)?;
+    run_hook(repo, Hook::PreRebase, &[&new_base_reference])?;
 
-    Ok(())
+    start_rebase(new_base_reference, repo)
 }
 
-fn continue_rebase(repo: &mut Repository) -> Result<(), String> {
-    ensure_no_non_staged_files(repo)?;
+/// Continua o rebase: depois de conflitos, cria o commit com a resolução do índice; depois de uma
+/// interrupção (o processo morreu no meio de um passo), volta a branch e o índice para o último passo
+/// registrado no diário. Em seguida aplica os commits restantes.
+fn continue_rebase(repo: &mut Repository) -> Result<(), MinigitError> {
     ensure_rebase_in_progress(repo)?;
+    let mut state = merge_rebase::load(repo)?
+        .ok_or_else(|| MinigitError::InvalidState("Não há um rebase em progresso para prosseguir.".to_string()))?;
 
-    let commits_to_apply = merge_rebase::get_state(repo, true).iter()
-        .map(|hash| {
-            let RGitObjectTypes::Commit(commit) = repo.get_object(hash).unwrap() else {
-                panic!("Objeto referenciado por merge_head não é um commit");
-            };
-            commit
-        })
-        .collect::<Vec<CommitObject>>();
+    merge_rebase::sync_branch(repo, &state)?;
 
-    let original_commit = commits_to_apply.first().unwrap();
+    match state.stopped_sha.clone() {
+        Some(stopped_sha) => {
+            ensure_no_unresolved_conflicts(repo)?;
+            ensure_no_non_staged_files(repo)?;
 
-    let commit_hash = create_commit_object_from_index(repo, original_commit.message.clone());
-    repo.update_curr_branch(&commit_hash);
-    rewrite_index_from_commit_id(repo, &commit_hash);
+            let original_commit = repo.get_commit(&stopped_sha)?;
+            let commit_hash = create_commit_object_from_index(repo, original_commit.message.clone(), false)?;
+            state.record_step(repo, &commit_hash)?;
+            repo.update_branch_ref(&state.head_name, &commit_hash)?;
+            rewrite_index_from_commit_id(repo, &commit_hash)?;
+        }
+        None => {
+            // Não se sabe em que passo a worktree parou: os arquivos rastreados voltam para o passo atual
+            println!("Retomando o rebase interrompido a partir de {}.", state.current());
+            checkout::update_worktree(repo, state.current(), state.current(), true)?;
+        }
+    }
 
-    apply_commits(repo, commits_to_apply[1..].to_vec(), repo.get_head())?;
-    Ok(())
+    let worktree_commit = state.current().to_string();
+    apply_commits(repo, state, &worktree_commit)
 }
 
-fn start_rebase(new_base_reference: String, repo: &mut Repository) -> Result<(), String> {
-    let current_branch_ref_path = repo.get_head();
-    let current_branch_head = repo.resolve_head();
-    let new_base_head = repo.resolve_reference(&new_base_reference);
+fn start_rebase(new_base_reference: String, repo: &mut Repository) -> Result<(), MinigitError> {
+    let current_branch_head = repo.resolve_head()?;
+    let new_base_head = repo.resolve_reference(&new_base_reference)?;
 
-    let curr_branch_history = repo.get_commit_history_from_commit(&current_branch_head);
-    let new_base_history = repo.get_commit_history_from_commit(&new_base_head);
+    let curr_branch_history = repo.get_commit_history_from_commit(&current_branch_head)?;
+    let new_base_history = repo.get_commit_history_from_commit(&new_base_head)?;
     let base_commit = base_commit(&curr_branch_history, &new_base_history);
 
     let commits_to_apply: Vec<CommitObject> = if new_base_head.is_empty() {
@@ -100,61 +104,62 @@ fn start_rebase(new_base_reference: String, repo: &mut Repository) -> Result<(),
         return Ok(());
     }
 
-    repo.update_branch_ref(&current_branch_ref_path, &new_base_head);
-
-    apply_commits(repo, commits_to_apply, current_branch_ref_path)?;
-
-    let current_base_head = repo.resolve_head();
-    let RGitObjectTypes::Commit(current_base_head_commit) = repo
-        .get_object(&current_base_head)
-        .unwrap() else {
-            panic!("Objeto referenciado por current_base_head não é um commit");
-        };
-    let RGitObjectTypes::Tree(current_base_head_commit_tree) = repo
-        .get_object(&current_base_head_commit.tree)
-        .unwrap() else {
-            panic!("Objeto referenciado por current_base_head_commit.tree não é uma tree");
-        };
+    let todo = commits_to_apply.iter().map(|commit| commit.hash()).collect();
+    let state = merge_rebase::start(repo, OperationKind::Rebase, &new_base_head, todo)?;
+    merge_rebase::sync_branch(repo, &state)?;
 
-    instanciate_tree_files(repo, &current_base_head_commit_tree);
-    println!("Rebase concluído. HEAD atual: {}", current_base_head);
-    Ok(())
+    apply_commits(repo, state, &current_branch_head)
 }
 
-fn apply_commits(repo: &mut Repository, commits_to_apply: Vec<CommitObject>, current_branch_ref_path: String) -> Result<(), String> {
-    for (index, commit) in commits_to_apply.iter().enumerate() {
-        let current_base_head = repo.resolve_head();
-        let RGitObjectTypes::Commit(current_base_head_commit) = repo
-            .get_object(&current_base_head)
-            .unwrap() else {
-                panic!("Objeto referenciado por current_base_head não é um commit");
-            };
-
-        let (merge_tree_id, conflicts) = create_merge_tree(repo, &commit, &current_base_head_commit);
+/// Aplica os commits de `todo` um a um sobre o commit atual do diário. Cada passo é registrado
+/// no diário antes de a branch ser movida. Para no primeiro conflito, com os arquivos em conflito
+/// no índice e na worktree, e finaliza o rebase quando não houver mais commits.
+///
+/// O índice e a worktree só são atualizados ao parar ou terminar, a partir de `worktree_commit` (o commit
+/// que eles refletem), mantendo os arquivos não rastreados.
+fn apply_commits(repo: &mut Repository, mut state: OperationState, worktree_commit: &str) -> Result<(), MinigitError> {
+    while let Some(original_hash) = state.todo.first().cloned() {
+        let commit = repo.get_commit(&original_hash)?;
+        let current_base_head = state.current().to_string();
+        let current_base_head_commit = repo.get_commit(&current_base_head)?;
+
+        let (merge_tree_id, conflicts) = create_merge_tree(repo, &commit, &current_base_head_commit)?;
         
         if conflicts.is_empty() {
-            let rebase_commit_id = create_rebase_commit(repo, &commit, current_base_head.clone(), merge_tree_id);
-            repo.update_branch_ref(&current_branch_ref_path, &rebase_commit_id);
-            rewrite_index_from_commit_id(repo, &rebase_commit_id);
+            let rebase_commit_id = create_rebase_commit(repo, &commit, current_base_head, merge_tree_id)?;
+            state.record_step(repo, &rebase_commit_id)?;
+            repo.update_branch_ref(&state.head_name, &rebase_commit_id)?;
         } else {
-            let remaining_commits: Vec<CommitObject> = commits_to_apply[index..].to_vec();
-            let RGitObjectTypes::Tree(merge_tree_obj) = repo
-                .get_object(&merge_tree_id)
-                .unwrap() else {
-                    panic!("Objeto referenciado por merge_tree_id não é uma tree");
-                };
-            let merge_tree_files = get_tree_as_map(repo, &merge_tree_obj);
+            let merge_tree_obj = repo.get_tree(&merge_tree_id)?;
+            let merge_tree_files = get_tree_as_map(repo, &merge_tree_obj)?;
             let non_conflict_files = get_non_conflict_files(&merge_tree_files, &conflicts);
 
-            instanciate_tree_files(repo, &merge_tree_obj);
-            repo.add_files(non_conflict_files);
-            interrupt_rebase(repo, remaining_commits);
-
-            let conflict_messages = get_conflict_files_messages(&conflicts);
-            return Err(format!("Conflitos encontrados durante o rebase nos arquivos:\n{}", conflict_messages));
+            let from_files = get_commit_files(repo, worktree_commit)?;
+            checkout::update_worktree_files(repo, &from_files, &get_tree_files(repo, &merge_tree_obj)?, false)?;
+            repo.add_files(non_conflict_files)?;
+            let parent_files = match commit.parent.first() {
+                Some(parent) => get_commit_tree_as_map(repo, &repo.get_commit(parent)?)?,
+                None => HashMap::new(),
+            };
+            record_conflicts(
+                repo,
+                &conflicts,
+                &parent_files,
+                &get_commit_tree_as_map(repo, &current_base_head_commit)?,
+                &get_commit_tree_as_map(repo, &commit)?,
+            )?;
+            state.record_stop(repo, &original_hash)?;
+
+            let mut conflict_files: Vec<String> = conflicts.into_iter().collect();
+            conflict_files.sort();
+            return Err(MinigitError::Conflict(conflict_files));
         }
     }
 
+    let current_base_head = state.current().to_string();
+    checkout::update_worktree(repo, worktree_commit, &current_base_head, false)?;
+    merge_rebase::finish(repo)?;
+    println!("Rebase concluído. HEAD atual: {}", current_base_head);
     Ok(())
 }
 
@@ -163,8 +168,8 @@ fn create_rebase_commit(
     original_commit: &CommitObject,
     current_branch_head: String,
     merge_tree_id: String, 
-) -> String {
-    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos();
+) -> Result<String, MinigitError> {
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos();
 
     let rebase_commit = CommitObject {
         tree: merge_tree_id,
@@ -177,16 +182,6 @@ fn create_rebase_commit(
     repo.create_object(&rebase_commit)
 }
 
-fn interrupt_rebase(repo: &mut Repository, not_applied_commits: Vec<CommitObject>) {
-    let mut rebase_head_content = String::new();
-    for commit in not_applied_commits {
-        rebase_head_content.push_str(&commit.hash());
-        rebase_head_content.push('\n');
-    }
-
-    std::fs::write(&repo.rebase_head_path, rebase_head_content).unwrap();
-}
-
 /// Retorna o commit base comum entre os dois históricos, se existir
 fn base_commit(history_a: &Vec<CommitObject>, history_b: &Vec<CommitObject>) -> Option<CommitObject> {
     for commit_a in history_a {
@@ -221,10 +216,11 @@ fn commits_to_apply(curr_branch_history: Vec<CommitObject>, base_commit: &Option
 
 /// Cria uma tree no repositório representando o merge commit_a e commit_b.
 /// Retorna o hash da nova tree criada caso não haja conflitos e a lista dos arquivos que deram conflito.
-/// Esta função deve entrar em pânico se ocorrer um erro inesperado.
-fn create_merge_tree(repo: &mut Repository, commit_a: &CommitObject, commit_b: &CommitObject) -> (String, HashSet<String>){
-    let commit_a_tree: HashMap<String, String> = get_commit_tree_as_map(repo, commit_a);
-    let commit_b_tree: HashMap<String, String> = get_commit_tree_as_map(repo, commit_b);
+fn create_merge_tree(repo: &mut Repository, commit_a: &CommitObject, commit_b: &CommitObject) -> Result<(String, HashSet<String>), MinigitError> {
+    let commit_a_tree: HashMap<String, String> = get_commit_tree_as_map(repo, commit_a)?;
+    let commit_b_tree: HashMap<String, String> = get_commit_tree_as_map(repo, commit_b)?;
+    let commit_a_files = get_commit_tree_files(repo, commit_a)?;
+    let commit_b_files = get_commit_tree_files(repo, commit_b)?;
     let mut merge_commit_tree: HashMap<String, String> = commit_b_tree.clone();
     let mut conflicts: HashSet<String> = HashSet::new();
 
@@ -240,34 +236,26 @@ fn create_merge_tree(repo: &mut Repository, commit_a: &CommitObject, commit_b: &
     
     let mut merge_staging_tree = StagingTree::Fork(HashMap::new());
     for (file_path, hash_obj) in &merge_commit_tree {
-        merge_staging_tree.insert(hash_obj.clone(), PathBuf::from_str(&file_path).unwrap());
+        let mode = pick_mode(file_path, hash_obj, &[&commit_a_files, &commit_b_files]);
+        merge_staging_tree.insert(hash_obj.clone(), mode, PathBuf::from(file_path));
     }
     
     if !conflicts.is_empty() {
         for conflicted_file_path in &conflicts {
-            let commit_a_blob = repo.get_object(&commit_a_tree[conflicted_file_path]).unwrap();
-            let commit_b_blob = repo.get_object(&commit_b_tree[conflicted_file_path]).unwrap();
-
-            let commit_a_blob = match commit_a_blob {
-                RGitObjectTypes::Blob(blob) => blob.content,
-                _ => panic!("Objeto esperado é um blob"),
-            };
+            let commit_a_blob = repo.get_blob(&commit_a_tree[conflicted_file_path])?.content;
+            let commit_b_blob = repo.get_blob(&commit_b_tree[conflicted_file_path])?.content;
 
-            let commit_b_blob = match commit_b_blob {
-                RGitObjectTypes::Blob(blob) => blob.content,
-                _ => panic!("Objeto esperado é um blob"),
-            };
-
-            let blob_id = create_conflict_blob(repo, commit_a_blob, commit_b_blob);
-            merge_staging_tree.insert(blob_id, PathBuf::from_str(conflicted_file_path).unwrap());
+            let blob_id = create_conflict_blob(repo, commit_a_blob, commit_b_blob)?;
+            let mode = pick_mode(conflicted_file_path, &blob_id, &[&commit_a_files, &commit_b_files]);
+            merge_staging_tree.insert(blob_id, mode, PathBuf::from(conflicted_file_path));
         }
     }
 
-    let merge_tree_id = create_tree_object_from_staging_tree(&merge_staging_tree, repo);
-    (merge_tree_id, conflicts)
+    let merge_tree_id = create_tree_object_from_staging_tree(&merge_staging_tree, repo)?;
+    Ok((merge_tree_id, conflicts))
 }
 
-pub fn create_conflict_blob(repo: &mut Repository, content_a: Vec<u8>, content_b: Vec<u8>) -> String {
+pub fn create_conflict_blob(repo: &mut Repository, content_a: Vec<u8>, content_b: Vec<u8>) -> Result<String, MinigitError> {
     let mut conflict_content: Vec<u8> = Vec::new();
     conflict_content.extend_from_slice(b"<<<<<<< HEAD\n");
     conflict_content.extend_from_slice(&content_a);
@@ -286,14 +274,6 @@ fn get_non_conflict_files(merge_tree_files: &HashMap<String, String>, conflicts:
     merge_tree_files
         .keys()
         .filter(|file_path_str| conflicts.get(*file_path_str).is_none())
-        .cloned()
-        .map(|file_path_str| PathBuf::from_str(&file_path_str).unwrap())
+        .map(PathBuf::from)
         .collect()
 }
-
-fn get_conflict_files_messages(conflicts: &HashSet<String>) -> String {
-    conflicts.iter()
-        .map(|file_path| format!("- {}", file_path))
-        .collect::<Vec<String>>()
-        .join("\n")
-}
@@ -1,12 +1,10 @@
 use clap::ValueEnum;
 
-use crate::commands::checkout::instanciate_commit;
-use crate::{Repository, staging};
+use crate::commands::checkout::{instanciate_commit, run_post_checkout_hook};
+use crate::{Repository, error::MinigitError, staging};
 use crate::checks::{ensure_no_detached_head, ensure_no_merge_in_progress, ensure_no_rebase_in_progress};
-use crate::objects::{CommitObject, RGitObjectTypes};
 use crate::staging::staging_area_from_commit;
-use crate::utils::{find_current_repo};
-use std::fs;
+use crate::utils::{find_current_repo, write_locked};
 
 #[derive(Copy, Clone, PartialEq, Eq, ValueEnum, Debug)]
 pub enum ResetTypes {
@@ -15,17 +13,16 @@ pub enum ResetTypes {
     Hard,
 }
 
-pub fn cmd_reset(mode: ResetTypes, commit_reference: &String) {
-    match reset_command_result(mode, commit_reference) {
-        Ok(_) => {},
-        Err(err) => {
-            println!("{}", err);
-        }
+pub fn cmd_reset(mode: ResetTypes, commit_reference: &String) -> Result<(), MinigitError> {
+    let result = reset_command_result(mode, commit_reference);
+    if let Err(err) = &result {
+        println!("{}", err);
     }
+    result
 }
 
-pub fn reset_command_result(mode: ResetTypes, commit_reference: &String) -> Result<(), String> {
-    let mut repo = find_current_repo().ok_or("Não é um repositório minigit")?;
+pub fn reset_command_result(mode: ResetTypes, commit_reference: &String) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
     ensure_no_detached_head(&repo)?;
     ensure_no_rebase_in_progress(&repo)?;
     ensure_no_merge_in_progress(&repo)?;
@@ -33,20 +30,15 @@ pub fn reset_command_result(mode: ResetTypes, commit_reference: &String) -> Resu
     reset(&mut repo, commit_reference, mode)
 }
 
-pub fn reset(repo: &mut Repository, commit_reference: &String, mode: ResetTypes) -> Result<(), String> {
-    if !repo.reference_exists(commit_reference) {
-        return Err("Referência para commit inválida. Deve ser um hash ou nome de branch existente".to_string());
+pub fn reset(repo: &mut Repository, commit_reference: &String, mode: ResetTypes) -> Result<(), MinigitError> {
+    if !repo.reference_exists(commit_reference)? {
+        return Err(MinigitError::InvalidRef("Referência para commit inválida. Deve ser um hash ou nome de branch existente".to_string()));
     }
 
-    let commit_hash = repo.resolve_reference(commit_reference);
+    let commit_hash = repo.resolve_reference(commit_reference)?;
+    let previous_head = repo.resolve_head()?;
 
-    // let RGitObjectTypes::Commit(commit_object) = repo
-    //     .get_object(&commit_hash)
-    //     .ok_or("Commit não encontrado no repositório")?
-    //     else {
-    //         panic!("Referência não é um commit");
-    //     };
-    repo.update_head(&commit_hash.to_string());
+    repo.update_head(&commit_hash.to_string())?;
 
     match mode {
         ResetTypes::Soft => {
@@ -56,13 +48,13 @@ pub fn reset(repo: &mut Repository, commit_reference: &String, mode: ResetTypes)
             let index_path = repo.get_repository_path(&["index"]);
 
             if commit_hash.is_empty() {
-                fs::write(index_path, "").unwrap();
+                write_locked(&index_path, "")?;
                 return Ok(());
             }
 
-            let commit_object = get_commit_object(&repo, &commit_hash);
-            let staging_area = staging_area_from_commit(&repo, &commit_object);
-            staging::rewrite_index(repo, &staging_area);
+            let commit_object = repo.get_commit(&commit_hash)?;
+            let staging_area = staging_area_from_commit(repo, &commit_object)?;
+            staging::rewrite_index(repo, &staging_area)?;
 
             println!("Mixed reset feito para {}", commit_hash);
         }
@@ -70,16 +62,18 @@ pub fn reset(repo: &mut Repository, commit_reference: &String, mode: ResetTypes)
             let index_path = repo.get_repository_path(&["index"]);
             
             if commit_hash.is_empty() {
-                fs::write(index_path, "").unwrap();
-                repo.clear_worktree();
+                write_locked(&index_path, "")?;
+                repo.clear_worktree()?;
+                run_post_checkout_hook(repo, &previous_head)?;
                 return Ok(());
             }
 
-            let commit_object = get_commit_object(&repo, &commit_hash);
-            let staging_area = staging_area_from_commit(&repo, &commit_object);
-            staging::rewrite_index(repo, &staging_area);
-            repo.clear_worktree();
-            instanciate_commit(commit_object, repo);
+            let commit_object = repo.get_commit(&commit_hash)?;
+            let staging_area = staging_area_from_commit(repo, &commit_object)?;
+            staging::rewrite_index(repo, &staging_area)?;
+            repo.clear_worktree()?;
+            instanciate_commit(commit_object, repo)?;
+            run_post_checkout_hook(repo, &previous_head)?;
 
             println!("Hard reset feito para {}", commit_hash);
         }
@@ -87,14 +81,3 @@ pub fn reset(repo: &mut Repository, commit_reference: &String, mode: ResetTypes)
 
     Ok(())
 }
-
-fn get_commit_object(repo: &Repository, commit_hash: &String) -> CommitObject {
-    let RGitObjectTypes::Commit(commit_object) = repo
-        .get_object(commit_hash)
-        .unwrap()
-        else {
-            panic!("Referência não é um commit");
-        };
-
-    commit_object
-}
@@ -0,0 +1,106 @@
+use std::{collections::BTreeSet, path::PathBuf};
+
+use crate::{
+    Repository,
+    error::MinigitError,
+    objects::get_commit_files,
+    staging::{STAGE_NORMAL, StagingArea, StagingEntry},
+    utils::{LockFile, find_current_repo, pathspec::Pathspec, remove_worktree_file, resolve_revision, write_worktree_file},
+};
+
+/// Restaura arquivos do índice ou de um commit
+///
+/// ## Argumentos
+/// - `files` - Pathspec dos arquivos a restaurar
+/// - `staged` - Restaura o índice (por padrão a partir do HEAD)
+/// - `worktree` - Restaura a worktree (por padrão a partir do índice). É o padrão se nenhum dos dois for passado
+/// - `source` - Revisão de onde os arquivos são lidos
+pub fn cmd_restore(files: Vec<String>, staged: bool, worktree: bool, source: Option<String>) -> Result<(), MinigitError> {
+    let result = cmd_restore_result(files, staged, worktree, source);
+    if let Err(e) = &result {
+        eprintln!("Erro ao restaurar arquivos: {}", e);
+    }
+    result
+}
+
+fn cmd_restore_result(files: Vec<String>, staged: bool, worktree: bool, source: Option<String>) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
+    let pathspec = Pathspec::parse(&repo, &files)?;
+
+    restore(&mut repo, &pathspec, source.as_deref(), staged, worktree || !staged)?;
+    Ok(())
+}
+
+/// Restaura os arquivos selecionados pela pathspec no índice e/ou na worktree
+///
+/// Sem `source`, o índice é restaurado a partir do HEAD e a worktree a partir do índice.
+/// Arquivos rastreados que não existem na origem são removidos.
+///
+/// Retorna os caminhos restaurados, relativos à raíz do repositório.
+pub fn restore(
+    repo: &mut Repository,
+    pathspec: &Pathspec,
+    source: Option<&str>,
+    staged: bool,
+    worktree: bool,
+) -> Result<Vec<PathBuf>, MinigitError> {
+    if pathspec.is_empty() {
+        return Err(MinigitError::InvalidArgument("Nenhum caminho fornecido. Use 'restore .' para restaurar tudo".to_string()));
+    }
+
+    let mut lock = LockFile::acquire(&repo.index_path)?;
+    let mut staging_area = StagingArea::new(repo)?;
+
+    let source_files = match source {
+        Some(revision) => get_commit_files(repo, &resolve_revision(repo, revision)?)?,
+        None if staged => get_commit_files(repo, &repo.resolve_head()?)?,
+        None => staging_area.entries.iter()
+            .filter(|entry| entry.stage == STAGE_NORMAL)
+            .map(|entry| (entry.path.to_string_lossy().to_string(), entry.tree_file()))
+            .collect(),
+    };
+
+    let tracked: BTreeSet<PathBuf> = staging_area.entries.iter().map(|entry| entry.path.clone()).collect();
+    let candidates: BTreeSet<PathBuf> = source_files.keys().map(PathBuf::from).chain(tracked).collect();
+
+    let unmatched = pathspec.unmatched(candidates.iter());
+    if !unmatched.is_empty() {
+        return Err(MinigitError::InvalidArgument(format!("pathspec '{}' não corresponde a nenhum arquivo conhecido", unmatched.join("', '"))));
+    }
+
+    let paths: Vec<PathBuf> = candidates.into_iter().filter(|path| pathspec.matches(path)).collect();
+
+    if worktree && !staged && source.is_none() {
+        let conflicted: Vec<String> = staging_area.conflicted_paths().into_iter()
+            .filter(|path| paths.contains(path))
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        if !conflicted.is_empty() {
+            return Err(MinigitError::Conflict(conflicted));
+        }
+    }
+
+    for path in &paths {
+        let source_file = source_files.get(path.to_string_lossy().as_ref());
+
+        if worktree {
+            match source_file {
+                Some(file) => write_worktree_file(repo, path, &repo.get_blob(&file.hash)?.content, file.mode)?,
+                None => remove_worktree_file(repo, path)?,
+            }
+        }
+
+        if staged {
+            match source_file {
+                Some(file) => staging_area.update_or_create_entry(StagingEntry::from_tree_file(file, path.clone())),
+                None => staging_area.remove_entry_with_path(path),
+            }
+        }
+    }
+
+    staging_area.refresh(repo)?;
+    lock.write_all(&staging_area.serialize()?)?;
+    lock.commit()?;
+
+    Ok(paths)
+}
@@ -1,27 +1,99 @@
-use std::fs;
+use std::{collections::{BTreeSet, HashMap}, path::PathBuf};
 
-use crate::{checks::ensure_no_detached_head, commands::add::get_paths_relative_to_repository, staging::{StagingArea}, utils::find_current_repo};
+use crate::{
+    Repository,
+    checks::ensure_no_detached_head,
+    error::MinigitError,
+    objects::get_commit_tree_as_map,
+    staging::StagingArea,
+    utils::{find_current_repo, is_worktree_file, pathspec::Pathspec, remove_worktree_file},
+};
 
-pub fn cmd_rm(files: Vec<String>) {
-    match cmd_rm_result(files) {
-        Ok(_) => {}
-        Err(e) => eprintln!("Erro ao remover arquivos do índice: {}", e),
+/// Remove arquivos do índice e, sem `cached`, também da worktree
+///
+/// ## Argumentos
+/// - `files` - Pathspec dos arquivos a remover
+/// - `cached` - Remove apenas do índice, mantendo o arquivo na worktree
+/// - `recursive` - Permite remover diretórios inteiros
+/// - `force` - Remove mesmo se o arquivo tiver mudanças que seriam perdidas
+pub fn cmd_rm(files: Vec<String>, cached: bool, recursive: bool, force: bool) -> Result<(), MinigitError> {
+    let result = cmd_rm_result(files, cached, recursive, force);
+    if let Err(e) = &result {
+        eprintln!("Erro ao remover arquivos: {}", e);
+        if matches!(e, MinigitError::DirtyWorktree(_)) {
+            eprintln!("Use --cached para manter os arquivos na worktree ou -f para forçar a remoção");
+        }
     }
+    result
 }
 
-fn cmd_rm_result(files: Vec<String>) -> Result<(), String> {
-    let repo = find_current_repo().ok_or("Não é um repositório minigit")?;
+fn cmd_rm_result(files: Vec<String>, cached: bool, recursive: bool, force: bool) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
 
     ensure_no_detached_head(&repo)?;
 
-    let repository_paths = get_paths_relative_to_repository(&repo, &files);
-    let mut staging_area = StagingArea::new(&repo);
-    for path in repository_paths {
-        staging_area.remove_entry_with_path(&path);
+    let pathspec = Pathspec::parse(&repo, &files)?;
+    let staging_area = StagingArea::new(&repo)?;
+    let tracked: BTreeSet<PathBuf> = staging_area.entries.iter().map(|entry| entry.path.clone()).collect();
+
+    let unmatched = pathspec.unmatched(tracked.iter());
+    if !unmatched.is_empty() {
+        return Err(MinigitError::InvalidArgument(format!("pathspec '{}' não corresponde a nenhum arquivo rastreado", unmatched.join("', '"))));
+    }
+
+    let directories = pathspec.directories(tracked.iter());
+    if let (false, Some(directory)) = (recursive, directories.first()) {
+        return Err(MinigitError::InvalidArgument(format!("'{}' não será removido recursivamente sem -r", directory)));
+    }
+
+    let paths_to_remove: Vec<PathBuf> = tracked.into_iter().filter(|path| pathspec.matches(path)).collect();
+
+    if !force {
+        let unsafe_paths = paths_with_changes(&repo, &staging_area, &paths_to_remove, cached)?;
+        if !unsafe_paths.is_empty() {
+            return Err(MinigitError::DirtyWorktree(unsafe_paths));
+        }
     }
 
-    let index_bytes = staging_area.serialize();
-    fs::write(repo.index_path, index_bytes).unwrap();
+    repo.remove_files(paths_to_remove.clone())?;
+
+    for path in &paths_to_remove {
+        if !cached {
+            remove_worktree_file(&repo, path)?;
+        }
+        println!("rm '{}'", path.display());
+    }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Retorna os arquivos cuja remoção perderia mudanças
+///
+/// Sem `cached`, o arquivo não pode ter mudanças no índice em relação ao HEAD nem na worktree em relação ao índice.
+/// Com `cached`, a versão do índice precisa ser igual à do HEAD ou à da worktree.
+fn paths_with_changes(repo: &Repository, staging_area: &StagingArea, paths: &[PathBuf], cached: bool) -> Result<Vec<PathBuf>, MinigitError> {
+    let head = repo.resolve_head()?;
+    let head_files = if head.is_empty() {
+        HashMap::new()
+    } else {
+        get_commit_tree_as_map(repo, &repo.get_commit(&head)?)?
+    };
+
+    let mut result = Vec::new();
+    for path in paths {
+        let Some(entry) = staging_area.find_entry(path) else {
+            // Arquivos em conflito não têm entrada normal e podem ser removidos
+            continue;
+        };
+
+        let matches_head = head_files.get(path.to_string_lossy().as_ref()) == Some(&entry.object_hash);
+        let matches_worktree = !is_worktree_file(&repo.worktree.join(path)) || !staging_area.is_modified(repo, entry)?;
+
+        let safe = if cached { matches_head || matches_worktree } else { matches_head && matches_worktree };
+        if !safe {
+            result.push(path.clone());
+        }
+    }
+
+    Ok(result)
+}
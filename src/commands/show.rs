@@ -0,0 +1,73 @@
+use std::io::Write;
+
+use crate::{
+    Repository,
+    commands::log::{color_patch, format_full, format_notes, load_notes},
+    diff::{diff_commit, diff_merge, format_combined, format_unified},
+    error::MinigitError,
+    history::LogEntry,
+    notes::DEFAULT_NOTES_REF,
+    objects::{CommitObject, MODE_TREE, RGitObjectTypes, TreeObject, parse_mode},
+    utils::{find_current_repo, resolve_object, terminal},
+};
+
+/// Linhas inalteradas exibidas ao redor de cada mudança
+const DIFF_CONTEXT: usize = 3;
+
+pub fn cmd_show(object: Option<String>) -> Result<(), MinigitError> {
+    let result = cmd_show_result(object.as_deref().unwrap_or(Repository::HEAD));
+    if let Err(e) = &result {
+        eprintln!("Erro no show: {}", e);
+    }
+    result
+}
+
+fn cmd_show_result(spec: &str) -> Result<(), MinigitError> {
+    let repo = find_current_repo()?;
+    let object_id = resolve_object(&repo, spec)?;
+
+    match repo.get_object(&object_id)? {
+        RGitObjectTypes::Commit(commit) => terminal::page(&format_commit(&repo, object_id, commit)?),
+        RGitObjectTypes::Tree(tree) => terminal::page(&format_tree(spec, &tree)),
+        RGitObjectTypes::Blob(blob) => {
+            std::io::stdout().write_all(&blob.content)?;
+            Ok(())
+        }
+    }
+}
+
+/// Dados do commit e notas como no `log`, seguidos das mudanças em relação ao primeiro pai
+/// ou, para merges, do diff combinado com todos os pais
+fn format_commit(repo: &Repository, hash: String, commit: CommitObject) -> Result<String, MinigitError> {
+    let entry = LogEntry { hash, commit };
+    let mut text = format_full(&entry);
+    text.push_str(&format_notes(repo, &entry.hash, &load_notes(repo, &[DEFAULT_NOTES_REF.to_string()])?)?);
+
+    if entry.commit.parent.len() > 1 {
+        for combined in diff_merge(repo, &entry.commit)? {
+            text.push_str(&color_patch(&format_combined(&combined, DIFF_CONTEXT)));
+        }
+    } else {
+        for file_diff in diff_commit(repo, &entry.commit)? {
+            text.push_str(&color_patch(&format_unified(&file_diff, DIFF_CONTEXT)));
+        }
+    }
+
+    Ok(text)
+}
+
+/// Cabeçalho `tree <especificação>` seguido dos nomes dos filhos, com `/` no fim das subárvores
+fn format_tree(spec: &str, tree: &TreeObject) -> String {
+    let mut text = format!("tree {}\n\n", spec);
+
+    for child in &tree.children {
+        let is_tree = parse_mode(&child.mode).is_ok_and(|mode| mode == MODE_TREE);
+        text.push_str(&child.name);
+        if is_tree {
+            text.push('/');
+        }
+        text.push('\n');
+    }
+
+    text
+}
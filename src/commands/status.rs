@@ -1,349 +1,206 @@
-use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
 use crate::{
-    config::RGitIgnore,
-    objects::{BlobObject, RGitObject, RGitObjectTypes, TreeObject},
-    staging::StagingArea,
-    utils::find_current_repo,
-    Repository,
+    error::MinigitError,
+    commands::log::short_hash,
+    status::{FileStatus, StatusReport, status},
+    utils::{find_current_repo, merge_rebase::{OperationKind, OperationState}, terminal::{self, GREEN, RED}},
 };
 
-const RED: &str = "\x1b[31m";
-const GREEN: &str = "\x1b[32m";
-const RESET: &str = "\x1b[0m";
+/// Formato de saída do comando `status`
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum StatusFormat
+{
+    /// Texto para leitura humana, colorido quando a saída é um terminal
+    Human,
+    /// Uma linha `XY caminho` por arquivo, estável entre versões
+    Porcelain,
+    /// Objeto JSON com a branch, o HEAD e as listas de mudanças
+    Json,
+}
 
-pub fn cmd_status()
+pub fn cmd_status(format: StatusFormat) -> Result<(), MinigitError>
 {
-    if let Err(e) = cmd_status_result()
+    let result = cmd_status_result(format);
+    if let Err(e) = &result
     {
         eprintln!("Erro ao obter status: {}", e);
     }
+    result
 }
 
-fn cmd_status_result() -> Result<(), String>
+fn cmd_status_result(format: StatusFormat) -> Result<(), MinigitError>
 {
-    let repo = find_current_repo().ok_or("Não está dentro de um repositório")?;
-    let staging_area = StagingArea::new(&repo);
-    let gitignore = RGitIgnore::new(&repo);
-
-    show_status_branch(&repo);
-
-    let has_staged_changes = compare_head_and_index(&repo, &staging_area)?;
-    let (has_unstaged_changes, has_untracked_files) = index_worktree_status(&repo, &staging_area, &gitignore)?;
-
-    let worktree_clean = !has_staged_changes && !has_unstaged_changes && !has_untracked_files;
-    let nothing_staged_but_worktree_dirty =
-        !has_staged_changes && (has_unstaged_changes || has_untracked_files);
+    let repo = find_current_repo()?;
+    let report = status(&repo)?;
 
-    if worktree_clean
+    match format
     {
-        println!("nada para commitar, árvore de trabalho limpa");
-    }
-    else if nothing_staged_but_worktree_dirty
-    {
-        println!("\nsem mudanças adicionadas para commitar (utilize o comando \"add\")");
+        StatusFormat::Human => print_human(&report),
+        StatusFormat::Porcelain => print_porcelain(&report),
+        StatusFormat::Json => terminal::print_json(&report)?,
     }
 
     Ok(())
 }
 
-// Exibe a branch atual ou o hash do HEAD destacado
-fn show_status_branch(repo: &Repository)
+fn print_human(report: &StatusReport)
 {
-    if repo.head_detached()
-    {
-        let head_hash = repo.resolve_head();
-        println!("HEAD destacado em {}\n", &head_hash[..7]);
-        return
-    }
-    let head_ref = repo.get_head();
-    let branch_name = head_ref
-        .strip_prefix("refs/heads/")
-        .unwrap_or(&head_ref);
-    
-    println!("Na branch {}\n", branch_name);
-}
-
-/// Compara HEAD com o índice (staging area)
-fn compare_head_and_index(repo: &Repository, staging_area: &StagingArea) -> Result<bool, String>
-{
-    let head_commit_id = repo.resolve_head();
+    show_status_branch(report);
 
-    if head_commit_id.is_empty()
+    if let Some(operation) = &report.operation
     {
-        return handle_initial_commit(staging_area);
+        print_operation(operation, !report.unmerged.is_empty());
     }
 
-    let head_tree = get_head_tree(repo, &head_commit_id)?;
-    let head_dict = tree_to_dict(&head_tree);
-    
-    let has_changes = check_staged_changes(staging_area, &head_dict);
-    Ok(has_changes)
-}
-
-fn handle_initial_commit(staging_area: &StagingArea) -> Result<bool, String>
-{
-    if staging_area.entries.is_empty()
+    if !report.staged.is_empty()
     {
-        return Ok(false);
+        print_changes("Mudanças a serem commitadas:", &report.staged, GREEN);
     }
 
-    println!("Mudanças a serem commitadas:");
-    for entry in &staging_area.entries
+    if !report.unmerged.is_empty()
     {
-        println!("  {}novo arquivo: {}{}", GREEN, entry.path.display(), RESET);
+        println!("Caminhos não mesclados (utilize o comando \"add\" para marcar a resolução):");
+        for file in &report.unmerged
+        {
+            println!("  {}", terminal::paint(&format!("modificado por ambos: {}", file.display()), RED));
+        }
     }
 
-    Ok(true)
-}
-
-fn check_staged_changes(staging_area: &StagingArea, head_dict: &HashMap<String, String>) -> bool
-{
-    let mut changes = Vec::new();
-
-    for entry in &staging_area.entries
+    if !report.unstaged.is_empty()
     {
-        let path_str = entry.path.to_string_lossy();
-
-        let action = match head_dict.get(path_str.as_ref())
-        {
-            Some(head_sha) if head_sha != &entry.object_hash => "modificado",
-            None => "novo arquivo",
-            _ => continue,
-        };
-
-        changes.push((entry.path.display().to_string(), action));
+        print_changes("Mudanças não preparadas para commit:", &report.unstaged, RED);
     }
 
-    for path in head_dict.keys()
+    if !report.untracked.is_empty()
     {
-        let staging_has_file = staging_area
-            .entries
-            .iter()
-            .any(|e| e.path.to_string_lossy() == path.as_str());
-
-        if !staging_has_file
+        println!("Arquivos não rastreados:");
+        for file in &report.untracked
         {
-            changes.push((path.clone(), "removido"));
+            println!("  {}", terminal::paint(&file.display().to_string(), RED));
         }
     }
 
-    if !changes.is_empty()
+    if report.is_clean()
     {
-        println!("Mudanças a serem commitadas:");
-        for (path, action) in &changes
-        {
-            println!("  {}{}: {}{}", GREEN, action, path, RESET);
-        }
+        println!("nada para commitar, árvore de trabalho limpa");
+    }
+    else if report.staged.is_empty()
+    {
+        println!("\nsem mudanças adicionadas para commitar (utilize o comando \"add\")");
     }
-
-    !changes.is_empty()
 }
 
-/// Compara índice com o worktree
-fn index_worktree_status(repo: &Repository, staging_area: &StagingArea, gitignore: &RGitIgnore) -> Result<(bool, bool), String>
+/// Imprime uma linha `XY caminho` por arquivo rastreado, ordenadas pelo caminho, seguidas de uma linha
+/// `?? caminho` por arquivo não rastreado. `X` é a mudança no índice, `Y` a mudança na worktree e arquivos
+/// com conflitos não resolvidos aparecem como `UU`. Arquivos renomeados (`R`) ou copiados (`C`) aparecem
+/// como `XY origem -> destino`. Um arquivo removido do índice que continua na worktree aparece nas duas
+/// partes (`D ` e `??`).
+fn print_porcelain(report: &StatusReport)
 {
-    let head_commit_id = repo.resolve_head();
+    let mut lines: BTreeMap<PathBuf, (char, char)> = BTreeMap::new();
+    let mut sources: BTreeMap<PathBuf, PathBuf> = BTreeMap::new();
 
-    let head_dict = if !head_commit_id.is_empty()
+    for change in &report.staged
     {
-        let head_tree = get_head_tree(repo, &head_commit_id)?;
-        tree_to_dict(&head_tree)
+        lines.entry(change.path.clone()).or_insert((' ', ' ')).0 = change.change.porcelain_code();
+        if let Some(source) = &change.source
+        {
+            sources.insert(change.path.clone(), source.clone());
+        }
     }
-    else {
-        HashMap::new()
-    };
-
-    let unstaged_files = check_unstaged_changes(repo, staging_area, gitignore, &head_dict);
-    let untracked_files = collect_untracked_files(&repo.worktree, &repo.worktree, staging_area, gitignore);
-
-    let has_unstaged_changes = !unstaged_files.is_empty();
-    let has_untracked_files = !untracked_files.is_empty();
 
-    if has_unstaged_changes
+    for change in &report.unstaged
     {
-        print_unstaged_changes(&unstaged_files);
+        lines.entry(change.path.clone()).or_insert((' ', ' ')).1 = change.change.porcelain_code();
     }
 
-    if has_untracked_files
+    for file in &report.unmerged
     {
-        print_untracked_files(&untracked_files);
+        lines.insert(file.clone(), ('U', 'U'));
     }
 
-    Ok((has_unstaged_changes, has_untracked_files))
-}
-
-fn check_unstaged_changes(
-    repo: &Repository,
-    staging_area: &StagingArea,
-    gitignore: &RGitIgnore,
-    head_dict: &HashMap<String, String>
-) -> Vec<(PathBuf, &'static str)>
-{
-    staging_area
-        .entries
-        .iter()
-        .filter_map(|entry|
-        {
-            let path_str = entry.path.to_string_lossy();
-            let is_ignored = gitignore.check_ignore(&entry.path);
-            let was_in_head = head_dict.contains_key(path_str.as_ref());
-
-            if is_ignored && was_in_head
-            {
-                return Some((entry.path.clone(), "removido"));
-            }
-            if is_ignored
-            {
-                return None;
-            }
-
-            let absolute_path = repo.worktree.join(&entry.path);
-
-            if !absolute_path.exists()
-            {
-                return Some((entry.path.clone(), "removido"));
-            }
-
-            let blob = BlobObject::from(&absolute_path);
-            let new_hash = blob.hash();
-
-            (new_hash != entry.object_hash).then_some((entry.path.clone(), "modificado"))
-        })
-        .collect()
-}
-
-fn print_unstaged_changes(unstaged_files: &[(PathBuf, &str)])
-{
-    println!("Mudanças não preparadas para commit:");
-    for (path, action) in unstaged_files
+    for (path, (index, worktree)) in lines
     {
-        println!("  {}{}: {}{}", RED, action, path.display(), RESET);
+        match sources.get(&path)
+        {
+            Some(source) => println!("{}{} {} -> {}", index, worktree, source.display(), path.display()),
+            None => println!("{}{} {}", index, worktree, path.display()),
+        }
     }
-}
 
-fn print_untracked_files(untracked_files: &[PathBuf])
-{
-    println!("Arquivos não rastreados:");
-    for file in untracked_files
+    for file in &report.untracked
     {
-        println!("  {}{}{}", RED, file.display(), RESET);
+        println!("?? {}", file.display());
     }
 }
 
-/// Obtém a tree do commit apontado por HEAD
-fn get_head_tree(repo: &Repository, head_commit_id: &str) -> Result<TreeObject, String>
+// Exibe a branch atual ou o hash do HEAD destacado
+fn show_status_branch(report: &StatusReport)
 {
-    let commit = repo
-        .get_object(&head_commit_id.to_string())
-        .ok_or("Não foi possível obter o commit do HEAD")?;
-
-    let RGitObjectTypes::Commit(c) = commit else
+    match &report.branch
     {
-        return Err("HEAD não aponta para um commit".to_string());
-    };
-
-    let tree_obj = repo
-        .get_object(&c.tree)
-        .ok_or("Não foi possível obter a tree do commit")?;
-
-    match tree_obj
-    {
-        RGitObjectTypes::Tree(t) => Ok(t),
-        _ => Err("Objeto referenciado não é uma tree".to_string()),
+        Some(branch_name) => println!("Na branch {}\n", branch_name),
+        None => println!("HEAD destacado em {}\n", &report.head[..7]),
     }
 }
 
-/// Converte uma tree em um dicionário flat de caminhos para hashes
-fn tree_to_dict(tree: &TreeObject) -> HashMap<String, String>
+/// Explica o merge ou rebase em andamento e o que fazer em seguida
+fn print_operation(operation: &OperationState, has_conflicts: bool)
 {
-    let mut result = HashMap::new();
-    tree_to_dict_recursive(tree, "", &mut result);
-    result
-}
+    let command = operation.kind.command();
 
-fn tree_to_dict_recursive(tree: &TreeObject, prefix: &str, result: &mut HashMap<String, String>)
-{
-    for child in &tree.children
+    match operation.kind
     {
-        let full_path = if prefix.is_empty()
+        OperationKind::Merge =>
         {
-            child.name.clone()
+            let target = operation.todo.first().or(operation.done.first().map(|step| &step.original));
+            println!("Merge de {} em andamento.", target.map_or("?", |target| short_hash(target)));
         }
-        else
+        OperationKind::Rebase =>
         {
-            format!("{}/{}", prefix, child.name)
-        };
-
-        result.insert(full_path, child.object_id.clone());
+            let total = operation.done.len() + operation.todo.len();
+            println!(
+                "Rebase em andamento sobre {}: {} de {} commits aplicados.",
+                short_hash(&operation.onto),
+                operation.done.len(),
+                total,
+            );
+        }
     }
-}
 
-/// Coleta todos os arquivos não rastreados no worktree
-fn collect_untracked_files(
-    current_dir: &Path,
-    worktree: &Path,
-    staging_area: &StagingArea,
-    gitignore: &RGitIgnore,
-) -> Vec<PathBuf>
-{
-    let mut untracked = Vec::new();
-
-    fn collect_recursive(
-        current_dir: &Path,
-        worktree: &Path,
-        staging_area: &StagingArea,
-        gitignore: &RGitIgnore,
-        untracked: &mut Vec<PathBuf>,
-    )
+    if let Some(stopped_sha) = &operation.stopped_sha
     {
-        if !current_dir.is_dir()
+        println!("Parado em {} por conflitos.", short_hash(stopped_sha));
+        if has_conflicts
         {
-            return;
+            println!("  (corrija os conflitos e use \"minigit add\" para marcá-los como resolvidos)");
         }
-        if current_dir
-            .file_name()
-            .is_some_and(|name| name == Repository::MINIGITDIR)
+        else
         {
-            return;
+            println!("  (todos os conflitos foram resolvidos: use \"minigit {} --continue\")", command);
         }
-        let Ok(entries) = std::fs::read_dir(current_dir) else
-        {
-            return;
-        };
-
-        for entry in entries.flatten()
+    }
+    else
+    {
+        println!("O {} foi interrompido antes de terminar.", command);
+        // Um merge interrompido antes do commit de merge não tem como ser retomado, só abortado
+        if operation.kind == OperationKind::Rebase || operation.todo.is_empty()
         {
-            let path = entry.path();
-
-            if path.is_dir()
-            {
-                collect_recursive(&path, worktree, staging_area, gitignore, untracked);
-            }
-            else if path.is_file()
-            {
-                if let Ok(relative_path) = path.strip_prefix(worktree)
-                {
-                    if gitignore.check_ignore(&relative_path.to_path_buf())
-                    {
-                        continue;
-                    }
-
-                    let is_tracked = staging_area
-                        .entries
-                        .iter()
-                        .any(|e| e.path == relative_path);
-
-                    if !is_tracked
-                    {
-                        untracked.push(relative_path.to_path_buf());
-                    }
-                }
-            }
+            println!("  (use \"minigit {} --continue\" para retomar)", command);
         }
     }
 
-    collect_recursive(current_dir, worktree, staging_area, gitignore, &mut untracked);
-    untracked
+    println!("  (use \"minigit {} --abort\" para voltar ao estado anterior ao {})\n", command, command);
+}
+
+fn print_changes(title: &str, changes: &[FileStatus], color: &str)
+{
+    println!("{}", title);
+    for change in changes
+    {
+        let line = format!("{}: {}", change.change.description(), change.display_path());
+        println!("  {}", terminal::paint(&line, color));
+    }
 }
@@ -0,0 +1,85 @@
+use crate::{
+    Repository,
+    checks::{ensure_no_merge_in_progress, ensure_no_rebase_in_progress},
+    commands::{
+        branch::{create_branch_at, validate_branch_name},
+        checkout::{run_post_checkout_hook, update_worktree},
+    },
+    error::MinigitError,
+    utils::{find_current_repo, resolve_head_or_branch_name, resolve_revision},
+};
+
+/// Muda para uma branch, opcionalmente criando-a antes
+///
+/// ## Argumentos
+/// - `branch` - Branch de destino ou, com `create`, o ponto de partida da nova branch
+/// - `create` - Nome da branch a ser criada
+pub fn cmd_switch(branch: Option<String>, create: Option<String>) -> Result<(), MinigitError> {
+    let result = cmd_switch_result(branch, create);
+    if let Err(e) = &result {
+        eprintln!("Erro ao mudar de branch: {}", e);
+    }
+    result
+}
+
+fn cmd_switch_result(branch: Option<String>, create: Option<String>) -> Result<(), MinigitError> {
+    let mut repo = find_current_repo()?;
+
+    match (create, branch) {
+        (Some(new_branch), start_point) => {
+            switch(&mut repo, &new_branch, Some(start_point.as_deref().unwrap_or(Repository::HEAD)))?;
+            println!("Mudou para a nova branch '{}'", new_branch);
+        }
+        (None, Some(branch)) => {
+            if repo.get_head()? == format!("refs/heads/{}", branch) {
+                println!("Já está na branch '{}'", branch);
+                return Ok(());
+            }
+            switch(&mut repo, &branch, None)?;
+            println!("Mudou para a branch '{}'", branch);
+        }
+        (None, None) => {
+            return Err(MinigitError::InvalidArgument("Forneça o nome da branch ou -c <nova branch>".to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Muda o HEAD para a branch, levando as mudanças locais que não conflitam com ela
+///
+/// Com `create_from`, cria a branch a partir dessa revisão antes de mudar. Só os arquivos que diferem
+/// entre o HEAD atual e a branch são alterados (veja `update_worktree`).
+///
+/// Retorna o hash do commit apontado pela branch, vazio se ela ainda não tiver commits.
+pub fn switch(repo: &mut Repository, branch: &str, create_from: Option<&str>) -> Result<String, MinigitError> {
+    ensure_no_rebase_in_progress(repo)?;
+    ensure_no_merge_in_progress(repo)?;
+
+    let previous_head = repo.resolve_head()?;
+
+    let target = match create_from {
+        Some(start_point) => {
+            validate_branch_name(branch)?;
+            if resolve_head_or_branch_name(branch, repo)?.is_some() {
+                return Err(MinigitError::AlreadyExists(format!("A branch '{}' já existe", branch)));
+            }
+
+            if start_point == Repository::HEAD { previous_head.clone() } else { resolve_revision(repo, start_point)? }
+        }
+        None => resolve_head_or_branch_name(branch, repo)?
+            .filter(|_| branch != Repository::HEAD)
+            .ok_or_else(|| MinigitError::InvalidRef(format!("A branch '{}' não existe", branch)))?,
+    };
+
+    update_worktree(repo, &previous_head, &target, false)?;
+
+    if create_from.is_some() {
+        create_branch_at(repo, branch, &target)?;
+    }
+    repo.change_head(&branch.to_string())?;
+
+    run_post_checkout_hook(repo, &previous_head)?;
+
+    Ok(target)
+}
@@ -1,48 +1,279 @@
-use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 
-use crate::utils::files;
+use crate::{config::{ConfigFile, ConfigKey}, error::MinigitError, utils::write_locked};
 
+/// Arquivo de configuração local, dentro da pasta `.minigit`
+const LOCAL_CONFIG: &str = "config";
+/// Arquivo de configuração específico da worktree, dentro da pasta `.minigit`
+const WORKTREE_CONFIG: &str = "config.worktree";
+/// Arquivo de configuração global, dentro da pasta do usuário
+const GLOBAL_CONFIG: &str = ".minigitconfig";
+/// Arquivo de configuração do sistema
+const SYSTEM_CONFIG: &str = "/etc/minigitconfig";
+/// Quantidade máxima de `include.path` aninhados
+const MAX_INCLUDE_DEPTH: usize = 10;
+
+/// Origem de um valor de configuração, da menor para a maior precedência
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum ConfigScope {
+    /// `/etc/minigitconfig` ou `$MINIGIT_CONFIG_SYSTEM`
+    System,
+    /// `~/.minigitconfig` ou `$MINIGIT_CONFIG_GLOBAL`
+    Global,
+    /// `.minigit/config`
+    Local,
+    /// `.minigit/config.worktree`
+    Worktree,
+    /// Variáveis `MINIGIT_CONFIG_COUNT`, `MINIGIT_CONFIG_KEY_<n>` e `MINIGIT_CONFIG_VALUE_<n>`
+    Command,
+}
+
+impl ConfigScope {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConfigScope::System => "system",
+            ConfigScope::Global => "global",
+            ConfigScope::Local => "local",
+            ConfigScope::Worktree => "worktree",
+            ConfigScope::Command => "command",
+        }
+    }
+}
+
+/// Um valor de configuração e a sua origem
+#[derive(Clone, Debug)]
+pub struct ConfigValue {
+    pub scope: ConfigScope,
+    pub key: ConfigKey,
+    pub value: String,
+}
+
+/// Configuração do minigit, combinando os arquivos do sistema, global, local e da worktree
+/// e as variáveis de ambiente `MINIGIT_CONFIG_*`. Valores de maior precedência sobrescrevem os de menor.
 pub struct GitConfig {
-    dict: HashMap<String, String>,
+    minigitdir: Option<PathBuf>,
+    values: Vec<ConfigValue>,
 }
 
 impl GitConfig {
-    pub fn new(config_bytes: Vec<u8>) -> Self {
-        let config_str = String::from_utf8(config_bytes).expect("Config deve ser um arquivo UTF8 válido");
-        let mut content_str = config_str.as_str();
-        let mut dict: HashMap<String, String> = HashMap::new();
-
-        while !content_str.is_empty() {
-            let (key, value, remainder) = files::read_value(content_str);
-            dict.insert(key, value);
-            content_str = remainder;
+    /// Carrega a configuração. Sem `minigitdir` (fora de um repositório), só as configurações
+    /// do sistema, global e das variáveis de ambiente são lidas.
+    pub fn load(minigitdir: Option<&Path>) -> Result<Self, MinigitError> {
+        let mut config = GitConfig { minigitdir: minigitdir.map(Path::to_path_buf), values: Vec::new() };
+        config.reload()?;
+        Ok(config)
+    }
+
+    fn reload(&mut self) -> Result<(), MinigitError> {
+        let mut values = Vec::new();
+
+        for scope in [ConfigScope::System, ConfigScope::Global, ConfigScope::Local, ConfigScope::Worktree] {
+            if let Some(path) = self.scope_path(scope) {
+                load_file(&path, scope, 0, &mut values)?;
+            }
         }
-        
-        GitConfig { dict }
+        load_environment(&mut values)?;
 
+        self.values = values;
+        Ok(())
     }
 
-    pub fn serialize(&self) -> Vec<u8> {
-        let mut result = Vec::new();
-        
-        for (key, value) in &self.dict {
-            result.extend_from_slice(format!("{} {}\n", key, value).as_bytes());
+    /// Caminho do arquivo de configuração do escopo, se houver um
+    pub fn scope_path(&self, scope: ConfigScope) -> Option<PathBuf> {
+        match scope {
+            ConfigScope::System if std::env::var_os("MINIGIT_CONFIG_NOSYSTEM").is_some() => None,
+            ConfigScope::System => Some(std::env::var_os("MINIGIT_CONFIG_SYSTEM").map_or_else(|| PathBuf::from(SYSTEM_CONFIG), PathBuf::from)),
+            ConfigScope::Global => std::env::var_os("MINIGIT_CONFIG_GLOBAL")
+                .map(PathBuf::from)
+                .or_else(|| home_dir().map(|home| home.join(GLOBAL_CONFIG))),
+            ConfigScope::Local => self.minigitdir.as_ref().map(|dir| dir.join(LOCAL_CONFIG)),
+            ConfigScope::Worktree => self.minigitdir.as_ref().map(|dir| dir.join(WORKTREE_CONFIG)),
+            ConfigScope::Command => None,
         }
+    }
+
+    /// Todos os valores, na ordem de precedência (o último de cada chave é o efetivo)
+    pub fn values(&self) -> &[ConfigValue] {
+        &self.values
+    }
+
+    /// Retorna o valor efetivo da chave, se ela existir
+    pub fn get(&self, key: &str) -> Option<&String> {
+        let key = ConfigKey::parse(key).ok()?;
+        self.values.iter().rev().find(|value| value.key == key).map(|value| &value.value)
+    }
+
+    /// Retorna o valor da chave como booleano (`true`/`yes`/`on`/`1` ou `false`/`no`/`off`/`0`/vazio)
+    pub fn get_bool(&self, key: &str) -> Result<Option<bool>, MinigitError> {
+        self.get(key).map(|value| parse_bool(value).ok_or_else(|| invalid_value(key, value, "booleano"))).transpose()
+    }
+
+    /// Retorna o valor da chave como inteiro, aceitando os sufixos `k`, `m` e `g`
+    pub fn get_int(&self, key: &str) -> Result<Option<i64>, MinigitError> {
+        self.get(key).map(|value| parse_int(value).ok_or_else(|| invalid_value(key, value, "inteiro"))).transpose()
+    }
 
-        result
+    /// Retorna o valor da chave como caminho, expandindo `~/` para a pasta do usuário
+    pub fn get_path(&self, key: &str) -> Option<PathBuf> {
+        self.get(key).map(|value| expand_path(value))
     }
 
+    /// Retorna `user.name`
     pub fn get_username(&self) -> String {
-        let default = "Desconhecido".to_string();
-        self.dict.get("username").unwrap_or(&default).clone()
+        self.get("user.name").cloned().unwrap_or_else(|| "Desconhecido".to_string())
     }
 
+    /// Retorna `user.email`
     pub fn get_email(&self) -> String {
-        let default = "SEM EMAIL".to_string();
-        self.dict.get("email").unwrap_or(&default).clone()
+        self.get("user.email").cloned().unwrap_or_else(|| "SEM EMAIL".to_string())
+    }
+
+    /// Retorna `core.filemode`: se o bit de execução dos arquivos da worktree deve ser respeitado.
+    /// O padrão é `true`
+    pub fn get_filemode(&self) -> bool {
+        self.get_bool("core.filemode").ok().flatten().unwrap_or(true)
+    }
+
+    /// Define o valor da chave no arquivo do escopo
+    pub fn set(&mut self, scope: ConfigScope, key: &str, value: &str) -> Result<(), MinigitError> {
+        let key = ConfigKey::parse(key)?;
+        self.edit_file(scope, |file| {
+            file.set(&key, value);
+            true
+        })?;
+        Ok(())
+    }
+
+    /// Remove a chave do arquivo do escopo, retornando se ela existia
+    pub fn unset(&mut self, scope: ConfigScope, key: &str) -> Result<bool, MinigitError> {
+        let key = ConfigKey::parse(key)?;
+        self.edit_file(scope, |file| file.unset(&key))
+    }
+
+    /// Remove a seção (`seção` ou `seção.subseção`) do arquivo do escopo, retornando se ela existia
+    pub fn remove_section(&mut self, scope: ConfigScope, name: &str) -> Result<bool, MinigitError> {
+        let (section, subsection) = match name.split_once('.') {
+            Some((section, subsection)) => (section.to_lowercase(), Some(subsection)),
+            None => (name.to_lowercase(), None),
+        };
+        self.edit_file(scope, |file| file.remove_section(&section, subsection))
+    }
+
+    /// Lê o arquivo do escopo, aplica `edit` e o reescreve se `edit` retornar `true`
+    fn edit_file(&mut self, scope: ConfigScope, edit: impl FnOnce(&mut ConfigFile) -> bool) -> Result<bool, MinigitError> {
+        let path = self.scope_path(scope)
+            .ok_or_else(|| MinigitError::InvalidConfig(format!("Não há arquivo de configuração para o escopo '{}'", scope.name())))?;
+
+        let content = match std::fs::read(&path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let mut file = ConfigFile::parse(&decode(content, &path)?, &path.to_string_lossy())?;
+
+        if !edit(&mut file) {
+            return Ok(false);
+        }
+
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        write_locked(&path, file.serialize())?;
+        self.reload()?;
+        Ok(true)
+    }
+}
+
+/// Lê os valores do arquivo, seguindo os `include.path`. Arquivos inexistentes são ignorados
+fn load_file(path: &Path, scope: ConfigScope, depth: usize, values: &mut Vec<ConfigValue>) -> Result<(), MinigitError> {
+    let content = match std::fs::read(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+    };
+    let file = ConfigFile::parse(&decode(content, path)?, &path.to_string_lossy())?;
+
+    for (key, value) in file.entries() {
+        values.push(ConfigValue { scope, key: key.clone(), value: value.clone() });
+
+        if key.section == "include" && key.subsection.is_none() && key.name == "path" {
+            if depth >= MAX_INCLUDE_DEPTH {
+                return Err(MinigitError::InvalidConfig(format!("Includes aninhados demais a partir de {}", path.display())));
+            }
+
+            // Caminhos relativos são relativos ao arquivo que contém o include
+            let included = expand_path(value);
+            let included = match path.parent() {
+                Some(parent) if included.is_relative() => parent.join(included),
+                _ => included,
+            };
+            load_file(&included, scope, depth + 1, values)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Lê as configurações das variáveis `MINIGIT_CONFIG_COUNT`, `MINIGIT_CONFIG_KEY_<n>` e `MINIGIT_CONFIG_VALUE_<n>`
+fn load_environment(values: &mut Vec<ConfigValue>) -> Result<(), MinigitError> {
+    let Ok(count) = std::env::var("MINIGIT_CONFIG_COUNT") else {
+        return Ok(());
+    };
+    let count: usize = count.trim().parse()
+        .map_err(|_| MinigitError::InvalidConfig(format!("MINIGIT_CONFIG_COUNT inválido: '{}'", count)))?;
+
+    for index in 0..count {
+        let key = std::env::var(format!("MINIGIT_CONFIG_KEY_{}", index))
+            .map_err(|_| MinigitError::InvalidConfig(format!("MINIGIT_CONFIG_KEY_{} não definida", index)))?;
+        let value = std::env::var(format!("MINIGIT_CONFIG_VALUE_{}", index))
+            .map_err(|_| MinigitError::InvalidConfig(format!("MINIGIT_CONFIG_VALUE_{} não definida", index)))?;
+
+        values.push(ConfigValue { scope: ConfigScope::Command, key: ConfigKey::parse(&key)?, value });
+    }
+
+    Ok(())
+}
+
+fn decode(content: Vec<u8>, path: &Path) -> Result<String, MinigitError> {
+    String::from_utf8(content)
+        .map_err(|_| MinigitError::InvalidConfig(format!("{} deve ser um arquivo UTF8 válido", path.display())))
+}
+
+fn invalid_value(key: &str, value: &str, expected: &str) -> MinigitError {
+    MinigitError::InvalidConfig(format!("Valor '{}' da chave '{}' não é um {} válido", value, key, expected))
+}
+
+fn home_dir() -> Option<PathBuf> {
+    std::env::var_os("HOME").or_else(|| std::env::var_os("USERPROFILE")).map(PathBuf::from)
+}
+
+/// Converte um valor booleano da configuração
+pub fn parse_bool(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" | "" => Some(false),
+        _ => None,
     }
+}
 
-    pub fn set(&mut self, key: String, value: String) {
-        self.dict.insert(key, value);
+/// Converte um valor inteiro da configuração, com sufixo opcional `k` (1024), `m` ou `g`
+pub fn parse_int(value: &str) -> Option<i64> {
+    let value = value.trim();
+    let (number, multiplier) = match value.chars().last()?.to_ascii_lowercase() {
+        'k' => (&value[..value.len() - 1], 1024),
+        'm' => (&value[..value.len() - 1], 1024 * 1024),
+        'g' => (&value[..value.len() - 1], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+
+    number.parse::<i64>().ok()?.checked_mul(multiplier)
+}
+
+/// Expande `~` e `~/` no início do caminho para a pasta do usuário
+pub fn expand_path(value: &str) -> PathBuf {
+    match (value.strip_prefix("~"), home_dir()) {
+        (Some(""), Some(home)) => home,
+        (Some(rest), Some(home)) if rest.starts_with('/') => home.join(&rest[1..]),
+        _ => PathBuf::from(value),
     }
-}
\ No newline at end of file
+}
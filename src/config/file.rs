@@ -0,0 +1,360 @@
+use std::fmt::Display;
+
+use crate::error::MinigitError;
+
+/// Chave de configuração no formato `seção.nome` ou `seção.subseção.nome`
+///
+/// A seção e o nome não diferenciam maiúsculas de minúsculas e são guardados em minúsculas.
+/// A subseção diferencia.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigKey {
+    pub section: String,
+    pub subsection: Option<String>,
+    pub name: String,
+}
+
+impl ConfigKey {
+    /// Lê uma chave fornecida pelo usuário
+    ///
+    /// As chaves antigas `username` e `email` são aceitas como `user.name` e `user.email`.
+    pub fn parse(key: &str) -> Result<Self, MinigitError> {
+        let key = match key {
+            "username" => "user.name",
+            "email" => "user.email",
+            other => other,
+        };
+        let invalid = || MinigitError::InvalidArgument(format!("Chave inválida '{}'. Use 'seção.nome' ou 'seção.subseção.nome'", key));
+
+        let (Some(first_dot), Some(last_dot)) = (key.find('.'), key.rfind('.')) else {
+            return Err(invalid());
+        };
+
+        let section = &key[..first_dot];
+        let name = &key[last_dot + 1..];
+        let subsection = (first_dot != last_dot).then(|| key[first_dot + 1..last_dot].to_string());
+
+        if !is_valid_section(section) || !is_valid_name(name) {
+            return Err(invalid());
+        }
+
+        Ok(ConfigKey { section: section.to_lowercase(), subsection, name: name.to_lowercase() })
+    }
+
+    fn in_section(&self, section: &str, subsection: Option<&str>) -> bool {
+        self.section == section && self.subsection.as_deref() == subsection
+    }
+}
+
+impl Display for ConfigKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.subsection {
+            Some(subsection) => write!(f, "{}.{}.{}", self.section, subsection, self.name),
+            None => write!(f, "{}.{}", self.section, self.name),
+        }
+    }
+}
+
+/// Uma linha (ou mais, se o valor continuar com `\`) de um arquivo de configuração, com o seu texto original
+enum ConfigLine {
+    Section { section: String, subsection: Option<String>, text: String },
+    Entry { key: ConfigKey, value: String, text: String },
+    Other(String),
+}
+
+impl ConfigLine {
+    fn text(&self) -> &str {
+        match self {
+            ConfigLine::Section { text, .. } | ConfigLine::Entry { text, .. } | ConfigLine::Other(text) => text,
+        }
+    }
+}
+
+/// Arquivo de configuração no formato INI:
+///
+/// ```text
+/// # comentário
+/// [user]
+///     name = Maria
+/// [branch "feature"]
+///     merge = refs/heads/master
+/// ```
+///
+/// Comentários e formatação são mantidos ao alterar o arquivo. O formato antigo (`chave valor` por linha)
+/// ainda é lido e é convertido para o formato INI na próxima escrita.
+pub struct ConfigFile {
+    lines: Vec<ConfigLine>,
+}
+
+impl ConfigFile {
+    /// Lê o conteúdo de um arquivo de configuração. `origin` identifica o arquivo nas mensagens de erro
+    pub fn parse(content: &str, origin: &str) -> Result<Self, MinigitError> {
+        let is_legacy = content.lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with(';'))
+            .is_some_and(|line| !line.starts_with('[') && !line.contains('='));
+
+        if is_legacy {
+            return Self::parse_legacy(content, origin);
+        }
+
+        let mut lines = Vec::new();
+        let mut current: Option<(String, Option<String>)> = None;
+        let mut physical_lines = content.lines().enumerate();
+
+        while let Some((index, line)) = physical_lines.next() {
+            let error = |message: &str| MinigitError::InvalidConfig(format!("{} na linha {} de {}", message, index + 1, origin));
+            let trimmed = line.trim_start();
+
+            if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+                lines.push(ConfigLine::Other(line.to_string()));
+            } else if trimmed.starts_with('[') {
+                let (section, subsection) = parse_section_header(trimmed).ok_or_else(|| error("Cabeçalho de seção inválido"))?;
+                current = Some((section.clone(), subsection.clone()));
+                lines.push(ConfigLine::Section { section, subsection, text: line.to_string() });
+            } else {
+                let Some((section, subsection)) = &current else {
+                    return Err(error("Chave fora de uma seção"));
+                };
+
+                let name_len = trimmed.find(|c: char| !c.is_ascii_alphanumeric() && c != '-').unwrap_or(trimmed.len());
+                let name = &trimmed[..name_len];
+                if !is_valid_name(name) {
+                    return Err(error("Nome de chave inválido"));
+                }
+
+                let rest = trimmed[name_len..].trim_start();
+                let mut text = line.to_string();
+                let value = if rest.is_empty() || rest.starts_with('#') || rest.starts_with(';') {
+                    // Chave sem valor equivale a `true`
+                    "true".to_string()
+                } else if let Some(raw_value) = rest.strip_prefix('=') {
+                    let mut raw_value = raw_value.to_string();
+                    loop {
+                        match parse_value(&raw_value).map_err(|message| error(&message))? {
+                            Some(value) => break value,
+                            None => {
+                                let (_, next) = physical_lines.next().ok_or_else(|| error("Continuação de linha no final do arquivo"))?;
+                                raw_value.push('\n');
+                                raw_value.push_str(next);
+                                text.push('\n');
+                                text.push_str(next);
+                            }
+                        }
+                    }
+                } else {
+                    return Err(error("Esperado '=' após o nome da chave"));
+                };
+
+                let key = ConfigKey { section: section.clone(), subsection: subsection.clone(), name: name.to_lowercase() };
+                lines.push(ConfigLine::Entry { key, value, text });
+            }
+        }
+
+        Ok(ConfigFile { lines })
+    }
+
+    /// Lê o formato antigo, com uma linha `chave valor` por configuração
+    fn parse_legacy(content: &str, origin: &str) -> Result<Self, MinigitError> {
+        let mut file = ConfigFile { lines: Vec::new() };
+
+        for (index, line) in content.lines().enumerate().filter(|(_, line)| !line.trim().is_empty()) {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let key = ConfigKey::parse(key)
+                .map_err(|_| MinigitError::InvalidConfig(format!("Chave inválida na linha {} de {}", index + 1, origin)))?;
+            file.set(&key, value);
+        }
+
+        Ok(file)
+    }
+
+    /// Retorna as chaves e valores na ordem em que aparecem no arquivo
+    pub fn entries(&self) -> impl Iterator<Item = (&ConfigKey, &String)> {
+        self.lines.iter().filter_map(|line| match line {
+            ConfigLine::Entry { key, value, .. } => Some((key, value)),
+            _ => None,
+        })
+    }
+
+    /// Define o valor da chave, substituindo a última ocorrência dela ou adicionando-a no fim da sua seção
+    pub fn set(&mut self, key: &ConfigKey, value: &str) {
+        let text = format!("\t{} = {}", key.name, format_value(value));
+
+        if let Some(index) = self.lines.iter().rposition(|line| matches!(line, ConfigLine::Entry { key: k, .. } if k == key)) {
+            self.lines[index] = ConfigLine::Entry { key: key.clone(), value: value.to_string(), text };
+            return;
+        }
+
+        let entry = ConfigLine::Entry { key: key.clone(), value: value.to_string(), text };
+        match self.last_line_of_section(key) {
+            Some(index) => self.lines.insert(index + 1, entry),
+            None => {
+                let header = match &key.subsection {
+                    Some(subsection) => format!("[{} \"{}\"]", key.section, subsection.replace('\\', "\\\\").replace('"', "\\\"")),
+                    None => format!("[{}]", key.section),
+                };
+                self.lines.push(ConfigLine::Section { section: key.section.clone(), subsection: key.subsection.clone(), text: header });
+                self.lines.push(entry);
+            }
+        }
+    }
+
+    /// Remove todas as ocorrências da chave, retornando se alguma foi removida
+    pub fn unset(&mut self, key: &ConfigKey) -> bool {
+        let before = self.lines.len();
+        self.lines.retain(|line| !matches!(line, ConfigLine::Entry { key: k, .. } if k == key));
+        self.lines.len() != before
+    }
+
+    /// Remove todas as ocorrências da seção, com as suas chaves e comentários, retornando se alguma foi removida
+    pub fn remove_section(&mut self, section: &str, subsection: Option<&str>) -> bool {
+        let before = self.lines.len();
+        let mut in_section = false;
+
+        self.lines.retain(|line| {
+            if let ConfigLine::Section { section: s, subsection: sub, .. } = line {
+                in_section = s == section && sub.as_deref() == subsection;
+            }
+            !in_section
+        });
+
+        self.lines.len() != before
+    }
+
+    /// Índice do cabeçalho ou da última chave da última seção com a mesma seção e subseção da chave
+    fn last_line_of_section(&self, key: &ConfigKey) -> Option<usize> {
+        let mut in_section = false;
+        let mut last = None;
+
+        for (index, line) in self.lines.iter().enumerate() {
+            match line {
+                ConfigLine::Section { section, subsection, .. } => {
+                    in_section = key.in_section(section, subsection.as_deref());
+                    if in_section {
+                        last = Some(index);
+                    }
+                }
+                ConfigLine::Entry { .. } if in_section => last = Some(index),
+                _ => {}
+            }
+        }
+
+        last
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut result = String::new();
+
+        for line in &self.lines {
+            result.push_str(line.text());
+            result.push('\n');
+        }
+
+        result.into_bytes()
+    }
+}
+
+fn is_valid_section(section: &str) -> bool {
+    !section.is_empty() && section.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn is_valid_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic()) && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+/// Lê `[seção]`, `[seção "subseção"]` ou o formato antigo `[seção.subseção]`
+fn parse_section_header(line: &str) -> Option<(String, Option<String>)> {
+    let inner = line.strip_prefix('[')?;
+
+    if let Some(quote) = inner.find('"') {
+        let section = inner[..quote].trim();
+        let mut subsection = String::new();
+        let mut chars = inner[quote + 1..].char_indices();
+
+        loop {
+            match chars.next()? {
+                (_, '\\') => subsection.push(chars.next()?.1),
+                (index, '"') => {
+                    let rest = inner[quote + 1 + index + 1..].strip_prefix(']')?;
+                    if !is_comment_or_empty(rest) || !is_valid_section(section) {
+                        return None;
+                    }
+                    return Some((section.to_lowercase(), Some(subsection)));
+                }
+                (_, c) => subsection.push(c),
+            }
+        }
+    }
+
+    let (name, rest) = inner.split_once(']')?;
+    if !is_comment_or_empty(rest) {
+        return None;
+    }
+
+    match name.trim().split_once('.') {
+        Some((section, subsection)) if is_valid_section(section) => Some((section.to_lowercase(), Some(subsection.to_lowercase()))),
+        None if is_valid_section(name.trim()) => Some((name.trim().to_lowercase(), None)),
+        _ => None,
+    }
+}
+
+fn is_comment_or_empty(text: &str) -> bool {
+    let text = text.trim_start();
+    text.is_empty() || text.starts_with('#') || text.starts_with(';')
+}
+
+/// Lê o valor após o `=`: remove espaços das pontas e comentários, trata aspas e escapes
+/// (`\n`, `\t`, `\b`, `\\`, `\"`).
+///
+/// Retorna `None` se o valor continuar na próxima linha (termina com `\`).
+fn parse_value(raw: &str) -> Result<Option<String>, String> {
+    let mut value = String::new();
+    let mut pending_spaces = String::new();
+    let mut in_quotes = false;
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        let escaped = match c {
+            '\\' => match chars.next() {
+                None => return Ok(None),
+                Some('\n') => continue,
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('b') => '\u{8}',
+                Some('\\') => '\\',
+                Some('"') => '"',
+                Some(other) => return Err(format!("Escape inválido '\\{}'", other)),
+            },
+            '"' => {
+                in_quotes = !in_quotes;
+                continue;
+            }
+            '#' | ';' if !in_quotes => break,
+            c if c.is_whitespace() && !in_quotes => {
+                if !value.is_empty() {
+                    pending_spaces.push(c);
+                }
+                continue;
+            }
+            c => c,
+        };
+
+        value.push_str(&pending_spaces);
+        pending_spaces.clear();
+        value.push(escaped);
+    }
+
+    if in_quotes {
+        return Err("Aspas não fechadas".to_string());
+    }
+
+    Ok(Some(value))
+}
+
+/// Formata o valor para o arquivo, usando aspas e escapes quando necessário
+fn format_value(value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n").replace('\t', "\\t");
+    let needs_quotes = value.starts_with(char::is_whitespace) || value.ends_with(char::is_whitespace)
+        || value.contains(['#', ';']);
+
+    if needs_quotes { format!("\"{}\"", escaped) } else { escaped }
+}
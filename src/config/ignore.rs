@@ -1,143 +1,338 @@
-use std::{collections::HashMap, path::{Path, PathBuf}};
+use std::{collections::HashMap, fmt::Display, path::{Path, PathBuf}};
 
-use glob::PatternError;
+use glob::{MatchOptions, Pattern};
 
-use crate::{Repository, objects::RGitObjectTypes, staging::StagingArea};
+use crate::{Repository, error::MinigitError};
+
+/// Arquivo de exclusões do repositório que não é versionado, dentro da pasta `.minigit`
+const INFO_EXCLUDE: &str = "info/exclude";
+
+/// `*` e `?` não casam com `/`, como no git
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Uma regra de um arquivo de ignore
+pub struct IgnoreRule {
+    /// Padrão como escrito no arquivo
+    pub pattern: String,
+    /// Arquivo de origem, relativo à raíz do repositório quando estiver dentro dele
+    pub source: PathBuf,
+    /// Linha da regra no arquivo de origem, a partir de 1
+    pub line: usize,
+    /// A regra começa com `!` e volta a incluir os caminhos
+    pub negated: bool,
+    /// A regra termina com `/` e só vale para diretórios
+    dir_only: bool,
+    /// A regra contém `/` e é relativa à pasta do arquivo de ignore, em vez de valer para qualquer nível
+    anchored: bool,
+    glob: Pattern,
+}
+
+impl IgnoreRule {
+    /// Interpreta uma linha de um arquivo de ignore. Linhas vazias e comentários retornam `None`
+    fn parse(line: &str, source: &Path, line_number: usize) -> Option<Self> {
+        let mut text = trim_trailing_spaces(line);
+        if text.is_empty() || text.starts_with('#') {
+            return None;
+        }
+
+        let negated = text.starts_with('!');
+        if negated {
+            text = &text[1..];
+        }
+
+        let dir_only = text.ends_with('/');
+        if dir_only {
+            text = &text[..text.len() - 1];
+        }
+
+        let anchored = text.contains('/');
+        let text = text.strip_prefix('/').unwrap_or(text);
+        if text.is_empty() {
+            return None;
+        }
+
+        let glob = Pattern::new(&pattern_to_glob(text)).ok()?;
+
+        Some(IgnoreRule {
+            pattern: line.trim_end().to_string(),
+            source: source.to_path_buf(),
+            line: line_number,
+            negated,
+            dir_only,
+            anchored,
+            glob,
+        })
+    }
+
+    /// Verifica se a regra casa com `relative_path`, relativo à pasta do arquivo de ignore
+    fn matches(&self, relative_path: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+
+        if self.anchored {
+            self.glob.matches_with(relative_path, MATCH_OPTIONS)
+        } else {
+            let name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+            self.glob.matches_with(name, MATCH_OPTIONS)
+        }
+    }
+}
+
+impl Display for IgnoreRule {
+    /// Formato do `check-ignore -v`: `<origem>:<linha>:<padrão>`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}:{}", self.source.display(), self.line, self.pattern)
+    }
+}
 
 /// Representa as regras de ignore de arquivos em um repositório
 /// Um repositório pode ter múltiplos arquivos de ignore.
-/// 
-/// A chave de `scoped_rules` é o caminho relativo do arquivo de ignore a partir da pasta raíz do projeto
-/// (ex: "", "src").
-/// O valor é a lista de regras de ignore contidas naquele arquivo.
-/// 
+///
+/// A chave de `scoped_rules` é o caminho relativo da pasta do `.gitignore` a partir da pasta raíz do projeto
+/// (ex: "", "src"). O `.gitignore` mais próximo do caminho tem precedência, e dentro de um arquivo a última
+/// regra que casar vence.
+///
+/// `global_rules` tem as regras do `core.excludesFile` seguidas das do `.minigit/info/exclude`,
+/// que valem a partir da raíz e têm precedência menor que qualquer `.gitignore`.
 pub struct RGitIgnore {
-    scoped_rules: HashMap<String, Vec<String>>
+    worktree: PathBuf,
+    scoped_rules: HashMap<String, Vec<IgnoreRule>>,
+    global_rules: Vec<IgnoreRule>,
 }
 
 impl RGitIgnore {
-    pub fn new(repo: &Repository) -> Self {
-        let mut scoped_rules: HashMap<String, Vec<String>> = HashMap::new();
-        let staging_area = StagingArea::new(repo);
-
-        for entry in staging_area.entries {
-            let relative_path = entry.path;
-            if relative_path.file_name().unwrap_or_default() == Repository::GITIGNORE {
-                let ignore_file_object = repo.get_object(&entry.object_hash).unwrap();
-
-                let ignore_file = match ignore_file_object {
-                    RGitObjectTypes::Blob(blob) => {
-                        String::from_utf8(blob.content).unwrap_or_default()
-                    }
-                    _ => {
-                        String::new()
-                    }
-                };
-
-                let rules = ignore_file.lines()
-                    .map(|line| line.trim().to_string())
-                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
-                    .collect::<Vec<String>>();
-
-                let key = relative_path.parent()
-                    .map(|p| p.to_str().unwrap_or("").to_string());
-
-                if let Some(k) = key {
-                    scoped_rules.insert(k, rules);
-                }
-            }
+    pub fn new(repo: &Repository) -> Result<Self, MinigitError> {
+        let mut global_rules = Vec::new();
+
+        if let Some(excludes_file) = repo.config.get_path("core.excludesfile") {
+            global_rules.extend(read_rules(&excludes_file, &excludes_file)?);
         }
+        global_rules.extend(read_rules(
+            &repo.minigitdir.join(INFO_EXCLUDE),
+            &Path::new(Repository::MINIGITDIR).join(INFO_EXCLUDE),
+        )?);
+
+        let mut ignore = RGitIgnore {
+            worktree: repo.worktree.clone(),
+            scoped_rules: HashMap::new(),
+            global_rules,
+        };
+        ignore.load_directory(Path::new(""))?;
+
+        Ok(ignore)
+    }
 
+    /// Cria regras de ignore só com os padrões fornecidos (como os `-e` do `clean`), que valem a partir da raíz
+    pub fn from_patterns(repo: &Repository, patterns: &[String]) -> Self {
+        let source = Path::new("");
         RGitIgnore {
-            scoped_rules
+            worktree: repo.worktree.clone(),
+            scoped_rules: HashMap::new(),
+            global_rules: patterns.iter().filter_map(|pattern| IgnoreRule::parse(pattern, source, 0)).collect(),
         }
     }
 
-    pub fn check_ignore(&self, relative_path: &PathBuf) -> bool {
-        let relative_components = relative_path.components();
+    /// Lê o `.gitignore` da pasta e das suas subpastas, pulando as pastas ignoradas
+    fn load_directory(&mut self, relative_dir: &Path) -> Result<(), MinigitError> {
+        let absolute_dir = self.worktree.join(relative_dir);
 
-        // Ignoramos tudo da pasta .minigit por padrão
-        if relative_components.clone().any(|comp| comp.as_os_str() == Repository::MINIGITDIR) {
-            return true;
+        let ignore_file = relative_dir.join(Repository::GITIGNORE);
+        let rules = read_rules(&self.worktree.join(&ignore_file), &ignore_file)?;
+        if !rules.is_empty() {
+            self.scoped_rules.insert(path_key(relative_dir), rules);
         }
-        
-        let mut ignore_dir = relative_path.to_path_buf();
-        ignore_dir.pop();
-
-        loop {
-            let ignore_dir_str = ignore_dir.to_str();
-            let last = ignore_dir.as_os_str().is_empty();
-
-            match ignore_dir_str {
-                None => break,
-                Some(s) => { 
-                    let maybe_ignore = self.check_ignore_rules(s.to_string(), relative_path);
-                    
-                    match maybe_ignore {
-                        Ok(ignore_result_option) => {
-                            if let Some(ignore_result) = ignore_result_option {
-                                return ignore_result;
-                            }
-                        }
-                        _ => {}
-                    }
-                    ignore_dir.pop();
-                }
+
+        let Ok(entries) = std::fs::read_dir(&absolute_dir) else {
+            return Ok(());
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            if !entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                continue;
             }
 
-            if last {
-                break;
+            let relative_path = relative_dir.join(entry.file_name());
+            if !self.check_ignore(&relative_path) {
+                self.load_directory(&relative_path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn check_ignore(&self, relative_path: &Path) -> bool {
+        Self::is_minigit_path(relative_path) || self.matching_rule(relative_path).is_some_and(|rule| !rule.negated)
+    }
+
+    /// Retorna a regra que decide se o caminho é ignorado, que pode ser uma regra de negação (`!`).
+    ///
+    /// Se uma pasta ancestral for ignorada, a regra dela é retornada: não é possível voltar a incluir
+    /// um arquivo de uma pasta ignorada.
+    pub fn matching_rule(&self, relative_path: &Path) -> Option<&IgnoreRule> {
+        let mut ancestor = PathBuf::new();
+        let components: Vec<_> = relative_path.components().collect();
+
+        for (index, component) in components.iter().enumerate() {
+            ancestor.push(component);
+            let is_dir = index + 1 < components.len() || self.worktree.join(&ancestor).is_dir();
+            let rule = self.rule_for(&ancestor, is_dir);
+
+            if index + 1 == components.len() || rule.is_some_and(|rule| !rule.negated) {
+                return rule;
             }
         }
 
-        false
+        None
     }
 
-    fn check_ignore_rules(&self, key: String, relative_path: &Path) -> Result<Option<bool>, PatternError> {
-        let mut last_result: Option<bool> = None;
-        let scoped_rules = self.scoped_rules.get(&key);
+    /// Procura a regra que casa com o caminho, sem considerar as pastas ancestrais
+    fn rule_for(&self, relative_path: &Path, is_dir: bool) -> Option<&IgnoreRule> {
+        let path = path_key(relative_path);
+        let mut ignore_dir = relative_path.parent();
+
+        while let Some(dir) = ignore_dir {
+            let key = path_key(dir);
+
+            if let Some(rules) = self.scoped_rules.get(&key) {
+                let scoped_path = if key.is_empty() { path.as_str() } else { &path[key.len() + 1..] };
 
-        match scoped_rules {
-            None => return Ok(None),
-            Some(rules) => {
-                for rule in rules{
-                    let result = Self::mathes_rule(&key, rule, relative_path)?;
-                    last_result = Some(result);
+                if let Some(rule) = rules.iter().rev().find(|rule| rule.matches(scoped_path, is_dir)) {
+                    return Some(rule);
                 }
             }
+
+            ignore_dir = dir.parent();
         }
-        
-        Ok(last_result)
+
+        self.global_rules.iter().rev().find(|rule| rule.matches(&path, is_dir))
     }
 
-    fn mathes_rule(key: &String, rule: &String, relative_path: &Path) -> Result<bool, PatternError> {
-        let prefix = if key.is_empty() {
-            String::new()
-        } else {
-            format!("{}/", key)
-        };
+    /// Ignoramos tudo da pasta .minigit por padrão
+    fn is_minigit_path(relative_path: &Path) -> bool {
+        relative_path.components().any(|comp| comp.as_os_str() == Repository::MINIGITDIR)
+    }
+}
+
+/// Lê as regras de um arquivo de ignore. Um arquivo inexistente não tem regras
+fn read_rules(path: &Path, source: &Path) -> Result<Vec<IgnoreRule>, MinigitError> {
+    let content = match std::fs::read(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+
+    Ok(String::from_utf8_lossy(&content)
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| IgnoreRule::parse(line, source, index + 1))
+        .collect())
+}
+
+/// Caminho relativo com `/` como separador, usado como chave e para casar as regras
+fn path_key(path: &Path) -> String {
+    path.components()
+        .map(|comp| comp.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
 
-        if rule.starts_with('!') {
-            let glob_str = format!("{}{}", &prefix, &rule[1..]);
-            let glob_str_dir = format!("{}{}/**/*", &prefix, &rule[1..]);
-            let matches = glob::Pattern::new(&glob_str)?.matches_path(relative_path);
-            let matches_dir = glob::Pattern::new(&glob_str_dir)?.matches_path(relative_path);
+/// Remove os espaços no final da linha, exceto os escapados com `\`
+fn trim_trailing_spaces(line: &str) -> &str {
+    let mut end = line.trim_end_matches(['\r', '\n']).len();
 
-            if matches || matches_dir {
-                return Ok(false);
+    while end > 0 && line.as_bytes()[end - 1] == b' ' {
+        if end > 1 && line.as_bytes()[end - 2] == b'\\' {
+            break;
+        }
+        end -= 1;
+    }
+
+    &line[..end]
+}
+
+/// Converte um padrão de gitignore para um `glob::Pattern` equivalente.
+///
+/// `\` escapa o próximo caractere, `[^...]` vira `[!...]` e `**` só é recursivo quando ocupa um
+/// componente inteiro (`**/`, `/**/` ou `/**`); nos outros casos vale como `*`.
+fn pattern_to_glob(pattern: &str) -> String {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut glob = String::new();
+    let mut index = 0;
+
+    while index < chars.len() {
+        match chars[index] {
+            '*' => {
+                let start = index;
+                while chars.get(index) == Some(&'*') {
+                    index += 1;
+                }
+
+                let whole_component = (start == 0 || chars[start - 1] == '/') && matches!(chars.get(index), None | Some('/'));
+                glob.push_str(if index - start > 1 && whole_component { "**" } else { "*" });
             }
-        } else {
-            let glob_str = format!("{}{}", &prefix, rule);
-            let glob_str_dir = format!("{}{}/**/*", &prefix, rule);
+            '[' => match character_class(&chars[index..]) {
+                Some((class, length)) => {
+                    glob.push_str(&class);
+                    index += length;
+                }
+                None => {
+                    glob.push_str("[[]");
+                    index += 1;
+                }
+            },
+            '\\' if index + 1 < chars.len() => {
+                glob.push_str(&literal(chars[index + 1]));
+                index += 2;
+            }
+            c => {
+                glob.push_str(&literal(c));
+                index += 1;
+            }
+        }
+    }
+
+    glob
+}
 
-            let matches = glob::Pattern::new(&glob_str)?.matches_path(relative_path);
-            let matches_dir = glob::Pattern::new(&glob_str_dir)?.matches_path(relative_path);
+/// Converte uma classe `[...]` do início de `chars`, retornando a classe do glob e quantos caracteres
+/// foram consumidos. Retorna `None` se a classe não for fechada
+fn character_class(chars: &[char]) -> Option<(String, usize)> {
+    let mut class = String::from("[");
+    let mut index = 1;
+
+    if matches!(chars.get(index), Some('!') | Some('^')) {
+        class.push('!');
+        index += 1;
+    }
 
-            if matches || matches_dir {
-                return Ok(true);
+    let first = index;
+    loop {
+        match *chars.get(index)? {
+            ']' if index > first => break,
+            '\\' => {
+                class.push(*chars.get(index + 1)?);
+                index += 2;
+            }
+            c => {
+                class.push(c);
+                index += 1;
             }
         }
+    }
+    class.push(']');
 
-        Ok(false)
+    Some((class, index + 1))
+}
+
+/// Escapa os caracteres especiais do glob
+fn literal(c: char) -> String {
+    match c {
+        '*' | '?' | '[' | ']' => format!("[{}]", c),
+        c => c.to_string(),
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,7 @@
 pub mod config;
+pub mod file;
 pub mod ignore;
 
 pub use config::*;
-pub use ignore::*;
\ No newline at end of file
+pub use file::*;
+pub use ignore::*;
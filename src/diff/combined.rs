@@ -0,0 +1,168 @@
+use std::{collections::HashMap, ops::Range, path::PathBuf};
+
+use serde::Serialize;
+
+use crate::{
+    Repository,
+    diff::{LineChange, blob_text, diff_lines, group_changes},
+    error::MinigitError,
+    objects::{CommitObject, get_commit_tree_as_map},
+};
+
+/// Linha do diff combinado de um merge
+///
+/// ## Atributos
+/// - `markers` - Um marcador por pai: `+` se a linha foi adicionada em relação àquele pai,
+///   `-` se só existe naquele pai e ` ` se é igual
+/// - `parent_lines` - Número da linha (a partir de 1) em cada pai, se existir nele
+/// - `new_line` - Número da linha (a partir de 1) no resultado do merge, se existir nele
+/// - `content` - Conteúdo da linha, sem a quebra de linha
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct CombinedLine {
+    pub markers: Vec<char>,
+    pub parent_lines: Vec<Option<usize>>,
+    pub new_line: Option<usize>,
+    pub content: String,
+}
+
+/// Diferenças de um arquivo do merge em relação a todos os pais ao mesmo tempo
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct CombinedDiff {
+    pub path: PathBuf,
+    pub lines: Vec<CombinedLine>,
+}
+
+/// Retorna o diff combinado do merge: só os arquivos que diferem de todos os pais, ou seja,
+/// os que o merge não copiou de nenhum dos lados (como conflitos resolvidos)
+pub fn diff_merge(repo: &Repository, commit: &CommitObject) -> Result<Vec<CombinedDiff>, MinigitError> {
+    let mut parents_files = Vec::new();
+    for parent in &commit.parent {
+        parents_files.push(get_commit_tree_as_map(repo, &repo.get_commit(parent)?)?);
+    }
+    let new_files = get_commit_tree_as_map(repo, commit)?;
+
+    let mut paths: Vec<&String> = new_files.keys()
+        .chain(parents_files.iter().flat_map(HashMap::keys))
+        .collect();
+    paths.sort();
+    paths.dedup();
+
+    let mut result = Vec::new();
+    for path in paths {
+        let new_hash = new_files.get(path);
+        if parents_files.iter().any(|parent_files| parent_files.get(path) == new_hash) {
+            continue;
+        }
+
+        let mut parent_texts = Vec::new();
+        for parent_files in &parents_files {
+            parent_texts.push(blob_text(repo, parent_files.get(path))?);
+        }
+        let new_text = blob_text(repo, new_hash)?;
+
+        result.push(CombinedDiff { path: PathBuf::from(path), lines: combine(&parent_texts, &new_text) });
+    }
+
+    Ok(result)
+}
+
+/// Junta as comparações de cada pai com o resultado: as linhas do resultado aparecem em ordem,
+/// com as linhas removidas de cada pai antes da linha do resultado que as substituiu
+fn combine(parent_texts: &[String], new_text: &str) -> Vec<CombinedLine> {
+    let new_lines: Vec<&str> = new_text.lines().collect();
+    let parent_count = parent_texts.len();
+
+    let mut added = vec![vec![false; new_lines.len()]; parent_count];
+    let mut parent_lines = vec![vec![None; new_lines.len()]; parent_count];
+    // removed[pai][posição]: linhas só do pai que aparecem antes da linha `posição` do resultado
+    let mut removed = vec![vec![Vec::new(); new_lines.len() + 1]; parent_count];
+
+    for (parent, parent_text) in parent_texts.iter().enumerate() {
+        let mut position = 0;
+        for line in diff_lines(parent_text, new_text) {
+            match line.change {
+                LineChange::Unchanged => {
+                    parent_lines[parent][position] = line.old_line;
+                    position += 1;
+                }
+                LineChange::Added => {
+                    added[parent][position] = true;
+                    position += 1;
+                }
+                LineChange::Removed => removed[parent][position].push((line.old_line, line.content)),
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    for position in 0..=new_lines.len() {
+        for (parent, removed_lines) in removed.iter().enumerate() {
+            for (old_line, content) in &removed_lines[position] {
+                let mut markers = vec![' '; parent_count];
+                markers[parent] = '-';
+                let mut lines = vec![None; parent_count];
+                lines[parent] = *old_line;
+                result.push(CombinedLine { markers, parent_lines: lines, new_line: None, content: content.clone() });
+            }
+        }
+
+        if let Some(content) = new_lines.get(position) {
+            result.push(CombinedLine {
+                markers: (0..parent_count).map(|parent| if added[parent][position] { '+' } else { ' ' }).collect(),
+                parent_lines: (0..parent_count).map(|parent| parent_lines[parent][position]).collect(),
+                new_line: Some(position + 1),
+                content: content.to_string(),
+            });
+        }
+    }
+
+    result
+}
+
+/// Formata o diff combinado no formato do `git show` para merges (`diff --cc`, `@@@ -a,b -c,d +e,f @@@`)
+pub fn format_combined(diff: &CombinedDiff, context: usize) -> String {
+    let path = diff.path.display();
+    let mut output = format!("diff --cc {}\n--- a/{}\n+++ b/{}\n", path, path, path);
+
+    let changed: Vec<usize> = (0..diff.lines.len())
+        .filter(|index| diff.lines[*index].markers.iter().any(|marker| *marker != ' '))
+        .collect();
+
+    for hunk in group_changes(&changed, diff.lines.len(), context) {
+        output.push_str(&format_combined_hunk(diff, hunk));
+    }
+
+    output
+}
+
+fn format_combined_hunk(diff: &CombinedDiff, hunk: Range<usize>) -> String {
+    let parent_count = diff.lines.first().map_or(0, |line| line.markers.len());
+    let at_signs = "@".repeat(parent_count + 1);
+
+    let mut ranges = Vec::new();
+    for parent in 0..parent_count {
+        let (start, count) = line_range(&diff.lines, &hunk, |line| line.parent_lines[parent]);
+        ranges.push(format!("-{},{}", start, count));
+    }
+    let (start, count) = line_range(&diff.lines, &hunk, |line| line.new_line);
+    ranges.push(format!("+{},{}", start, count));
+
+    let mut output = format!("{} {} {}\n", at_signs, ranges.join(" "), at_signs);
+    for line in &diff.lines[hunk] {
+        output.push_str(&format!("{}{}\n", line.markers.iter().collect::<String>(), line.content));
+    }
+
+    output
+}
+
+/// Primeira linha e quantidade de linhas de uma versão dentro do trecho. Quando o trecho não tem
+/// linhas daquela versão, usa a última linha anterior (ou 0)
+fn line_range(lines: &[CombinedLine], hunk: &Range<usize>, line_number: impl Fn(&CombinedLine) -> Option<usize>) -> (usize, usize) {
+    let hunk_lines = &lines[hunk.clone()];
+    let count = hunk_lines.iter().filter_map(&line_number).count();
+    let start = hunk_lines.iter().find_map(&line_number)
+        .or_else(|| lines[..hunk.start].iter().rev().find_map(&line_number))
+        .unwrap_or(0);
+
+    (start, count)
+}
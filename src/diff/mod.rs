@@ -0,0 +1,408 @@
+pub mod combined;
+pub mod rename;
+
+use std::{collections::HashMap, ops::Range, path::PathBuf};
+
+use serde::Serialize;
+
+use crate::{Repository, error::MinigitError, objects::{BlobObject, CommitObject, RGitObject, get_commit_tree_as_map}, staging::{STAGE_NORMAL, StagingArea}, status::non_staged_files, utils::is_worktree_file};
+
+pub use combined::{CombinedDiff, CombinedLine, diff_merge, format_combined};
+pub use rename::{RenameKind, RenameSource, detect_renames, find_renames, similarity};
+
+/// Tipo de uma linha no resultado de uma comparação
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineChange {
+    /// A linha existe nas duas versões
+    Unchanged,
+    /// A linha só existe na versão nova
+    Added,
+    /// A linha só existe na versão antiga
+    Removed,
+}
+
+/// Linha do resultado de uma comparação
+///
+/// ## Atributos
+/// - `change` - Se a linha foi mantida, adicionada ou removida
+/// - `old_line` - Número da linha (a partir de 1) na versão antiga, se existir nela
+/// - `new_line` - Número da linha (a partir de 1) na versão nova, se existir nela
+/// - `content` - Conteúdo da linha, sem a quebra de linha
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct DiffLine {
+    pub change: LineChange,
+    pub old_line: Option<usize>,
+    pub new_line: Option<usize>,
+    pub content: String,
+}
+
+/// Diferenças entre duas versões de um arquivo
+///
+/// ## Atributos
+/// - `path` - Caminho do arquivo relativo à raíz do repositório
+/// - `old_hash` - Hash do blob da versão antiga, `None` se o arquivo é novo
+/// - `new_hash` - Hash do blob da versão nova, `None` se o arquivo foi removido
+/// - `lines` - Todas as linhas das duas versões, na ordem em que aparecem
+/// - `source` - Arquivo de origem, se este arquivo foi renomeado ou copiado de outro
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct FileDiff {
+    pub path: PathBuf,
+    pub old_hash: Option<String>,
+    pub new_hash: Option<String>,
+    pub lines: Vec<DiffLine>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<RenameSource>,
+}
+
+impl FileDiff {
+    /// Retorna apenas as linhas adicionadas ou removidas
+    pub fn changes(&self) -> impl Iterator<Item = &DiffLine> {
+        self.lines.iter().filter(|line| line.change != LineChange::Unchanged)
+    }
+
+    /// Quantidade de linhas adicionadas
+    pub fn insertions(&self) -> usize {
+        self.lines.iter().filter(|line| line.change == LineChange::Added).count()
+    }
+
+    /// Quantidade de linhas removidas
+    pub fn deletions(&self) -> usize {
+        self.lines.iter().filter(|line| line.change == LineChange::Removed).count()
+    }
+
+    /// Caminho exibido para o usuário: `origem -> destino` para arquivos renomeados ou copiados
+    pub fn display_path(&self) -> String {
+        match &self.source {
+            Some(source) => format!("{} -> {}", source.path.display(), self.path.display()),
+            None => self.path.display().to_string(),
+        }
+    }
+}
+
+/// Compara dois textos linha a linha usando a maior subsequência comum
+///
+/// As linhas removidas sempre aparecem antes das adicionadas no mesmo trecho.
+pub fn diff_lines(old_text: &str, new_text: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old_text.lines().collect();
+    let new: Vec<&str> = new_text.lines().collect();
+
+    // lcs[i][j] = tamanho da maior subsequência comum entre old[i..] e new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            result.push(DiffLine {
+                change: LineChange::Unchanged,
+                old_line: Some(i + 1),
+                new_line: Some(j + 1),
+                content: old[i].to_string(),
+            });
+            i += 1;
+            j += 1;
+        } else if i < old.len() && (j == new.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            result.push(DiffLine {
+                change: LineChange::Removed,
+                old_line: Some(i + 1),
+                new_line: None,
+                content: old[i].to_string(),
+            });
+            i += 1;
+        } else {
+            result.push(DiffLine {
+                change: LineChange::Added,
+                old_line: None,
+                new_line: Some(j + 1),
+                content: new[j].to_string(),
+            });
+            j += 1;
+        }
+    }
+
+    result
+}
+
+/// Compara os arquivos não adicionados da worktree com as versões do commit apontado pelo HEAD
+///
+/// Arquivos que não existem no HEAD são comparados com um arquivo vazio, e arquivos rastreados
+/// que foram apagados da worktree aparecem como removidos. Renomeações e cópias são detectadas
+/// entre esses arquivos (veja `detect_renames`).
+pub fn diff_worktree(repo: &Repository) -> Result<Vec<FileDiff>, MinigitError> {
+    let head_commit = repo.resolve_head()?;
+
+    let tree_files = if head_commit.is_empty() {
+        HashMap::new()
+    } else {
+        let commit = repo.get_commit(&head_commit)?;
+        get_commit_tree_as_map(repo, &commit)?
+    };
+
+    let mut result = Vec::new();
+    for path in non_staged_files(repo)? {
+        let path_string = path.to_string_lossy().to_string();
+        let old_hash = tree_files.get(&path_string).cloned();
+
+        let old_content = match &old_hash {
+            Some(hash) => String::from_utf8_lossy(&repo.get_blob(hash)?.content).to_string(),
+            None => String::new(),
+        };
+
+        let new_bytes = BlobObject::try_from(&repo.worktree.join(&path))?.content;
+        let new_hash = BlobObject::new(new_bytes.clone()).hash();
+        let new_content = String::from_utf8_lossy(&new_bytes).to_string();
+
+        result.push(FileDiff {
+            path,
+            old_hash,
+            new_hash: Some(new_hash),
+            lines: diff_lines(&old_content, &new_content),
+            source: None,
+        });
+    }
+
+    let staging_area = StagingArea::new(repo)?;
+    for entry in staging_area.entries.iter().filter(|entry| entry.stage == STAGE_NORMAL) {
+        let Some(old_hash) = tree_files.get(entry.path.to_string_lossy().as_ref()) else {
+            continue;
+        };
+
+        if !is_worktree_file(&repo.worktree.join(&entry.path)) {
+            result.push(FileDiff {
+                path: entry.path.clone(),
+                old_hash: Some(old_hash.clone()),
+                new_hash: None,
+                lines: diff_lines(&blob_text(repo, Some(old_hash))?, ""),
+                source: None,
+            });
+        }
+    }
+
+    result.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(detect_renames(result, &tree_files))
+}
+
+/// Compara duas árvores representadas como mapas de caminho -> hash do blob
+///
+/// Retorna uma entrada para cada arquivo adicionado, removido, modificado, renomeado ou copiado,
+/// ordenadas pelo caminho.
+pub fn diff_trees(
+    repo: &Repository,
+    old_files: &HashMap<String, String>,
+    new_files: &HashMap<String, String>,
+) -> Result<Vec<FileDiff>, MinigitError> {
+    Ok(detect_renames(diff_trees_exact(repo, old_files, new_files)?, old_files))
+}
+
+/// Compara duas árvores caminho a caminho, sem detectar renomeações
+pub fn diff_trees_exact(
+    repo: &Repository,
+    old_files: &HashMap<String, String>,
+    new_files: &HashMap<String, String>,
+) -> Result<Vec<FileDiff>, MinigitError> {
+    let mut paths: Vec<&String> = old_files.keys().chain(new_files.keys()).collect();
+    paths.sort();
+    paths.dedup();
+
+    let mut result = Vec::new();
+    for path in paths {
+        let old_hash = old_files.get(path);
+        let new_hash = new_files.get(path);
+
+        if old_hash == new_hash {
+            continue;
+        }
+
+        let old_content = blob_text(repo, old_hash)?;
+        let new_content = blob_text(repo, new_hash)?;
+
+        result.push(FileDiff {
+            path: PathBuf::from(path),
+            old_hash: old_hash.cloned(),
+            new_hash: new_hash.cloned(),
+            lines: diff_lines(&old_content, &new_content),
+            source: None,
+        });
+    }
+
+    Ok(result)
+}
+
+/// Retorna as mudanças introduzidas pelo commit em relação ao seu primeiro pai
+pub fn diff_commit(repo: &Repository, commit: &CommitObject) -> Result<Vec<FileDiff>, MinigitError> {
+    let old_files = match commit.parent.first() {
+        Some(parent) => get_commit_tree_as_map(repo, &repo.get_commit(parent)?)?,
+        None => HashMap::new(),
+    };
+    let new_files = get_commit_tree_as_map(repo, commit)?;
+
+    diff_trees(repo, &old_files, &new_files)
+}
+
+/// Formata as diferenças no formato unificado (`--- a/...`, `+++ b/...`, `@@ ... @@`)
+///
+/// ## Argumentos
+/// - `file_diff` - Diferenças do arquivo
+/// - `context` - Quantidade de linhas inalteradas exibidas ao redor de cada mudança
+pub fn format_unified(file_diff: &FileDiff, context: usize) -> String {
+    let path = file_diff.path.display();
+    let mut output = String::new();
+
+    if let Some(source) = &file_diff.source {
+        let kind = match source.kind {
+            RenameKind::Renamed => "rename",
+            RenameKind::Copied => "copy",
+        };
+        output.push_str(&format!("similarity index {}%\n", source.similarity));
+        output.push_str(&format!("{} from {}\n{} to {}\n", kind, source.path.display(), kind, path));
+
+        if file_diff.changes().next().is_none() {
+            return output;
+        }
+    }
+
+    let old_path = file_diff.source.as_ref().map_or(file_diff.path.display(), |source| source.path.display());
+    match &file_diff.old_hash {
+        Some(_) => output.push_str(&format!("--- a/{}\n", old_path)),
+        None => output.push_str("--- /dev/null\n"),
+    }
+    match &file_diff.new_hash {
+        Some(_) => output.push_str(&format!("+++ b/{}\n", path)),
+        None => output.push_str("+++ /dev/null\n"),
+    }
+
+    for hunk in hunks(file_diff, context) {
+        output.push_str(&format_hunk(file_diff, hunk));
+    }
+
+    output
+}
+
+/// Agrupa as mudanças do arquivo em trechos, juntando mudanças próximas
+///
+/// Cada trecho é o intervalo de índices de `file_diff.lines` com as mudanças e até `context`
+/// linhas inalteradas antes e depois delas.
+pub fn hunks(file_diff: &FileDiff, context: usize) -> Vec<Range<usize>> {
+    let lines = &file_diff.lines;
+    let changed: Vec<usize> = (0..lines.len())
+        .filter(|i| lines[*i].change != LineChange::Unchanged)
+        .collect();
+
+    group_changes(&changed, lines.len(), context)
+}
+
+/// Agrupa os índices das linhas alteradas (em ordem) em trechos com até `context` linhas ao redor
+fn group_changes(changed: &[usize], line_count: usize, context: usize) -> Vec<Range<usize>> {
+    let mut result = Vec::new();
+    let mut index = 0;
+    while index < changed.len() {
+        let start = changed[index].saturating_sub(context);
+        let mut end = (changed[index] + context + 1).min(line_count);
+
+        // Junta as mudanças próximas no mesmo trecho
+        while index + 1 < changed.len() && changed[index + 1] <= end + context {
+            index += 1;
+            end = (changed[index] + context + 1).min(line_count);
+        }
+        index += 1;
+
+        result.push(start..end);
+    }
+
+    result
+}
+
+/// Formata um trecho no formato unificado, com o cabeçalho `@@ -a,b +c,d @@`
+pub fn format_hunk(file_diff: &FileDiff, hunk: Range<usize>) -> String {
+    let lines = &file_diff.lines;
+    let (start, end) = (hunk.start, hunk.end);
+    let hunk_lines = &lines[start..end];
+
+    let old_count = hunk_lines.iter().filter(|l| l.change != LineChange::Added).count();
+    let new_count = hunk_lines.iter().filter(|l| l.change != LineChange::Removed).count();
+    let old_start = first_line_number(&lines[..end], start, |l| l.old_line);
+    let new_start = first_line_number(&lines[..end], start, |l| l.new_line);
+
+    let mut output = format!("@@ -{},{} +{},{} @@\n", old_start, old_count, new_start, new_count);
+    for line in hunk_lines {
+        let marker = match line.change {
+            LineChange::Unchanged => ' ',
+            LineChange::Added => '+',
+            LineChange::Removed => '-',
+        };
+        output.push_str(&format!("{}{}\n", marker, line.content));
+    }
+
+    output
+}
+
+/// Número da primeira linha do trecho que começa em `start`, no formato do cabeçalho `@@`.
+/// Quando o trecho não tem linhas daquela versão, usa a última linha anterior (ou 0).
+fn first_line_number(lines: &[DiffLine], start: usize, line_number: impl Fn(&DiffLine) -> Option<usize>) -> usize {
+    if let Some(number) = lines[start..].iter().find_map(&line_number) {
+        return number;
+    }
+
+    lines[..start].iter().rev().find_map(&line_number).unwrap_or(0)
+}
+
+fn blob_text(repo: &Repository, hash: Option<&String>) -> Result<String, MinigitError> {
+    match hash {
+        Some(hash) => Ok(String::from_utf8_lossy(&repo.get_blob(hash)?.content).to_string()),
+        None => Ok(String::new()),
+    }
+}
+
+/// Monta o conteúdo do arquivo aplicando apenas os trechos selecionados sobre a versão antiga
+///
+/// Linhas dentro dos trechos selecionados ficam como na versão nova; as demais ficam como na versão antiga.
+/// `old_final_newline` e `new_final_newline` dizem se cada versão termina com '\n': a última linha do
+/// resultado só termina com '\n' se a versão de onde ela veio também terminar.
+pub fn apply_hunks(file_diff: &FileDiff, selected: &[Range<usize>], old_final_newline: bool, new_final_newline: bool) -> String {
+    let old_len = file_diff.lines.iter().filter_map(|line| line.old_line).max();
+    let new_len = file_diff.lines.iter().filter_map(|line| line.new_line).max();
+    let mut output = String::new();
+    let mut final_newline = true;
+
+    for (index, line) in file_diff.lines.iter().enumerate() {
+        let use_new = selected.iter().any(|hunk| hunk.contains(&index));
+        let keep = match line.change {
+            LineChange::Unchanged => true,
+            LineChange::Added => use_new,
+            LineChange::Removed => !use_new,
+        };
+
+        if keep {
+            output.push_str(&line.content);
+            output.push('\n');
+
+            let from_new = match line.change {
+                LineChange::Unchanged => use_new,
+                LineChange::Added => true,
+                LineChange::Removed => false,
+            };
+            final_newline = if from_new {
+                new_final_newline || line.new_line != new_len
+            } else {
+                old_final_newline || line.old_line != old_len
+            };
+        }
+    }
+
+    if !final_newline {
+        output.pop();
+    }
+
+    output
+}
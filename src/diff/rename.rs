@@ -0,0 +1,219 @@
+use std::{collections::{HashMap, HashSet}, path::PathBuf};
+
+use serde::Serialize;
+
+use crate::{Repository, error::MinigitError};
+
+use super::{FileDiff, LineChange, diff_lines, diff_trees_exact};
+
+/// Similaridade mínima (em %) para que um arquivo seja considerado renomeado ou copiado de outro
+pub const SIMILARITY_THRESHOLD: u8 = 50;
+
+/// Como um arquivo foi criado a partir de outro
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenameKind {
+    /// O arquivo de origem foi removido
+    Renamed,
+    /// O arquivo de origem continua existindo
+    Copied,
+}
+
+/// Origem de um arquivo renomeado ou copiado
+///
+/// ## Atributos
+/// - `kind` - Se o arquivo foi renomeado ou copiado
+/// - `path` - Caminho do arquivo de origem, relativo à raíz do repositório
+/// - `similarity` - Porcentagem de linhas em comum entre a origem e o arquivo novo
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct RenameSource {
+    pub kind: RenameKind,
+    pub path: PathBuf,
+    pub similarity: u8,
+}
+
+/// Calcula a porcentagem de linhas em comum entre dois textos
+///
+/// Retorna 0 sem comparar as linhas quando a diferença de tamanho já impede a similaridade mínima.
+pub fn similarity(old_text: &str, new_text: &str) -> u8 {
+    let old_count = old_text.lines().count();
+    let new_count = new_text.lines().count();
+    let total = old_count + new_count;
+
+    if total == 0 {
+        return 100;
+    }
+
+    if 200 * old_count.min(new_count) / total < SIMILARITY_THRESHOLD as usize {
+        return 0;
+    }
+
+    let common = diff_lines(old_text, new_text).iter()
+        .filter(|line| line.change == LineChange::Unchanged)
+        .count();
+
+    (200 * common / total) as u8
+}
+
+/// Junta arquivos removidos e adicionados que são renomeações, e marca arquivos adicionados que são cópias
+///
+/// Cada arquivo adicionado é comparado com os removidos; os pares mais semelhantes (acima de
+/// `SIMILARITY_THRESHOLD`) viram uma única entrada com `source` preenchido. Os arquivos adicionados
+/// restantes são cópias se tiverem o mesmo conteúdo de algum arquivo de `old_files`, ou se forem
+/// semelhantes a algum arquivo modificado nas mesmas mudanças. Arquivos vazios são ignorados.
+///
+/// ## Argumentos
+/// - `diffs` - Diferenças ordenadas pelo caminho, como retornadas por `diff_trees`
+/// - `old_files` - Mapa de caminho -> hash do blob da versão antiga
+pub fn detect_renames(diffs: Vec<FileDiff>, old_files: &HashMap<String, String>) -> Vec<FileDiff> {
+    let is_added = |file_diff: &FileDiff| file_diff.old_hash.is_none() && file_diff.new_hash.is_some();
+    let is_deleted = |file_diff: &FileDiff| file_diff.old_hash.is_some() && file_diff.new_hash.is_none();
+
+    let added: Vec<usize> = (0..diffs.len()).filter(|i| is_added(&diffs[*i]) && !diffs[*i].lines.is_empty()).collect();
+    let deleted: Vec<usize> = (0..diffs.len()).filter(|i| is_deleted(&diffs[*i]) && !diffs[*i].lines.is_empty()).collect();
+
+    if added.is_empty() {
+        return diffs;
+    }
+
+    // Renomeações: os pares mais semelhantes primeiro
+    let mut pairs = Vec::new();
+    for &new_index in &added {
+        for &old_index in &deleted {
+            let score = if diffs[old_index].old_hash == diffs[new_index].new_hash {
+                100
+            } else {
+                similarity(&old_text(&diffs[old_index]), &new_text(&diffs[new_index]))
+            };
+
+            if score >= SIMILARITY_THRESHOLD {
+                pairs.push((score, new_index, old_index));
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.0.cmp(&a.0).then(diffs[a.1].path.cmp(&diffs[b.1].path)).then(diffs[a.2].path.cmp(&diffs[b.2].path)));
+
+    // Arquivo adicionado -> (índice da origem, tipo, similaridade)
+    let mut sources: HashMap<usize, (usize, RenameKind, u8)> = HashMap::new();
+    let mut used_deleted: HashSet<usize> = HashSet::new();
+    for (score, new_index, old_index) in pairs {
+        if sources.contains_key(&new_index) || used_deleted.contains(&old_index) {
+            continue;
+        }
+        sources.insert(new_index, (old_index, RenameKind::Renamed, score));
+        used_deleted.insert(old_index);
+    }
+
+    // Cópias de arquivos que continuam existindo
+    let deleted_paths: HashSet<String> = deleted.iter().map(|i| diffs[*i].path.to_string_lossy().to_string()).collect();
+    let modified: Vec<usize> = (0..diffs.len()).filter(|i| diffs[*i].old_hash.is_some() && diffs[*i].new_hash.is_some()).collect();
+    let mut exact_copies: HashMap<usize, String> = HashMap::new();
+
+    let remaining: Vec<usize> = added.iter().copied().filter(|i| !sources.contains_key(i)).collect();
+    for new_index in remaining {
+        let new_hash = diffs[new_index].new_hash.as_ref();
+
+        let mut identical: Vec<&String> = old_files.iter()
+            .filter(|(path, hash)| Some(*hash) == new_hash && !deleted_paths.contains(*path))
+            .map(|(path, _)| path)
+            .collect();
+        identical.sort();
+
+        if let Some(path) = identical.first() {
+            exact_copies.insert(new_index, path.to_string());
+            continue;
+        }
+
+        let best = modified.iter()
+            .map(|&old_index| (similarity(&old_text(&diffs[old_index]), &new_text(&diffs[new_index])), old_index))
+            .filter(|(score, _)| *score >= SIMILARITY_THRESHOLD)
+            .max_by(|a, b| a.0.cmp(&b.0).then(diffs[b.1].path.cmp(&diffs[a.1].path)));
+
+        if let Some((score, old_index)) = best {
+            sources.insert(new_index, (old_index, RenameKind::Copied, score));
+        }
+    }
+
+    let mut result = Vec::new();
+    for (index, file_diff) in diffs.iter().enumerate() {
+        if used_deleted.contains(&index) {
+            continue;
+        }
+
+        if let Some(path) = exact_copies.get(&index) {
+            let text = new_text(file_diff);
+            result.push(FileDiff {
+                path: file_diff.path.clone(),
+                old_hash: file_diff.new_hash.clone(),
+                new_hash: file_diff.new_hash.clone(),
+                lines: diff_lines(&text, &text),
+                source: Some(RenameSource { kind: RenameKind::Copied, path: PathBuf::from(path), similarity: 100 }),
+            });
+        } else if let Some((old_index, kind, score)) = sources.get(&index) {
+            let old_diff = &diffs[*old_index];
+            result.push(FileDiff {
+                path: file_diff.path.clone(),
+                old_hash: old_diff.old_hash.clone(),
+                new_hash: file_diff.new_hash.clone(),
+                lines: diff_lines(&old_text(old_diff), &new_text(file_diff)),
+                source: Some(RenameSource { kind: *kind, path: old_diff.path.clone(), similarity: *score }),
+            });
+        } else {
+            result.push(file_diff.clone());
+        }
+    }
+
+    result
+}
+
+/// Retorna as renomeações entre duas árvores representadas como mapas de caminho -> hash do blob
+///
+/// O resultado mapeia o caminho novo para o caminho antigo.
+pub fn find_renames(
+    repo: &Repository,
+    old_files: &HashMap<String, String>,
+    new_files: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, MinigitError> {
+    let removed: HashMap<String, String> = old_files.iter()
+        .filter(|(path, _)| !new_files.contains_key(*path))
+        .map(|(path, hash)| (path.clone(), hash.clone()))
+        .collect();
+    let added: HashMap<String, String> = new_files.iter()
+        .filter(|(path, _)| !old_files.contains_key(*path))
+        .map(|(path, hash)| (path.clone(), hash.clone()))
+        .collect();
+
+    if removed.is_empty() || added.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let diffs = diff_trees_exact(repo, &removed, &added)?;
+    Ok(detect_renames(diffs, &HashMap::new())
+        .into_iter()
+        .filter_map(|file_diff| match file_diff.source {
+            Some(RenameSource { kind: RenameKind::Renamed, path, .. }) => {
+                Some((file_diff.path.to_string_lossy().to_string(), path.to_string_lossy().to_string()))
+            }
+            _ => None,
+        })
+        .collect())
+}
+
+/// Conteúdo da versão antiga do arquivo, reconstruído a partir das linhas da comparação
+fn old_text(file_diff: &FileDiff) -> String {
+    join_lines(file_diff, LineChange::Added)
+}
+
+/// Conteúdo da versão nova do arquivo, reconstruído a partir das linhas da comparação
+fn new_text(file_diff: &FileDiff) -> String {
+    join_lines(file_diff, LineChange::Removed)
+}
+
+fn join_lines(file_diff: &FileDiff, skip: LineChange) -> String {
+    let mut text = String::new();
+    for line in file_diff.lines.iter().filter(|line| line.change != skip) {
+        text.push_str(&line.content);
+        text.push('\n');
+    }
+    text
+}
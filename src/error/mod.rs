@@ -0,0 +1,131 @@
+use std::{fmt, path::PathBuf};
+
+/// Erro retornado pelas operações do minigit
+///
+/// Cada variante corresponde a um código de saída distinto no CLI (veja [`MinigitError::exit_code`]).
+#[derive(Debug)]
+pub enum MinigitError {
+    /// O diretório não está dentro de um repositório minigit
+    NotARepository,
+    /// O item que seria criado (repositório, branch, diretório...) já existe
+    AlreadyExists(String),
+    /// Não existe objeto com o hash informado
+    ObjectNotFound(String),
+    /// O objeto existe mas está corrompido ou não é do tipo esperado
+    CorruptObject(String),
+    /// O arquivo de índice está corrompido
+    CorruptIndex(String),
+    /// O arquivo de configuração é inválido
+    InvalidConfig(String),
+    /// A referência não existe ou aponta para algo inválido
+    InvalidRef(String),
+    /// Argumento inválido fornecido pelo usuário
+    InvalidArgument(String),
+    /// O repositório não está em um estado que permita a operação (merge em progresso, HEAD destacado...)
+    InvalidState(String),
+    /// A operação encontrou conflitos nos arquivos listados
+    Conflict(Vec<String>),
+    /// Existem arquivos com mudanças não commitadas
+    DirtyWorktree(Vec<PathBuf>),
+    /// Existem arquivos com mudanças não adicionadas ao índice
+    UnstagedChanges(Vec<PathBuf>),
+    /// Um gancho terminou com código diferente de zero
+    HookFailed { hook: String, code: Option<i32> },
+    /// A operação foi abortada pelo usuário
+    Aborted(String),
+    /// O arquivo de lock já existe: outro processo do minigit está alterando o repositório
+    Locked(PathBuf),
+    /// Erro de entrada e saída
+    Io(std::io::Error),
+}
+
+impl MinigitError {
+    /// Código de saída do processo correspondente ao erro
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            MinigitError::InvalidArgument(_) => 2,
+            MinigitError::NotARepository => 3,
+            MinigitError::AlreadyExists(_) => 4,
+            MinigitError::ObjectNotFound(_) => 5,
+            MinigitError::CorruptObject(_) => 6,
+            MinigitError::CorruptIndex(_) => 7,
+            MinigitError::InvalidConfig(_) => 8,
+            MinigitError::InvalidRef(_) => 9,
+            MinigitError::InvalidState(_) => 10,
+            MinigitError::Conflict(_) => 11,
+            MinigitError::DirtyWorktree(_) => 12,
+            MinigitError::UnstagedChanges(_) => 13,
+            MinigitError::HookFailed { .. } => 14,
+            MinigitError::Aborted(_) => 15,
+            MinigitError::Locked(_) => 16,
+            MinigitError::Io(_) => 74,
+        }
+    }
+}
+
+impl fmt::Display for MinigitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MinigitError::NotARepository => write!(f, "Diretório não está dentro um repositório minigit"),
+            MinigitError::AlreadyExists(message) => write!(f, "{}", message),
+            MinigitError::ObjectNotFound(hash) => write!(f, "Objeto {} não encontrado no repositório", hash),
+            MinigitError::CorruptObject(message) => write!(f, "Objeto corrompido: {}", message),
+            MinigitError::CorruptIndex(message) => write!(f, "Índice corrompido: {}", message),
+            MinigitError::InvalidConfig(message) => write!(f, "Configuração inválida: {}", message),
+            MinigitError::InvalidRef(reference) => write!(f, "Referência inválida: {}", reference),
+            MinigitError::InvalidArgument(message) => write!(f, "{}", message),
+            MinigitError::InvalidState(message) => write!(f, "{}", message),
+            MinigitError::Conflict(files) => {
+                write!(f, "Conflitos encontrados nos arquivos:")?;
+                for file in files {
+                    write!(f, "\n- {}", file)?;
+                }
+                Ok(())
+            }
+            MinigitError::DirtyWorktree(files) => {
+                writeln!(f, "Existem arquivos com mudanças não commitadas no repositório:")?;
+                writeln!(f, "Faça o commit dessas mudanças com 'minigit commit -m <mensagem>' ou descarte as mudanças antes de continuar.")?;
+                write!(f, "\nArquivos com mudanças não commitadas:")?;
+                write_file_list(f, files)
+            }
+            MinigitError::UnstagedChanges(files) => {
+                writeln!(f, "Existem arquivos não adicionados no repositório:")?;
+                writeln!(f, "Adicione-os com 'minigit add <arquivos>' ou descarte as mudanças antes de continuar.")?;
+                write!(f, "\nArquivos não adicionados:")?;
+                write_file_list(f, files)
+            }
+            MinigitError::HookFailed { hook, code } => match code {
+                Some(code) => write!(f, "Gancho '{}' falhou com código {}", hook, code),
+                None => write!(f, "Gancho '{}' foi interrompido", hook),
+            },
+            MinigitError::Aborted(message) => write!(f, "{}", message),
+            MinigitError::Locked(lock_path) => {
+                writeln!(f, "Não foi possível criar '{}': outro processo do minigit está em execução neste repositório.", lock_path.display())?;
+                write!(f, "Se nenhum outro processo estiver em execução, apague o arquivo e tente novamente.")
+            }
+            MinigitError::Io(err) => write!(f, "Erro de entrada e saída: {}", err),
+        }
+    }
+}
+
+fn write_file_list(f: &mut fmt::Formatter<'_>, files: &[PathBuf]) -> fmt::Result {
+    for file in files {
+        write!(f, "\n- {}", file.display())?;
+    }
+    Ok(())
+}
+
+impl std::error::Error for MinigitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MinigitError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for MinigitError {
+    fn from(err: std::io::Error) -> Self {
+        MinigitError::Io(err)
+    }
+}
@@ -0,0 +1,371 @@
+use std::{collections::{BTreeMap, HashSet}, fmt::Display};
+
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::{
+    Repository,
+    error::MinigitError,
+    objects::{CommitObject, MODE_TREE, TreeObject, is_valid_tree_entry_name, parse_mode},
+    staging::StagingArea,
+    utils::{is_valid_sha1, sha1sum},
+};
+
+/// Tipo de problema encontrado pelo `fsck`
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FsckIssueKind {
+    /// O conteúdo do objeto não tem o hash do nome do arquivo
+    HashMismatch,
+    /// O objeto não pôde ser lido: cabeçalho, commit ou árvore mal formatados
+    CorruptObject,
+    /// Um objeto, referência ou entrada do índice aponta para um objeto que não existe
+    MissingObject,
+    /// Um objeto aponta para outro de um tipo diferente do esperado (ex: pai que não é um commit)
+    WrongType,
+    /// Uma árvore tem uma entrada com nome inválido (vazio, `.`, `..`, `.minigit` ou com `/`)
+    BadTreeEntry,
+    /// Um arquivo de referência (HEAD, branch, MERGE_HEAD...) está mal formatado ou aponta para um não-commit
+    BadRef,
+    /// O índice não pôde ser lido
+    BadIndex,
+    /// Objeto inalcançável que nenhum outro objeto inalcançável referencia
+    Dangling,
+    /// Objeto que não é alcançável a partir das referências nem do índice
+    Unreachable,
+}
+
+impl FsckIssueKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            FsckIssueKind::HashMismatch => "hash-mismatch",
+            FsckIssueKind::CorruptObject => "corrupt",
+            FsckIssueKind::MissingObject => "missing",
+            FsckIssueKind::WrongType => "wrong-type",
+            FsckIssueKind::BadTreeEntry => "bad-tree-entry",
+            FsckIssueKind::BadRef => "bad-ref",
+            FsckIssueKind::BadIndex => "bad-index",
+            FsckIssueKind::Dangling => "dangling",
+            FsckIssueKind::Unreachable => "unreachable",
+        }
+    }
+
+    /// Objetos soltos não são erros: são só lixo deixado por resets, rebases e merges abortados
+    pub fn is_error(&self) -> bool {
+        !matches!(self, FsckIssueKind::Dangling | FsckIssueKind::Unreachable)
+    }
+}
+
+/// Problema encontrado pelo `fsck`
+///
+/// ## Atributos
+/// - `kind` - Tipo do problema
+/// - `object_type` - Tipo do objeto envolvido (`commit`, `tree` ou `blob`), se conhecido
+/// - `object` - Hash do objeto ou nome da referência com problema
+/// - `message` - Detalhes do problema
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize)]
+pub struct FsckIssue {
+    pub kind: FsckIssueKind,
+    pub object_type: Option<String>,
+    pub object: String,
+    pub message: Option<String>,
+}
+
+impl Display for FsckIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.kind.name())?;
+        if let Some(object_type) = &self.object_type {
+            write!(f, " {}", object_type)?;
+        }
+        write!(f, " {}", self.object)?;
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+/// Opções do `fsck`
+///
+/// ## Atributos
+/// - `unreachable` - Lista todos os objetos inalcançáveis em vez de só os soltos (dangling)
+/// - `dangling` - Lista os objetos soltos
+#[derive(Clone, Debug)]
+pub struct FsckOptions {
+    pub unreachable: bool,
+    pub dangling: bool,
+}
+
+impl Default for FsckOptions {
+    fn default() -> Self {
+        FsckOptions { unreachable: false, dangling: true }
+    }
+}
+
+/// Resultado do `fsck`: a quantidade de objetos verificados e os problemas encontrados, em ordem
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct FsckReport {
+    pub object_count: usize,
+    pub issues: Vec<FsckIssue>,
+}
+
+impl FsckReport {
+    /// Retorna os problemas que indicam um repositório corrompido
+    pub fn errors(&self) -> impl Iterator<Item = &FsckIssue> {
+        self.issues.iter().filter(|issue| issue.kind.is_error())
+    }
+
+    fn push(&mut self, kind: FsckIssueKind, object_type: Option<&str>, object: &str, message: Option<String>) {
+        self.issues.push(FsckIssue {
+            kind,
+            object_type: object_type.map(String::from),
+            object: object.to_string(),
+            message,
+        });
+    }
+}
+
+/// Objeto lido com sucesso: o tipo, os objetos para os quais ele aponta, com o tipo esperado de cada um,
+/// e, para árvores, os nomes de entrada inválidos
+struct ObjectInfo {
+    object_type: String,
+    links: Vec<(String, &'static str)>,
+    bad_names: Vec<String>,
+}
+
+/// Objetos da pasta `objects`: os lidos com sucesso e os hashes de todos os arquivos, inclusive os corrompidos,
+/// para que objetos corrompidos não sejam informados também como inexistentes
+#[derive(Default)]
+struct ObjectStore {
+    objects: BTreeMap<String, ObjectInfo>,
+    present: HashSet<String>,
+}
+
+/// Verifica a integridade do repositório: o hash e o formato de todos os objetos, se os objetos
+/// referenciados por commits, árvores, referências e pelo índice existem e têm o tipo certo, e
+/// quais objetos não são alcançáveis a partir das referências e do índice.
+pub fn fsck(repo: &Repository, options: &FsckOptions) -> Result<FsckReport, MinigitError> {
+    let mut report = FsckReport::default();
+
+    let store = read_objects(repo, &mut report)?;
+    report.object_count = store.present.len();
+    check_links(&store, &mut report);
+
+    let roots = check_refs(repo, &store, &mut report)?;
+    check_reachability(&store.objects, roots, options, &mut report);
+
+    report.issues.sort();
+    Ok(report)
+}
+
+/// Lê todos os objetos da pasta `objects`, verificando o hash e o formato de cada um
+fn read_objects(repo: &Repository, report: &mut FsckReport) -> Result<ObjectStore, MinigitError> {
+    let mut store = ObjectStore::default();
+    let objects_dir = repo.get_repository_path(&["objects"]);
+
+    for entry in WalkDir::new(&objects_dir).min_depth(2).max_depth(2).sort_by_file_name() {
+        let entry = entry.map_err(std::io::Error::from)?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let relative = entry.path().strip_prefix(&objects_dir).unwrap_or(entry.path());
+        let hash: String = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+        if !is_valid_sha1(&hash) {
+            report.push(FsckIssueKind::CorruptObject, None, &relative.display().to_string(), Some("nome de arquivo não é um hash".to_string()));
+            continue;
+        }
+
+        store.present.insert(hash.clone());
+        let bytes = std::fs::read(entry.path())?;
+        let actual_hash = sha1sum(&bytes);
+        if actual_hash != hash {
+            report.push(FsckIssueKind::HashMismatch, None, &hash, Some(format!("o conteúdo tem o hash {}", actual_hash)));
+        }
+
+        match parse_object(&hash, bytes) {
+            Ok(info) => {
+                for name in &info.bad_names {
+                    report.push(FsckIssueKind::BadTreeEntry, Some("tree"), &hash, Some(format!("nome de entrada inválido '{}'", name.escape_debug())));
+                }
+                store.objects.insert(hash, info);
+            }
+            Err(err) => report.push(FsckIssueKind::CorruptObject, None, &hash, Some(err.to_string())),
+        }
+    }
+
+    Ok(store)
+}
+
+fn parse_object(hash: &str, bytes: Vec<u8>) -> Result<ObjectInfo, MinigitError> {
+    let (object_type, content) = Repository::split_object_bytes(hash, bytes)?;
+    let malformed_hash = |field: &str, value: &str| {
+        MinigitError::CorruptObject(format!("{} '{}' não é um hash válido", field, value))
+    };
+
+    let mut bad_names = Vec::new();
+    let links = match object_type.as_str() {
+        "commit" => {
+            let commit = CommitObject::new(content)?;
+            if !is_valid_sha1(&commit.tree) {
+                return Err(malformed_hash("árvore", &commit.tree));
+            }
+
+            let mut links = vec![(commit.tree.clone(), "tree")];
+            for parent in commit.parent {
+                if !is_valid_sha1(&parent) {
+                    return Err(malformed_hash("pai", &parent));
+                }
+                links.push((parent, "commit"));
+            }
+            links
+        }
+        "tree" => {
+            let mut links = Vec::new();
+            for child in TreeObject::new(content)?.children {
+                let mode = parse_mode(&child.mode)?;
+                if !is_valid_sha1(&child.object_id) {
+                    return Err(malformed_hash(&child.name, &child.object_id));
+                }
+                if !is_valid_tree_entry_name(&child.name) {
+                    bad_names.push(child.name.clone());
+                }
+                links.push((child.object_id, if mode == MODE_TREE { "tree" } else { "blob" }));
+            }
+            links
+        }
+        "blob" => Vec::new(),
+        other => return Err(MinigitError::CorruptObject(format!("tipo de objeto desconhecido '{}'", other))),
+    };
+
+    Ok(ObjectInfo { object_type, links, bad_names })
+}
+
+/// Verifica se os objetos para os quais cada commit e árvore apontam existem e têm o tipo esperado
+fn check_links(store: &ObjectStore, report: &mut FsckReport) {
+    for (hash, info) in &store.objects {
+        for (target, expected_type) in &info.links {
+            match store.objects.get(target) {
+                None if store.present.contains(target) => {}
+                None => report.push(
+                    FsckIssueKind::MissingObject,
+                    Some(expected_type),
+                    target,
+                    Some(format!("referenciado por {} {}", info.object_type, hash)),
+                ),
+                Some(target_info) if target_info.object_type != *expected_type => report.push(
+                    FsckIssueKind::WrongType,
+                    Some(&target_info.object_type),
+                    target,
+                    Some(format!("{} {} espera um {}", info.object_type, hash, expected_type)),
+                ),
+                Some(_) => {}
+            }
+        }
+    }
+}
+
+/// Verifica o HEAD, as branches, os arquivos de merge e rebase e o índice, retornando os objetos
+/// a partir dos quais os demais são alcançáveis
+fn check_refs(repo: &Repository, store: &ObjectStore, report: &mut FsckReport) -> Result<Vec<String>, MinigitError> {
+    let objects = &store.objects;
+    let mut roots = Vec::new();
+
+    let mut check_commit = |name: &str, value: &str, report: &mut FsckReport| {
+        if !is_valid_sha1(value) {
+            report.push(FsckIssueKind::BadRef, None, name, Some(format!("conteúdo inválido '{}'", value)));
+        } else if !store.present.contains(value) {
+            report.push(FsckIssueKind::BadRef, None, name, Some(format!("aponta para o objeto inexistente {}", value)));
+        } else if !objects.contains_key(value) {
+            report.push(FsckIssueKind::BadRef, None, name, Some(format!("aponta para o objeto corrompido {}", value)));
+        } else if objects[value].object_type != "commit" {
+            report.push(FsckIssueKind::BadRef, None, name, Some(format!("aponta para o {} {}, que não é um commit", objects[value].object_type, value)));
+        } else {
+            roots.push(value.to_string());
+        }
+    };
+
+    let head = std::fs::read_to_string(&repo.head_path)?;
+    let head = head.trim();
+    match head.strip_prefix("ref: ") {
+        Some(reference) if !reference.starts_with("refs/heads/") => {
+            report.push(FsckIssueKind::BadRef, None, Repository::HEAD, Some(format!("referência inválida '{}'", reference)));
+        }
+        Some(_) => {}
+        None => check_commit(Repository::HEAD, head, report),
+    }
+
+    // Branches (`refs/heads`) e notas (`refs/notes`)
+    let refs_path = repo.minigitdir.join("refs");
+    for entry in WalkDir::new(&refs_path).sort_by_file_name().into_iter().filter_map(|entry| entry.ok()) {
+        if !entry.file_type().is_file() || entry.file_name() != Repository::INDEX {
+            continue;
+        }
+
+        let ref_dir = entry.path().parent().unwrap_or(entry.path());
+        let name = format!("refs/{}", ref_dir.strip_prefix(&refs_path).unwrap_or(ref_dir).display());
+        let value = std::fs::read_to_string(entry.path())?;
+
+        // Branches sem commits (recém-criadas em um repositório vazio) ficam vazias
+        if !value.trim().is_empty() {
+            check_commit(&name, value.trim(), report);
+        }
+    }
+
+    for (name, path) in [
+        (Repository::MERGE_HEAD, &repo.merge_head_path),
+        (Repository::ORIG_HEAD, &repo.orig_head_path),
+        (Repository::REBASE_HEAD, &repo.rebase_head_path),
+    ] {
+        if path.is_file() {
+            check_commit(name, std::fs::read_to_string(path)?.trim(), report);
+        }
+    }
+
+    match StagingArea::new(repo) {
+        Ok(staging_area) => {
+            for entry in &staging_area.entries {
+                if store.present.contains(&entry.object_hash) {
+                    roots.push(entry.object_hash.clone());
+                } else {
+                    let message = format!("referenciado pelo índice em {}", entry.path.display());
+                    report.push(FsckIssueKind::MissingObject, Some("blob"), &entry.object_hash, Some(message));
+                }
+            }
+        }
+        Err(err) => report.push(FsckIssueKind::BadIndex, None, Repository::INDEX, Some(err.to_string())),
+    }
+
+    Ok(roots)
+}
+
+/// Marca os objetos alcançáveis a partir de `roots` e informa os inalcançáveis: todos com
+/// `options.unreachable`, ou só os soltos (que nenhum outro objeto inalcançável referencia)
+fn check_reachability(objects: &BTreeMap<String, ObjectInfo>, roots: Vec<String>, options: &FsckOptions, report: &mut FsckReport) {
+    let mut reachable = HashSet::new();
+    let mut stack = roots;
+
+    while let Some(hash) = stack.pop() {
+        let Some(info) = objects.get(&hash) else {
+            continue;
+        };
+        if reachable.insert(hash) {
+            stack.extend(info.links.iter().map(|(target, _)| target.clone()));
+        }
+    }
+
+    let unreachable: Vec<(&String, &ObjectInfo)> = objects.iter()
+        .filter(|(hash, _)| !reachable.contains(*hash))
+        .collect();
+    let referenced: HashSet<&String> = unreachable.iter()
+        .flat_map(|(_, info)| info.links.iter().map(|(target, _)| target))
+        .collect();
+
+    for (hash, info) in unreachable {
+        if options.unreachable {
+            report.push(FsckIssueKind::Unreachable, Some(&info.object_type), hash, None);
+        } else if options.dangling && !referenced.contains(hash) {
+            report.push(FsckIssueKind::Dangling, Some(&info.object_type), hash, None);
+        }
+    }
+}
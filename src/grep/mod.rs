@@ -0,0 +1,202 @@
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+
+use crate::{
+    Repository,
+    error::MinigitError,
+    objects::get_commit_tree_as_map,
+    staging::StagingArea,
+    utils::{pathspec::Pathspec, resolve_revision},
+};
+
+/// Quantidade de bytes do início do arquivo verificados ao decidir se ele é binário, como no git
+pub const BINARY_CHECK_LEN: usize = 8000;
+
+/// De onde o conteúdo dos arquivos é lido
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum GrepSource {
+    /// Arquivos rastreados (presentes no índice), lidos da worktree
+    #[default]
+    Worktree,
+    /// Blobs do índice
+    Index,
+    /// Blobs da árvore da revisão, lidos direto do banco de objetos
+    Revision(String),
+}
+
+/// Opções do `grep`
+///
+/// ## Atributos
+/// - `pattern` - Expressão regular procurada em cada linha
+/// - `source` - Worktree, índice ou revisão onde a busca é feita
+/// - `paths` - Pathspec que limita os arquivos buscados
+/// - `ignore_case` - Ignora a diferença entre maiúsculas e minúsculas
+/// - `word` - Só aceita ocorrências que formam palavras inteiras
+/// - `before_context` / `after_context` - Linhas exibidas antes e depois de cada linha encontrada
+#[derive(Clone, Debug, Default)]
+pub struct GrepOptions {
+    pub pattern: String,
+    pub source: GrepSource,
+    pub paths: Vec<String>,
+    pub ignore_case: bool,
+    pub word: bool,
+    pub before_context: usize,
+    pub after_context: usize,
+}
+
+/// Linha exibida no resultado: uma linha encontrada ou uma linha de contexto
+#[derive(Clone, Debug, Serialize)]
+pub struct GrepLine {
+    pub number: usize,
+    pub content: String,
+    pub is_match: bool,
+}
+
+/// Arquivo com ao menos uma linha encontrada
+///
+/// ## Atributos
+/// - `path` - Caminho relativo à raíz do repositório
+/// - `match_count` - Quantidade de linhas encontradas
+/// - `lines` - Linhas encontradas e de contexto, em ordem
+#[derive(Clone, Debug, Serialize)]
+pub struct GrepFile {
+    pub path: String,
+    pub match_count: usize,
+    pub lines: Vec<GrepLine>,
+}
+
+/// Resultado da busca em um arquivo, junto da posição dele na lista de arquivos
+type FileResult = (usize, Result<Option<GrepFile>, MinigitError>);
+
+/// Procura o padrão nos arquivos da origem escolhida, sem alterar a worktree.
+///
+/// Os arquivos são buscados em paralelo e retornados em ordem de caminho; arquivos binários
+/// (com um byte nulo no início) são ignorados.
+pub fn grep(repo: &Repository, options: &GrepOptions) -> Result<Vec<GrepFile>, MinigitError> {
+    let regex = build_regex(options)?;
+    let pathspec = Pathspec::parse(repo, &options.paths)?;
+
+    let files: Vec<(String, String)> = source_files(repo, &options.source)?
+        .into_iter()
+        .filter(|(path, _)| pathspec.matches(Path::new(path)))
+        .collect();
+
+    let next = AtomicUsize::new(0);
+    let workers = std::thread::available_parallelism().map_or(1, |count| count.get()).min(files.len());
+
+    let results: Vec<Vec<FileResult>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| scope.spawn(|| {
+                let mut found = Vec::new();
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some((path, hash)) = files.get(index) else {
+                        break;
+                    };
+                    let file = read_content(repo, &options.source, path, hash)
+                        .map(|content| content.and_then(|content| search(path, &content, &regex, options)));
+                    found.push((index, file));
+                }
+                found
+            }))
+            .collect();
+
+        handles.into_iter()
+            .map(|handle| handle.join().expect("thread do grep terminou com pânico"))
+            .collect()
+    });
+
+    let mut ordered = BTreeMap::new();
+    for (index, result) in results.into_iter().flatten() {
+        if let Some(file) = result? {
+            ordered.insert(index, file);
+        }
+    }
+
+    Ok(ordered.into_values().collect())
+}
+
+fn build_regex(options: &GrepOptions) -> Result<Regex, MinigitError> {
+    let pattern = if options.word {
+        format!(r"\b(?:{})\b", options.pattern)
+    } else {
+        options.pattern.clone()
+    };
+
+    RegexBuilder::new(&pattern)
+        .case_insensitive(options.ignore_case)
+        .build()
+        .map_err(|err| MinigitError::InvalidArgument(format!("Expressão regular inválida '{}': {}", options.pattern, err)))
+}
+
+/// Arquivos da origem, ordenados por caminho: pares (caminho, hash do blob)
+fn source_files(repo: &Repository, source: &GrepSource) -> Result<Vec<(String, String)>, MinigitError> {
+    let mut files: Vec<(String, String)> = match source {
+        GrepSource::Worktree | GrepSource::Index => StagingArea::new(repo)?
+            .entries
+            .iter()
+            .filter(|entry| entry.stage == 0)
+            .map(|entry| (entry.path.to_string_lossy().to_string(), entry.object_hash.clone()))
+            .collect(),
+        GrepSource::Revision(revision) => {
+            let commit = repo.get_commit(&resolve_revision(repo, revision)?)?;
+            get_commit_tree_as_map(repo, &commit)?.into_iter().collect()
+        }
+    };
+
+    files.sort();
+    Ok(files)
+}
+
+/// Lê o conteúdo do arquivo, ou `None` se ele foi removido da worktree ou é binário
+fn read_content(repo: &Repository, source: &GrepSource, path: &str, hash: &str) -> Result<Option<String>, MinigitError> {
+    let bytes = match source {
+        GrepSource::Worktree => match std::fs::read(repo.worktree.join(path)) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        },
+        GrepSource::Index | GrepSource::Revision(_) => repo.get_blob(&hash.to_string())?.content,
+    };
+
+    if bytes.iter().take(BINARY_CHECK_LEN).any(|byte| *byte == 0) {
+        return Ok(None);
+    }
+
+    Ok(Some(String::from_utf8_lossy(&bytes).to_string()))
+}
+
+/// Procura o padrão nas linhas do conteúdo, incluindo as linhas de contexto pedidas
+fn search(path: &str, content: &str, regex: &Regex, options: &GrepOptions) -> Option<GrepFile> {
+    let lines: Vec<&str> = content.lines().collect();
+    let matches: Vec<usize> = (0..lines.len()).filter(|index| regex.is_match(lines[*index])).collect();
+
+    if matches.is_empty() {
+        return None;
+    }
+
+    let mut shown = vec![false; lines.len()];
+    for index in &matches {
+        let start = index.saturating_sub(options.before_context);
+        let end = (index + options.after_context).min(lines.len() - 1);
+        shown[start..=end].iter_mut().for_each(|line| *line = true);
+    }
+
+    let result = shown.iter()
+        .enumerate()
+        .filter(|(_, shown)| **shown)
+        .map(|(index, _)| GrepLine {
+            number: index + 1,
+            content: lines[index].to_string(),
+            is_match: regex.is_match(lines[index]),
+        })
+        .collect();
+
+    Some(GrepFile { path: path.to_string(), match_count: matches.len(), lines: result })
+}
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{
+    Repository,
+    diff::{LineChange, diff_lines, find_renames},
+    error::MinigitError,
+    objects::{CommitObject, get_commit_tree_as_map},
+    utils::resolve_revision,
+};
+
+/// Origem de uma linha do arquivo
+///
+/// ## Atributos
+/// - `hash` - Commit que introduziu a linha
+/// - `commit` - Objeto desse commit (autor, data e mensagem)
+/// - `path` - Caminho do arquivo naquele commit, que muda se o arquivo foi renomeado
+/// - `original_line` - Número da linha (a partir de 1) no arquivo daquele commit
+/// - `final_line` - Número da linha (a partir de 1) no arquivo da revisão pedida
+/// - `content` - Conteúdo da linha, sem a quebra de linha
+/// - `boundary` - O commit não tem pais, então todas as linhas restantes vieram dele
+#[derive(Clone, Debug, Serialize)]
+pub struct BlameLine {
+    pub hash: String,
+    pub commit: CommitObject,
+    pub path: String,
+    pub original_line: usize,
+    pub final_line: usize,
+    pub content: String,
+    pub boundary: bool,
+}
+
+/// Opções do `blame`
+///
+/// ## Atributos
+/// - `revision` - Revisão cujo arquivo é anotado (o HEAD por padrão)
+/// - `ignore_whitespace` - Ignora mudanças só de espaços ao comparar as versões
+#[derive(Clone, Debug, Default)]
+pub struct BlameOptions {
+    pub revision: Option<String>,
+    pub ignore_whitespace: bool,
+}
+
+/// Linhas ainda sem origem, esperando para serem comparadas com os pais de um commit:
+/// pares (índice no resultado, número da linha no arquivo do commit)
+type PendingLines = Vec<(usize, usize)>;
+
+/// Atribui cada linha do arquivo `path` (relativo à raíz do repositório) ao commit que a introduziu
+///
+/// Os commits são visitados do mais recente para o mais antigo. As linhas que ficam iguais às de um pai
+/// são passadas para ele, seguindo renomeações; as que sobram são atribuídas ao próprio commit.
+pub fn blame(repo: &Repository, path: &str, options: &BlameOptions) -> Result<Vec<BlameLine>, MinigitError> {
+    let revision = options.revision.as_deref().unwrap_or(Repository::HEAD);
+    let start = resolve_revision(repo, revision)?;
+    let mut cache = CommitCache { repo, commits: HashMap::new(), trees: HashMap::new() };
+
+    let text = cache.file_text(&start, path)?
+        .ok_or_else(|| MinigitError::InvalidArgument(format!("Arquivo '{}' não existe em {}", path, revision)))?;
+
+    let line_count = text.lines().count();
+    let mut result: Vec<Option<BlameLine>> = vec![None; line_count];
+
+    let mut pending: HashMap<(String, String), PendingLines> = HashMap::new();
+    pending.insert((start, path.to_string()), (0..line_count).map(|index| (index, index + 1)).collect());
+
+    while let Some(key) = most_recent(&pending, &cache.commits) {
+        let mut remaining = pending.remove(&key).unwrap_or_default();
+        let (hash, suspect_path) = key;
+        let commit = cache.commit(&hash)?.clone();
+        let suspect_text = cache.file_text(&hash, &suspect_path)?.unwrap_or_default();
+
+        for parent in &commit.parent {
+            if remaining.is_empty() {
+                break;
+            }
+
+            let Some(parent_path) = cache.parent_path(&hash, parent, &suspect_path)? else {
+                continue;
+            };
+            let parent_text = cache.file_text(parent, &parent_path)?.unwrap_or_default();
+            let unchanged = unchanged_lines(&parent_text, &suspect_text, options.ignore_whitespace);
+
+            let (passed, kept): (PendingLines, PendingLines) = remaining.into_iter()
+                .partition(|(_, line)| unchanged.contains_key(line));
+            remaining = kept;
+
+            if !passed.is_empty() {
+                pending.entry((parent.clone(), parent_path))
+                    .or_default()
+                    .extend(passed.into_iter().map(|(index, line)| (index, unchanged[&line])));
+            }
+        }
+
+        let suspect_lines: Vec<&str> = suspect_text.lines().collect();
+        for (index, line) in remaining {
+            result[index] = Some(BlameLine {
+                hash: hash.clone(),
+                commit: commit.clone(),
+                path: suspect_path.clone(),
+                original_line: line,
+                final_line: index + 1,
+                content: suspect_lines.get(line - 1).unwrap_or(&"").to_string(),
+                boundary: commit.parent.is_empty(),
+            });
+        }
+    }
+
+    Ok(result.into_iter().flatten().collect())
+}
+
+/// Entre os commits com linhas pendentes, retorna o mais recente
+fn most_recent(pending: &HashMap<(String, String), PendingLines>, commits: &HashMap<String, CommitObject>) -> Option<(String, String)> {
+    pending.keys()
+        .max_by(|a, b| {
+            let timestamp = |key: &(String, String)| commits.get(&key.0).map_or(0, |commit| commit.timestamp);
+            timestamp(a).cmp(&timestamp(b)).then_with(|| b.cmp(a))
+        })
+        .cloned()
+}
+
+/// Mapeia o número de cada linha do texto novo que não mudou para o número dela no texto antigo
+fn unchanged_lines(old_text: &str, new_text: &str, ignore_whitespace: bool) -> HashMap<usize, usize> {
+    let lines = if ignore_whitespace {
+        diff_lines(&without_whitespace(old_text), &without_whitespace(new_text))
+    } else {
+        diff_lines(old_text, new_text)
+    };
+
+    lines.into_iter()
+        .filter(|line| line.change == LineChange::Unchanged)
+        .filter_map(|line| Some((line.new_line?, line.old_line?)))
+        .collect()
+}
+
+/// Remove os espaços de cada linha, mantendo a quantidade de linhas
+fn without_whitespace(text: &str) -> String {
+    text.lines()
+        .map(|line| line.split_whitespace().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Commits e árvores já lidos, para que cada commit seja lido uma única vez
+struct CommitCache<'a> {
+    repo: &'a Repository,
+    commits: HashMap<String, CommitObject>,
+    /// Arquivos de cada commit (caminho -> hash do blob)
+    trees: HashMap<String, HashMap<String, String>>,
+}
+
+impl CommitCache<'_> {
+    fn load(&mut self, hash: &str) -> Result<(), MinigitError> {
+        if !self.commits.contains_key(hash) {
+            let commit = self.repo.get_commit(&hash.to_string())?;
+            self.trees.insert(hash.to_string(), get_commit_tree_as_map(self.repo, &commit)?);
+            self.commits.insert(hash.to_string(), commit);
+        }
+        Ok(())
+    }
+
+    fn commit(&mut self, hash: &str) -> Result<&CommitObject, MinigitError> {
+        self.load(hash)?;
+        Ok(&self.commits[hash])
+    }
+
+    fn files(&mut self, hash: &str) -> Result<&HashMap<String, String>, MinigitError> {
+        self.load(hash)?;
+        Ok(&self.trees[hash])
+    }
+
+    /// Conteúdo do arquivo no commit, ou `None` se ele não existir nesse commit
+    fn file_text(&mut self, hash: &str, path: &str) -> Result<Option<String>, MinigitError> {
+        match self.files(hash)?.get(path).cloned() {
+            Some(blob_hash) => Ok(Some(String::from_utf8_lossy(&self.repo.get_blob(&blob_hash)?.content).to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Caminho do arquivo no pai: o mesmo caminho ou, se ele não existir no pai, o caminho antigo de uma renomeação
+    fn parent_path(&mut self, hash: &str, parent: &str, path: &str) -> Result<Option<String>, MinigitError> {
+        let parent_files = self.files(parent)?.clone();
+        if parent_files.contains_key(path) {
+            return Ok(Some(path.to_string()));
+        }
+
+        let files = self.files(hash)?.clone();
+        Ok(find_renames(self.repo, &parent_files, &files)?.remove(path))
+    }
+}
@@ -0,0 +1,218 @@
+pub mod blame;
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::{
+    Repository,
+    diff::find_renames,
+    error::MinigitError,
+    objects::{CommitObject, get_commit_tree_as_map},
+    utils::{list_branches, resolve_revision},
+};
+
+pub use blame::{BlameLine, BlameOptions, blame};
+
+/// Commit retornado pelo histórico, junto do seu hash
+#[derive(Clone, Debug, Serialize)]
+pub struct LogEntry {
+    pub hash: String,
+    #[serde(flatten)]
+    pub commit: CommitObject,
+}
+
+/// Filtros do histórico de commits
+///
+/// ## Atributos
+/// - `revisions` - Revisões de partida. `A..B` inclui B e exclui o que é alcançável de A;
+///   `^A` exclui o que é alcançável de A. Sem revisões, parte do HEAD.
+/// - `all` - Parte de todas as branches, além das revisões fornecidas
+/// - `max_count` - Quantidade máxima de commits retornados
+/// - `author` - Só inclui commits cujo autor contém o texto
+/// - `grep` - Só inclui commits cuja mensagem contém o texto
+/// - `since` / `until` - Só inclui commits feitos nesse intervalo (timestamps em nanossegundos)
+/// - `paths` - Só inclui commits que alteram algum arquivo dentro desses caminhos
+/// - `follow` - Segue renomeações do único arquivo em `paths`
+#[derive(Clone, Debug, Default)]
+pub struct LogOptions {
+    pub revisions: Vec<String>,
+    pub all: bool,
+    pub max_count: Option<usize>,
+    pub author: Option<String>,
+    pub grep: Option<String>,
+    pub since: Option<u128>,
+    pub until: Option<u128>,
+    pub paths: Vec<String>,
+    pub follow: bool,
+}
+
+/// Retorna o histórico de commits filtrado, do mais recente para o mais antigo
+pub fn log(repo: &Repository, options: &LogOptions) -> Result<Vec<LogEntry>, MinigitError> {
+    if options.follow && options.paths.len() != 1 {
+        return Err(MinigitError::InvalidArgument("--follow exige exatamente um caminho".to_string()));
+    }
+
+    let (include, exclude) = starting_points(repo, options)?;
+
+    let excluded = reachable_commits(repo, &exclude)?;
+    let mut commits: Vec<LogEntry> = reachable_commits(repo, &include)?
+        .into_iter()
+        .filter(|(hash, _)| !excluded.contains_key(hash))
+        .map(|(hash, commit)| LogEntry { hash, commit })
+        .collect();
+
+    commits.sort_by(|a, b| b.commit.timestamp.cmp(&a.commit.timestamp).then_with(|| a.hash.cmp(&b.hash)));
+
+    let mut paths = options.paths.clone();
+    let mut result = Vec::new();
+
+    for entry in commits {
+        if options.max_count.is_some_and(|max_count| result.len() >= max_count) {
+            break;
+        }
+
+        if !matches_metadata(&entry.commit, options) {
+            continue;
+        }
+
+        if !paths.is_empty() {
+            let (touches, renamed_from) = touches_paths(repo, &entry.commit, &paths, options.follow)?;
+            if let Some(old_path) = renamed_from {
+                paths = vec![old_path];
+            }
+            if !touches {
+                continue;
+            }
+        }
+
+        result.push(entry);
+    }
+
+    Ok(result)
+}
+
+/// Separa as revisões em pontos de partida incluídos e excluídos
+fn starting_points(repo: &Repository, options: &LogOptions) -> Result<(Vec<String>, Vec<String>), MinigitError> {
+    let mut include = Vec::new();
+    let mut exclude = Vec::new();
+
+    for revision in &options.revisions {
+        if let Some((from, to)) = revision.split_once("..") {
+            let from = if from.is_empty() { Repository::HEAD } else { from };
+            let to = if to.is_empty() { Repository::HEAD } else { to };
+            exclude.push(resolve_revision(repo, from)?);
+            include.push(resolve_revision(repo, to)?);
+        } else if let Some(excluded) = revision.strip_prefix('^') {
+            exclude.push(resolve_revision(repo, excluded)?);
+        } else {
+            include.push(resolve_revision(repo, revision)?);
+        }
+    }
+
+    if options.all {
+        for branch in list_branches(repo)? {
+            if !branch.commit.is_empty() {
+                include.push(branch.commit);
+            }
+        }
+    }
+
+    if include.is_empty() && (options.revisions.is_empty() || !exclude.is_empty()) && !options.all {
+        let head = repo.resolve_head()?;
+        if !head.is_empty() {
+            include.push(head);
+        }
+    }
+
+    Ok((include, exclude))
+}
+
+/// Retorna todos os commits alcançáveis a partir dos commits fornecidos
+fn reachable_commits(repo: &Repository, starts: &[String]) -> Result<HashMap<String, CommitObject>, MinigitError> {
+    let mut visited: HashMap<String, CommitObject> = HashMap::new();
+    let mut stack: Vec<String> = starts.to_vec();
+
+    while let Some(hash) = stack.pop() {
+        if visited.contains_key(&hash) {
+            continue;
+        }
+
+        let commit = repo.get_commit(&hash)?;
+        stack.extend(commit.parent.iter().cloned());
+        visited.insert(hash, commit);
+    }
+
+    Ok(visited)
+}
+
+fn matches_metadata(commit: &CommitObject, options: &LogOptions) -> bool {
+    if options.author.as_ref().is_some_and(|author| !commit.author.to_lowercase().contains(&author.to_lowercase())) {
+        return false;
+    }
+
+    if options.grep.as_ref().is_some_and(|grep| !commit.message.contains(grep.as_str())) {
+        return false;
+    }
+
+    if options.since.is_some_and(|since| commit.timestamp < since) {
+        return false;
+    }
+
+    if options.until.is_some_and(|until| commit.timestamp > until) {
+        return false;
+    }
+
+    true
+}
+
+/// Verifica se o commit altera algum arquivo dentro de `paths` em relação aos seus pais
+///
+/// Um merge só é incluído se os caminhos diferem de todos os pais; se forem iguais aos de algum pai,
+/// as mudanças já aparecem nos commits daquele lado do histórico.
+///
+/// Com `follow`, se o arquivo foi criado neste commit renomeando outro arquivo (com conteúdo igual ou
+/// semelhante), retorna também o caminho antigo para que os commits anteriores sejam filtrados por ele.
+fn touches_paths(repo: &Repository, commit: &CommitObject, paths: &[String], follow: bool) -> Result<(bool, Option<String>), MinigitError> {
+    let new_files = get_commit_tree_as_map(repo, commit)?;
+
+    let mut parents_files = Vec::new();
+    for parent in &commit.parent {
+        parents_files.push(get_commit_tree_as_map(repo, &repo.get_commit(parent)?)?);
+    }
+    if parents_files.is_empty() {
+        parents_files.push(HashMap::new());
+    }
+
+    let mut touches = true;
+    for parent_files in &parents_files {
+        touches &= changed_paths(parent_files, &new_files)
+            .any(|changed_path| paths.iter().any(|path| is_inside(changed_path, path)));
+    }
+
+    let old_files = &parents_files[0];
+    if !follow || !touches {
+        return Ok((touches, None));
+    }
+
+    let followed = &paths[0];
+    let renamed_from = match (old_files.get(followed), new_files.get(followed)) {
+        (None, Some(_)) => find_renames(repo, old_files, &new_files)?.remove(followed),
+        _ => None,
+    };
+
+    Ok((touches, renamed_from))
+}
+
+/// Caminhos adicionados, removidos ou modificados entre duas árvores
+fn changed_paths<'a>(old_files: &'a HashMap<String, String>, new_files: &'a HashMap<String, String>) -> impl Iterator<Item = &'a String> {
+    old_files.keys()
+        .chain(new_files.keys())
+        .filter(|path| old_files.get(*path) != new_files.get(*path))
+}
+
+/// Verifica se o arquivo `file_path` é o próprio `path` ou está dentro da pasta `path`
+fn is_inside(file_path: &str, path: &str) -> bool {
+    let path = path.trim_end_matches('/');
+    path.is_empty() || path == "." || file_path == path || file_path.starts_with(&format!("{}/", path))
+}
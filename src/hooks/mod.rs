@@ -0,0 +1,165 @@
+use std::{path::Path, process::Command};
+
+use crate::{Repository, error::MinigitError};
+
+/// Ganchos (hooks) executados pelo minigit em pontos definidos das operações
+///
+/// Cada gancho é um executável em `.minigit/hooks/<nome>`. Ganchos `pre-*` e `commit-msg`
+/// abortam a operação quando terminam com código diferente de zero. Ganchos `post-*` são
+/// executados depois que a operação já foi concluída, então falhas neles só geram um aviso.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Hook {
+    PreCommit,
+    CommitMsg,
+    PostCommit,
+    PreMerge,
+    PostMerge,
+    PreRebase,
+    PostCheckout,
+}
+
+impl Hook {
+    pub const ALL: [Hook; 7] = [
+        Hook::PreCommit,
+        Hook::CommitMsg,
+        Hook::PostCommit,
+        Hook::PreMerge,
+        Hook::PostMerge,
+        Hook::PreRebase,
+        Hook::PostCheckout,
+    ];
+
+    /// Nome do arquivo do gancho dentro de `.minigit/hooks`
+    pub fn name(&self) -> &'static str {
+        match self {
+            Hook::PreCommit => "pre-commit",
+            Hook::CommitMsg => "commit-msg",
+            Hook::PostCommit => "post-commit",
+            Hook::PreMerge => "pre-merge",
+            Hook::PostMerge => "post-merge",
+            Hook::PreRebase => "pre-rebase",
+            Hook::PostCheckout => "post-checkout",
+        }
+    }
+
+    /// Conteúdo do script de exemplo instalado pelo `init` em `.minigit/hooks/<nome>.sample`
+    pub fn sample(&self) -> &'static str {
+        match self {
+            Hook::PreCommit => concat!(
+                "#!/bin/sh\n",
+                "# Executado antes de criar um commit. Sem argumentos.\n",
+                "# Saia com código diferente de zero para abortar o commit.\n",
+                "# Para ativar, renomeie este arquivo para 'pre-commit' e torne-o executável.\n",
+                "#\n",
+                "# Exemplo: impede commits com espaços em branco no final das linhas.\n",
+                "\n",
+                "if grep -rnI '[[:space:]]$' --exclude-dir=.minigit . ; then\n",
+                "    echo \"Espaços em branco no final das linhas encontrados.\" >&2\n",
+                "    exit 1\n",
+                "fi\n",
+            ),
+            Hook::CommitMsg => concat!(
+                "#!/bin/sh\n",
+                "# Executado com o caminho do arquivo que contém a mensagem do commit ($1).\n",
+                "# O gancho pode editar o arquivo; a mensagem final é lida de volta após a execução.\n",
+                "# Saia com código diferente de zero para abortar o commit.\n",
+                "#\n",
+                "# Exemplo: exige um identificador de ticket (ex: PROJ-123) na mensagem.\n",
+                "\n",
+                "if ! grep -qE '[A-Z]+-[0-9]+' \"$1\"; then\n",
+                "    echo \"A mensagem do commit deve conter um identificador de ticket (ex: PROJ-123).\" >&2\n",
+                "    exit 1\n",
+                "fi\n",
+            ),
+            Hook::PostCommit => concat!(
+                "#!/bin/sh\n",
+                "# Executado depois que um commit é criado. Sem argumentos.\n",
+                "# O resultado não afeta o commit.\n",
+                "\n",
+                "echo \"Commit criado em $(cat \"$MINIGIT_DIR/HEAD\")\"\n",
+            ),
+            Hook::PreMerge => concat!(
+                "#!/bin/sh\n",
+                "# Executado antes de um merge com a referência que será mergida ($1).\n",
+                "# Saia com código diferente de zero para abortar o merge.\n",
+                "\n",
+                "exit 0\n",
+            ),
+            Hook::PostMerge => concat!(
+                "#!/bin/sh\n",
+                "# Executado depois de um merge concluído sem conflitos.\n",
+                "# Recebe 1 se o merge foi um fast-forward e 0 caso contrário ($1).\n",
+                "\n",
+                "exit 0\n",
+            ),
+            Hook::PreRebase => concat!(
+                "#!/bin/sh\n",
+                "# Executado antes de um rebase com a nova base ($1).\n",
+                "# Saia com código diferente de zero para abortar o rebase.\n",
+                "\n",
+                "exit 0\n",
+            ),
+            Hook::PostCheckout => concat!(
+                "#!/bin/sh\n",
+                "# Executado depois que a árvore de trabalho é atualizada por checkout ou reset.\n",
+                "# Recebe o commit anterior ($1), o novo commit ($2) e 1 para checkout de commit\n",
+                "# ou 0 para checkout de arquivos ($3).\n",
+                "\n",
+                "exit 0\n",
+            ),
+        }
+    }
+}
+
+/// Executa o gancho especificado, se ele existir e for executável
+///
+/// O gancho é executado na raíz do repositório e recebe as variáveis de ambiente
+/// `MINIGIT_DIR`, `MINIGIT_WORK_TREE` e `MINIGIT_INDEX_FILE`.
+///
+/// ## Retorna
+/// - Ok(()): se o gancho não existir ou terminar com sucesso
+/// - Err(MinigitError::HookFailed): se o gancho terminar com código diferente de zero
+/// - Err(MinigitError::Io): se o gancho não puder ser executado
+pub fn run_hook(repo: &Repository, hook: Hook, args: &[&str]) -> Result<(), MinigitError> {
+    let hook_path = repo.hooks_path.join(hook.name());
+
+    if !is_executable(&hook_path) {
+        return Ok(());
+    }
+
+    let status = Command::new(&hook_path)
+        .args(args)
+        .current_dir(&repo.worktree)
+        .env("MINIGIT_DIR", &repo.minigitdir)
+        .env("MINIGIT_WORK_TREE", &repo.worktree)
+        .env("MINIGIT_INDEX_FILE", &repo.index_path)
+        .status()?;
+
+    if !status.success() {
+        return Err(MinigitError::HookFailed { hook: hook.name().to_string(), code: status.code() });
+    }
+
+    Ok(())
+}
+
+/// Executa um gancho `post-*`. Como a operação já foi concluída, falhas só geram um aviso.
+pub fn run_post_hook(repo: &Repository, hook: Hook, args: &[&str]) {
+    if let Err(err) = run_hook(repo, hook, args) {
+        eprintln!("Aviso: {}", err);
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    match std::fs::metadata(path) {
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
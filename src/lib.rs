@@ -1,9 +1,34 @@
-use core::panic;
+//! Biblioteca do minigit, uma implementação simplificada do git.
+//!
+//! O ponto de entrada é [`Repository`]: abra um repositório existente com [`Repository::open`]
+//! ou crie um novo com [`Repository::init`]. As operações retornam estruturas de dados
+//! (como [`status::StatusReport`], [`diff::FileDiff`] e [`MergeOutcome`]) e erros do tipo
+//! [`MinigitError`], sem imprimir nada. Os objetos armazenados podem ser lidos e escritos com
+//! [`Repository::get_object`] e [`Repository::create_object`].
+//!
+//! ## Exemplo
+//! ```
+//! use minigit::{MinigitError, Repository, objects::RGitObject};
+//!
+//! # let dir = std::env::temp_dir().join(format!("minigit-doc-{}", std::process::id()));
+//! # std::fs::create_dir_all(&dir)?;
+//! let mut repo = Repository::init(&dir)?;
+//!
+//! std::fs::write(dir.join("leiame.txt"), "olá\n")?;
+//! repo.add_files(vec!["leiame.txt".into()])?;
+//! let commit_hash = repo.commit("Primeiro commit", true)?;
+//!
+//! assert!(repo.status()?.is_clean());
+//! assert_eq!(repo.get_commit_history()?[0].hash(), commit_hash);
+//! # std::fs::remove_dir_all(&dir)?;
+//! # Ok::<(), MinigitError>(())
+//! ```
+
 use std::{fs::{self, File}, path::{Path, PathBuf}};
-use crate::{config::{GitConfig, RGitIgnore}, objects::{BlobObject, CommitObject, RGitObject, RGitObjectTypes, TreeObject}, staging::{StagingArea, StagingEntry}, utils::{is_valid_sha1, reference_exists, refs}};
+use crate::{config::{ConfigScope, GitConfig, RGitIgnore}, objects::{BlobObject, CommitObject, RGitObject, RGitObjectTypes, TreeObject, worktree_mode}, staging::{FileStat, StagingArea, StagingEntry}, utils::{LockFile, find_repo, is_valid_sha1, reference_exists, refs, sha1sum, write_locked}, status::StatusReport, diff::FileDiff, history::{BlameLine, BlameOptions, LogEntry, LogOptions}, grep::{GrepFile, GrepOptions}, fsck::{FsckOptions, FsckReport}, archive::ArchiveOptions, bundle::{BundleSummary, RefUpdate}};
 
 /// Estrutura que representa o repositório do projeto
-/// 
+///
 /// ## Atributos
 /// - `worktree` - Caminho para a pasta raíz do repostitório
 /// - `gitdir` - Caminho para a pasta .minigit do repositório
@@ -16,32 +41,34 @@ pub struct Repository{
     pub merge_head_path: PathBuf,
     pub orig_head_path: PathBuf,
     pub rebase_head_path: PathBuf,
+    pub hooks_path: PathBuf,
     pub config: GitConfig
 }
 
 impl Repository {
     const MINIGITDIR : &'static str = ".minigit";
-    const CONFIG : &'static str = "config";
     const HEAD : &'static str = "HEAD";
     const GITIGNORE : &'static str = ".gitignore";
     const INDEX : &'static str = "index";
     const MERGE_HEAD : &'static str = "MERGE_HEAD";
     const ORIG_HEAD : &'static str = "ORIG_HEAD";
     const REBASE_HEAD : &'static str = "REBASE_HEAD";
+    const HOOKS : &'static str = "hooks";
+    const COMMIT_EDITMSG : &'static str = "COMMIT_EDITMSG";
 
-    pub fn new(path: &Path) -> Self {
+    pub fn new(path: &Path) -> Result<Self, MinigitError> {
         let minigit_path = path.join(Self::MINIGITDIR);
-        let config_path = minigit_path.join(Self::CONFIG);
         let head_path = minigit_path.join(Self::HEAD);
         let index_path = minigit_path.join(Self::INDEX);
         let merge_head_path = minigit_path.join(Self::MERGE_HEAD);
         let orig_head_path = minigit_path.join(Self::ORIG_HEAD);
         let refs_heads_path = minigit_path.join("refs").join("heads");
         let rebase_head_path = minigit_path.join(Self::REBASE_HEAD);
+        let hooks_path = minigit_path.join(Self::HOOKS);
 
-        let config_bytes = std::fs::read(&config_path).unwrap_or_default();
+        let config = GitConfig::load(Some(&minigit_path))?;
 
-        Repository {
+        Ok(Repository {
             worktree: path.to_path_buf(),
             minigitdir: minigit_path,
             head_path: head_path,
@@ -50,166 +77,239 @@ impl Repository {
             merge_head_path: merge_head_path,
             orig_head_path: orig_head_path,
             rebase_head_path: rebase_head_path,
-            config: GitConfig::new(config_bytes)
-        }
+            hooks_path,
+            config
+        })
+    }
+
+    /// Abre o repositório que contém `path`, procurando a pasta `.minigit` em `path` e nos seus ancestrais
+    ///
+    /// Retorna `MinigitError::NotARepository` se nenhum repositório for encontrado.
+    pub fn open(path: &Path) -> Result<Self, MinigitError> {
+        find_repo(path)
     }
 
-    pub fn add_files(&mut self, relative_file_paths: Vec<PathBuf>) {
-        let mut staging = StagingArea::new(self);
-        let ignore = RGitIgnore::new(self);
+    /// Cria um novo repositório com a worktree em `path`, na branch `master` e sem commits
+    ///
+    /// Retorna `MinigitError::AlreadyExists` se `path` já fizer parte de um repositório.
+    pub fn init(path: &Path) -> Result<Self, MinigitError> {
+        commands::init::init(path)
+    }
+
+    /// Adiciona os arquivos ao índice, criando os blobs correspondentes
+    ///
+    /// Arquivos ignorados são pulados e arquivos que não existem mais na worktree são removidos do índice.
+    /// O modo do arquivo (executável ou link simbólico) também é registrado, respeitando `core.filemode`.
+    ///
+    /// ## Argumentos
+    /// - `relative_file_paths` - Caminhos relativos à raíz do repositório
+    pub fn add_files(&mut self, relative_file_paths: Vec<PathBuf>) -> Result<(), MinigitError> {
+        let mut lock = LockFile::acquire(&self.index_path)?;
+        let mut staging = StagingArea::new(self)?;
+        let ignore = RGitIgnore::new(self)?;
 
         for relative_path in relative_file_paths {
             let absolute_path = self.worktree.join(&relative_path);
 
             if ignore.check_ignore(&relative_path) {
                 continue;
-            } else if absolute_path.exists() {
-                let blob = BlobObject::from(&absolute_path);
-                let hash = self.create_object(&blob);
-                let last_content_change = fs::metadata(&absolute_path).unwrap().modified().unwrap()
-                    .duration_since(std::time::UNIX_EPOCH)
-                    .unwrap()
-                    .as_nanos();
-                
-                let entry = StagingEntry {
-                    last_content_change,
-                    object_hash: hash,
-                    path: relative_path,
-                    mode_type: 0o100644, // arquivo normal
-                };
+            } else if let Ok(metadata) = fs::symlink_metadata(&absolute_path) {
+                let previous_mode = staging.find_entry(&relative_path).map(|entry| entry.mode_type);
+                let blob = BlobObject::try_from(&absolute_path)?;
+                let hash = self.create_object(&blob)?;
+                let mut entry = StagingEntry::new(hash, relative_path);
+                entry.mode_type = worktree_mode(self, &metadata, previous_mode);
+                entry.stat = FileStat::from_metadata(&metadata);
                 staging.update_or_create_entry(entry);
             } else {
                 staging.remove_entry_with_path(&relative_path);
             }
         }
-        
-        std::fs::write(&self.index_path, staging.serialize()).unwrap();
+
+        lock.write_all(&staging.serialize()?)?;
+        lock.commit()
+    }
+
+    /// Remove os arquivos do índice. Os arquivos na worktree não são alterados.
+    ///
+    /// ## Argumentos
+    /// - `relative_file_paths` - Caminhos relativos à raíz do repositório
+    pub fn remove_files(&mut self, relative_file_paths: Vec<PathBuf>) -> Result<(), MinigitError> {
+        let mut lock = LockFile::acquire(&self.index_path)?;
+        let mut staging = StagingArea::new(self)?;
+
+        for relative_path in relative_file_paths {
+            staging.remove_entry_with_path(&relative_path);
+        }
+
+        lock.write_all(&staging.serialize()?)?;
+        lock.commit()
     }
 
-    /// Atualiza o arquivo de configuração do repositório
-    pub fn update_config(&mut self, key: String, value: String) {
-        self.config.set(key, value);
-        let config_path = self.minigitdir.join(Self::CONFIG);
-        std::fs::write(&config_path, &self.config.serialize()).unwrap();
+    /// Move ou renomeia arquivos e diretórios rastreados na worktree e no índice
+    ///
+    /// Se `destination` for um diretório existente, as origens são movidas para dentro dele.
+    /// Sem `force`, retorna `MinigitError::AlreadyExists` se o destino já existir.
+    ///
+    /// ## Argumentos
+    /// - `sources` - Caminhos relativos à raíz do repositório
+    /// - `destination` - Caminho relativo à raíz do repositório
+    pub fn move_files(&mut self, sources: &[PathBuf], destination: &Path, force: bool) -> Result<Vec<(PathBuf, PathBuf)>, MinigitError> {
+        commands::mv::move_paths(self, sources, destination, force)
+    }
+
+    /// Atualiza o arquivo de configuração local do repositório
+    pub fn update_config(&mut self, key: String, value: String) -> Result<(), MinigitError> {
+        self.config.set(ConfigScope::Local, &key, &value)
+    }
+
+    /// Remove a chave do arquivo de configuração local do repositório
+    pub fn remove_config(&mut self, key: &str) -> Result<(), MinigitError> {
+        self.config.unset(ConfigScope::Local, key)?;
+        Ok(())
+    }
+
+    /// Remove a seção (`seção` ou `seção.subseção`) do arquivo de configuração local do repositório
+    pub fn remove_config_section(&mut self, name: &str) -> Result<(), MinigitError> {
+        self.config.remove_section(ConfigScope::Local, name)?;
+        Ok(())
     }
 
     /// Retorna o hash do commit apontado pelo HEAD do repositório
-    pub fn resolve_head(&self) -> String {
+    pub fn resolve_head(&self) -> Result<String, MinigitError> {
         refs::resolve_head(self)
     }
 
     /// Verifica se a referência fornecida existe no repositório
     /// Referência pode ser o nome de uma branch ou um hash de commit direto
-    /// 
-    pub fn reference_exists(&self, reference: &String) -> bool {
+    ///
+    pub fn reference_exists(&self, reference: &str) -> Result<bool, MinigitError> {
         refs::reference_exists(reference, self)
     }
 
     /// Retorna o hash do commit apontado pela referência fornecida
     /// Referência pode ser o nome de uma branch ou um hash de commit direto
-    /// 
-    /// Esta função entra em pânico se a referência não existir. Verifique se a referência existe antes de chamar esta função.
-    pub fn resolve_reference(&self, reference: &String) -> String {
-        if !refs::reference_exists(reference, self) {
-            panic!("Referência {} não existe!", reference);
+    ///
+    /// Retorna `MinigitError::InvalidRef` se a referência não existir.
+    pub fn resolve_reference(&self, reference: &String) -> Result<String, MinigitError> {
+        if is_valid_sha1(reference) {
+            self.get_commit(reference)?;
+            return Ok(reference.clone());
         }
 
-        refs::resolve_head_or_branch_name(reference, self).unwrap()
+        refs::resolve_head_or_branch_name(reference, self)?
+            .ok_or_else(|| MinigitError::InvalidRef(reference.clone()))
     }
 
     /// Verifica se o HEAD do repositório está destacado (é um hash de commit direto)
-    pub fn head_detached(&self) -> bool {
-        let head_string = std::fs::read_to_string(&self.head_path).unwrap();
+    pub fn head_detached(&self) -> Result<bool, MinigitError> {
+        let head_string = std::fs::read_to_string(&self.head_path)?;
         let head_string = head_string.trim();
-        !head_string.starts_with("ref: ")
+        Ok(!head_string.starts_with("ref: "))
     }
 
     /// Retorna o nome da referência apontada pelo HEAD do repositório
-    pub fn get_head(&self) -> String {
-        let head_string = std::fs::read_to_string(&self.head_path).unwrap();
+    pub fn get_head(&self) -> Result<String, MinigitError> {
+        let head_string = std::fs::read_to_string(&self.head_path)?;
 
-        if head_string.starts_with("ref: ") {
-            head_string[5..].trim().to_string()
+        if let Some(reference) = head_string.strip_prefix("ref: ") {
+            Ok(reference.trim().to_string())
         } else {
-            head_string.trim().to_string()
+            Ok(head_string.trim().to_string())
         }
     }
 
     /// Atualiza a branch atual para apontar para o novo commit
-    /// Entra em pânico se o HEAD estiver destacado ou corrompido.
-    pub fn update_curr_branch(&mut self, commit_id: &String) {
-        let head_ref = self.get_head();
+    /// Retorna um erro se o HEAD estiver destacado ou corrompido.
+    pub fn update_curr_branch(&mut self, commit_id: &str) -> Result<(), MinigitError> {
+        let head_ref = self.current_branch_ref()?;
+        self.update_branch_ref(&head_ref, commit_id)
+    }
+
+    /// Atualiza a branch atual para o novo commit só se ela ainda apontar para `expected_old`
+    /// (vazio para uma branch sem commits)
+    ///
+    /// Retorna `MinigitError::InvalidState` se outro processo tiver movido a branch desde que
+    /// `expected_old` foi lido, sem alterá-la.
+    pub fn compare_and_swap_curr_branch(&mut self, expected_old: &str, commit_id: &str) -> Result<(), MinigitError> {
+        let head_ref = self.current_branch_ref()?;
+        refs::update_ref(self, &head_ref, commit_id, Some(expected_old))
+    }
+
+    fn current_branch_ref(&self) -> Result<String, MinigitError> {
+        let head_ref = self.get_head()?;
 
         if is_valid_sha1(&head_ref) {
-            panic!("HEAD está destacado!");
+            return Err(MinigitError::InvalidState("HEAD está destacado!".to_string()));
         }
 
-        self.update_branch_ref(&head_ref.to_string(), commit_id);
+        Ok(head_ref)
     }
 
     /// Atualiza a branch especificada para apontar para o novo commit
-    /// Entra em pânico se a branch não existir
-    pub fn update_branch(&mut self, branch_name: &String, commit_id: &String) {
-        if !self.reference_exists(branch_name) || is_valid_sha1(branch_name) {
-            panic!("Branch {} não existe!", branch_name);
+    /// Retorna um erro se a branch não existir
+    pub fn update_branch(&mut self, branch_name: &str, commit_id: &str) -> Result<(), MinigitError> {
+        if is_valid_sha1(branch_name) || !self.reference_exists(branch_name)? {
+            return Err(MinigitError::InvalidRef(format!("Branch {} não existe!", branch_name)));
         }
 
         let branch_ref_str = format!("refs/heads/{}", branch_name);
-        self.update_branch_ref(&branch_ref_str, commit_id);
+        self.update_branch_ref(&branch_ref_str, commit_id)
     }
 
     /// Atualiza a referência de branch especificada para apontar para o novo commit
-    /// Entra em pânico se a referência não existir ou não estiver no formato '/refs/heads/...'
-    pub fn update_branch_ref(&mut self, branch_ref: &String, commit_id: &String) {
-        let branch_head = refs::resolve_ref_path(branch_ref, self);
+    /// Retorna um erro se a referência não existir ou não estiver no formato '/refs/heads/...'
+    pub fn update_branch_ref(&mut self, branch_ref: &str, commit_id: &str) -> Result<(), MinigitError> {
+        let branch_head = refs::resolve_ref_path(branch_ref, self)?;
 
         if branch_head.is_none() {
-            panic!("Referência {} não existe!", branch_ref);
+            return Err(MinigitError::InvalidRef(format!("Referência {} não existe!", branch_ref)));
         }
 
-        let branch_path_str = format!("{}/index", branch_ref);
-        let parts = branch_path_str.split('/').collect::<Vec<&str>>();
-        let branch_path = self.get_repository_path(parts.as_slice());
-        std::fs::write(&branch_path, commit_id).unwrap();
+        refs::update_ref(self, branch_ref, commit_id, None)
     }
 
-    pub fn update_head(&mut self, commit_id: &String) {
-        let head_ref = self.get_head();
+    pub fn update_head(&mut self, commit_id: &String) -> Result<(), MinigitError> {
+        let head_ref = self.get_head()?;
         let head_path = self.minigitdir.join(head_ref);
         let index_file_path = head_path.join("index");
 
-        std::fs::write(&index_file_path, commit_id).unwrap();
+        write_locked(&index_file_path, commit_id)
     }
 
     /// Muda o HEAD do repositório para o novo valor
-    /// 
+    ///
     /// `new_head` pode ser o hash de um commit ou o nome de uma branch existente.
-    /// Certifique-se de que o valor passado é uma referência existente.
-    pub fn change_head(&mut self, new_head: &String) {
-        if !reference_exists(new_head, self) {
-            panic!("Novo HEAD não é um commit ou uma branch válida");
+    /// Retorna `MinigitError::InvalidRef` se o valor não for uma referência existente.
+    pub fn change_head(&mut self, new_head: &String) -> Result<(), MinigitError> {
+        if !reference_exists(new_head, self)? {
+            return Err(MinigitError::InvalidRef(format!("Novo HEAD '{}' não é um commit ou uma branch válida", new_head)));
         }
 
-        let is_commit_id = is_valid_sha1(&new_head);
+        let is_commit_id = is_valid_sha1(new_head);
         let new_head_content = if is_commit_id {
             new_head.clone()
         } else {
             format!("ref: refs/heads/{}", new_head)
         };
-        
-        std::fs::write(&self.head_path, new_head_content).unwrap();
+
+        write_locked(&self.head_path, new_head_content)
     }
 
 
 
     /// Constroí um caminho de arquivo a partir da pasta .minigit do repositório
-    /// 
+    ///
     /// ## Argumentos
     /// - `parts` - As partes que formam o caminho
-    /// 
+    ///
     /// ## Exemplo
     /// ```
-    /// get_repository_path(&["a", "b", "c"]) // .minigit/a/b/c
+    /// # use std::path::Path;
+    /// # use minigit::Repository;
+    /// let repo = Repository::new(Path::new("projeto")).unwrap();
+    /// let path = repo.get_repository_path(&["a", "b", "c"]);
+    /// assert_eq!(path, Path::new("projeto/.minigit/a/b/c"));
     /// ```
     pub fn get_repository_path(&self, parts: &[&str]) -> PathBuf {
         let mut path = self.minigitdir.clone();
@@ -220,220 +320,367 @@ impl Repository {
     }
 
     /// Cria uma pasta no caminho especificado relativo ao .minigit
-    /// 
+    ///
     /// ## Argumentos
     /// - `parts` - Partes do caminho até a pasta
-    pub fn create_repository_dir(&mut self, parts: &[&str]) {
+    pub fn create_repository_dir(&mut self, parts: &[&str]) -> Result<(), MinigitError> {
         let path = self.get_repository_path(parts);
-        std::fs::create_dir_all(&path).expect("Deveria criar o diretório");
+        std::fs::create_dir_all(&path)?;
+        Ok(())
     }
 
     /// Cria um arquivo no caminho especificado relativo ao .minigit
-    /// 
+    ///
     /// ## Argumentos
-    /// - `parts` - Partes do caminho até o arquivo 
-    pub fn create_repository_file(&mut self, parts: &[&str]) -> File {
+    /// - `parts` - Partes do caminho até o arquivo
+    pub fn create_repository_file(&mut self, parts: &[&str]) -> Result<File, MinigitError> {
         if parts.is_empty() {
-            panic!("Foi tentado criar um arquivo sem nome")
+            return Err(MinigitError::InvalidArgument("Foi tentado criar um arquivo sem nome".to_string()));
         }
 
-        self.create_repository_dir(&parts[0..parts.len()-1]);
+        self.create_repository_dir(&parts[0..parts.len()-1])?;
 
         let path = self.get_repository_path(parts);
 
-        File::create(path).expect("Deveria criar o arquivo")
+        Ok(File::create(path)?)
     }
 
-    pub fn get_commit_history(&self) -> Vec<CommitObject> {
-        self.get_commit_history_from_commit(&self.resolve_head())
+    /// Retorna o histórico de commits a partir do HEAD, do mais recente para o mais antigo
+    pub fn get_commit_history(&self) -> Result<Vec<CommitObject>, MinigitError> {
+        self.get_commit_history_from_commit(&self.resolve_head()?)
     }
 
     /// Retorna o histórico de commits do repositório a partir do commit fornecido
     /// Os commits estão ordenados do mais recente para o mais antigo
-    /// Entra em pânico se o commit não existir
-    fn get_commit_history_from_commit(&self, start_commit: &String) -> Vec<CommitObject> {
+    /// Retorna um erro se algum commit do histórico não existir
+    pub fn get_commit_history_from_commit(&self, start_commit: &str) -> Result<Vec<CommitObject>, MinigitError> {
         let mut stack_of_ids: Vec<String> = Vec::new();
         let mut commit_history: Vec<CommitObject> = Vec::new();
-        let head = start_commit.clone();
+        let head = start_commit.to_string();
 
         if head.is_empty() {
-            return commit_history;
+            return Ok(commit_history);
         }
 
         stack_of_ids.push(head);
 
-        while !stack_of_ids.is_empty() {
-            let current_commit_id = stack_of_ids.pop().unwrap();
-            let object = self.get_object(&current_commit_id);
-            if let Some(RGitObjectTypes::Commit(commit)) = object {
-                for parent in &commit.parent {
-                    stack_of_ids.push(parent.clone());
-                }
-                commit_history.push(commit);
-            } else {
-                break;
+        while let Some(current_commit_id) = stack_of_ids.pop() {
+            let commit = self.get_commit(&current_commit_id)?;
+            for parent in &commit.parent {
+                stack_of_ids.push(parent.clone());
             }
+            commit_history.push(commit);
         }
 
         commit_history.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
 
-        commit_history
+        Ok(commit_history)
     }
-    
+
 
     /// Cria um objeto .minigit no repositório.
-    /// Por questões de performance e organização, o objeto ficará em `.minigit/objects/<a>/<b>`, 
+    /// Por questões de performance e organização, o objeto ficará em `.minigit/objects/<a>/<b>`,
     /// onde `a` são os dois primeiros dígitos do hash e `b` é o restante do hash.
-    /// 
+    ///
     /// ## Argumentos
-    /// - `object` - O objeto RGit 
-    /// 
+    /// - `object` - O objeto RGit
+    ///
     /// ## Retorna
     /// O hash do objeto criado
-    pub fn create_object<T : RGitObject>(&mut self, object: &T) -> String {
+    pub fn create_object<T : RGitObject>(&mut self, object: &T) -> Result<String, MinigitError> {
         let hash = object.hash();
         let (dir, file_name) = hash.split_at(2);
 
         let path = self.get_repository_path(&["objects", dir, file_name]);
 
         if path.exists() {
-            return hash;
+            return Ok(hash);
         }
 
-        self.create_repository_file(&["objects", dir, file_name]);
-        std::fs::write(&path, object.get_object_bytes()).unwrap();
+        self.create_repository_file(&["objects", dir, file_name])?;
+        std::fs::write(&path, object.get_object_bytes())?;
 
-        hash
+        Ok(hash)
     }
 
-    pub fn get_object(&self, object_id: &String) -> Option<RGitObjectTypes> {
-        if object_id.is_empty() {
-            return None;
-        }
-
-        let (dir, file_name) = object_id.split_at(2);
-        let file_path = self.get_repository_path(&["objects", dir, file_name]);
-        
-        if !file_path.exists() {
-            return None;
-        }
-
-        let (object_type, object_size, object_content) = Self::split_object_bytes(std::fs::read(&file_path).unwrap());
-
-        if object_size != object_content.len() {
-            panic!("Objeto foi corrompido!");
-        }
+    /// Lê o objeto com o hash fornecido
+    ///
+    /// Retorna `MinigitError::ObjectNotFound` se o objeto não existir e
+    /// `MinigitError::CorruptObject` se o arquivo do objeto estiver mal formatado.
+    pub fn get_object(&self, object_id: &String) -> Result<RGitObjectTypes, MinigitError> {
+        let (object_type, object_content) = self.get_raw_object(object_id)?;
 
         match object_type.as_str() {
             "commit" => {
-                let commit = CommitObject::new(object_content.to_vec());
-                Some(RGitObjectTypes::Commit(commit))
+                let commit = CommitObject::new(object_content)?;
+                Ok(RGitObjectTypes::Commit(commit))
             },
             "blob" => {
-                let blob = BlobObject::new(object_content.to_vec());
-                Some(RGitObjectTypes::Blob(blob))
+                let blob = BlobObject::new(object_content);
+                Ok(RGitObjectTypes::Blob(blob))
             },
             "tree" => {
-                let tree = TreeObject::new(object_content.to_vec());
-                Some(RGitObjectTypes::Tree(tree))
+                let tree = TreeObject::new(object_content)?;
+                Ok(RGitObjectTypes::Tree(tree))
             },
             _ => {
-                panic!("Tipo de objeto desconhecido!");
+                Err(MinigitError::CorruptObject(format!("{} tem tipo de objeto desconhecido '{}'", object_id, object_type)))
             }
         }
     }
 
-    fn split_object_bytes(object_bytes: Vec<u8>) -> (String, usize, Vec<u8>) {
-        let space = object_bytes.iter().position(|x| *x == b' ').unwrap();
+    /// Lê o tipo (`blob`, `tree` ou `commit`) e o conteúdo sem interpretação do objeto com o hash fornecido
+    ///
+    /// Retorna os mesmos erros de [`Repository::get_object`], exceto pelo tipo desconhecido. Com
+    /// `core.verifyObjects` ligado, também retorna `MinigitError::CorruptObject` se o conteúdo não tiver esse hash.
+    pub fn get_raw_object(&self, object_id: &str) -> Result<(String, Vec<u8>), MinigitError> {
+        if !is_valid_sha1(object_id) {
+            return Err(MinigitError::ObjectNotFound(object_id.to_string()));
+        }
+
+        let (dir, file_name) = object_id.split_at(2);
+        let file_path = self.get_repository_path(&["objects", dir, file_name]);
+
+        if !file_path.exists() {
+            return Err(MinigitError::ObjectNotFound(object_id.to_string()));
+        }
+
+        let object_bytes = std::fs::read(&file_path)?;
+        if self.config.get_bool("core.verifyobjects")?.unwrap_or(false) && sha1sum(&object_bytes) != object_id {
+            return Err(MinigitError::CorruptObject(format!("{}: o conteúdo não corresponde ao hash", object_id)));
+        }
+
+        Self::split_object_bytes(object_id, object_bytes)
+    }
+
+    /// Lê o commit com o hash fornecido. Retorna um erro se o objeto não for um commit.
+    pub fn get_commit(&self, object_id: &String) -> Result<CommitObject, MinigitError> {
+        match self.get_object(object_id)? {
+            RGitObjectTypes::Commit(commit) => Ok(commit),
+            _ => Err(MinigitError::CorruptObject(format!("{} não é um commit", object_id))),
+        }
+    }
+
+    /// Lê a árvore com o hash fornecido. Retorna um erro se o objeto não for uma árvore.
+    pub fn get_tree(&self, object_id: &String) -> Result<TreeObject, MinigitError> {
+        match self.get_object(object_id)? {
+            RGitObjectTypes::Tree(tree) => Ok(tree),
+            _ => Err(MinigitError::CorruptObject(format!("{} não é uma árvore", object_id))),
+        }
+    }
+
+    /// Lê o blob com o hash fornecido. Retorna um erro se o objeto não for um blob.
+    pub fn get_blob(&self, object_id: &String) -> Result<BlobObject, MinigitError> {
+        match self.get_object(object_id)? {
+            RGitObjectTypes::Blob(blob) => Ok(blob),
+            _ => Err(MinigitError::CorruptObject(format!("{} não é um blob", object_id))),
+        }
+    }
+
+    fn split_object_bytes(object_id: &str, object_bytes: Vec<u8>) -> Result<(String, Vec<u8>), MinigitError> {
+        let corrupt = |reason: &str| MinigitError::CorruptObject(format!("{}: {}", object_id, reason));
+
+        let space = object_bytes.iter().position(|x| *x == b' ')
+            .ok_or_else(|| corrupt("cabeçalho sem tipo"))?;
 
         let (object_type, object_content) = object_bytes.split_at(space);
         let object_content = &object_content[1..];
 
-        let null = object_content.iter().position(|x| *x == b'\0').unwrap();
+        let null = object_content.iter().position(|x| *x == b'\0')
+            .ok_or_else(|| corrupt("cabeçalho sem tamanho"))?;
         let (object_size, object_content) = object_content.split_at(null);
         let object_content = &object_content[1..];
-        let object_size = std::str::from_utf8(object_size).unwrap().parse::<usize>().unwrap();
+        let object_size = std::str::from_utf8(object_size).ok()
+            .and_then(|size| size.parse::<usize>().ok())
+            .ok_or_else(|| corrupt("tamanho inválido"))?;
 
         if object_size != object_content.len() {
-            panic!("Objeto foi corrompido!");
+            return Err(corrupt("tamanho não corresponde ao conteúdo"));
         }
 
-        let object_type_str = std::str::from_utf8(object_type).unwrap().to_string();
+        let object_type_str = std::str::from_utf8(object_type)
+            .map_err(|_| corrupt("tipo inválido"))?
+            .to_string();
 
-        (object_type_str, object_size, object_content.to_vec())
+        Ok((object_type_str, object_content.to_vec()))
     }
 
-    pub fn clear_worktree(&mut self) {
-        Self::clear_directory(&self.worktree, &RGitIgnore::new(self), self);
+    pub fn clear_worktree(&mut self) -> Result<(), MinigitError> {
+        Self::clear_directory(&self.worktree, &RGitIgnore::new(self)?, self)
     }
 
-    fn clear_directory(absolute_path: &PathBuf, ignore: &RGitIgnore, repo: &Repository) {
-        for entry in std::fs::read_dir(absolute_path).unwrap() {
-            let entry = entry.unwrap();
+    fn clear_directory(absolute_path: &PathBuf, ignore: &RGitIgnore, repo: &Repository) -> Result<(), MinigitError> {
+        for entry in std::fs::read_dir(absolute_path)? {
+            let entry = entry?;
             let entry_path = entry.path();
 
             if entry_path.is_dir() {
-                Self::clear_directory(&entry_path, ignore, repo);
+                Self::clear_directory(&entry_path, ignore, repo)?;
 
-                if entry_path.read_dir().unwrap().next().is_none() {
-                    std::fs::remove_dir(&entry_path).unwrap();
+                if entry_path.read_dir()?.next().is_none() {
+                    std::fs::remove_dir(&entry_path)?;
                 }
-                
+
                 continue;
             }
 
-            let relative_path = entry_path.strip_prefix(&repo.worktree).unwrap();
+            let Ok(relative_path) = entry_path.strip_prefix(&repo.worktree) else {
+                continue;
+            };
 
             if !ignore.check_ignore(&relative_path.to_path_buf()) {
-                std::fs::remove_file(&entry_path).unwrap();
+                std::fs::remove_file(&entry_path)?;
             }
         }
+
+        Ok(())
     }
 
-    pub fn delete_branch(&mut self, branch_name: &String) -> Result<(), String> {
-        let branch_path_str = format!("refs/heads/{}", branch_name);
-        let parts = branch_path_str.split('/').collect::<Vec<&str>>();
-        let branch_path = self.get_repository_path(parts.as_slice());
+    /// Cria um commit com o conteúdo do índice na branch atual e retorna o seu hash
+    ///
+    /// Se `run_hooks` for falso, os ganchos `pre-commit` e `commit-msg` não são executados.
+    pub fn commit(&mut self, message: &str, run_hooks: bool) -> Result<String, MinigitError> {
+        commands::commit::commit(self, message.to_string(), run_hooks)
+    }
 
-        if !branch_path.exists() {
-            return Err(String::from(format!("Branch {} não existe!", branch_name)));
-        }
+    /// Faz o merge da branch ou commit `target` na branch atual
+    ///
+    /// Conflitos não são um erro: o resultado é `MergeOutcome::Conflicts` e o repositório fica
+    /// com um merge em progresso.
+    pub fn merge(&mut self, target: &str) -> Result<MergeOutcome, MinigitError> {
+        commands::merge::merge(self, &target.to_string())
+    }
 
-        let current_head = self.get_head();
+    /// Muda o HEAD para a branch ou commit `reference` e atualiza a worktree e o índice
+    ///
+    /// Só os arquivos que diferem entre o HEAD e `reference` são alterados. Se `force` for falso e algum
+    /// deles tiver mudanças locais, retorna `MinigitError::DirtyWorktree` com esses arquivos; com `force`,
+    /// as mudanças locais dos arquivos rastreados são descartadas. Arquivos não rastreados são mantidos.
+    /// Retorna o hash do commit apontado pelo novo HEAD.
+    pub fn checkout(&mut self, reference: &str, force: bool) -> Result<String, MinigitError> {
+        commands::checkout::checkout(self, &reference.to_string(), force)
+    }
 
-        if current_head == format!("ref: refs/heads/{}", branch_name) {
-            return Err(String::from("Não é possível deletar a branch atualmente ativa"));
-        }
+    /// Muda o HEAD para a branch, mantendo as mudanças locais que não conflitam com ela
+    ///
+    /// Com `create_from`, cria a branch a partir dessa revisão antes de mudar. Se algum arquivo que
+    /// difere entre o HEAD e a branch tiver mudanças locais, retorna `MinigitError::DirtyWorktree`.
+    /// Retorna o hash do commit apontado pela branch.
+    pub fn switch(&mut self, branch: &str, create_from: Option<&str>) -> Result<String, MinigitError> {
+        commands::switch::switch(self, branch, create_from)
+    }
 
-        std::fs::remove_file(&branch_path).map_err(|_| "Erro ao deletar a branch")?;
+    /// Restaura os arquivos selecionados pela pathspec no índice (`staged`) e/ou na worktree (`worktree`)
+    ///
+    /// Sem `source`, o índice é restaurado a partir do HEAD e a worktree a partir do índice.
+    /// Os caminhos da pathspec são relativos ao diretório atual.
+    pub fn restore(&mut self, paths: &[String], source: Option<&str>, staged: bool, worktree: bool) -> Result<Vec<PathBuf>, MinigitError> {
+        let pathspec = utils::pathspec::Pathspec::parse(self, paths)?;
+        commands::restore::restore(self, &pathspec, source, staged, worktree)
+    }
 
-        Ok(())
+    /// Retorna as mudanças do índice e da worktree em relação ao HEAD
+    pub fn status(&self) -> Result<StatusReport, MinigitError> {
+        status::status(self)
+    }
+
+    /// Retorna as diferenças linha a linha entre os arquivos não adicionados da worktree e o HEAD
+    pub fn diff(&self) -> Result<Vec<FileDiff>, MinigitError> {
+        diff::diff_worktree(self)
     }
 
-    pub fn create_branch(&mut self, branch_name: &String) -> Result<(), String> {
-        let branch_path_str = format!("refs/heads/{}", branch_name);
-        let parts = branch_path_str.split('/').collect::<Vec<&str>>();
-        let branch_path = self.get_repository_path(parts.as_slice());
+    /// Retorna o histórico de commits filtrado pelas opções fornecidas
+    pub fn log(&self, options: &LogOptions) -> Result<Vec<LogEntry>, MinigitError> {
+        history::log(self, options)
+    }
 
-        if branch_path.exists() {
-            return Err(String::from(format!("Branch {} já existe!", branch_name)));
-        }
+    /// Retorna, para cada linha do arquivo `path` (relativo à raíz do repositório), o commit que a introduziu
+    pub fn blame(&self, path: &str, options: &BlameOptions) -> Result<Vec<BlameLine>, MinigitError> {
+        history::blame(self, path, options)
+    }
 
-        let head_commit = self.resolve_head();
-        let branch_path_parent = branch_path.parent().ok_or("Erro ao criar a branch")?;
-        std::fs::create_dir_all(&branch_path_parent).map_err(|_| "Erro ao criar a branch")?;
-        std::fs::write(&branch_path, head_commit).map_err(|_| "Erro ao criar a branch")?;
+    /// Procura a expressão regular nos arquivos da worktree, do índice ou de uma revisão
+    pub fn grep(&self, options: &GrepOptions) -> Result<Vec<GrepFile>, MinigitError> {
+        grep::grep(self, options)
+    }
 
-        Ok(())
+    /// Verifica a integridade dos objetos, das referências e do índice
+    pub fn fsck(&self, options: &FsckOptions) -> Result<FsckReport, MinigitError> {
+        fsck::fsck(self, options)
+    }
+
+    /// Retorna a nota do commit no namespace de notas (ex: `commits`), se houver
+    pub fn note(&self, namespace: &str, commit: &str) -> Result<Option<String>, MinigitError> {
+        notes::get_note(self, namespace, commit)
+    }
+
+    /// Define ou, com `None`, remove a nota do commit no namespace de notas, sem alterar o commit
+    pub fn set_note(&mut self, namespace: &str, commit: &str, content: Option<&str>) -> Result<(), MinigitError> {
+        notes::set_note(self, namespace, commit, content, "Notas atualizadas pela API do minigit")
+    }
+
+    /// Grava em `out` um tar, tar.gz ou zip com os arquivos de uma revisão e retorna o hash do commit arquivado
+    pub fn archive(&self, options: &ArchiveOptions, out: &mut dyn std::io::Write) -> Result<String, MinigitError> {
+        archive::archive(self, options, out)
+    }
+
+    /// Grava em `path` um bundle com os commits das revisões (ex: `master`, `v1..master`) e os objetos deles
+    pub fn create_bundle(&self, path: &Path, revisions: &[String], all: bool) -> Result<BundleSummary, MinigitError> {
+        bundle::create_bundle(self, path, revisions, all)
+    }
+
+    /// Verifica se o bundle está íntegro e se o repositório tem os commits que ele exige
+    pub fn verify_bundle(&self, path: &Path) -> Result<BundleSummary, MinigitError> {
+        bundle::verify_bundle(self, path)
+    }
+
+    /// Importa os objetos do bundle e cria ou avança as branches dele
+    pub fn unbundle(&mut self, path: &Path, force: bool) -> Result<Vec<RefUpdate>, MinigitError> {
+        bundle::unbundle(self, path, force)
+    }
+
+    /// Deleta a branch. Sem `force`, retorna `MinigitError::InvalidState` se ela não tiver sido mesclada no HEAD
+    ///
+    /// Retorna o hash do commit para o qual a branch apontava.
+    pub fn delete_branch(&mut self, branch_name: &str, force: bool) -> Result<String, MinigitError> {
+        commands::branch::delete_branch(self, branch_name, force)
+    }
+
+    /// Cria a branch a partir da revisão `start_point` (o HEAD por padrão)
+    pub fn create_branch(&mut self, branch_name: &str, start_point: Option<&str>) -> Result<(), MinigitError> {
+        commands::branch::create_branch(self, branch_name, start_point)
+    }
+
+    /// Renomeia a branch `old_name` (a atual por padrão) para `new_name`
+    ///
+    /// Sem `force`, retorna `MinigitError::AlreadyExists` se já existir uma branch com o novo nome.
+    pub fn rename_branch(&mut self, old_name: Option<&str>, new_name: &str, force: bool) -> Result<(), MinigitError> {
+        commands::branch::rename_branch(self, old_name, new_name, force)
+    }
+
+    /// Faz a branch (a atual por padrão) seguir a branch `upstream`
+    pub fn set_upstream(&mut self, branch_name: Option<&str>, upstream: &str) -> Result<String, MinigitError> {
+        commands::branch::set_upstream(self, branch_name, upstream)
     }
 }
 
 mod commands;
-mod staging;
-mod objects;
-mod utils;
-mod config;
-mod status;
 mod checks;
+pub mod staging;
+pub mod objects;
+pub mod utils;
+pub mod config;
+pub mod status;
+pub mod hooks;
+pub mod error;
+pub mod diff;
+pub mod history;
+pub mod grep;
+pub mod fsck;
+pub mod notes;
+pub mod archive;
+pub mod bundle;
 
 pub use commands::cli_main;
+pub use commands::merge::MergeOutcome;
+pub use error::MinigitError;
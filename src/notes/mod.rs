@@ -0,0 +1,121 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    Repository,
+    error::MinigitError,
+    objects::{BlobObject, CommitObject, MODE_FILE, TreeObject, TreeObjectChild, format_mode},
+    utils::{is_valid_sha1, refs},
+};
+
+/// Namespace usado quando nenhum `--ref` é informado
+pub const DEFAULT_NOTES_REF: &str = "commits";
+
+/// Nota anexada a um commit
+///
+/// ## Atributos
+/// - `commit` - Hash do commit anotado
+/// - `note` - Hash do blob com o conteúdo da nota
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct NoteEntry {
+    pub commit: String,
+    pub note: String,
+}
+
+/// Retorna o nome completo da referência de notas: `ci` e `refs/notes/ci` viram `refs/notes/ci`
+///
+/// Retorna `MinigitError::InvalidArgument` se o namespace não puder ser usado como nome de referência.
+pub fn notes_ref(namespace: &str) -> Result<String, MinigitError> {
+    let name = namespace.strip_prefix("refs/notes/").unwrap_or(namespace);
+
+    let has_invalid_component = name.split('/').any(|part| part.is_empty() || part == "." || part == Repository::INDEX);
+    if name.starts_with('-') || name.contains(char::is_whitespace) || name.contains("..") || has_invalid_component {
+        return Err(MinigitError::InvalidArgument(format!("'{}' não é um namespace de notas válido", namespace)));
+    }
+
+    Ok(format!("refs/notes/{}", name))
+}
+
+/// Notas do namespace: o commit de notas atual (vazio se ainda não houver notas) e o mapa commit -> blob
+fn read_notes(repo: &Repository, reference: &str) -> Result<(String, BTreeMap<String, String>), MinigitError> {
+    let path = repo.minigitdir.join(reference).join(Repository::INDEX);
+    let notes_commit = match std::fs::read_to_string(&path) {
+        Ok(content) => content.trim().to_string(),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.into()),
+    };
+
+    let mut notes = BTreeMap::new();
+    if notes_commit.is_empty() {
+        return Ok((notes_commit, notes));
+    }
+
+    let commit = repo.get_commit(&notes_commit)
+        .map_err(|err| MinigitError::InvalidRef(format!("A referência '{}' não aponta para um commit de notas: {}", reference, err)))?;
+    for child in repo.get_tree(&commit.tree)?.children {
+        notes.insert(child.name, child.object_id);
+    }
+
+    Ok((notes_commit, notes))
+}
+
+/// Retorna o conteúdo da nota do commit no namespace, ou `None` se ele não tiver nota
+pub fn get_note(repo: &Repository, namespace: &str, commit: &str) -> Result<Option<String>, MinigitError> {
+    let (_, notes) = read_notes(repo, &notes_ref(namespace)?)?;
+
+    match notes.get(commit) {
+        Some(blob) => Ok(Some(String::from_utf8_lossy(&repo.get_blob(blob)?.content).to_string())),
+        None => Ok(None),
+    }
+}
+
+/// Lista as notas do namespace, ordenadas pelo hash do commit anotado
+pub fn list_notes(repo: &Repository, namespace: &str) -> Result<Vec<NoteEntry>, MinigitError> {
+    let (_, notes) = read_notes(repo, &notes_ref(namespace)?)?;
+    Ok(notes.into_iter().map(|(commit, note)| NoteEntry { commit, note }).collect())
+}
+
+/// Define (`Some`) ou remove (`None`) a nota do commit no namespace
+///
+/// Cria um novo commit de notas, com o anterior como pai, cuja árvore mapeia o hash de cada commit
+/// anotado para o blob da nota. A referência só é atualizada se nenhum outro processo tiver alterado
+/// as notas no meio tempo.
+pub fn set_note(repo: &mut Repository, namespace: &str, commit: &str, content: Option<&str>, message: &str) -> Result<(), MinigitError> {
+    if !is_valid_sha1(commit) {
+        return Err(MinigitError::InvalidArgument(format!("'{}' não é um hash de commit", commit)));
+    }
+
+    let reference = notes_ref(namespace)?;
+    let (old_commit, mut notes) = read_notes(repo, &reference)?;
+
+    match content {
+        Some(content) => {
+            let blob = repo.create_object(&BlobObject::new(content.as_bytes().to_vec()))?;
+            notes.insert(commit.to_string(), blob);
+        }
+        None => {
+            notes.remove(commit);
+        }
+    }
+
+    let tree = TreeObject {
+        children: notes.into_iter()
+            .map(|(name, object_id)| TreeObjectChild { mode: format_mode(MODE_FILE), object_id, name })
+            .collect(),
+    };
+    let tree_id = repo.create_object(&tree)?;
+
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos();
+    let notes_commit = CommitObject {
+        tree: tree_id,
+        author: format!("{} <{}>", repo.config.get_username(), repo.config.get_email()),
+        message: message.to_string(),
+        timestamp: now,
+        parent: if old_commit.is_empty() { Vec::new() } else { vec![old_commit.clone()] },
+    };
+    let notes_commit_id = repo.create_object(&notes_commit)?;
+
+    std::fs::create_dir_all(repo.minigitdir.join(&reference))?;
+    refs::update_ref(repo, &reference, &notes_commit_id, Some(&old_commit))
+}
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::objects::RGitObject;
+use crate::{error::MinigitError, objects::RGitObject};
 
 pub struct BlobObject {
     pub content: Vec<u8>
@@ -17,8 +17,9 @@ impl RGitObject for BlobObject {
         self.content.clone()
     }
 
-    fn deserialize(&mut self, object_bytes: Vec<u8>) {
+    fn deserialize(&mut self, object_bytes: Vec<u8>) -> Result<(), MinigitError> {
         self.content = object_bytes;
+        Ok(())
     }
 
     fn object_type(&self) -> &'static str {
@@ -26,9 +27,17 @@ impl RGitObject for BlobObject {
     }
 }
 
-impl From<&PathBuf> for BlobObject {
-    fn from(absolute_path: &PathBuf) -> Self {
-        let content = std::fs::read(absolute_path).unwrap();
-        BlobObject { content }
+impl TryFrom<&PathBuf> for BlobObject {
+    type Error = MinigitError;
+
+    /// Lê o conteúdo do arquivo. Para links simbólicos, o conteúdo é o destino do link
+    fn try_from(absolute_path: &PathBuf) -> Result<Self, Self::Error> {
+        if absolute_path.is_symlink() {
+            let target = std::fs::read_link(absolute_path)?;
+            return Ok(BlobObject { content: target.to_string_lossy().as_bytes().to_vec() });
+        }
+
+        let content = std::fs::read(absolute_path)?;
+        Ok(BlobObject { content })
     }
-}
\ No newline at end of file
+}
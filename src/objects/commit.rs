@@ -1,8 +1,10 @@
 use std::{collections::HashMap, fs};
 
-use crate::{Repository, objects::{RGitObject, RGitObjectTypes, create_tree_object_from_staging_tree, get_tree_as_map}, staging::instantiate_staging_tree_from_index, utils::files};
+use serde::Serialize;
 
-#[derive(Debug, Clone)]
+use crate::{Repository, error::MinigitError, hooks::{Hook, run_hook}, objects::{RGitObject, TreeFile, create_tree_object_from_staging_tree, get_tree_as_map, get_tree_files}, staging::instantiate_staging_tree_from_index, utils::files};
+
+#[derive(Debug, Clone, Serialize)]
 pub struct CommitObject {
     pub tree: String,
     pub author: String,
@@ -12,24 +14,27 @@ pub struct CommitObject {
 }
 
 impl CommitObject {
-    pub fn new(content_bytes: Vec<u8>) -> Self {
-        let content_str = String::from_utf8(content_bytes).expect("Commit deve ser um arquivo UTF8 válido");
-        
-        let (_, tree, remainder) = files::read_value(&content_str);
-        let (_, author, remainder) = files::read_value(remainder);
-        let (_, message, remainder) = files::read_value(remainder);
-        let (_, timestamp_str, mut remainder) = files::read_value(remainder);
-
-        let timestamp: u128 = timestamp_str.parse().expect("Timestamp deve ser um número válido");
+    pub fn new(content_bytes: Vec<u8>) -> Result<Self, MinigitError> {
+        let content_str = String::from_utf8(content_bytes)
+            .map_err(|_| MinigitError::CorruptObject("Commit deve ser um arquivo UTF8 válido".to_string()))?;
+        let malformed = || MinigitError::CorruptObject("Commit mal formatado".to_string());
+
+        let (_, tree, remainder) = files::read_value(&content_str).ok_or_else(malformed)?;
+        let (_, author, remainder) = files::read_value(remainder).ok_or_else(malformed)?;
+        let (_, message, remainder) = files::read_value(remainder).ok_or_else(malformed)?;
+        let (_, timestamp_str, mut remainder) = files::read_value(remainder).ok_or_else(malformed)?;
+
+        let timestamp: u128 = timestamp_str.parse()
+            .map_err(|_| MinigitError::CorruptObject("Timestamp deve ser um número válido".to_string()))?;
         let mut parent: Vec<String> = Vec::new();
 
         while !remainder.is_empty() {
-            let (_, parent_commit, new_remainder) = files::read_value(remainder);
+            let (_, parent_commit, new_remainder) = files::read_value(remainder).ok_or_else(malformed)?;
             parent.push(parent_commit);
             remainder = new_remainder;
         }
 
-        Self { tree, author, message, timestamp, parent }
+        Ok(Self { tree, author, message, timestamp, parent })
     }
 }
 
@@ -54,9 +59,9 @@ impl RGitObject for CommitObject {
         result
     }
 
-    #[allow(unused_variables)]
-    fn deserialize(&mut self, object_bytes: Vec<u8>) {
-        panic!("Não implementado");
+    fn deserialize(&mut self, object_bytes: Vec<u8>) -> Result<(), MinigitError> {
+        *self = Self::new(object_bytes)?;
+        Ok(())
     }
 
     fn object_type(&self) -> &'static str {
@@ -66,16 +71,28 @@ impl RGitObject for CommitObject {
 
 /// Cria um objeto de commit a partir do índice atual do repositório.
 /// 
-/// Retorna o hash do commit criado.
-pub fn create_commit_object_from_index(repo: &mut Repository, message: String) -> String {
-    let staging_tree = instantiate_staging_tree_from_index(repo);
-    let tree_id = create_tree_object_from_staging_tree(&staging_tree, repo);
+/// Se `run_hooks` for verdadeiro, os ganchos `pre-commit` e `commit-msg` são executados antes
+/// de criar o commit. O `commit-msg` pode alterar a mensagem. Com ou sem ganchos, os espaços e
+/// quebras de linha no fim da mensagem são removidos.
+/// 
+/// Retorna o hash do commit criado ou um erro se algum gancho abortar o commit.
+pub fn create_commit_object_from_index(repo: &mut Repository, message: String, run_hooks: bool) -> Result<String, MinigitError> {
+    let message = if run_hooks {
+        run_hook(repo, Hook::PreCommit, &[])?;
+        run_commit_msg_hook(repo, message)?
+    } else {
+        message
+    };
+    let message = message.trim_end().to_string();
+
+    let staging_tree = instantiate_staging_tree_from_index(repo)?;
+    let tree_id = create_tree_object_from_staging_tree(&staging_tree, repo)?;
 
     let author_name = repo.config.get_username();
     let author_email = repo.config.get_email();
     let author = format!("{} <{}>", author_name, author_email);
     
-    let head = repo.resolve_head();
+    let head = repo.resolve_head()?;
     let mut parents: Vec<String> = if head.is_empty() {
         Vec::new()
     } else {
@@ -83,8 +100,7 @@ pub fn create_commit_object_from_index(repo: &mut Repository, message: String) -
     };
     
     if repo.merge_head_path.exists() {
-        let merge_head_hash = fs::read_to_string(&repo.merge_head_path)
-            .expect("Deveria ser possível ler MERGE_HEAD")
+        let merge_head_hash = fs::read_to_string(&repo.merge_head_path)?
             .trim()
             .to_string();
 
@@ -93,7 +109,7 @@ pub fn create_commit_object_from_index(repo: &mut Repository, message: String) -
         }
     }
 
-    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos();
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_nanos();
 
     let commit = CommitObject {
         tree: tree_id,
@@ -103,17 +119,39 @@ pub fn create_commit_object_from_index(repo: &mut Repository, message: String) -
         parent: parents,
     };
 
-    return repo.create_object(&commit);
+    repo.create_object(&commit)
+}
+
+/// Escreve a mensagem em `.minigit/COMMIT_EDITMSG`, executa o gancho `commit-msg`
+/// e retorna a mensagem possivelmente editada pelo gancho.
+fn run_commit_msg_hook(repo: &Repository, message: String) -> Result<String, MinigitError> {
+    let message_path = repo.minigitdir.join(Repository::COMMIT_EDITMSG);
+    fs::write(&message_path, &message)?;
+
+    let message_path_str = message_path.to_string_lossy().to_string();
+    run_hook(repo, Hook::CommitMsg, &[&message_path_str])?;
+
+    Ok(fs::read_to_string(&message_path)?)
 }
 
 /// Transforma o commit em um HashMap de caminho de arquivo para blob hash
-pub fn get_commit_tree_as_map(repo: &Repository, commit: &CommitObject) -> HashMap<String, String>{
-    let commit_tree = repo.get_object(&commit.tree).unwrap();
+pub fn get_commit_tree_as_map(repo: &Repository, commit: &CommitObject) -> Result<HashMap<String, String>, MinigitError> {
+    let tree_obj = repo.get_tree(&commit.tree)?;
+    get_tree_as_map(repo, &tree_obj)
+}
 
-    match commit_tree {
-        RGitObjectTypes::Tree(tree_obj) => {
-            get_tree_as_map(repo, &tree_obj)
-        }
-        _ => panic!("Objeto de árvore esperado para o commit"),
+/// Transforma o commit (a partir do seu hash) em um HashMap de caminho de arquivo para modo e hash do blob.
+/// Um hash vazio (branch sem commits) resulta em um mapa vazio.
+pub fn get_commit_files(repo: &Repository, commit_hash: &str) -> Result<HashMap<String, TreeFile>, MinigitError> {
+    if commit_hash.is_empty() {
+        return Ok(HashMap::new());
     }
-}
\ No newline at end of file
+
+    get_commit_tree_files(repo, &repo.get_commit(&commit_hash.to_string())?)
+}
+
+/// Transforma o commit em um HashMap de caminho de arquivo para modo e hash do blob
+pub fn get_commit_tree_files(repo: &Repository, commit: &CommitObject) -> Result<HashMap<String, TreeFile>, MinigitError> {
+    let tree_obj = repo.get_tree(&commit.tree)?;
+    get_tree_files(repo, &tree_obj)
+}
@@ -2,6 +2,7 @@ pub mod blob;
 pub mod commit;
 pub mod tree;
 pub mod object;
+pub mod mode;
 
 pub enum RGitObjectTypes {
     Blob(BlobObject),
@@ -13,3 +14,4 @@ pub use object::*;
 pub use blob::*;
 pub use commit::*;
 pub use tree::*;
+pub use mode::*;
@@ -0,0 +1,55 @@
+use std::fs::Metadata;
+
+use crate::{Repository, error::MinigitError};
+
+/// Modo de um arquivo normal
+pub const MODE_FILE: u32 = 0o100644;
+/// Modo de um arquivo executável
+pub const MODE_EXECUTABLE: u32 = 0o100755;
+/// Modo de um link simbólico. O blob guarda o destino do link
+pub const MODE_SYMLINK: u32 = 0o120000;
+/// Modo de uma subárvore
+pub const MODE_TREE: u32 = 0o040000;
+
+/// Formata o modo como nos objetos árvore (ex: `100644`, `040000`)
+pub fn format_mode(mode: u32) -> String {
+    format!("{:06o}", mode)
+}
+
+/// Lê um modo no formato dos objetos árvore, retornando `MinigitError::CorruptObject` se ele não for suportado
+pub fn parse_mode(mode: &str) -> Result<u32, MinigitError> {
+    match u32::from_str_radix(mode, 8) {
+        Ok(mode @ (MODE_FILE | MODE_EXECUTABLE | MODE_SYMLINK | MODE_TREE)) => Ok(mode),
+        _ => Err(MinigitError::CorruptObject(format!("Modo inválido na árvore: '{}'", mode))),
+    }
+}
+
+/// Calcula o modo de um arquivo da worktree a partir dos seus metadados (sem seguir links simbólicos)
+///
+/// Com `core.filemode` desligado, o bit de execução é ignorado e o modo `previous` (o do índice) é mantido.
+pub fn worktree_mode(repo: &Repository, metadata: &Metadata, previous: Option<u32>) -> u32 {
+    if metadata.file_type().is_symlink() {
+        return MODE_SYMLINK;
+    }
+
+    if !repo.config.get_filemode() {
+        return match previous {
+            Some(MODE_EXECUTABLE) => MODE_EXECUTABLE,
+            _ => MODE_FILE,
+        };
+    }
+
+    if is_executable(metadata) { MODE_EXECUTABLE } else { MODE_FILE }
+}
+
+#[cfg(unix)]
+fn is_executable(metadata: &Metadata) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_metadata: &Metadata) -> bool {
+    false
+}
@@ -1,4 +1,4 @@
-use crate::utils::sha1sum;
+use crate::{error::MinigitError, utils::sha1sum};
 
 pub trait RGitObject {
     /// Hash do objeto
@@ -10,7 +10,7 @@ pub trait RGitObject {
     fn serialize(&self) -> Vec<u8>;
 
     /// Crie o objeto a partir do conteúdo do arquivo que o representa
-    fn deserialize(&mut self, object_bytes: Vec<u8>);
+    fn deserialize(&mut self, object_bytes: Vec<u8>) -> Result<(), MinigitError>;
 
     fn get_object_bytes(&self) -> Vec<u8> {
         let mut content_bytes = self.serialize();
@@ -23,7 +23,5 @@ pub trait RGitObject {
         object_bytes
     }
 
-    fn object_type(&self) -> &'static str {
-        panic!("Não implementado")
-    }
-}
\ No newline at end of file
+    fn object_type(&self) -> &'static str;
+}
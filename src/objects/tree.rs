@@ -1,12 +1,14 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, path::{Path, PathBuf}};
 
-use crate::{utils, Repository, objects::{RGitObject, RGitObjectTypes}, staging::StagingTree};
+use serde::Serialize;
+
+use crate::{utils, Repository, error::MinigitError, objects::{MODE_FILE, MODE_TREE, RGitObject, RGitObjectTypes, format_mode, parse_mode}, staging::StagingTree};
 
 pub struct TreeObject {
     pub children: Vec<TreeObjectChild>
 }
 
-#[derive(PartialEq, Eq, PartialOrd, Ord)]
+#[derive(PartialEq, Eq, PartialOrd, Ord, Serialize)]
 pub struct TreeObjectChild {
     pub mode: String,
     pub object_id: String,
@@ -25,8 +27,9 @@ impl RGitObject for TreeObject {
         result.as_bytes().to_vec()
     }
 
-    fn deserialize(&mut self, object_bytes: Vec<u8>) {
-        self.children = Self::children_from_bytes(object_bytes);
+    fn deserialize(&mut self, object_bytes: Vec<u8>) -> Result<(), MinigitError> {
+        self.children = Self::children_from_bytes(object_bytes)?;
+        Ok(())
     }
 
     fn object_type(&self) -> &'static str {
@@ -35,53 +38,74 @@ impl RGitObject for TreeObject {
 }
 
 impl TreeObject {
-    pub fn new(object_bytes: Vec<u8>) -> Self {
-        TreeObject { children: Self::children_from_bytes(object_bytes) }
+    pub fn new(object_bytes: Vec<u8>) -> Result<Self, MinigitError> {
+        Ok(TreeObject { children: Self::children_from_bytes(object_bytes)? })
     }
 
-    fn children_from_bytes(object_bytes: Vec<u8>) -> Vec<TreeObjectChild> {
+    fn children_from_bytes(object_bytes: Vec<u8>) -> Result<Vec<TreeObjectChild>, MinigitError> {
         let mut result: Vec<TreeObjectChild> = Vec::new();
-        let mut object_str = str::from_utf8(&object_bytes).expect("O objeto deve ser uma string UTF-8 válida");
+        let mut object_str = str::from_utf8(&object_bytes)
+            .map_err(|_| MinigitError::CorruptObject("O objeto árvore deve ser uma string UTF-8 válida".to_string()))?;
 
         while let Some(new_line) = object_str.find('\n') {
             let record = &object_str[..new_line];
-            result.push(Self::parse_child(record));
+            result.push(Self::parse_child(record)?);
             object_str = &object_str[new_line+1..];
         }
 
         result.sort();
 
-        result
+        Ok(result)
     }
 
-    fn parse_child(record: &str) -> TreeObjectChild {
-        let space = record.find(' ');
-        let null = record.find('\0');
+    fn parse_child(record: &str) -> Result<TreeObjectChild, MinigitError> {
+        let (Some(space), Some(null)) = (record.find(' '), record.find('\0')) else {
+            return Err(MinigitError::CorruptObject("Objeto árvore mal formatado".to_string()));
+        };
 
-        if space.is_none() || null.is_none() {
-            panic!("Objeto árvore mal formatado");
+        if null < space {
+            return Err(MinigitError::CorruptObject("Objeto árvore mal formatado".to_string()));
         }
 
-        let space = space.unwrap();
-        let null = null.unwrap();
-
-        TreeObjectChild { 
+        Ok(TreeObjectChild { 
             mode: record[..space].to_string(), 
             name: record[space+1..null].to_string(),
             object_id: record[null+1..].to_string() 
-        }
+        })
     }
 }
 
+/// Verifica se `name` pode ser o nome de uma entrada de árvore
+///
+/// Nomes vazios, `.`, `..`, `.minigit` ou com `/` ou `\0` sairiam do diretório da árvore ou
+/// escreveriam dentro do repositório ao instanciá-la na worktree.
+pub fn is_valid_tree_entry_name(name: &str) -> bool {
+    !matches!(name, "" | "." | "..") && name != Repository::MINIGITDIR && !name.contains(['/', '\0'])
+}
+
+/// Um arquivo de uma árvore: o modo (`MODE_FILE`, `MODE_EXECUTABLE` ou `MODE_SYMLINK`) e o hash do blob
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeFile {
+    pub mode: u32,
+    pub hash: String,
+}
 
 /// Transforma a árvore do commit em um HashMap de caminho relativo ao repositório -> hash do objeto
-pub fn get_tree_as_map(repo: &Repository, tree: &TreeObject) -> HashMap<String, String> {
-    let mut result: HashMap<String, String> = HashMap::new();
+pub fn get_tree_as_map(repo: &Repository, tree: &TreeObject) -> Result<HashMap<String, String>, MinigitError> {
+    Ok(get_tree_files(repo, tree)?
+        .into_iter()
+        .map(|(path, file)| (path, file.hash))
+        .collect())
+}
+
+/// Transforma a árvore do commit em um HashMap de caminho relativo ao repositório -> modo e hash do blob
+pub fn get_tree_files(repo: &Repository, tree: &TreeObject) -> Result<HashMap<String, TreeFile>, MinigitError> {
+    let mut result: HashMap<String, TreeFile> = HashMap::new();
     let curr_dir = PathBuf::new();
 
-    get_tree_as_map_recursive(repo, tree, curr_dir, &mut result);
+    get_tree_files_recursive(repo, tree, curr_dir, &mut result)?;
 
-    result
+    Ok(result)
 }
 
 /// Função recursiva auxiliar para transformar a árvore do commit em um HashMap
@@ -89,42 +113,67 @@ pub fn get_tree_as_map(repo: &Repository, tree: &TreeObject) -> HashMap<String,
 /// Resultado final é armazenado em `result`
 /// 
 /// `curr_dir` é RELATIVO ao working directory do repositório
-pub fn get_tree_as_map_recursive(repo: &Repository, tree: &TreeObject, curr_dir: PathBuf, result: &mut HashMap<String, String>) {
+fn get_tree_files_recursive(repo: &Repository, tree: &TreeObject, curr_dir: PathBuf, result: &mut HashMap<String, TreeFile>) -> Result<(), MinigitError> {
     for child in &tree.children {
-        let object = repo.get_object(&child.object_id).unwrap();
+        let object = repo.get_object(&child.object_id)?;
 
         match object {
             RGitObjectTypes::Blob(blob_obj) => {
                 let file_path = curr_dir.clone().join(&child.name);
-                let file_path_str = file_path.to_str().unwrap().to_string();
+                let file_path_str = file_path.to_string_lossy().to_string();
 
-                result.insert(file_path_str, blob_obj.hash());
+                result.insert(file_path_str, TreeFile { mode: blob_mode(&child.mode)?, hash: blob_obj.hash() });
             }
             RGitObjectTypes::Tree(tree_obj) => {
                 let new_dir = curr_dir.clone().join(&child.name);
 
-                get_tree_as_map_recursive(repo, &tree_obj, new_dir, result);
+                get_tree_files_recursive(repo, &tree_obj, new_dir, result)?;
             }
-            _ => panic!("Objeto inválido na árvore do commit"),
+            _ => return Err(MinigitError::CorruptObject(format!("Objeto inválido na árvore do commit: {}", child.object_id))),
         }
     }
+
+    Ok(())
+}
+
+/// Modo de um blob da árvore. Árvores antigas usavam `100644` para tudo, então só o modo é validado
+fn blob_mode(mode: &str) -> Result<u32, MinigitError> {
+    match parse_mode(mode)? {
+        MODE_TREE => Err(MinigitError::CorruptObject(format!("Blob com modo de árvore '{}'", mode))),
+        mode => Ok(mode),
+    }
+}
+
+/// Escolhe o modo de `path` com conteúdo `hash` a partir das versões em `sides`
+///
+/// Usa o modo da primeira versão com o mesmo conteúdo ou, se nenhuma tiver, o da primeira que tiver o arquivo.
+pub fn pick_mode(path: &str, hash: &str, sides: &[&HashMap<String, TreeFile>]) -> u32 {
+    sides.iter()
+        .filter_map(|side| side.get(path))
+        .find(|file| file.hash == hash)
+        .or_else(|| sides.iter().find_map(|side| side.get(path)))
+        .map_or(MODE_FILE, |file| file.mode)
 }
 
-pub fn create_tree_object_from_staging_tree(staging_tree: &StagingTree, repo: &mut Repository) -> String {
+pub fn create_tree_object_from_staging_tree(staging_tree: &StagingTree, repo: &mut Repository) -> Result<String, MinigitError> {
     let mut object: TreeObject = TreeObject {
         children: Vec::new()
     };
 
     match staging_tree {
-        StagingTree::Blob(blob_id) => {
-            return blob_id.clone();
+        StagingTree::Blob(blob_id, _) => {
+            return Ok(blob_id.clone());
         },
         StagingTree::Fork(children) => {
             for (name, child) in children {
-                let child_id = create_tree_object_from_staging_tree(child, repo);
+                let child_id = create_tree_object_from_staging_tree(child, repo)?;
+                let mode = match child.as_ref() {
+                    StagingTree::Blob(_, mode) => *mode,
+                    StagingTree::Fork(_) => MODE_TREE,
+                };
 
                 let tree_child = TreeObjectChild {
-                    mode: "100644".to_string(),
+                    mode: format_mode(mode),
                     object_id: child_id,
                     name: name.clone(),
                 };
@@ -134,35 +183,36 @@ pub fn create_tree_object_from_staging_tree(staging_tree: &StagingTree, repo: &m
         }
     }
 
-    repo.create_object(&object);
-
-    object.hash()
+    // A ordem dos filhos não pode depender do HashMap, senão o mesmo conteúdo geraria árvores diferentes
+    object.children.sort();
+    repo.create_object(&object)
 }
 
-pub fn instanciate_tree_files(repository: &mut Repository, tree: &TreeObject) {
-    instanciate_subtree_files(repository, tree, &repository.worktree.clone());
+pub fn instanciate_tree_files(repository: &mut Repository, tree: &TreeObject) -> Result<(), MinigitError> {
+    instanciate_subtree_files(repository, tree, &repository.worktree.clone())
 }
 
-fn instanciate_subtree_files(repository: &mut Repository, tree: &TreeObject, current_dir: &PathBuf) {
+fn instanciate_subtree_files(repository: &mut Repository, tree: &TreeObject, current_dir: &Path) -> Result<(), MinigitError> {
     for child in &tree.children {
-        let object = repository
-            .get_object(&child.object_id)
-            .expect("Objeto da tree deveria existir");
+        let object = repository.get_object(&child.object_id)?;
         
         let path = current_dir.join(child.name.clone());
 
         match object {
             RGitObjectTypes::Blob(blob) => {
-                utils::create_file(&path, &blob.content);
+                let filemode = repository.config.get_filemode();
+                utils::write_file_with_mode(&path, &blob.content, blob_mode(&child.mode)?, filemode)?;
             },
             RGitObjectTypes::Tree(tree) => {
-                utils::create_dir(&path);
+                utils::create_dir(&path)?;
 
-                instanciate_subtree_files(repository, &tree, &path);
+                instanciate_subtree_files(repository, &tree, &path)?;
             }
             _ => {
-                panic!("Objeto não é blob ou tree.");
+                return Err(MinigitError::CorruptObject(format!("Objeto {} não é blob ou tree.", child.object_id)));
             }
         }
     }
-}
\ No newline at end of file
+
+    Ok(())
+}
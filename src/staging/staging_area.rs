@@ -1,162 +1,485 @@
-use std::collections::HashMap;
-use std::fmt::Debug;
-use std::fs::{File};
-use std::io::{BufRead, BufReader};
+use std::collections::{HashMap, HashSet};
+use std::fs::Metadata;
 use std::str::FromStr;
-use std::{path::PathBuf};
+use std::path::{Path, PathBuf};
+
+use sha1::{Digest, Sha1};
 
 use crate::Repository;
-use crate::objects::{CommitObject, RGitObjectTypes, tree};
+use crate::error::MinigitError;
+use crate::objects::{BlobObject, CommitObject, MODE_FILE, RGitObject, TreeFile, tree, worktree_mode};
+use crate::utils::{LockFile, is_valid_sha1, write_locked};
+
+/// Assinatura no início do arquivo `index` binário
+const INDEX_SIGNATURE: &[u8; 4] = b"MIDX";
+
+/// Versão atual do formato binário do `index`
+const INDEX_VERSION: u32 = 1;
+
+/// Tamanho do cabeçalho: assinatura, versão e quantidade de entradas
+const HEADER_LEN: usize = 12;
+
+/// Tamanho do checksum SHA-1 no final do arquivo
+const CHECKSUM_LEN: usize = 20;
+
+/// Tamanho da parte fixa de uma entrada: ctime, mtime, dev, ino, size (u64), mode (u32),
+/// hash (20 bytes), estágio (u8) e tamanho do caminho (u16)
+const ENTRY_FIXED_LEN: usize = 5 * 8 + 4 + 20 + 1 + 2;
+
+/// Estágio de uma entrada sem conflitos
+pub const STAGE_NORMAL: u8 = 0;
+/// Estágio com a versão do ancestral comum de um arquivo em conflito
+pub const STAGE_BASE: u8 = 1;
+/// Estágio com a nossa versão (HEAD) de um arquivo em conflito
+pub const STAGE_OURS: u8 = 2;
+/// Estágio com a versão deles (branch sendo mesclada) de um arquivo em conflito
+pub const STAGE_THEIRS: u8 = 3;
+
+/// Informações do sistema de arquivos guardadas no índice para evitar recalcular o hash
+/// de arquivos que não mudaram desde o último `add`
+///
+/// ## Atributos
+/// - `ctime` - Última mudança nos metadados do arquivo, em nanossegundos
+/// - `mtime` - Última mudança no conteúdo do arquivo, em nanossegundos
+/// - `dev` - Dispositivo onde o arquivo está
+/// - `ino` - Número do inode do arquivo
+/// - `size` - Tamanho do arquivo em bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FileStat {
+    pub ctime: u64,
+    pub mtime: u64,
+    pub dev: u64,
+    pub ino: u64,
+    pub size: u64,
+}
+
+impl FileStat {
+    #[cfg(unix)]
+    pub fn from_metadata(metadata: &Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+
+        let to_nanos = |seconds: i64, nanos: i64| (seconds as u64).saturating_mul(1_000_000_000).saturating_add(nanos as u64);
+
+        FileStat {
+            ctime: to_nanos(metadata.ctime(), metadata.ctime_nsec()),
+            mtime: to_nanos(metadata.mtime(), metadata.mtime_nsec()),
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+            size: metadata.size(),
+        }
+    }
+
+    #[cfg(not(unix))]
+    pub fn from_metadata(metadata: &Metadata) -> Self {
+        let to_nanos = |time: std::io::Result<std::time::SystemTime>| time.ok()
+            .and_then(|time| time.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default();
+
+        FileStat {
+            ctime: to_nanos(metadata.created()),
+            mtime: to_nanos(metadata.modified()),
+            dev: 0,
+            ino: 0,
+            size: metadata.len(),
+        }
+    }
+}
 
 /// Uma entrada da staging area
+///
+/// ## Atributos
+/// - `stat` - Informações do arquivo no momento em que foi adicionado
+/// - `mode_type` - Modo do arquivo (`MODE_FILE`, `MODE_EXECUTABLE` ou `MODE_SYMLINK`)
+/// - `object_hash` - Hash do blob com o conteúdo do arquivo
+/// - `stage` - 0 para entradas normais; 1, 2 e 3 para as versões base, nossa e deles de um arquivo em conflito
+/// - `path` - Caminho relativo à raíz do repositório
 #[derive(Debug, Clone)]
 pub struct StagingEntry {
-    pub last_content_change: u128, //in nanos
+    pub stat: FileStat,
     pub mode_type: u32,
     pub object_hash: String,
+    pub stage: u8,
     pub path: PathBuf, // caminho relativo ao worktree
 }
 
+impl StagingEntry {
+    /// Cria uma entrada normal (estágio 0) para o blob, sem informações do arquivo
+    ///
+    /// Entradas sem informações do arquivo sempre têm o hash recalculado ao serem comparadas com a worktree.
+    pub fn new(object_hash: String, path: PathBuf) -> Self {
+        StagingEntry {
+            stat: FileStat::default(),
+            mode_type: MODE_FILE,
+            object_hash,
+            stage: STAGE_NORMAL,
+            path,
+        }
+    }
 
+    /// Cria uma entrada normal (estágio 0) para o arquivo de uma árvore, mantendo o seu modo
+    pub fn from_tree_file(file: &TreeFile, path: PathBuf) -> Self {
+        let mut entry = StagingEntry::new(file.hash.clone(), path);
+        entry.mode_type = file.mode;
+        entry
+    }
 
-impl StagingEntry {
-    /// Converte a entrada de staging em um array de bytes para ser escrito no arquivo de índice
-    pub fn as_bytes(&self) -> Vec<u8> {
-        let mut entry_str: String = String::new();
+    /// Retorna o modo e o hash da entrada como em uma árvore
+    pub fn tree_file(&self) -> TreeFile {
+        TreeFile { mode: self.mode_type, hash: self.object_hash.clone() }
+    }
 
-        entry_str.push_str(&self.last_content_change.to_string());
-        entry_str.push(' ');
+    /// Converte a entrada de staging em um array de bytes para ser escrito no arquivo de índice
+    pub fn as_bytes(&self) -> Result<Vec<u8>, MinigitError> {
+        let path = self.path.to_string_lossy();
+        let path_len = u16::try_from(path.len())
+            .map_err(|_| MinigitError::InvalidArgument(format!("Caminho muito longo: '{}'", path)))?;
+        let hash = hex::decode(&self.object_hash)
+            .map_err(|_| MinigitError::CorruptIndex(format!("Hash inválido '{}'", self.object_hash)))?;
+
+        let mut bytes = Vec::with_capacity(ENTRY_FIXED_LEN + path.len());
+        for value in [self.stat.ctime, self.stat.mtime, self.stat.dev, self.stat.ino, self.stat.size] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        bytes.extend_from_slice(&self.mode_type.to_be_bytes());
+        bytes.extend_from_slice(&hash);
+        bytes.push(self.stage);
+        bytes.extend_from_slice(&path_len.to_be_bytes());
+        bytes.extend_from_slice(path.as_bytes());
 
-        entry_str.push_str(&self.mode_type.to_string());
-        entry_str.push(' ');
+        Ok(bytes)
+    }
 
-        entry_str.push_str(&self.object_hash.to_string());
-        entry_str.push(' ');
+    /// Lê uma entrada a partir de `bytes`, retornando a entrada e a quantidade de bytes consumidos
+    pub fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), MinigitError> {
+        let truncated = || MinigitError::CorruptIndex("Entrada truncada".to_string());
+        let fixed = bytes.get(..ENTRY_FIXED_LEN).ok_or_else(truncated)?;
+
+        let read_u64 = |offset: usize| u64::from_be_bytes(fixed[offset..offset + 8].try_into().unwrap_or_default());
+        let stat = FileStat {
+            ctime: read_u64(0),
+            mtime: read_u64(8),
+            dev: read_u64(16),
+            ino: read_u64(24),
+            size: read_u64(32),
+        };
+        let mode_type = u32::from_be_bytes(fixed[40..44].try_into().unwrap_or_default());
+        let object_hash = hex::encode(&fixed[44..64]);
+        let stage = fixed[64];
+        let path_len = u16::from_be_bytes([fixed[65], fixed[66]]) as usize;
+
+        if stage > STAGE_THEIRS {
+            return Err(MinigitError::CorruptIndex(format!("Estágio inválido {}", stage)));
+        }
 
-        entry_str.push_str(self.path.to_str().unwrap());
+        let path_bytes = bytes.get(ENTRY_FIXED_LEN..ENTRY_FIXED_LEN + path_len).ok_or_else(truncated)?;
+        let path = String::from_utf8(path_bytes.to_vec())
+            .map_err(|_| MinigitError::CorruptIndex("Caminho não é UTF8 válido".to_string()))?;
 
-        entry_str.as_bytes().to_vec()
+        let entry = StagingEntry { stat, mode_type, object_hash, stage, path: PathBuf::from(path) };
+        Ok((entry, ENTRY_FIXED_LEN + path_len))
     }
 
-    pub fn from_string(s: String) -> Self {
-        let parts: Vec<&str> = s.split(' ').collect();
+    /// Lê uma entrada do formato de texto antigo (`mtime modo hash caminho`)
+    ///
+    /// As informações do arquivo são descartadas, então o hash é recalculado na próxima comparação.
+    pub fn from_string(s: String) -> Result<Self, MinigitError> {
+        let parts: Vec<&str> = s.splitn(4, ' ').collect();
 
-        StagingEntry { 
-            last_content_change: parse_to::<u128>(parts[0]), 
-            mode_type: parse_to::<u32>(parts[1]), 
-            object_hash: parts[2].to_string(), 
-            path: PathBuf::from(parts[3])
+        if parts.len() != 4 {
+            return Err(MinigitError::CorruptIndex(format!("Entrada mal formatada: '{}'", s)));
+        }
+
+        parse_to::<u128>(parts[0])?;
+        if !is_valid_sha1(parts[2]) {
+            return Err(MinigitError::CorruptIndex(format!("Hash inválido '{}'", parts[2])));
         }
+
+        Ok(StagingEntry {
+            stat: FileStat::default(),
+            mode_type: parse_to::<u32>(parts[1])?,
+            object_hash: parts[2].to_string(),
+            stage: STAGE_NORMAL,
+            path: PathBuf::from(parts[3])
+        })
     }
 }
 
 /// Representa a área de staging, onde os arquivos alterados vão antes de serem comitados.
-/// 
+///
 /// A implementação é através de um arquivo que mantém um mapeamento de caminho -> hash do objeto.
-/// 
+///
 /// Quando o usuário adicionar um arquivo em staging (comando add), devemos atualizar a entrada correspondente
+///
+/// O arquivo `index` é binário: um cabeçalho (`MIDX`, versão e quantidade de entradas), as entradas
+/// ordenadas por caminho e estágio, e o SHA-1 de todo o conteúdo anterior.
 pub struct StagingArea {
     pub entries: Vec<StagingEntry>,
+    /// Momento (em nanossegundos) em que o arquivo `index` foi escrito pela última vez.
+    /// Arquivos modificados a partir desse momento sempre têm o hash recalculado.
+    pub timestamp: u64,
 }
 
 impl StagingArea {
     /// Carrega a área de staging do arquivo `index`
-    pub fn new(repo: &Repository) -> Self {
+    ///
+    /// Um arquivo `index` inexistente é tratado como uma área de staging vazia.
+    /// Índices no formato de texto antigo também são aceitos e serão reescritos no formato binário.
+    pub fn new(repo: &Repository) -> Result<Self, MinigitError> {
         let index_file_path = repo.minigitdir.join(Repository::INDEX);
 
-        let index_file = File::open(&index_file_path).unwrap();
-        let reader = BufReader::new(index_file);
-        let mut entries: Vec<StagingEntry> = Vec::new();
+        let index_content = match std::fs::read(&index_file_path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let timestamp = std::fs::metadata(&index_file_path)
+            .map(|metadata| FileStat::from_metadata(&metadata).mtime)
+            .unwrap_or_default();
+
+        let mut entries = if index_content.starts_with(INDEX_SIGNATURE) {
+            parse_binary_index(&index_content)?
+        } else {
+            parse_text_index(&index_content)?
+        };
+        entries.sort_by(compare_entries);
 
-        for line in reader.lines() {
-            let line = line.unwrap();
-            entries.push(StagingEntry::from_string(line));
-        }
+        Ok(StagingArea { entries, timestamp })
+    }
 
-        StagingArea { entries }
+    /// Cria uma área de staging com as entradas fornecidas
+    pub fn from_entries(mut entries: Vec<StagingEntry>) -> Self {
+        entries.sort_by(compare_entries);
+        StagingArea { entries, timestamp: 0 }
+    }
+
+    /// Procura a entrada de um arquivo no estágio fornecido usando busca binária
+    fn position(&self, path: &Path, stage: u8) -> Result<usize, usize> {
+        self.entries.binary_search_by(|entry| {
+            entry.path.as_path().cmp(path).then(entry.stage.cmp(&stage))
+        })
+    }
+
+    /// Retorna a entrada normal (estágio 0) de um arquivo
+    pub fn find_entry(&self, path: &Path) -> Option<&StagingEntry> {
+        self.position(path, STAGE_NORMAL).ok().map(|position| &self.entries[position])
     }
 
     /// Atualiza a entrada de um arquivo. Cria uma caso ela não exista
+    ///
+    /// Adicionar a entrada normal de um arquivo resolve o conflito, removendo os estágios 1, 2 e 3.
     pub fn update_or_create_entry(&mut self, entry: StagingEntry) {
-        let curr_entry = self.entries.iter().position(|e| e.path == entry.path);
+        if entry.stage == STAGE_NORMAL {
+            self.remove_conflict_stages(&entry.path);
+        }
 
-        if let Some(position) = curr_entry {
-            self.entries[position] = entry;
-        } else {
-            self.entries.push(entry);
+        match self.position(&entry.path, entry.stage) {
+            Ok(position) => self.entries[position] = entry,
+            Err(position) => self.entries.insert(position, entry),
         }
     }
 
+    /// Remove todas as entradas do arquivo, inclusive as de conflito
     pub fn remove_entry_with_path(&mut self, path: &PathBuf) {
         self.entries.retain(|e| &e.path != path);
     }
 
+    /// Marca o arquivo como em conflito, trocando a entrada normal pelas versões base, nossa e deles
+    ///
+    /// Versões ausentes (arquivo criado ou removido em algum dos lados) não geram entrada.
+    pub fn add_conflict(&mut self, path: &Path, base: Option<&String>, ours: Option<&String>, theirs: Option<&String>) {
+        self.remove_entry_with_path(&path.to_path_buf());
+
+        for (stage, hash) in [(STAGE_BASE, base), (STAGE_OURS, ours), (STAGE_THEIRS, theirs)] {
+            if let Some(hash) = hash {
+                let mut entry = StagingEntry::new(hash.clone(), path.to_path_buf());
+                entry.stage = stage;
+                self.update_or_create_entry(entry);
+            }
+        }
+    }
+
+    fn remove_conflict_stages(&mut self, path: &Path) {
+        self.entries.retain(|e| e.path != path || e.stage == STAGE_NORMAL);
+    }
+
+    /// Retorna os caminhos dos arquivos com conflitos não resolvidos, ordenados e sem repetição
+    pub fn conflicted_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.entries.iter()
+            .filter(|entry| entry.stage != STAGE_NORMAL)
+            .map(|entry| entry.path.clone())
+            .collect();
+        paths.dedup();
+        paths
+    }
+
+    /// Verifica se o arquivo da worktree ainda corresponde à entrada sem precisar ler o seu conteúdo
+    ///
+    /// Só é confiável se as informações do arquivo forem iguais às guardadas no índice e o arquivo
+    /// tiver sido modificado antes da última escrita do índice; caso contrário, uma mudança feita
+    /// no mesmo instante da escrita poderia passar despercebida.
+    pub fn is_stat_clean(&self, entry: &StagingEntry, metadata: &Metadata) -> bool {
+        let stat = FileStat::from_metadata(metadata);
+        entry.stat != FileStat::default() && entry.stat == stat && stat.mtime < self.timestamp
+    }
+
+    /// Verifica se o arquivo da worktree é diferente da entrada, usando as informações do arquivo
+    /// quando possível e recalculando o hash do conteúdo caso contrário
+    ///
+    /// Uma mudança de modo (bit de execução ou link simbólico) também conta como modificação.
+    pub fn is_modified(&self, repo: &Repository, entry: &StagingEntry) -> Result<bool, MinigitError> {
+        let absolute_path = repo.worktree.join(&entry.path);
+        let metadata = std::fs::symlink_metadata(&absolute_path)?;
+
+        if worktree_mode(repo, &metadata, Some(entry.mode_type)) != entry.mode_type {
+            return Ok(true);
+        }
+
+        if self.is_stat_clean(entry, &metadata) {
+            return Ok(false);
+        }
+
+        Ok(BlobObject::try_from(&absolute_path)?.hash() != entry.object_hash)
+    }
+
+    /// Atualiza as informações dos arquivos da worktree cujo conteúdo é igual ao da entrada,
+    /// para que as próximas comparações não precisem recalcular o hash
+    pub fn refresh(&mut self, repo: &Repository) -> Result<(), MinigitError> {
+        for entry in self.entries.iter_mut().filter(|entry| entry.stage == STAGE_NORMAL) {
+            let absolute_path = repo.worktree.join(&entry.path);
+            let Ok(metadata) = std::fs::symlink_metadata(&absolute_path) else {
+                continue;
+            };
+
+            if !metadata.is_dir() && BlobObject::try_from(&absolute_path)?.hash() == entry.object_hash {
+                entry.stat = FileStat::from_metadata(&metadata);
+            }
+        }
+        Ok(())
+    }
+
     /// Serializa a área de staging de volta para o arquivo `index`
-    pub fn serialize(&self) -> Vec<u8> {
+    pub fn serialize(&self) -> Result<Vec<u8>, MinigitError> {
+        let entry_count = u32::try_from(self.entries.len())
+            .map_err(|_| MinigitError::InvalidArgument("Quantidade de entradas muito grande".to_string()))?;
+
         let mut index_file_content: Vec<u8> = Vec::new();
+        index_file_content.extend_from_slice(INDEX_SIGNATURE);
+        index_file_content.extend_from_slice(&INDEX_VERSION.to_be_bytes());
+        index_file_content.extend_from_slice(&entry_count.to_be_bytes());
 
         for entry in &self.entries {
-            let mut entry_bytes = entry.as_bytes();
-            entry_bytes.push(b'\n');
-            index_file_content.append(&mut entry_bytes);
+            index_file_content.append(&mut entry.as_bytes()?);
         }
 
-        index_file_content
+        let checksum = Sha1::digest(&index_file_content);
+        index_file_content.extend_from_slice(&checksum);
+
+        Ok(index_file_content)
     }
 
+    /// Retorna as entradas normais (estágio 0) indexadas pelo caminho
     pub fn get_entries_as_map(&self) -> HashMap<PathBuf, StagingEntry> {
         let mut entries_map: HashMap<PathBuf, StagingEntry> = HashMap::new();
-        for entry in self.entries.clone() {
-            entries_map.insert(entry.path.clone(), entry);
+        for entry in self.entries.iter().filter(|entry| entry.stage == STAGE_NORMAL) {
+            entries_map.insert(entry.path.clone(), entry.clone());
         }
         entries_map
     }
 }
 
-pub fn rewrite_index_from_commit_id(repo: &mut Repository, commit_id: &String) {
-    let RGitObjectTypes::Commit(commit_object) = repo
-        .get_object(commit_id)
-        .unwrap()
-        else { panic!("ID de commit inválido ao reescrever índice"); };
+fn compare_entries(a: &StagingEntry, b: &StagingEntry) -> std::cmp::Ordering {
+    a.path.cmp(&b.path).then(a.stage.cmp(&b.stage))
+}
 
-    let staging_area = staging_area_from_commit(repo, &commit_object);
-    rewrite_index(repo, &staging_area);
+fn parse_binary_index(content: &[u8]) -> Result<Vec<StagingEntry>, MinigitError> {
+    if content.len() < HEADER_LEN + CHECKSUM_LEN {
+        return Err(MinigitError::CorruptIndex("Arquivo de índice truncado".to_string()));
+    }
+
+    let (data, checksum) = content.split_at(content.len() - CHECKSUM_LEN);
+    if Sha1::digest(data).as_slice() != checksum {
+        return Err(MinigitError::CorruptIndex("Checksum do índice não confere".to_string()));
+    }
+
+    let version = u32::from_be_bytes(data[4..8].try_into().unwrap_or_default());
+    if version != INDEX_VERSION {
+        return Err(MinigitError::CorruptIndex(format!("Versão do índice não suportada: {}", version)));
+    }
+
+    let entry_count = u32::from_be_bytes(data[8..12].try_into().unwrap_or_default()) as usize;
+    let mut entries = Vec::with_capacity(entry_count);
+    let mut offset = HEADER_LEN;
+
+    for _ in 0..entry_count {
+        let (entry, len) = StagingEntry::from_bytes(&data[offset..])?;
+        entries.push(entry);
+        offset += len;
+    }
+
+    if offset != data.len() {
+        return Err(MinigitError::CorruptIndex("Dados inesperados após as entradas do índice".to_string()));
+    }
+
+    Ok(entries)
 }
 
-pub fn rewrite_index(repo: &mut Repository, staging_area: &StagingArea) {
-    let index_file_path = repo.minigitdir.join(Repository::INDEX);
-    std::fs::write(&index_file_path, staging_area.serialize()).unwrap();
+fn parse_text_index(content: &[u8]) -> Result<Vec<StagingEntry>, MinigitError> {
+    let content = String::from_utf8(content.to_vec())
+        .map_err(|_| MinigitError::CorruptIndex("Índice não é UTF8 válido".to_string()))?;
+
+    content.lines()
+        .map(|line| StagingEntry::from_string(line.to_string()))
+        .collect()
 }
 
-fn parse_to<T: FromStr>(s: &str) -> T 
-    where
-        <T as FromStr>::Err: Debug
-{
-    str::parse::<T>(s).expect("Deveria ser possível o parsing")
+pub fn rewrite_index_from_commit_id(repo: &mut Repository, commit_id: &String) -> Result<(), MinigitError> {
+    let commit_object = repo.get_commit(commit_id)?;
+
+    let staging_area = staging_area_from_commit(repo, &commit_object)?;
+    rewrite_index(repo, &staging_area)
 }
 
-pub fn staging_area_from_commit(repository: &Repository, commit: &CommitObject) -> StagingArea {
-    let tree_hash = &commit.tree;
-    let RGitObjectTypes::Tree(tree_object) = repository
-        .get_object(tree_hash)
-        .unwrap()
-        else { panic!("Commit aponta para árvore inválida"); };
-
-    let tree_files = tree::get_tree_as_map(repository, &tree_object);
-    let relative_file_paths: Vec<String> = tree_files.keys().cloned().collect();
-
-    StagingArea {
-        entries: relative_file_paths.iter().map(|path| {
-            let full_path = repository.worktree.join(path);
-            let object_hash = tree_files.get(path).unwrap().clone();
-            let relative_path = full_path.strip_prefix(&repository.worktree).unwrap();
-
-            StagingEntry {
-                last_content_change: commit.timestamp,
-                mode_type: 0o100644, // arquivo normal
-                object_hash: object_hash.to_string(),
-                path: relative_path.to_path_buf(),
-            }
-        }).collect()
+/// Registra os arquivos em conflito no índice com as versões base, nossa e deles
+///
+/// ## Argumentos
+/// - `conflicts` - Caminhos dos arquivos em conflito
+/// - `base`, `ours`, `theirs` - Mapas de caminho -> hash do blob de cada versão
+pub fn record_conflicts(
+    repo: &mut Repository,
+    conflicts: &HashSet<String>,
+    base: &HashMap<String, String>,
+    ours: &HashMap<String, String>,
+    theirs: &HashMap<String, String>,
+) -> Result<(), MinigitError> {
+    let mut lock = LockFile::acquire(&repo.index_path)?;
+    let mut staging_area = StagingArea::new(repo)?;
+
+    for path in conflicts {
+        staging_area.add_conflict(Path::new(path), base.get(path), ours.get(path), theirs.get(path));
     }
-}
\ No newline at end of file
+
+    lock.write_all(&staging_area.serialize()?)?;
+    lock.commit()
+}
+
+pub fn rewrite_index(repo: &mut Repository, staging_area: &StagingArea) -> Result<(), MinigitError> {
+    let index_file_path = repo.minigitdir.join(Repository::INDEX);
+    write_locked(&index_file_path, staging_area.serialize()?)
+}
+
+fn parse_to<T: FromStr>(s: &str) -> Result<T, MinigitError> {
+    str::parse::<T>(s).map_err(|_| MinigitError::CorruptIndex(format!("Valor inválido '{}'", s)))
+}
+
+pub fn staging_area_from_commit(repository: &Repository, commit: &CommitObject) -> Result<StagingArea, MinigitError> {
+    let tree_object = repository.get_tree(&commit.tree)?;
+
+    let tree_files = tree::get_tree_files(repository, &tree_object)?;
+
+    Ok(StagingArea::from_entries(
+        tree_files.into_iter()
+            .map(|(path, file)| StagingEntry::from_tree_file(&file, PathBuf::from(path)))
+            .collect()
+    ))
+}
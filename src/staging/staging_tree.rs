@@ -1,58 +1,54 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use crate::{Repository, staging::StagingArea};
+use crate::{Repository, error::MinigitError, staging::StagingArea};
 
 pub enum StagingTree {
-    Blob(String),
+    /// Hash e modo do blob
+    Blob(String, u32),
     Fork(HashMap<String, Box<StagingTree>>)
 }
 
 impl StagingTree {
-    pub fn insert(&mut self, blob_id: String, path: PathBuf) {
+    pub fn insert(&mut self, blob_id: String, mode: u32, path: PathBuf) {
         let mut components = path.components();
 
         if let Some(root) = components.next() {
             let next = components.next();
-            let root_str = root.as_os_str().to_str().unwrap().to_string();
+            let root_str = root.as_os_str().to_string_lossy().to_string();
 
             match next {
                 None => {
                     match self {
-                        StagingTree::Blob(_) => {}
+                        StagingTree::Blob(..) => {}
                         StagingTree::Fork(children) => {
-                            let child_name = root.as_os_str().to_str().unwrap().to_string();
-                            children.insert(child_name, Box::new(StagingTree::Blob(blob_id)));
+                            children.insert(root_str, Box::new(StagingTree::Blob(blob_id, mode)));
                         }
                     }
                 },
                 Some(_) => {
                     match self {
-                        StagingTree::Blob(_) => {},
+                        StagingTree::Blob(..) => {},
                         StagingTree::Fork(children) => {
-                            let mut new_tree = StagingTree::Fork(HashMap::new());
-                            let path_without_root = path.strip_prefix(root.as_os_str()).unwrap().to_path_buf();
-                            new_tree.insert(blob_id, path_without_root);
-
-                            children.insert(root_str, Box::new(new_tree));
+                            // Reaproveita a pasta se ela já tiver outros arquivos
+                            let subtree = children.entry(root_str)
+                                .or_insert_with(|| Box::new(StagingTree::Fork(HashMap::new())));
+                            let path_without_root: PathBuf = path.components().skip(1).collect();
+                            subtree.insert(blob_id, mode, path_without_root);
                         }
                     }
                 }
             }
-
-            return;
         }
-
-        unreachable!();
     }
 }
 
-pub fn instantiate_staging_tree_from_index(repo: &mut Repository) -> StagingTree {
-    let staging_area = StagingArea::new(repo);
+pub fn instantiate_staging_tree_from_index(repo: &mut Repository) -> Result<StagingTree, MinigitError> {
+    let staging_area = StagingArea::new(repo)?;
     let mut staging_tree = StagingTree::Fork(HashMap::new());
 
     for entry in staging_area.entries {
-        staging_tree.insert(entry.object_hash, entry.path);
+        staging_tree.insert(entry.object_hash, entry.mode_type, entry.path);
     }
 
-    staging_tree
+    Ok(staging_tree)
 }
\ No newline at end of file
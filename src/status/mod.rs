@@ -1,93 +1,308 @@
-use std::{collections::HashMap, fs, path::PathBuf};
+use std::{collections::HashMap, path::{Path, PathBuf}};
 
+use serde::Serialize;
 use walkdir::WalkDir;
 
-use crate::{Repository, config::RGitIgnore, objects::{CommitObject, RGitObjectTypes}, staging::{StagingArea, StagingEntry}};
+use crate::{Repository, config::RGitIgnore, diff::{RenameKind, detect_renames, diff_trees_exact}, error::MinigitError, objects::get_commit_tree_as_map, staging::{STAGE_NORMAL, StagingArea}, utils::{is_worktree_file, merge_rebase::{self, OperationState}}};
 
 /// Retorna uma lista de arquivos não adicionados (modificados ou novos) no repositório
-pub fn non_staged_files(repo: &Repository) -> Vec<PathBuf> {
-    let ignore = RGitIgnore::new(repo);
-    let staging_area = StagingArea::new(repo);
-    let staging_area_entries_map = staging_area.get_entries_as_map();
+pub fn non_staged_files(repo: &Repository) -> Result<Vec<PathBuf>, MinigitError> {
+    let ignore = RGitIgnore::new(repo)?;
+    let staging_area = StagingArea::new(repo)?;
 
-    let all_files = WalkDir::new(&repo.worktree)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file())
-        .map(|e| get_relative_path(repo, &e.path().to_path_buf()))
-        .filter(|path| is_not_ignored(&ignore, path))
-        .collect::<Vec<PathBuf>>();
+    let all_files = worktree_files(repo, &ignore);
 
-    all_files.iter()
-        .filter(|path| is_non_staged(repo, &staging_area_entries_map, *path))
-        .cloned()
-        .collect()
+    let mut result = Vec::new();
+    for path in all_files {
+        if is_non_staged(repo, &staging_area, &path)? {
+            result.push(path);
+        }
+    }
+    Ok(result)
 }
 
+/// Lista os arquivos rastreados da worktree com mudanças em relação ao HEAD, no índice ou na worktree
+///
+/// Arquivos não rastreados só entram na lista se o repositório ainda não tiver commits.
+pub fn get_uncommited_files(repo: &Repository) -> Result<Vec<PathBuf>, MinigitError> {
+    let ignore = RGitIgnore::new(repo)?;
+    let staging_area = StagingArea::new(repo)?;
+    let last_commit_hash = repo.resolve_head()?;
 
-pub fn get_uncommited_files(repo: &Repository) -> Vec<PathBuf> {
-    let ignore = RGitIgnore::new(repo);
-    let staging_area = StagingArea::new(repo);
-    let staging_area_entries_map = staging_area.get_entries_as_map();
-    let last_commit_hash = repo.resolve_head();
-
-    let all_files = WalkDir::new(&repo.worktree)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().is_file())
-        .map(|e| get_relative_path(repo, &e.path().to_path_buf()))
-        .filter(|path| is_not_ignored(&ignore, path))
-        .collect::<Vec<PathBuf>>();
+    let all_files = worktree_files(repo, &ignore);
 
     if last_commit_hash.is_empty() {
-        return all_files;
+        return Ok(all_files);
     }
 
-    let RGitObjectTypes::Commit(commit_object) = repo.get_object(&last_commit_hash).unwrap()
-        else { panic!("Não é commit") };
+    let commit_object = repo.get_commit(&last_commit_hash)?;
+    let commit_files = get_commit_tree_as_map(repo, &commit_object)?;
+
+    let mut result = Vec::new();
+    for path in all_files {
+        if is_uncommited(repo, &staging_area, &path, &commit_files)? {
+            result.push(path);
+        }
+    }
+    Ok(result)
+}
 
-    all_files.iter()
-        .filter(|path| is_uncommited(repo, &staging_area_entries_map, *path, &commit_object))
-        .cloned()
-        .collect()
+/// Lista os arquivos da worktree que não são ignorados, relativos à raíz do repositório
+pub fn worktree_files(repo: &Repository, ignore: &RGitIgnore) -> Vec<PathBuf> {
+    WalkDir::new(&repo.worktree)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| !e.file_type().is_dir())
+        .filter_map(|e| get_relative_path(repo, e.path()))
+        .filter(|path| is_not_ignored(ignore, path))
+        .collect::<Vec<PathBuf>>()
 }
 
-fn get_relative_path(repo: &Repository, full_path: &PathBuf) -> PathBuf {
-    full_path.strip_prefix(&repo.worktree).unwrap().to_path_buf()
+fn get_relative_path(repo: &Repository, full_path: &Path) -> Option<PathBuf> {
+    full_path.strip_prefix(&repo.worktree).ok().map(|p| p.to_path_buf())
 }
 
-fn is_not_ignored(ignore: &RGitIgnore, relative_path: &PathBuf) -> bool {
+fn is_not_ignored(ignore: &RGitIgnore, relative_path: &Path) -> bool {
     !ignore.check_ignore(relative_path)
 }
 
-fn is_non_staged(repo: &Repository, staging_area_entries_map: &HashMap<PathBuf, StagingEntry>,  relative_path: &PathBuf) -> bool {
-    let entry_opt = staging_area_entries_map.get(relative_path);
-    
-    match entry_opt {
-        None => true,
-        Some(entry) => {
-            let RGitObjectTypes::Blob(blob) = repo.get_object(&entry.object_hash).unwrap()
-                else { panic!("Objeto do staging area não é um blob"); };
-            blob.content != fs::read(repo.worktree.join(relative_path)).unwrap()
-        },
+fn is_non_staged(repo: &Repository, staging_area: &StagingArea, relative_path: &Path) -> Result<bool, MinigitError> {
+    match staging_area.find_entry(relative_path) {
+        None => Ok(true),
+        Some(entry) => staging_area.is_modified(repo, entry),
     }
 }
 
 fn is_uncommited(
-    repo: &Repository, 
-    staging_area_entries_map: &HashMap<PathBuf, StagingEntry>,  
-    relative_path: &PathBuf,
-    last_commit: &CommitObject
-) -> bool {
-    let entry_opt: Option<&StagingEntry> = staging_area_entries_map.get(relative_path);
-
-    match entry_opt {
-        None => true,
+    repo: &Repository,
+    staging_area: &StagingArea,
+    relative_path: &Path,
+    commit_files: &HashMap<String, String>,
+) -> Result<bool, MinigitError> {
+    match staging_area.find_entry(relative_path) {
+        // Fora do índice, só é uma mudança se o arquivo estiver no HEAD (removido do índice)
+        None => Ok(commit_files.contains_key(relative_path.to_string_lossy().as_ref())),
         Some(entry) => {
-            let last_staged = entry.last_content_change.clone();
+            let committed_hash = commit_files.get(relative_path.to_string_lossy().as_ref());
 
-            is_non_staged(repo, staging_area_entries_map, relative_path) ||
-            last_staged > last_commit.timestamp
+            Ok(committed_hash != Some(&entry.object_hash) ||
+            staging_area.is_modified(repo, entry)?)
         },
     }
-}
\ No newline at end of file
+}
+
+/// Tipo de mudança de um arquivo no relatório de status
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChange {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+    Copied,
+}
+
+impl FileChange {
+    /// Descrição usada pelo comando `status`
+    pub fn description(&self) -> &'static str {
+        match self {
+            FileChange::Added => "novo arquivo",
+            FileChange::Modified => "modificado",
+            FileChange::Deleted => "removido",
+            FileChange::Renamed => "renomeado",
+            FileChange::Copied => "copiado",
+        }
+    }
+
+    /// Letra usada no formato `--porcelain` do comando `status`
+    pub fn porcelain_code(&self) -> char {
+        match self {
+            FileChange::Added => 'A',
+            FileChange::Modified => 'M',
+            FileChange::Deleted => 'D',
+            FileChange::Renamed => 'R',
+            FileChange::Copied => 'C',
+        }
+    }
+}
+
+/// Arquivo com mudanças, relativo à raíz do repositório
+///
+/// Arquivos renomeados ou copiados têm em `source` o caminho do arquivo de origem.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct FileStatus {
+    pub path: PathBuf,
+    pub change: FileChange,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<PathBuf>,
+}
+
+impl FileStatus {
+    fn new(path: PathBuf, change: FileChange) -> Self {
+        FileStatus { path, change, source: None }
+    }
+
+    /// Caminho exibido para o usuário: `origem -> destino` para arquivos renomeados ou copiados
+    pub fn display_path(&self) -> String {
+        match &self.source {
+            Some(source) => format!("{} -> {}", source.display(), self.path.display()),
+            None => self.path.display().to_string(),
+        }
+    }
+}
+
+/// Estado da worktree e do índice em relação ao HEAD
+///
+/// ## Atributos
+/// - `branch` - Nome da branch atual, `None` se o HEAD estiver destacado
+/// - `head` - Hash do commit apontado pelo HEAD, vazio se ainda não houver commits
+/// - `staged` - Mudanças entre o HEAD e o índice
+/// - `unstaged` - Mudanças entre o índice e a worktree
+/// - `untracked` - Arquivos da worktree que não estão no índice nem são ignorados
+/// - `unmerged` - Arquivos com conflitos de merge ainda não resolvidos
+/// - `operation` - Merge ou rebase em andamento, se houver
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct StatusReport {
+    pub branch: Option<String>,
+    pub head: String,
+    pub staged: Vec<FileStatus>,
+    pub unstaged: Vec<FileStatus>,
+    pub untracked: Vec<PathBuf>,
+    pub unmerged: Vec<PathBuf>,
+    pub operation: Option<OperationState>,
+}
+
+impl StatusReport {
+    /// Verifica se não há nenhuma mudança a ser commitada nem arquivos não rastreados
+    pub fn is_clean(&self) -> bool {
+        self.staged.is_empty() && self.unstaged.is_empty() && self.untracked.is_empty() && self.unmerged.is_empty()
+    }
+}
+
+/// Calcula o status do repositório
+pub fn status(repo: &Repository) -> Result<StatusReport, MinigitError> {
+    let staging_area = StagingArea::new(repo)?;
+    let ignore = RGitIgnore::new(repo)?;
+    let head = repo.resolve_head()?;
+
+    let branch = if repo.head_detached()? {
+        None
+    } else {
+        let head_ref = repo.get_head()?;
+        Some(head_ref.strip_prefix("refs/heads/").unwrap_or(&head_ref).to_string())
+    };
+
+    let head_files = if head.is_empty() {
+        HashMap::new()
+    } else {
+        get_commit_tree_as_map(repo, &repo.get_commit(&head)?)?
+    };
+
+    let unmerged = staging_area.conflicted_paths();
+    let staged = staged_changes(repo, &staging_area, &head_files, &unmerged)?;
+    let unstaged = unstaged_changes(repo, &staging_area, &ignore, &head_files)?;
+    let untracked = untracked_files(repo, &staging_area, &ignore, &unmerged);
+    let operation = merge_rebase::load(repo)?;
+
+    Ok(StatusReport { branch, head, staged, unstaged, untracked, unmerged, operation })
+}
+
+/// Compara o HEAD com o índice, detectando arquivos renomeados e copiados
+///
+/// Só os arquivos adicionados e removidos são comparados linha a linha, então cópias só são
+/// detectadas quando o conteúdo é idêntico ao de um arquivo do HEAD.
+fn staged_changes(
+    repo: &Repository,
+    staging_area: &StagingArea,
+    head_files: &HashMap<String, String>,
+    unmerged: &[PathBuf],
+) -> Result<Vec<FileStatus>, MinigitError> {
+    let index_files: HashMap<String, String> = staging_area.entries.iter()
+        .filter(|entry| entry.stage == STAGE_NORMAL)
+        .map(|entry| (entry.path.to_string_lossy().to_string(), entry.object_hash.clone()))
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut added = HashMap::new();
+    for (path, hash) in &index_files {
+        match head_files.get(path) {
+            Some(head_hash) if head_hash != hash => changes.push(FileStatus::new(PathBuf::from(path), FileChange::Modified)),
+            None => { added.insert(path.clone(), hash.clone()); }
+            _ => {}
+        }
+    }
+
+    let removed: HashMap<String, String> = head_files.iter()
+        .filter(|(path, _)| !index_files.contains_key(*path) && !unmerged.contains(&PathBuf::from(path)))
+        .map(|(path, hash)| (path.clone(), hash.clone()))
+        .collect();
+
+    let mut removals = Vec::new();
+    for file_diff in detect_renames(diff_trees_exact(repo, &removed, &added)?, head_files) {
+        let change = match (&file_diff.source, &file_diff.new_hash) {
+            (Some(source), _) if source.kind == RenameKind::Renamed => FileChange::Renamed,
+            (Some(_), _) => FileChange::Copied,
+            (None, None) => FileChange::Deleted,
+            (None, Some(_)) => FileChange::Added,
+        };
+
+        let status = FileStatus { path: file_diff.path, change, source: file_diff.source.map(|source| source.path) };
+        if change == FileChange::Deleted {
+            removals.push(status);
+        } else {
+            changes.push(status);
+        }
+    }
+
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    changes.extend(removals);
+    Ok(changes)
+}
+
+/// Compara o índice com a worktree
+fn unstaged_changes(
+    repo: &Repository,
+    staging_area: &StagingArea,
+    ignore: &RGitIgnore,
+    head_files: &HashMap<String, String>,
+) -> Result<Vec<FileStatus>, MinigitError> {
+    let mut changes = Vec::new();
+
+    for entry in staging_area.entries.iter().filter(|entry| entry.stage == STAGE_NORMAL) {
+        let is_ignored = ignore.check_ignore(&entry.path);
+        let was_in_head = head_files.contains_key(entry.path.to_string_lossy().as_ref());
+
+        if is_ignored {
+            if was_in_head {
+                changes.push(FileStatus::new(entry.path.clone(), FileChange::Deleted));
+            }
+            continue;
+        }
+
+        let absolute_path = repo.worktree.join(&entry.path);
+
+        if !is_worktree_file(&absolute_path) {
+            changes.push(FileStatus::new(entry.path.clone(), FileChange::Deleted));
+            continue;
+        }
+
+        if staging_area.is_modified(repo, entry)? {
+            changes.push(FileStatus::new(entry.path.clone(), FileChange::Modified));
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Lista os arquivos da worktree que não estão no índice
+fn untracked_files(repo: &Repository, staging_area: &StagingArea, ignore: &RGitIgnore, unmerged: &[PathBuf]) -> Vec<PathBuf> {
+    let staged_paths = staging_area.get_entries_as_map();
+
+    let mut untracked: Vec<PathBuf> = worktree_files(repo, ignore)
+        .into_iter()
+        .filter(|path| !staged_paths.contains_key(path) && !unmerged.contains(path))
+        .collect();
+    untracked.sort();
+
+    untracked
+}
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, TimeZone};
+
+use crate::error::MinigitError;
+
+const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+/// Converte o timestamp de um commit (nanossegundos desde a época Unix) para a data local
+pub fn commit_datetime(timestamp: u128) -> DateTime<Local> {
+    Local.timestamp_opt((timestamp / NANOS_PER_SECOND) as i64, 0)
+        .single()
+        .unwrap_or_default()
+}
+
+/// Formata o timestamp de um commit no formato usado pelo `log` (dd/mm/aaaa hh:mm:ss)
+pub fn format_commit_date(timestamp: u128) -> String {
+    commit_datetime(timestamp).format("%d/%m/%Y %H:%M:%S").to_string()
+}
+
+/// Interpreta uma data fornecida pelo usuário e retorna o timestamp em nanossegundos
+///
+/// Formatos aceitos:
+/// - `aaaa-mm-dd`, `aaaa-mm-dd hh:mm` e `aaaa-mm-dd hh:mm:ss` (horário local)
+/// - `<n> <unidade> ago`, onde a unidade é second, minute, hour, day, week, month ou year (no singular ou plural)
+/// - `now`, `today` e `yesterday`
+pub fn parse_date(value: &str) -> Result<u128, MinigitError> {
+    let value = value.trim();
+    let invalid = || MinigitError::InvalidArgument(format!("Data inválida: '{}'", value));
+
+    let datetime = if let Some(datetime) = parse_relative(value) {
+        datetime
+    } else if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        let naive = date.and_hms_opt(0, 0, 0).ok_or_else(invalid)?;
+        Local.from_local_datetime(&naive).earliest().ok_or_else(invalid)?
+    } else {
+        let naive = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+            .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M"))
+            .map_err(|_| invalid())?;
+        Local.from_local_datetime(&naive).earliest().ok_or_else(invalid)?
+    };
+
+    let seconds = u128::try_from(datetime.timestamp()).map_err(|_| invalid())?;
+    Ok(seconds * NANOS_PER_SECOND)
+}
+
+fn parse_relative(value: &str) -> Option<DateTime<Local>> {
+    let now = Local::now();
+
+    match value {
+        "now" => return Some(now),
+        "today" => return Local.from_local_datetime(&now.date_naive().and_hms_opt(0, 0, 0)?).earliest(),
+        "yesterday" => return Some(now - Duration::days(1)),
+        _ => {}
+    }
+
+    let parts: Vec<&str> = value.split_whitespace().collect();
+    let [amount, unit, "ago"] = parts.as_slice() else {
+        return None;
+    };
+
+    let amount: i64 = amount.parse().ok()?;
+    let duration = match unit.trim_end_matches('s') {
+        "second" => Duration::seconds(amount),
+        "minute" => Duration::minutes(amount),
+        "hour" => Duration::hours(amount),
+        "day" => Duration::days(amount),
+        "week" => Duration::weeks(amount),
+        "month" => Duration::days(amount * 30),
+        "year" => Duration::days(amount * 365),
+        _ => return None,
+    };
+
+    Some(now - duration)
+}
@@ -1,44 +1,145 @@
-use std::{fs::{self, File}, io::{Write}, path::PathBuf};
+use std::{fs::{self, File}, io::{Write}, path::{Path, PathBuf}};
 
-pub fn create_file(path: &PathBuf, content: &Vec<u8>) {
-    let mut file = File::create(path).expect("Deveria criar o arquivo.");
-    file.write(&content).expect("Deveria escrever no arquivo.");
+use crate::{Repository, error::MinigitError, objects::{MODE_EXECUTABLE, MODE_SYMLINK}};
+
+pub fn create_file(path: &Path, content: &[u8]) -> Result<(), MinigitError> {
+    let mut file = File::create(path)?;
+    file.write_all(content)?;
+    Ok(())
 }
 
-pub fn create_dir(path: &PathBuf) {
+pub fn create_dir(path: &PathBuf) -> Result<(), MinigitError> {
     if path.exists() {
         if path.is_dir() {
-            return;
+            return Ok(());
         } else {
-            fs::remove_file(path).expect("Deveria remover o arquivo");
+            fs::remove_file(path)?;
+        }
+    }
+    fs::create_dir(path)?;
+    Ok(())
+}
+
+/// Escreve o arquivo (relativo à raíz do repositório) na worktree, criando as pastas que faltarem
+///
+/// O modo (`MODE_FILE`, `MODE_EXECUTABLE` ou `MODE_SYMLINK`) é aplicado como em `write_file_with_mode`.
+pub fn write_worktree_file(repo: &Repository, path: &Path, content: &[u8], mode: u32) -> Result<(), MinigitError> {
+    let absolute_path = repo.worktree.join(path);
+    if let Some(parent) = absolute_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    write_file_with_mode(&absolute_path, content, mode, repo.config.get_filemode())
+}
+
+/// Escreve o arquivo com o modo fornecido, substituindo o que existir no caminho
+///
+/// Para `MODE_SYMLINK`, `content` é o destino do link. Com `filemode` desligado, as permissões
+/// do arquivo não são alteradas.
+pub fn write_file_with_mode(absolute_path: &Path, content: &[u8], mode: u32, filemode: bool) -> Result<(), MinigitError> {
+    if absolute_path.is_symlink() {
+        fs::remove_file(absolute_path)?;
+    }
+
+    if mode == MODE_SYMLINK {
+        if absolute_path.exists() {
+            fs::remove_file(absolute_path)?;
+        }
+        return create_symlink(&String::from_utf8_lossy(content), absolute_path);
+    }
+
+    fs::write(absolute_path, content)?;
+    if filemode {
+        set_executable(absolute_path, mode == MODE_EXECUTABLE)?;
+    }
+    Ok(())
+}
+
+/// Verifica se existe um arquivo ou link simbólico (mesmo quebrado) no caminho
+pub fn is_worktree_file(absolute_path: &Path) -> bool {
+    fs::symlink_metadata(absolute_path).is_ok_and(|metadata| !metadata.is_dir())
+}
+
+#[cfg(unix)]
+fn create_symlink(target: &str, absolute_path: &Path) -> Result<(), MinigitError> {
+    std::os::unix::fs::symlink(target, absolute_path)?;
+    Ok(())
+}
+
+/// Sem suporte a links simbólicos, o link é escrito como um arquivo com o destino
+#[cfg(not(unix))]
+fn create_symlink(target: &str, absolute_path: &Path) -> Result<(), MinigitError> {
+    fs::write(absolute_path, target)?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_executable(absolute_path: &Path, executable: bool) -> Result<(), MinigitError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut permissions = fs::metadata(absolute_path)?.permissions();
+    let mode = permissions.mode();
+    // O bit de execução segue o de leitura, como no git
+    let new_mode = if executable { mode | ((mode & 0o444) >> 2) } else { mode & !0o111 };
+
+    if new_mode != mode {
+        permissions.set_mode(new_mode);
+        fs::set_permissions(absolute_path, permissions)?;
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_absolute_path: &Path, _executable: bool) -> Result<(), MinigitError> {
+    Ok(())
+}
+
+/// Apaga o arquivo (relativo à raíz do repositório) da worktree e as pastas que ficarem vazias
+pub fn remove_worktree_file(repo: &Repository, path: &Path) -> Result<(), MinigitError> {
+    let absolute_path = repo.worktree.join(path);
+    if absolute_path.is_file() || absolute_path.is_symlink() {
+        fs::remove_file(&absolute_path)?;
+    }
+
+    let mut parent = absolute_path.parent();
+    while let Some(dir) = parent {
+        if dir == repo.worktree || fs::remove_dir(dir).is_err() {
+            break;
         }
+        parent = dir.parent();
     }
-    fs::create_dir(path).expect("Deveria criar o diretório");
+
+    Ok(())
 }
 
-pub fn get_current_dir() -> PathBuf {
-    std::env::current_dir().expect("Deveria obter o diretório atual")
+pub fn get_current_dir() -> Result<PathBuf, MinigitError> {
+    Ok(std::env::current_dir()?)
 }
 
 /// Lê um valor do conteúdo de um arquivo no formato `<chave> <valor>`, retornando o valor lido e o restante do conteúdo
 /// 
-/// Retorna a chave, o valor e o restante do conteúdo.
+/// Retorna a chave, o valor e o restante do conteúdo, ou None se o conteúdo não estiver no formato esperado.
 /// O valor pode conter múltiplas linhas, desde que cada linha subsequente comece com um espaço
-pub fn read_value(content: &str) -> (String, String, &str) {
-    let parts: Vec<&str> = content.splitn(2, ' ').collect();
-    let key = parts[0].to_string();
-    let mut remainder = parts[1];
+pub fn read_value(content: &str) -> Option<(String, String, &str)> {
+    let (key, mut remainder) = content.split_once(' ')?;
 
-    let new_line = remainder.find('\n').unwrap_or(remainder.len());
-    let mut value = remainder[..new_line].to_string();
-    remainder = &remainder[new_line+1..];
+    let (first_line, rest) = split_line(remainder);
+    let mut value = first_line.to_string();
+    remainder = rest;
 
-    while remainder.starts_with(' ') {
-        let new_line = remainder.find('\n').unwrap_or(remainder.len());
+    while let Some(continuation) = remainder.strip_prefix(' ') {
+        let (line, rest) = split_line(continuation);
         value.push('\n');
-        value.push_str(&remainder[..new_line]);
-        remainder = &remainder[new_line+1..];
+        value.push_str(line);
+        remainder = rest;
     }
 
-    (key, value, remainder)
-}
\ No newline at end of file
+    Some((key.to_string(), value, remainder))
+}
+
+/// Separa a primeira linha do restante do conteúdo, descartando a quebra de linha
+fn split_line(content: &str) -> (&str, &str) {
+    match content.find('\n') {
+        Some(new_line) => (&content[..new_line], &content[new_line+1..]),
+        None => (content, ""),
+    }
+}
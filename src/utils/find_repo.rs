@@ -1,9 +1,12 @@
-use std::path::PathBuf;
+use std::path::Path;
 
-use crate::Repository;
+use crate::{Repository, error::MinigitError};
 
-pub fn find_repo(current_path: &PathBuf) -> Option<Repository> {
-    let mut workdir = current_path.clone();
+/// Procura um repositório minigit no caminho fornecido ou em algum dos seus ancestrais
+/// 
+/// Retorna `MinigitError::NotARepository` se nenhum repositório for encontrado.
+pub fn find_repo(current_path: &Path) -> Result<Repository, MinigitError> {
+    let mut workdir = current_path.to_path_buf();
     while !has_repository(&workdir) {
         if !workdir.pop() {
             break;
@@ -11,17 +14,17 @@ pub fn find_repo(current_path: &PathBuf) -> Option<Repository> {
     }
 
     if let Some(_parent) = workdir.parent() {
-        Some(Repository::new(&workdir))
+        Repository::new(&workdir)
     } else {
-        None
+        Err(MinigitError::NotARepository)
     }
 }
 
-pub fn find_current_repo() -> Option<Repository> {
-    let current_path = std::env::current_dir().unwrap();
+pub fn find_current_repo() -> Result<Repository, MinigitError> {
+    let current_path = std::env::current_dir()?;
     find_repo(&current_path)
 }
 
-fn has_repository(path: &PathBuf) -> bool {
+fn has_repository(path: &Path) -> bool {
     path.join(Repository::MINIGITDIR).exists()
-}
\ No newline at end of file
+}
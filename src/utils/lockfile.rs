@@ -0,0 +1,87 @@
+use std::{fs::{File, OpenOptions}, io::{ErrorKind, Write}, path::{Path, PathBuf}};
+
+use crate::error::MinigitError;
+
+/// Arquivo `<caminho>.lock` usado para atualizar um arquivo do repositório de forma atômica
+///
+/// O lock é criado com `O_EXCL`, então só um processo consegue obtê-lo por vez. O novo conteúdo é
+/// escrito no lock e [`LockFile::commit`] o renomeia por cima do arquivo original: quem lê o arquivo
+/// vê o conteúdo antigo ou o novo, nunca um arquivo pela metade. Se o lock for descartado sem
+/// `commit` (erro, panic), ele é apagado e o arquivo original continua intacto.
+#[derive(Debug)]
+pub struct LockFile {
+    path: PathBuf,
+    lock_path: PathBuf,
+    file: File,
+    committed: bool,
+}
+
+impl LockFile {
+    /// Cria o lock do arquivo `path`
+    ///
+    /// Retorna `MinigitError::Locked` se o lock já existir, ou seja, se outro processo estiver
+    /// atualizando o mesmo arquivo.
+    pub fn acquire(path: &Path) -> Result<LockFile, MinigitError> {
+        let mut lock_name = path.file_name().unwrap_or_default().to_os_string();
+        lock_name.push(".lock");
+        let lock_path = path.with_file_name(lock_name);
+
+        let file = match OpenOptions::new().write(true).create_new(true).open(&lock_path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == ErrorKind::AlreadyExists => return Err(MinigitError::Locked(lock_path)),
+            Err(err) => return Err(err.into()),
+        };
+
+        Ok(LockFile { path: path.to_path_buf(), lock_path, file, committed: false })
+    }
+
+    /// Conteúdo atual do arquivo protegido pelo lock, sem espaços nas pontas, ou `None` se ele não existir
+    pub fn read_current(&self) -> Result<Option<String>, MinigitError> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(content) => Ok(Some(content.trim().to_string())),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Escreve no lock. O arquivo original só é alterado no `commit`
+    pub fn write_all(&mut self, content: &[u8]) -> Result<(), MinigitError> {
+        self.file.write_all(content)?;
+        Ok(())
+    }
+
+    /// Grava o conteúdo escrito no disco e renomeia o lock por cima do arquivo original
+    pub fn commit(mut self) -> Result<(), MinigitError> {
+        self.file.sync_all()?;
+        std::fs::rename(&self.lock_path, &self.path)?;
+        self.committed = true;
+        Ok(())
+    }
+}
+
+/// Permite gravar no lock com `BufWriter` e afins. O arquivo original só é alterado no `commit`
+impl Write for LockFile {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.file.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        // Depois do commit, o lock já virou o arquivo original
+        if !self.committed {
+            let _ = std::fs::remove_file(&self.lock_path);
+        }
+    }
+}
+
+/// Substitui o conteúdo do arquivo de forma atômica, usando um [`LockFile`]
+pub fn write_locked(path: &Path, content: impl AsRef<[u8]>) -> Result<(), MinigitError> {
+    let mut lock = LockFile::acquire(path)?;
+    lock.write_all(content.as_ref())?;
+    lock.commit()
+}
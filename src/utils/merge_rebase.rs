@@ -1,96 +1,285 @@
-use core::panic;
-use std::fs;
+use std::{fs, io::ErrorKind, path::PathBuf};
 
-use crate::{Repository, commands::checkout, objects::RGitObjectTypes};
+use serde::Serialize;
 
-/// Verifica se há um merge ou rebase em progresso no repositório
-/// 
-/// ## Argumentos
-/// - is_rebase: bool - true para rebase, false para merge
-pub fn is_in_progress(repo: &Repository, is_rebase: bool) -> bool {
-    if is_rebase {
-        repo.rebase_head_path.exists()
-    } else {
-        repo.merge_head_path.exists()
+use crate::{Repository, commands::checkout, error::MinigitError, utils::{refs, write_locked}};
+
+/// Pasta do diário da operação em andamento, dentro de `.minigit`
+const STATE_DIR: &str = "sequencer";
+/// Arquivo escrito por último ao iniciar a operação: sem ele, o diário está incompleto e é descartado
+const KIND_FILE: &str = "kind";
+const HEAD_NAME_FILE: &str = "head-name";
+const ORIG_HEAD_FILE: &str = "orig-head";
+const ONTO_FILE: &str = "onto";
+const TODO_FILE: &str = "todo";
+const DONE_FILE: &str = "done";
+const STOPPED_SHA_FILE: &str = "stopped-sha";
+
+/// Operação de várias etapas que pode ser interrompida
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OperationKind {
+    Merge,
+    Rebase,
+}
+
+impl OperationKind {
+    /// Nome do comando que continua ou aborta a operação
+    pub fn command(&self) -> &'static str {
+        match self {
+            OperationKind::Merge => "merge",
+            OperationKind::Rebase => "rebase",
+        }
+    }
+
+    fn parse(name: &str) -> Option<OperationKind> {
+        match name {
+            "merge" => Some(OperationKind::Merge),
+            "rebase" => Some(OperationKind::Rebase),
+            _ => None,
+        }
     }
 }
 
-/// Aborta um merge ou rebase em progresso no repositório
-/// 
-/// ## Panics
-/// Entra em pânico se não houver um merge ou rebase em progresso. 
-/// Isso precisa ser verificado antes de chamar essa função.
-pub fn abort(repo: &mut Repository, is_rebase: bool) {
-    if !is_in_progress(repo, is_rebase) {
-        panic!("Não há um merge ou rebase em progresso para abortar.");
+/// Passo já aplicado: o commit original e o commit criado a partir dele
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct DoneStep {
+    pub original: String,
+    pub new: String,
+}
+
+/// Diário de um merge ou rebase em andamento, gravado em `.minigit/sequencer`
+///
+/// Cada campo é um arquivo da pasta, reescrito de forma atômica a cada passo. A branch só é movida
+/// depois que o passo está no diário, então, se o processo morrer no meio, `--continue` retoma do
+/// último passo registrado e `--abort` volta para `orig_head`.
+///
+/// ## Atributos
+/// - `kind` - Merge ou rebase
+/// - `head_name` - Referência da branch que está sendo alterada (ex: `refs/heads/master`)
+/// - `orig_head` - Commit da branch antes da operação, restaurado pelo `--abort`
+/// - `onto` - Commit sobre o qual os novos commits são criados: a nova base no rebase, `orig_head` no merge
+/// - `todo` - Commits que ainda faltam ser aplicados, em ordem (no merge, o commit sendo mergido)
+/// - `done` - Commits já aplicados, em ordem
+/// - `stopped_sha` - Commit em que a operação parou por conflitos, se tiver parado
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct OperationState {
+    pub kind: OperationKind,
+    pub head_name: String,
+    pub orig_head: String,
+    pub onto: String,
+    pub todo: Vec<String>,
+    pub done: Vec<DoneStep>,
+    pub stopped_sha: Option<String>,
+}
+
+impl OperationState {
+    /// Commit em que a branch deve estar: o último criado pela operação ou, antes do primeiro passo, `onto`
+    pub fn current(&self) -> &str {
+        self.done.last().map_or(&self.onto, |step| &step.new)
     }
 
-    let original_commit = fs::read_to_string(&repo.orig_head_path).unwrap();
-    let RGitObjectTypes::Commit(commit_object) = repo.get_object(&original_commit).unwrap()
-        else { panic!("Commit original inválido") };
+    /// Verifica se a operação parou por conflitos, em vez de ter sido interrompida no meio de um passo
+    pub fn is_stopped(&self) -> bool {
+        self.stopped_sha.is_some()
+    }
 
-    repo.update_branch_ref(&repo.get_head(), &original_commit);
+    /// Registra que o primeiro commit de `todo` foi aplicado como `new`
+    ///
+    /// `done` é gravado antes de `todo`: se o processo morrer entre os dois, [`load`] percebe que o
+    /// commit já foi aplicado e o remove de `todo`.
+    pub fn record_step(&mut self, repo: &Repository, new: &str) -> Result<(), MinigitError> {
+        let original = if self.todo.is_empty() { String::new() } else { self.todo.remove(0) };
+        self.done.push(DoneStep { original, new: new.to_string() });
 
-    repo.clear_worktree();
-    checkout::instanciate_commit(commit_object, repo);
+        write_state_file(repo, DONE_FILE, &format_done(&self.done))?;
+        write_state_file(repo, TODO_FILE, &format_lines(&self.todo))?;
+        self.clear_stop(repo)
+    }
 
-    finish(repo, is_rebase);
+    /// Registra que a operação parou por conflitos ao aplicar `commit`
+    pub fn record_stop(&mut self, repo: &Repository, commit: &str) -> Result<(), MinigitError> {
+        self.stopped_sha = Some(commit.to_string());
+        write_state_file(repo, STOPPED_SHA_FILE, commit)?;
+        if self.kind == OperationKind::Rebase {
+            write_locked(&repo.rebase_head_path, commit)?;
+        }
+        Ok(())
+    }
 
-    println!("{} abortado com sucesso.", if is_rebase { "Rebase" } else { "Merge" });
+    fn clear_stop(&mut self, repo: &Repository) -> Result<(), MinigitError> {
+        self.stopped_sha = None;
+        remove_if_exists(&state_dir(repo).join(STOPPED_SHA_FILE))?;
+        remove_if_exists(&repo.rebase_head_path)
+    }
 }
 
-/// Inicia um merge ou rebase, criando os arquivos de controle necessários
-/// 
-/// ## Panics
-/// Entra em pânico se já houver um merge ou rebase em progresso.
-/// Isso precisa ser verificado antes de chamar essa função.
-pub fn start(repo: &mut Repository, is_rebase: bool) {
-    if is_in_progress(repo, is_rebase) {
-        panic!("Já há um merge ou rebase em progresso.");
+fn state_dir(repo: &Repository) -> PathBuf {
+    repo.minigitdir.join(STATE_DIR)
+}
+
+fn write_state_file(repo: &Repository, name: &str, content: &str) -> Result<(), MinigitError> {
+    write_locked(&state_dir(repo).join(name), content)
+}
+
+fn read_state_file(repo: &Repository, name: &str) -> Result<Option<String>, MinigitError> {
+    match fs::read_to_string(state_dir(repo).join(name)) {
+        Ok(content) => Ok(Some(content.trim().to_string())),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
     }
+}
 
-    let head_path = if is_rebase {
-        &repo.rebase_head_path
-    } else {
-        &repo.merge_head_path
+fn remove_if_exists(path: &PathBuf) -> Result<(), MinigitError> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+fn format_lines(lines: &[String]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+fn format_done(done: &[DoneStep]) -> String {
+    done.iter().map(|step| format!("{} {}\n", step.original, step.new)).collect()
+}
+
+/// Lê o diário da operação em andamento, ou `None` se não houver nenhuma
+///
+/// Retorna `MinigitError::InvalidState` se o diário estiver mal formatado.
+pub fn load(repo: &Repository) -> Result<Option<OperationState>, MinigitError> {
+    let Some(kind) = read_state_file(repo, KIND_FILE)? else {
+        return Ok(None);
+    };
+    let corrupt = |detail: &str| MinigitError::InvalidState(format!(
+        "O estado da operação em andamento (.minigit/{}) está corrompido: {}", STATE_DIR, detail,
+    ));
+
+    let kind = OperationKind::parse(&kind).ok_or_else(|| corrupt(&format!("tipo de operação '{}' desconhecido", kind)))?;
+    let required = |name: &str| -> Result<String, MinigitError> {
+        read_state_file(repo, name)?.ok_or_else(|| corrupt(&format!("arquivo '{}' não encontrado", name)))
     };
-    fs::write(&repo.orig_head_path, &repo.resolve_head()).unwrap();
-    fs::write(head_path, "").unwrap();
+
+    let mut done = Vec::new();
+    for line in read_state_file(repo, DONE_FILE)?.unwrap_or_default().lines() {
+        let (original, new) = line.split_once(' ').ok_or_else(|| corrupt(&format!("linha '{}' inválida em '{}'", line, DONE_FILE)))?;
+        done.push(DoneStep { original: original.to_string(), new: new.to_string() });
+    }
+
+    let mut state = OperationState {
+        kind,
+        head_name: required(HEAD_NAME_FILE)?,
+        orig_head: required(ORIG_HEAD_FILE)?,
+        onto: required(ONTO_FILE)?,
+        todo: required(TODO_FILE)?.lines().map(String::from).collect(),
+        done,
+        stopped_sha: read_state_file(repo, STOPPED_SHA_FILE)?.filter(|sha| !sha.is_empty()),
+    };
+
+    // O processo morreu entre gravar `done` e `todo`: o passo já foi aplicado
+    if let Some(last) = state.done.last() && state.todo.first() == Some(&last.original) {
+        state.todo.remove(0);
+        state.stopped_sha = None;
+    }
+
+    Ok(Some(state))
+}
+
+/// Verifica se há um merge ou rebase em progresso no repositório
+pub fn is_in_progress(repo: &Repository, kind: OperationKind) -> bool {
+    read_state_file(repo, KIND_FILE).ok().flatten().and_then(|name| OperationKind::parse(&name)) == Some(kind)
+}
+
+/// Inicia um merge ou rebase, gravando o diário e o ORIG_HEAD
+///
+/// A branch ainda não é alterada: isso fica para [`sync_branch`] e os passos seguintes.
+/// Retorna `MinigitError::InvalidState` se já houver um merge ou rebase em progresso.
+pub fn start(repo: &mut Repository, kind: OperationKind, onto: &str, todo: Vec<String>) -> Result<OperationState, MinigitError> {
+    if let Some(state) = load(repo)? {
+        return Err(MinigitError::InvalidState(format!(
+            "Já há um {} em progresso. Use '{} --continue' ou '{} --abort' antes de continuar.",
+            state.kind.command(), state.kind.command(), state.kind.command(),
+        )));
+    }
+
+    // Sem o arquivo `kind`, a pasta é de um início que não terminou: a branch não foi alterada
+    let dir = state_dir(repo);
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    fs::create_dir_all(&dir)?;
+
+    let state = OperationState {
+        kind,
+        head_name: repo.get_head()?,
+        orig_head: repo.resolve_head()?,
+        onto: onto.to_string(),
+        todo,
+        done: Vec::new(),
+        stopped_sha: None,
+    };
+
+    write_locked(&repo.orig_head_path, &state.orig_head)?;
+    write_state_file(repo, HEAD_NAME_FILE, &state.head_name)?;
+    write_state_file(repo, ORIG_HEAD_FILE, &state.orig_head)?;
+    write_state_file(repo, ONTO_FILE, &state.onto)?;
+    write_state_file(repo, TODO_FILE, &format_lines(&state.todo))?;
+    write_state_file(repo, DONE_FILE, "")?;
+    write_state_file(repo, KIND_FILE, kind.command())?;
+
+    Ok(state)
+}
+
+/// Garante que o HEAD aponta para a branch da operação e que a branch está no commit atual do diário
+///
+/// Usado ao retomar uma operação interrompida, quando o processo pode ter morrido antes de mover a branch.
+pub fn sync_branch(repo: &mut Repository, state: &OperationState) -> Result<(), MinigitError> {
+    if repo.get_head()? != state.head_name {
+        write_locked(&repo.head_path, format!("ref: {}", state.head_name))?;
+    }
+
+    if refs::resolve_ref_path(&state.head_name, repo)?.as_deref() != Some(state.current()) {
+        repo.update_branch_ref(&state.head_name, state.current())?;
+    }
+
+    Ok(())
 }
 
-/// Finaliza um merge ou rebase em progresso, removendo os arquivos de controle
+/// Aborta o merge ou rebase em progresso, voltando a branch, o índice e a worktree para o ORIG_HEAD
+///
+/// Retorna o estado da operação abortada, ou `MinigitError::InvalidState` se não houver um merge ou
+/// rebase desse tipo em progresso.
+pub fn abort(repo: &mut Repository, kind: OperationKind) -> Result<OperationState, MinigitError> {
+    let state = load(repo)?
+        .filter(|state| state.kind == kind)
+        .ok_or_else(|| MinigitError::InvalidState(format!("Não há um {} em progresso para abortar.", kind.command())))?;
+
+    repo.get_commit(&state.orig_head)?;
+
+    if repo.get_head()? != state.head_name {
+        write_locked(&repo.head_path, format!("ref: {}", state.head_name))?;
+    }
+    repo.update_branch_ref(&state.head_name, &state.orig_head)?;
+    checkout::update_worktree(repo, state.current(), &state.orig_head, true)?;
+
+    finish(repo)?;
+
+    Ok(state)
+}
+
+/// Finaliza o merge ou rebase em progresso, removendo o diário e os arquivos de controle
 /// Nada acontece se não houver um merge ou rebase em progresso.
-pub fn finish(repo: &mut Repository, is_rebase: bool) {
-    if is_in_progress(repo, is_rebase) {
-        if is_rebase {
-            fs::remove_file(&repo.rebase_head_path).unwrap();
-        } else {
-            fs::remove_file(&repo.merge_head_path).unwrap();
-        }
-        fs::remove_file(&repo.orig_head_path).unwrap();
-    }
-}
-
-/// Retorna os commits que ainda faltam ser mergidos em um merge ou rebase em progresso.
-/// 
-/// ## Panics
-/// Entra em pânico se não houver um merge ou rebase em progresso.
-/// Isso precisa ser verificado antes de chamar essa função.
-pub fn get_state(repo: &Repository, is_rebase: bool) -> Vec<String> {
-    if is_in_progress(repo, is_rebase) {
-        let head_path = if is_rebase {
-            &repo.rebase_head_path
-        } else {
-            &repo.merge_head_path
-        };
-
-        let head_content = fs::read_to_string(head_path).unwrap();
-        head_content
-            .split('\n')
-            .filter(|line| !line.trim().is_empty())
-            .map(|s| s.to_string())
-            .collect::<Vec<String>>()
-    } else {
-        panic!("Não há um merge ou rebase em progresso.");
-    }
-}
\ No newline at end of file
+pub fn finish(repo: &mut Repository) -> Result<(), MinigitError> {
+    for path in [&repo.merge_head_path, &repo.rebase_head_path, &repo.orig_head_path] {
+        remove_if_exists(path)?;
+    }
+
+    // Sem o `kind`, um diário removido pela metade é tratado como inexistente
+    let dir = state_dir(repo);
+    if dir.exists() {
+        remove_if_exists(&dir.join(KIND_FILE))?;
+        fs::remove_dir_all(&dir)?;
+    }
+    Ok(())
+}
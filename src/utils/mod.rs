@@ -3,8 +3,15 @@ pub mod find_repo;
 pub mod files;
 pub mod refs;
 pub mod merge_rebase;
+pub mod terminal;
+pub mod revision;
+pub mod dates;
+pub mod pathspec;
+pub mod lockfile;
 
 pub use sha1sum::*;
 pub use find_repo::*;
 pub use files::*;
-pub use refs::*;
\ No newline at end of file
+pub use refs::*;
+pub use revision::*;
+pub use lockfile::*;
@@ -0,0 +1,151 @@
+use std::path::{Component, Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+
+use crate::{Repository, error::MinigitError, utils::get_current_dir};
+
+/// Um padrão de uma pathspec, já relativo à raíz do repositório
+#[derive(Debug, Clone)]
+struct PathspecItem {
+    /// Texto original fornecido pelo usuário, usado nas mensagens de erro
+    original: String,
+    /// Caminho relativo à raíz do repositório (vazio representa a raíz)
+    path: String,
+    /// Padrão glob, se o caminho tiver `*`, `?` ou `[`
+    pattern: Option<Pattern>,
+}
+
+impl PathspecItem {
+    fn matches(&self, path: &str) -> bool {
+        if let Some(pattern) = &self.pattern {
+            let options = MatchOptions { require_literal_separator: false, ..MatchOptions::new() };
+            return pattern.matches_with(path, options);
+        }
+
+        is_inside(path, &self.path)
+    }
+}
+
+/// Conjunto de caminhos e padrões usados para selecionar arquivos em comandos como `add` e `rm`
+///
+/// Cada item pode ser:
+/// - um arquivo ou diretório (`src`, `.`, `../leiame.txt`), que seleciona o arquivo ou tudo dentro do diretório
+/// - um padrão glob (`'*.rs'`, `'src/**/teste_*'`), onde `*` também corresponde a `/`
+/// - uma exclusão (`:(exclude)alvo`, `:!alvo` ou `:^alvo`), que remove da seleção o que corresponder ao padrão
+///
+/// Os caminhos são relativos ao diretório atual. Sem nenhum item incluído, todos os arquivos são selecionados.
+#[derive(Debug, Clone, Default)]
+pub struct Pathspec {
+    includes: Vec<PathspecItem>,
+    excludes: Vec<PathspecItem>,
+}
+
+impl Pathspec {
+    /// Interpreta os itens da pathspec em relação ao diretório atual
+    ///
+    /// Retorna `MinigitError::InvalidArgument` se algum caminho estiver fora do repositório ou
+    /// se algum padrão glob for inválido.
+    pub fn parse(repo: &Repository, specs: &[String]) -> Result<Self, MinigitError> {
+        let mut pathspec = Pathspec::default();
+
+        for spec in specs {
+            let (exclude, pattern_text) = if let Some(rest) = spec.strip_prefix(":(exclude)") {
+                (true, rest)
+            } else if let Some(rest) = spec.strip_prefix(":!").or_else(|| spec.strip_prefix(":^")) {
+                (true, rest)
+            } else {
+                (false, spec.as_str())
+            };
+
+            let path = relative_to_repository(repo, pattern_text)
+                .map_err(|_| MinigitError::InvalidArgument(format!("{} está fora do repositório", spec)))?
+                .to_string_lossy()
+                .to_string();
+
+            let pattern = if pattern_text.contains(['*', '?', '[']) {
+                Some(Pattern::new(&path)
+                    .map_err(|err| MinigitError::InvalidArgument(format!("Padrão inválido '{}': {}", spec, err)))?)
+            } else {
+                None
+            };
+
+            let item = PathspecItem { original: spec.clone(), path, pattern };
+            if exclude {
+                pathspec.excludes.push(item);
+            } else {
+                pathspec.includes.push(item);
+            }
+        }
+
+        Ok(pathspec)
+    }
+
+    /// Verifica se nenhum caminho foi incluído explicitamente
+    pub fn is_empty(&self) -> bool {
+        self.includes.is_empty()
+    }
+
+    /// Verifica se o caminho (relativo à raíz do repositório) é selecionado pela pathspec
+    pub fn matches(&self, path: &Path) -> bool {
+        let path = path.to_string_lossy();
+
+        let included = self.includes.is_empty() || self.includes.iter().any(|item| item.matches(&path));
+        included && !self.excludes.iter().any(|item| item.matches(&path))
+    }
+
+    /// Retorna os itens incluídos que não selecionam nenhum dos caminhos fornecidos
+    pub fn unmatched<'a>(&self, paths: impl IntoIterator<Item = &'a PathBuf> + Clone) -> Vec<String> {
+        self.includes.iter()
+            .filter(|item| !paths.clone().into_iter().any(|path| item.matches(&path.to_string_lossy())))
+            .map(|item| item.original.clone())
+            .collect()
+    }
+
+    /// Retorna os itens incluídos que são diretórios: caminhos sem glob que não correspondem
+    /// exatamente a nenhum dos caminhos fornecidos, apenas a caminhos dentro deles
+    pub fn directories<'a>(&self, paths: impl IntoIterator<Item = &'a PathBuf> + Clone) -> Vec<String> {
+        self.includes.iter()
+            .filter(|item| item.pattern.is_none())
+            .filter(|item| {
+                let mut matched = paths.clone().into_iter().map(|path| path.to_string_lossy().to_string())
+                    .filter(|path| item.matches(path))
+                    .peekable();
+                matched.peek().is_some() && matched.all(|path| path != item.path)
+            })
+            .map(|item| item.original.clone())
+            .collect()
+    }
+}
+
+/// Transforma um caminho relativo ao diretório atual em um caminho relativo à raíz do repositório
+///
+/// Os componentes `.` e `..` são resolvidos; a raíz do repositório é representada por um caminho vazio.
+/// Retorna `MinigitError::InvalidArgument` se o caminho estiver fora do repositório.
+pub fn relative_to_repository(repo: &Repository, path: &str) -> Result<PathBuf, MinigitError> {
+    normalize(&get_current_dir()?.join(path))
+        .strip_prefix(&repo.worktree)
+        .map(|relative| relative.to_path_buf())
+        .map_err(|_| MinigitError::InvalidArgument(format!("{} está fora do repositório", path)))
+}
+
+/// Verifica se `path` é o próprio `dir` ou está dentro dele. `dir` vazio representa a raíz.
+fn is_inside(path: &str, dir: &str) -> bool {
+    dir.is_empty() || path == dir || path.starts_with(&format!("{}/", dir))
+}
+
+/// Resolve os componentes `.` e `..` de um caminho absoluto sem acessar o sistema de arquivos
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            other => result.push(other),
+        }
+    }
+
+    result
+}
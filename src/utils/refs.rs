@@ -1,106 +1,184 @@
-use std::{path::PathBuf, str::FromStr};
+use std::path::PathBuf;
 
-use crate::{Repository, objects::{RGitObjectTypes}, utils::is_valid_sha1};
+use serde::Serialize;
+use walkdir::WalkDir;
+
+use crate::{Repository, error::MinigitError, utils::{LockFile, is_valid_sha1}};
 
 /// Retorna o hash do commit referenciado pela `reference`
 /// Referência pode ser:
 /// - HEAD
 /// - nome de branch (ex: "main", "master")
-/// 
+///
 /// A função retorna None se a referência não existe.
-/// Esta função pode dar pânico se pasta .minigit estiver corrompida.
-pub fn resolve_head_or_branch_name(reference: &str, repo: &Repository) -> Option<String> {
+/// Retorna um erro se a pasta .minigit estiver corrompida.
+pub fn resolve_head_or_branch_name(reference: &str, repo: &Repository) -> Result<Option<String>, MinigitError> {
     if reference == Repository::HEAD {
-        return Some(resolve_head(repo));
+        return Ok(Some(resolve_head(repo)?));
     }
 
-    return resolve_ref_path(format!("refs/heads/{}", reference).as_str(), repo);
+    resolve_ref_path(format!("refs/heads/{}", reference).as_str(), repo)
 }
 
 
 /// Retorna o hash do commit apontado por HEAD
-/// 
+///
 /// HEAD pode ter o seguinte formato:
 /// - ref: refs/heads/main
 /// - hash do commit diretamente
 ///
-/// HEAD SEMPRE deve apontar para um commit válido. Caso contrário, a função dará pânico.
-pub fn resolve_head(repo: &Repository) -> String {
-    let head_string = std::fs::read_to_string(&repo.head_path).unwrap();
+/// HEAD SEMPRE deve apontar para um commit válido. Caso contrário, a função retorna `MinigitError::InvalidRef`.
+pub fn resolve_head(repo: &Repository) -> Result<String, MinigitError> {
+    let head_string = std::fs::read_to_string(&repo.head_path)?;
     let head_string = head_string.trim();
-    let is_commit = !head_string.starts_with("ref: ");
+    let invalid_head = || MinigitError::InvalidRef(format!("HEAD contém uma referência inválida: '{}'", head_string));
 
-    if is_commit {
+    let Some(reference) = head_string.strip_prefix("ref: ") else {
         if !is_valid_sha1(head_string) {
-            panic!("HEAD contém uma referência inválida");
+            return Err(invalid_head());
         }
 
-        let object = repo.get_object(&head_string.to_string());
+        repo.get_commit(&head_string.to_string()).map_err(|_| invalid_head())?;
+        return Ok(head_string.to_string());
+    };
 
-        match object {
-            None => panic!("HEAD contém uma referência inválida"),
-            Some(obj) => match obj {
-                RGitObjectTypes::Commit(_) => {
-                    return head_string.to_string();
-                }
-                _ => panic!("HEAD contém uma referência inválida"),
-            },
-        }
-    }
-
-    let reference = &head_string[5..]; // remove "ref: "
-    match resolve_ref_path(reference, repo) {
-        None => panic!("HEAD contém uma referência inválida"),
-        Some(commit_id) => commit_id,
-    }
+    resolve_ref_path(reference, repo)?.ok_or_else(invalid_head)
 }
 
 /// Retorna o hash do commit referenciado pela `reference` em 'refs/heads/.../index'
-/// 
+///
 /// Retorna None se a referência não existir.
 /// Assumimos que as referências sempre apontam para um commit e que o caminho começa com "refs/heads/".
 /// Caso a referência exista e não aponte para nada, isso é considerado válido e retornamos uma string vazia.
-pub fn resolve_ref_path(reference: &str, repo: &Repository) -> Option<String> {
+pub fn resolve_ref_path(reference: &str, repo: &Repository) -> Result<Option<String>, MinigitError> {
     if !reference.starts_with("refs/heads/") {
-        panic!("resolve_ref_path deve ser chamado apenas com referências que começam com 'refs/heads/'");
+        return Err(MinigitError::InvalidRef(format!("'{}' não começa com 'refs/heads/'", reference)));
     }
 
-    let path = PathBuf::from_str(reference.trim()).ok()?;
+    let path = PathBuf::from(reference.trim());
     let branch_index_path = path.join("index");
     let full_path = repo.minigitdir.join(branch_index_path);
-    let commit_at_ref = std::fs::read_to_string(&full_path).ok()?;
+    let Ok(commit_at_ref) = std::fs::read_to_string(&full_path) else {
+        return Ok(None);
+    };
 
     if commit_at_ref.trim().is_empty() {
-        return Some(String::new());
+        return Ok(Some(String::new()));
     }
 
     let commit_id = commit_at_ref.trim().to_string();
-    let obj = repo.get_object(&commit_id);
-
-    match obj {
-        None => panic!("A referência '{}' aponta para um objeto inválido", reference),
-        Some(o) => match o {
-            RGitObjectTypes::Commit(_) => Some(commit_id),
-            _ => panic!("A referência '{}' não aponta para um commit", reference),
-        },
+
+    match repo.get_commit(&commit_id) {
+        Ok(_) => Ok(Some(commit_id)),
+        Err(MinigitError::ObjectNotFound(_)) => {
+            Err(MinigitError::InvalidRef(format!("A referência '{}' aponta para um objeto inexistente", reference)))
+        }
+        Err(MinigitError::CorruptObject(_)) => {
+            Err(MinigitError::InvalidRef(format!("A referência '{}' não aponta para um commit", reference)))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Escreve o commit na referência `refs/heads/...` ou `refs/notes/...`, com o arquivo da referência
+/// travado por um [`LockFile`]
+///
+/// Se `expected_old` for informado, a referência só é alterada se ainda apontar para ele (vazio para uma
+/// branch sem commits ou uma referência que ainda não existe); caso contrário, retorna
+/// `MinigitError::InvalidState` sem alterá-la.
+pub fn update_ref(repo: &Repository, reference: &str, commit_id: &str, expected_old: Option<&str>) -> Result<(), MinigitError> {
+    if !reference.starts_with("refs/heads/") && !reference.starts_with("refs/notes/") {
+        return Err(MinigitError::InvalidRef(format!("'{}' não começa com 'refs/heads/' nem 'refs/notes/'", reference)));
+    }
+    validate_ref_components(reference)?;
+
+    let path = repo.minigitdir.join(PathBuf::from(reference.trim()).join(Repository::INDEX));
+    let mut lock = LockFile::acquire(&path)?;
+
+    if let Some(expected_old) = expected_old {
+        let current = lock.read_current()?.unwrap_or_default();
+        if current != expected_old.trim() {
+            return Err(MinigitError::InvalidState(format!(
+                "A referência '{}' foi alterada por outro processo (esperado '{}', encontrado '{}')",
+                reference, expected_old.trim(), current,
+            )));
+        }
+    }
+
+    lock.write_all(commit_id.as_bytes())?;
+    lock.commit()
+}
+
+/// Garante que a referência só gera caminhos dentro da pasta de referências
+///
+/// Retorna `MinigitError::InvalidRef` se algum componente for vazio, '.', '..' ou 'index' (o arquivo
+/// que guarda o commit), ou se o nome tiver barras invertidas ou caracteres de controle.
+pub fn validate_ref_components(reference: &str) -> Result<(), MinigitError> {
+    let invalid_component = reference.split('/')
+        .any(|part| part.is_empty() || part == "." || part == ".." || part == Repository::INDEX);
+
+    if invalid_component || reference.contains('\\') || reference.contains(char::is_control) {
+        return Err(MinigitError::InvalidRef(format!("'{}' não é um nome de referência válido", reference.escape_debug())));
     }
+
+    Ok(())
 }
 
 /// Verifica se a referência passada existe
-pub fn reference_exists(reference: &str, repo: &Repository) -> bool {
+pub fn reference_exists(reference: &str, repo: &Repository) -> Result<bool, MinigitError> {
     if is_valid_sha1(reference) {
-        let object = repo.get_object(&reference.to_string());
-        return match object {
-            Some(obj) => match obj {
-                RGitObjectTypes::Commit(_) => true,
-                _ => false,
-            },
-            None => false,
+        return match repo.get_commit(&reference.to_string()) {
+            Ok(_) => Ok(true),
+            Err(MinigitError::ObjectNotFound(_)) | Err(MinigitError::CorruptObject(_)) => Ok(false),
+            Err(err) => Err(err),
         };
     }
 
-    resolve_head_or_branch_name(reference, repo).is_some()
+    Ok(resolve_head_or_branch_name(reference, repo)?.is_some())
+}
+
+
+
+/// Branch do repositório
+///
+/// ## Atributos
+/// - `name` - Nome da branch (ex: "master", "feature/login")
+/// - `commit` - Hash do commit apontado pela branch, vazio se ainda não houver commits
+/// - `current` - Se a branch é a apontada pelo HEAD
+#[derive(Clone, PartialEq, Eq, Debug, Serialize)]
+pub struct BranchInfo {
+    pub name: String,
+    pub commit: String,
+    pub current: bool,
 }
 
+/// Lista as branches do repositório em ordem alfabética
+pub fn list_branches(repo: &Repository) -> Result<Vec<BranchInfo>, MinigitError> {
+    let head_ref = repo.get_head()?;
+    let mut branches = Vec::new();
+
+    for entry in WalkDir::new(&repo.refs_heads_path).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() || entry.file_name() != Repository::INDEX {
+            continue;
+        }
 
+        let Some(branch_dir) = entry.path().parent() else {
+            continue;
+        };
+        let Ok(relative) = branch_dir.strip_prefix(&repo.refs_heads_path) else {
+            continue;
+        };
 
+        let name = relative.components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<String>>()
+            .join("/");
+        let commit = std::fs::read_to_string(entry.path())?.trim().to_string();
+        let current = head_ref == format!("refs/heads/{}", name);
+
+        branches.push(BranchInfo { name, commit, current });
+    }
+
+    branches.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(branches)
+}
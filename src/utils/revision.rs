@@ -0,0 +1,154 @@
+use crate::{Repository, error::MinigitError, staging::{STAGE_NORMAL, StagingArea}, utils::{is_valid_sha1, resolve_head_or_branch_name}};
+
+/// Tamanho mínimo de um hash abreviado
+const MIN_ABBREV_LEN: usize = 4;
+
+/// Resolve uma revisão para o hash do commit correspondente
+///
+/// A revisão é formada por uma base seguida de zero ou mais sufixos:
+/// - base: `HEAD`, nome de branch, hash completo ou hash abreviado (mínimo 4 caracteres)
+/// - `~n`: n-ésimo ancestral seguindo sempre o primeiro pai (`~` sozinho equivale a `~1`)
+/// - `^n`: n-ésimo pai do commit (`^` sozinho equivale a `^1` e `^0` é o próprio commit)
+///
+/// ## Exemplo
+/// `HEAD~2`, `master^2`, `a1b2c3d~1^2`
+pub fn resolve_revision(repo: &Repository, revision: &str) -> Result<String, MinigitError> {
+    let base_end = revision.find(['~', '^']).unwrap_or(revision.len());
+    let (base, mut suffixes) = revision.split_at(base_end);
+
+    let mut commit_hash = resolve_base(repo, base)?;
+
+    while let Some(operator) = suffixes.chars().next() {
+        suffixes = &suffixes[1..];
+        let digits_end = suffixes.find(|c: char| !c.is_ascii_digit()).unwrap_or(suffixes.len());
+        let (digits, remainder) = suffixes.split_at(digits_end);
+        suffixes = remainder;
+
+        let count = if digits.is_empty() {
+            1
+        } else {
+            digits.parse::<usize>()
+                .map_err(|_| MinigitError::InvalidRef(format!("Revisão inválida: '{}'", revision)))?
+        };
+
+        commit_hash = match operator {
+            '~' => nth_ancestor(repo, &commit_hash, count, revision)?,
+            '^' => nth_parent(repo, &commit_hash, count, revision)?,
+            _ => return Err(MinigitError::InvalidRef(format!("Revisão inválida: '{}'", revision))),
+        };
+    }
+
+    Ok(commit_hash)
+}
+
+/// Resolve a especificação de um objeto qualquer (commit, árvore ou blob) para o seu hash
+///
+/// Além das revisões aceitas por [`resolve_revision`], aceita:
+/// - `rev:caminho`: o arquivo ou pasta no caminho (relativo à raíz do repositório) na árvore da revisão
+/// - `rev:`: a árvore da revisão
+/// - `:caminho`: o arquivo no índice
+/// - hash completo ou abreviado de qualquer objeto
+pub fn resolve_object(repo: &Repository, spec: &str) -> Result<String, MinigitError> {
+    let Some((revision, path)) = spec.split_once(':') else {
+        return resolve_revision(repo, spec).or_else(|err| expand_object_id(repo, spec).map_err(|_| err));
+    };
+
+    let path = path.trim_matches('/');
+    if revision.is_empty() {
+        let staging_area = StagingArea::new(repo)?;
+        return staging_area.entries.iter()
+            .find(|entry| entry.stage == STAGE_NORMAL && entry.path.to_string_lossy() == path)
+            .map(|entry| entry.object_hash.clone())
+            .ok_or_else(|| MinigitError::InvalidRef(format!("Caminho '{}' não está no índice", path)));
+    }
+
+    let commit = repo.get_commit(&resolve_revision(repo, revision)?)?;
+    let mut object_id = commit.tree;
+
+    for component in path.split('/').filter(|component| !component.is_empty()) {
+        let tree = repo.get_tree(&object_id)
+            .map_err(|_| MinigitError::InvalidRef(format!("Caminho '{}' não existe em '{}'", path, revision)))?;
+        object_id = tree.children.into_iter()
+            .find(|child| child.name == component)
+            .map(|child| child.object_id)
+            .ok_or_else(|| MinigitError::InvalidRef(format!("Caminho '{}' não existe em '{}'", path, revision)))?;
+    }
+
+    Ok(object_id)
+}
+
+/// Expande um hash abreviado para o hash completo do objeto
+///
+/// Retorna `MinigitError::ObjectNotFound` se nenhum objeto começar com o prefixo e
+/// `MinigitError::InvalidRef` se o prefixo for ambíguo.
+pub fn expand_object_id(repo: &Repository, prefix: &str) -> Result<String, MinigitError> {
+    let prefix = prefix.to_lowercase();
+
+    if is_valid_sha1(&prefix) {
+        return Ok(prefix);
+    }
+
+    if prefix.len() < MIN_ABBREV_LEN || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(MinigitError::ObjectNotFound(prefix));
+    }
+
+    let (dir, rest) = prefix.split_at(2);
+    let objects_dir = repo.get_repository_path(&["objects", dir]);
+
+    let mut matches = Vec::new();
+    if let Ok(entries) = std::fs::read_dir(&objects_dir) {
+        for entry in entries {
+            let file_name = entry?.file_name().to_string_lossy().to_string();
+            if file_name.starts_with(rest) {
+                matches.push(format!("{}{}", dir, file_name));
+            }
+        }
+    }
+
+    match matches.len() {
+        0 => Err(MinigitError::ObjectNotFound(prefix)),
+        1 => Ok(matches.remove(0)),
+        _ => Err(MinigitError::InvalidRef(format!("Hash abreviado '{}' é ambíguo", prefix))),
+    }
+}
+
+fn resolve_base(repo: &Repository, base: &str) -> Result<String, MinigitError> {
+    if base.is_empty() {
+        return Err(MinigitError::InvalidRef("Revisão vazia".to_string()));
+    }
+
+    if let Some(commit_hash) = resolve_head_or_branch_name(base, repo)? {
+        if commit_hash.is_empty() {
+            return Err(MinigitError::InvalidRef(format!("'{}' ainda não aponta para nenhum commit", base)));
+        }
+        return Ok(commit_hash);
+    }
+
+    let object_id = expand_object_id(repo, base)
+        .map_err(|err| match err {
+            MinigitError::ObjectNotFound(_) => MinigitError::InvalidRef(format!("Revisão '{}' não existe", base)),
+            err => err,
+        })?;
+
+    repo.get_commit(&object_id)?;
+    Ok(object_id)
+}
+
+fn nth_ancestor(repo: &Repository, commit_hash: &str, count: usize, revision: &str) -> Result<String, MinigitError> {
+    let mut current = commit_hash.to_string();
+    for _ in 0..count {
+        current = nth_parent(repo, &current, 1, revision)?;
+    }
+    Ok(current)
+}
+
+fn nth_parent(repo: &Repository, commit_hash: &String, count: usize, revision: &str) -> Result<String, MinigitError> {
+    if count == 0 {
+        return Ok(commit_hash.clone());
+    }
+
+    let commit = repo.get_commit(commit_hash)?;
+    commit.parent.get(count - 1)
+        .cloned()
+        .ok_or_else(|| MinigitError::InvalidRef(format!("Revisão '{}' não existe: commit {} não tem o pai {}", revision, commit_hash, count)))
+}
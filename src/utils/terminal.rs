@@ -0,0 +1,61 @@
+use std::{io::{IsTerminal, Write}, process::{Command, Stdio}};
+
+use serde::Serialize;
+
+use crate::error::MinigitError;
+
+pub const RED: &str = "\x1b[31m";
+pub const GREEN: &str = "\x1b[32m";
+pub const YELLOW: &str = "\x1b[33m";
+pub const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// Verifica se a saída padrão é um terminal interativo
+pub fn stdout_is_terminal() -> bool {
+    std::io::stdout().is_terminal()
+}
+
+/// Cores só são usadas quando a saída é um terminal e a variável `NO_COLOR` não está definida
+pub fn use_color() -> bool {
+    stdout_is_terminal() && std::env::var_os("NO_COLOR").is_none()
+}
+
+/// Envolve o texto com o código de cor ANSI, se cores estiverem habilitadas
+pub fn paint(text: &str, color: &str) -> String {
+    if use_color() {
+        format!("{}{}{}", color, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Exibe a saída usando o pager `less`
+///
+/// A saída é impressa diretamente se não for um terminal ou se o `less` não estiver instalado.
+pub fn page(output: &str) -> Result<(), MinigitError> {
+    if !stdout_is_terminal() {
+        print!("{}", output);
+        return Ok(());
+    }
+
+    let Ok(mut child) = Command::new("less")
+        .arg("-R")
+        .stdin(Stdio::piped())
+        .spawn() else {
+            print!("{}", output);
+            return Ok(());
+        };
+
+    if let Some(stdin) = child.stdin.as_mut() {
+        stdin.write_all(output.as_bytes())?;
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Imprime o valor como JSON formatado
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> Result<(), MinigitError> {
+    let json = serde_json::to_string_pretty(value).map_err(std::io::Error::other)?;
+    writeln!(std::io::stdout(), "{}", json)?;
+    Ok(())
+}
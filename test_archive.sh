@@ -0,0 +1,96 @@
+#!/bin/bash
+
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do comando archive ==="
+echo ""
+
+echo "--- 1. Repositório com subpastas, executável e link simbólico ---"
+mkdir repo
+cd repo
+"$MINIGIT" init > /dev/null
+mkdir -p src/util docs
+echo "principal" > leiame.txt
+printf '#!/bin/sh\necho oi\n' > run.sh
+chmod +x run.sh
+echo "fn main() {}" > src/main.rs
+echo "pub fn ajuda() {}" > src/util/ajuda.rs
+echo "manual" > docs/manual.md
+ln -s leiame.txt atalho
+for i in $(seq 1 200); do echo "linha repetida número $i"; done > docs/grande.txt
+"$MINIGIT" add . > /dev/null
+"$MINIGIT" commit "Primeira versão" > /dev/null
+echo "principal v2" > leiame.txt
+"$MINIGIT" add leiame.txt
+"$MINIGIT" commit "Segunda versão" > /dev/null
+commit=$("$MINIGIT" log --format "%H" | sed -n 1p)
+echo ""
+
+echo "--- 2. tar da revisão (ESPERADO: arquivos da revisão sem .minigit, diretórios antes do conteúdo) ---"
+"$MINIGIT" archive HEAD -o ../versao.tar
+tar tf ../versao.tar | sort
+tar tf ../versao.tar | grep -c "\.minigit"
+echo ""
+
+echo "--- 3. Extração do tar (ESPERADO: conteúdo igual, run.sh executável, atalho como link) ---"
+mkdir ../extraido
+tar xf ../versao.tar -C ../extraido
+cat ../extraido/leiame.txt
+stat -c "%A %n" ../extraido/run.sh ../extraido/src/main.rs | sed 's|\.\./extraido/||'
+readlink ../extraido/atalho
+diff -r --exclude=.minigit . ../extraido && echo "Conteúdo idêntico à worktree"
+echo ""
+
+echo "--- 4. Hash do commit como comentário (ESPERADO: comment=<commit> no cabeçalho pax) ---"
+head -c 1536 ../versao.tar | tr -d '\0' | grep -ao "comment=[0-9a-f]*" | sed "s/$commit/<commit>/"
+echo ""
+
+echo "--- 5. Revisão anterior na saída padrão (ESPERADO: leiame.txt da primeira versão) ---"
+"$MINIGIT" archive HEAD~1 > ../antiga.tar
+tar xOf ../antiga.tar leiame.txt
+echo ""
+
+echo "--- 6. tar.gz com prefixo e caminhos (ESPERADO: só src, tudo dentro de projeto-1.0/) ---"
+"$MINIGIT" archive --format=tar.gz --prefix=projeto-1.0/ HEAD src -o ../projeto.tar.gz
+gzip -t ../projeto.tar.gz && echo "gzip válido"
+tar tzf ../projeto.tar.gz
+echo ""
+
+echo "--- 7. zip deduzido pela extensão (ESPERADO: zip íntegro, comentário com o commit, modos preservados) ---"
+"$MINIGIT" archive HEAD -o ../versao.zip
+unzip -tq ../versao.zip
+unzip -z ../versao.zip | sed -n 2p | sed "s/$commit/<commit>/"
+mkdir ../zip
+unzip -q ../versao.zip -d ../zip
+stat -c "%A %n" ../zip/run.sh ../zip/docs/grande.txt | sed 's|\.\./zip/||'
+readlink ../zip/atalho
+diff -r --exclude=.minigit . ../zip && echo "Conteúdo idêntico à worktree"
+[ "$(stat -c %s ../versao.zip)" -lt "$(stat -c %s docs/grande.txt)" ] && echo "docs/grande.txt foi comprimido"
+echo ""
+
+echo "--- 8. Saída reproduzível (ESPERADO: o mesmo commit gera arquivos idênticos) ---"
+"$MINIGIT" archive HEAD -o ../de-novo.zip
+cmp ../versao.zip ../de-novo.zip && echo "zip idêntico"
+"$MINIGIT" archive --format tar HEAD | cmp - ../versao.tar && echo "tar idêntico"
+echo ""
+
+echo "--- 9. Pathspec sem arquivos (ESPERADO: erro, nenhum arquivo criado e nenhum byte na saída padrão) ---"
+"$MINIGIT" archive HEAD inexistente -o ../vazio.tar
+echo "Código de saída: $?"
+ls ../vazio.tar ../vazio.tar.lock 2>&1 | sed 's/.*: //'
+echo "Bytes na saída padrão: $("$MINIGIT" archive HEAD inexistente 2> /dev/null | wc -c)"
+echo ""
+
+echo "--- 10. Erros (ESPERADO: revisão inválida, prefixo fora da pasta, formato desconhecido) ---"
+"$MINIGIT" archive naoexiste -o ../x.tar
+echo "Código de saída: $?"
+"$MINIGIT" archive --prefix=../fora/ HEAD -o ../x.tar
+echo "Código de saída: $?"
+"$MINIGIT" archive --format=rar HEAD 2>&1 | sed -n 1p
+echo ""
+
+echo "=== Teste concluído ==="
@@ -0,0 +1,144 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do comando bisect ==="
+echo ""
+
+mkdir repo
+cd repo
+
+echo "--- 1. Criando 8 commits; o problema aparece no commit 6 ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Teste"
+for i in 1 2 3 4 5 6 7 8; do
+    if [ $i -ge 6 ]; then echo "quebrado $i" > estado.txt; else echo "ok $i" > estado.txt; fi
+    "$MINIGIT" add estado.txt
+    "$MINIGIT" commit "Commit $i" > /dev/null
+done
+"$MINIGIT" log --oneline
+echo ""
+
+echo "--- 2. Comandos sem bisect em andamento (ESPERADO: erros) ---"
+"$MINIGIT" bisect good
+echo "Código de saída: $?"
+"$MINIGIT" bisect reset
+echo ""
+
+echo "--- 3. start sem commits e marcações parciais (ESPERADO: aguardando ruim e bom, depois só bom) ---"
+"$MINIGIT" bisect start
+"$MINIGIT" bisect bad
+echo ""
+
+echo "--- 4. Marcando o primeiro commit como bom (ESPERADO: muda para o meio do intervalo) ---"
+"$MINIGIT" bisect good HEAD~7
+cat estado.txt
+echo ""
+
+echo "--- 5. Respondendo até achar o commit 6 (ESPERADO: 'Commit 6' é o primeiro commit ruim) ---"
+for _ in 1 2 3 4; do
+    if grep -q quebrado estado.txt; then
+        saida=$("$MINIGIT" bisect bad)
+    else
+        saida=$("$MINIGIT" bisect good)
+    fi
+    echo "$saida"
+    echo "$saida" | grep -q "primeiro commit ruim" && break
+done
+echo ""
+
+echo "--- 6. Estado salvo em .minigit (ESPERADO: BISECT_BAD, BISECT_GOOD, BISECT_LOG, BISECT_SKIP, BISECT_START) ---"
+ls .minigit | grep BISECT
+cat .minigit/BISECT_START
+echo ""
+
+echo "--- 7. bisect log (ESPERADO: linhas 'minigit bisect ...' e comentários) ---"
+"$MINIGIT" bisect log > ../registro.txt
+sed -E 's/[0-9a-f]{40}/<hash>/g' ../registro.txt
+echo ""
+
+echo "--- 8. start com bisect em andamento (ESPERADO: erro) ---"
+"$MINIGIT" bisect start
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 9. reset volta para a branch original (ESPERADO: HEAD em master, sem arquivos BISECT) ---"
+"$MINIGIT" bisect reset
+cat .minigit/HEAD; echo ""
+ls .minigit | grep BISECT
+cat estado.txt
+echo ""
+
+echo "--- 10. replay refaz a busca registrada (ESPERADO: Commit 6 é o primeiro commit ruim) ---"
+"$MINIGIT" bisect replay ../registro.txt
+"$MINIGIT" bisect reset > /dev/null
+echo ""
+
+echo "--- 11. skip evita o commit testado (ESPERADO: outro commit é escolhido) ---"
+"$MINIGIT" bisect start HEAD HEAD~7
+"$MINIGIT" bisect skip
+echo ""
+
+echo "--- 12. Pulando tudo o que sobra (ESPERADO: lista dos commits possíveis) ---"
+"$MINIGIT" bisect skip master~1 master~2 master~3 master~4 master~5 master~6 > /dev/null
+"$MINIGIT" bisect skip | sed -E 's/[0-9a-f]{40}/<hash>/g'
+"$MINIGIT" bisect reset > /dev/null
+echo ""
+
+echo "--- 13. bisect run com script (ESPERADO: Commit 6 encontrado automaticamente) ---"
+cat > ../testa.sh <<'SCRIPT'
+#!/bin/bash
+grep -q quebrado estado.txt && exit 1
+exit 0
+SCRIPT
+chmod +x ../testa.sh
+"$MINIGIT" bisect start master master~7 > /dev/null
+"$MINIGIT" bisect run ../testa.sh | grep -v "^\[" | sed -E 's/[0-9a-f]{40}/<hash>/g'
+"$MINIGIT" bisect reset > /dev/null
+echo ""
+
+echo "--- 14. bisect run com código 125 pula commits (ESPERADO: Commit 6 encontrado mesmo pulando o commit 7) ---"
+cat > ../testa.sh <<'SCRIPT'
+#!/bin/bash
+grep -q "quebrado 7" estado.txt && exit 125
+grep -q quebrado estado.txt && exit 1
+exit 0
+SCRIPT
+"$MINIGIT" bisect start master master~7 > /dev/null
+"$MINIGIT" bisect run ../testa.sh | grep "primeiro commit ruim" | sed -E 's/[0-9a-f]{40}/<hash>/g'
+"$MINIGIT" bisect log | grep "^# skip" | sed -E 's/[0-9a-f]{40}/<hash>/g'
+"$MINIGIT" bisect reset > /dev/null
+echo ""
+
+echo "--- 15. bisect run com código acima de 127 aborta (ESPERADO: erro) ---"
+"$MINIGIT" bisect start master master~7 > /dev/null
+"$MINIGIT" bisect run sh -c "exit 200" | grep -v "^\["
+echo "Código de saída: ${PIPESTATUS[0]}"
+"$MINIGIT" bisect reset > /dev/null
+echo ""
+
+echo "--- 16. Commit ruim ancestral do bom (ESPERADO: erro dizendo que bons e ruins são inconsistentes) ---"
+"$MINIGIT" bisect start master~7 master 2>&1 | sed -E 's/[0-9a-f]{7}/<h>/g'
+echo "Código de saída: ${PIPESTATUS[0]}"
+"$MINIGIT" bisect reset > /dev/null
+"$MINIGIT" bisect start master master 2>&1 | sed -E 's/[0-9a-f]{7}/<h>/g'
+echo "Código de saída: ${PIPESTATUS[0]}"
+"$MINIGIT" bisect reset > /dev/null
+echo ""
+
+echo "--- 17. Log do bisect travado por outro processo (ESPERADO: erro de lock, código 16, log intacto) ---"
+"$MINIGIT" bisect start master master~7 > /dev/null
+antes=$(cat .minigit/BISECT_LOG)
+touch .minigit/BISECT_LOG.lock
+"$MINIGIT" bisect good
+echo "Código de saída: $?"
+rm .minigit/BISECT_LOG.lock
+[ "$(cat .minigit/BISECT_LOG)" = "$antes" ] && echo "log intacto"
+ls .minigit/*.lock 2>&1 | sed 's/.*cannot access.*/nenhum lock/'
+"$MINIGIT" bisect reset > /dev/null
+echo ""
+
+echo "=== Teste concluído ==="
@@ -0,0 +1,68 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do comando blame ==="
+echo ""
+
+echo "--- 1. Criando o histórico com dois autores ---"
+"$MINIGIT" init
+"$MINIGIT" config user.name "Alice"
+"$MINIGIT" config user.email "alice@exemplo.com"
+printf 'linha um\nlinha dois\nlinha três\n' > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Cria a.txt"
+
+"$MINIGIT" config user.name "Bob"
+"$MINIGIT" config user.email "bob@exemplo.com"
+printf 'linha um\nlinha DOIS\nlinha três\nlinha quatro\n' > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Altera a segunda linha"
+
+"$MINIGIT" config user.name "Carol"
+"$MINIGIT" mv a.txt b.txt
+"$MINIGIT" commit "Renomeia para b.txt"
+
+"$MINIGIT" config user.name "Dave"
+printf '    linha um\nlinha DOIS\nlinha três\nlinha quatro\nlinha cinco\n' > b.txt
+"$MINIGIT" add b.txt
+"$MINIGIT" commit "Indenta e adiciona linha"
+echo ""
+
+echo "--- 2. Blame segue a renomeação (ESPERADO: Dave nas linhas 1 e 5, Bob nas 2 e 4, Alice na 3 vinda de a.txt) ---"
+"$MINIGIT" blame b.txt | sed -E 's/[0-9]{2}\/[0-9]{2}\/[0-9]{4} [0-9:]{8}/<data>/'
+echo ""
+
+echo "--- 3. -w ignora a mudança só de espaços (ESPERADO: linha 1 de Alice) ---"
+"$MINIGIT" blame -w b.txt | sed -E 's/[0-9]{2}\/[0-9]{2}\/[0-9]{4} [0-9:]{8}/<data>/'
+echo ""
+
+echo "--- 4. Intervalos com -L (ESPERADO: linhas 2-3, depois 4, depois 4-5) ---"
+"$MINIGIT" blame -L 2,3 b.txt | sed -E 's/[0-9]{2}\/[0-9]{2}\/[0-9]{4} [0-9:]{8}/<data>/'
+"$MINIGIT" blame -L 4,+1 b.txt | sed -E 's/[0-9]{2}\/[0-9]{2}\/[0-9]{4} [0-9:]{8}/<data>/'
+"$MINIGIT" blame -L 4, b.txt | sed -E 's/[0-9]{2}\/[0-9]{2}\/[0-9]{4} [0-9:]{8}/<data>/'
+echo ""
+
+echo "--- 5. Intervalos inválidos (ESPERADO: erros) ---"
+"$MINIGIT" blame -L 3,1 b.txt
+"$MINIGIT" blame -L 10,12 b.txt
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 6. Revisão antiga e alias annotate (ESPERADO: a.txt de HEAD~2 com Alice e Bob) ---"
+"$MINIGIT" annotate a.txt HEAD~2 | sed -E 's/[0-9]{2}\/[0-9]{2}\/[0-9]{4} [0-9:]{8}/<data>/'
+echo ""
+
+echo "--- 7. Formato porcelain (ESPERADO: dados de cada commit só na primeira vez) ---"
+"$MINIGIT" blame --porcelain -L 2,4 b.txt | sed -E 's/^[0-9a-f]{40}/<hash>/; s/^author-time [0-9]+/author-time <tempo>/'
+echo ""
+
+echo "--- 8. Arquivo inexistente (ESPERADO: erro) ---"
+"$MINIGIT" blame nada.txt
+echo "Código de saída: $?"
+echo ""
+
+echo "=== Teste concluído ==="
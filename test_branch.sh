@@ -0,0 +1,95 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do gerenciamento de branches ==="
+echo ""
+
+echo "--- 1. Criando o repositório ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Teste"
+"$MINIGIT" config email "teste@exemplo.com"
+echo "um" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Primeiro commit"
+echo "dois" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Segundo commit"
+echo ""
+
+echo "--- 2. Criando branches no HEAD e em outro ponto de partida (ESPERADO: antiga no 'Primeiro commit') ---"
+"$MINIGIT" branch nova
+"$MINIGIT" branch antiga HEAD~1
+"$MINIGIT" branch -v
+echo ""
+
+echo "--- 3. Listagem simples marca a branch atual (ESPERADO: '* master') ---"
+"$MINIGIT" branch
+echo ""
+
+echo "--- 4. Commit em uma branch que não foi mesclada ---"
+"$MINIGIT" switch -c feature
+echo "três" > b.txt
+"$MINIGIT" add b.txt
+"$MINIGIT" commit "Commit da feature"
+"$MINIGIT" switch master
+echo ""
+
+echo "--- 5. --merged e --no-merged (ESPERADO: antiga, master e nova / feature) ---"
+"$MINIGIT" branch --merged
+echo "..."
+"$MINIGIT" branch --no-merged
+echo ""
+
+echo "--- 6. -d recusa branch não mesclada, -D deleta (ESPERADO: erro e depois 'deletada') ---"
+"$MINIGIT" branch -d feature
+echo "Código de saída: $?"
+"$MINIGIT" branch -D feature
+"$MINIGIT" branch -d antiga
+echo ""
+
+echo "--- 7. Renomeando outra branch e a branch atual (ESPERADO: '* principal' e 'renomeada') ---"
+"$MINIGIT" branch -m nova renomeada
+"$MINIGIT" branch -m principal
+"$MINIGIT" branch
+"$MINIGIT" status | grep "Na branch"
+echo ""
+
+echo "--- 8. -m recusa sobrescrever, -M sobrescreve (ESPERADO: erro e só 'principal' e 'renomeada') ---"
+"$MINIGIT" branch copia
+"$MINIGIT" branch -m copia renomeada
+echo "Código de saída: $?"
+"$MINIGIT" branch -M copia renomeada
+"$MINIGIT" branch
+echo ""
+
+echo "--- 9. Copiando a branch atual (ESPERADO: 'backup' e HEAD ainda em 'principal') ---"
+"$MINIGIT" branch -c backup
+"$MINIGIT" branch
+echo ""
+
+echo "--- 10. Upstream aparece no -v e acompanha a renomeação (ESPERADO: '[principal]' em 'seguidora', só a seção 'seguidora' na configuração) ---"
+"$MINIGIT" branch -u principal renomeada
+"$MINIGIT" branch -m renomeada seguidora
+"$MINIGIT" branch -v
+grep '^\[branch' .minigit/config
+echo ""
+
+echo "--- 11. Nomes inválidos (ESPERADO: erros) ---"
+"$MINIGIT" branch "com espaço"
+"$MINIGIT" branch a..b
+"$MINIGIT" branch existe/
+"$MINIGIT" branch principal
+echo ""
+
+echo "--- 12. -d, -D, -m e -c sem nome de branch (ESPERADO: erro de argumento, código 2, nada listado) ---"
+for flag in -d -D -m -c; do
+    "$MINIGIT" branch $flag
+    echo "Código de saída: $?"
+done
+echo ""
+
+echo "=== Teste concluído ==="
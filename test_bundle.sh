@@ -0,0 +1,194 @@
+#!/bin/bash
+
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do comando bundle ==="
+echo ""
+
+echo "--- 1. Repositório de origem com duas branches ---"
+mkdir origem
+cd origem
+"$MINIGIT" init > /dev/null
+mkdir docs
+echo "v1" > app.txt
+echo "manual" > docs/manual.md
+"$MINIGIT" add . > /dev/null
+"$MINIGIT" commit "Primeira versão" > /dev/null
+echo "v2" > app.txt
+"$MINIGIT" add app.txt
+"$MINIGIT" commit "Segunda versão" > /dev/null
+"$MINIGIT" branch feature
+echo ""
+
+echo "--- 2. Bundle completo (ESPERADO: 2 commits, 3 referências, nenhum commit exigido) ---"
+"$MINIGIT" bundle create ../completo.bundle master feature HEAD
+sed -n 1p ../completo.bundle
+"$MINIGIT" bundle verify ../completo.bundle | sed -E 's/[0-9a-f]{40}/<hash>/'
+"$MINIGIT" bundle create ../todas.bundle --all
+sed -n '2,/^$/{/./p}' ../todas.bundle | cut -d' ' -f2
+echo ""
+
+echo "--- 3. Clone a partir do bundle (ESPERADO: histórico, branches e worktree iguais aos da origem) ---"
+cd ..
+"$MINIGIT" clone completo.bundle destino
+cd destino
+"$MINIGIT" log --format "%s"
+"$MINIGIT" branch
+cat app.txt docs/manual.md
+"$MINIGIT" status
+"$MINIGIT" fsck
+echo ""
+
+echo "--- 4. Bundle incremental (ESPERADO: só o commit novo, exigindo a 'Segunda versão') ---"
+cd ../origem
+echo "v3" > app.txt
+"$MINIGIT" add app.txt
+"$MINIGIT" commit "Terceira versão" > /dev/null
+"$MINIGIT" bundle create ../incremental.bundle master~1..master
+"$MINIGIT" bundle verify ../incremental.bundle | sed -E 's/[0-9a-f]{40}/<hash>/'
+echo ""
+
+echo "--- 5. Repositório sem os commits exigidos (ESPERADO: verify e unbundle recusam, nada é importado) ---"
+mkdir ../vazio
+cd ../vazio
+"$MINIGIT" init > /dev/null
+"$MINIGIT" bundle verify ../incremental.bundle 2>&1 | sed -E 's/[0-9a-f]{40}/<hash>/'
+"$MINIGIT" bundle unbundle ../incremental.bundle > /dev/null 2>&1
+echo "Código de saída: $?"
+ls .minigit/objects | wc -l
+echo ""
+
+echo "--- 6. Unbundle na branch atual (ESPERADO: master recusada, mas os objetos ficam disponíveis para o merge) ---"
+cd ../destino
+"$MINIGIT" bundle unbundle ../incremental.bundle 2>&1 | sed -E 's/[0-9a-f]{40}/<hash>/'
+echo "Código de saída: ${PIPESTATUS[0]}"
+novo=$(sed -n 3p ../incremental.bundle | cut -d' ' -f1)
+"$MINIGIT" merge "$novo" > /dev/null
+"$MINIGIT" log --format "%s" | sed -n 1p
+cat app.txt
+echo ""
+
+echo "--- 7. Unbundle de branch que não é a atual (ESPERADO: fast-forward de feature) ---"
+cd ../origem
+"$MINIGIT" switch feature > /dev/null
+echo "recurso" > recurso.txt
+"$MINIGIT" add recurso.txt
+"$MINIGIT" commit "Novo recurso" > /dev/null
+"$MINIGIT" bundle create ../feature.bundle master..feature
+cd ../destino
+"$MINIGIT" bundle unbundle ../feature.bundle | sed -E 's/[0-9a-f]{7}/<h>/g'
+"$MINIGIT" log --format "%s" feature | sed -n 1p
+"$MINIGIT" bundle unbundle ../feature.bundle
+echo ""
+
+echo "--- 8. Branch reescrita na origem (ESPERADO: recusada sem --force, substituída com --force) ---"
+cd ../origem
+"$MINIGIT" reset --mode hard master > /dev/null
+echo "recurso refeito" > recurso.txt
+"$MINIGIT" add recurso.txt
+"$MINIGIT" commit "Recurso refeito" > /dev/null
+"$MINIGIT" bundle create ../reescrita.bundle master..feature
+cd ../destino
+"$MINIGIT" bundle unbundle ../reescrita.bundle 2>&1 | sed -E 's/[0-9a-f]{7}/<h>/g'
+echo "Código de saída: ${PIPESTATUS[0]}"
+"$MINIGIT" bundle unbundle --force ../reescrita.bundle | sed -E 's/[0-9a-f]{7}/<h>/g'
+"$MINIGIT" log --format "%s" feature | sed -n 1p
+"$MINIGIT" fsck --no-dangling
+echo ""
+
+echo "--- 9. Bundle corrompido ou inválido (ESPERADO: erros, nenhum objeto gravado) ---"
+cd ..
+head -c -20 completo.bundle > truncado.bundle
+mkdir corrompido
+cd corrompido
+"$MINIGIT" init > /dev/null
+"$MINIGIT" bundle verify ../truncado.bundle
+echo "Código de saída: $?"
+"$MINIGIT" bundle verify ../origem/app.txt
+echo "Código de saída: $?"
+ls .minigit/objects | wc -l
+echo ""
+
+echo "--- 10. Erros ao criar (ESPERADO: sem branches, revisão que não é branch, bundle vazio) ---"
+cd ../origem
+"$MINIGIT" bundle create ../x.bundle
+echo "Código de saída: $?"
+"$MINIGIT" bundle create ../x.bundle master~1
+echo "Código de saída: $?"
+"$MINIGIT" bundle create ../x.bundle master..master
+echo "Código de saída: $?"
+ls ../x.bundle 2>&1 | sed 's/.*: //'
+echo ""
+
+echo "--- 11. Referência maliciosa no bundle (ESPERADO: bundle recusado, nada criado fora do .minigit) ---"
+cd ..
+perl -0777 -pe 's#refs/heads/feature\n#refs/heads/../../../pwned\n#' completo.bundle > malicioso.bundle
+perl -0777 -pe 's#refs/heads/feature\n#refs/heads/../..\n#' completo.bundle > indice.bundle
+mkdir vitima
+cd vitima
+"$MINIGIT" init > /dev/null
+"$MINIGIT" bundle verify ../malicioso.bundle
+echo "Código de saída: $?"
+"$MINIGIT" bundle unbundle ../malicioso.bundle
+echo "Código de saída: $?"
+"$MINIGIT" bundle unbundle ../indice.bundle
+echo "Código de saída: $?"
+ls ../../pwned 2>&1 | sed 's/.*: //'
+"$MINIGIT" status > /dev/null && echo "índice intacto"
+cd ..
+"$MINIGIT" clone malicioso.bundle clone-malicioso
+echo "Código de saída: $?"
+ls ../pwned 2>&1 | sed 's/.*: //'
+echo ""
+
+echo "--- 12. Objeto com tamanho gigante no bundle (ESPERADO: bundle corrompido, nada gravado) ---"
+perl -MCompress::Zlib -0777 -ne 'print "$1\n", compress("blob 18446744073709551615\0abc") if /\A(.*?\n)\n/s' completo.bundle > gigante.bundle
+perl -MCompress::Zlib -0777 -ne 'print "$1\n", compress("blob 1000000000000\0abc") if /\A(.*?\n)\n/s' completo.bundle > enorme.bundle
+mkdir gigante
+cd gigante
+"$MINIGIT" init > /dev/null
+"$MINIGIT" bundle verify ../gigante.bundle
+echo "Código de saída: $?"
+"$MINIGIT" bundle unbundle ../enorme.bundle
+echo "Código de saída: $?"
+ls .minigit/objects | wc -l
+cd ..
+echo ""
+
+echo "--- 13. Árvore com nome de entrada inválido no bundle (ESPERADO: bundles recusados, nada gravado nem criado) ---"
+# Monta um bundle com um commit cuja árvore tem uma única entrada chamada $2
+arvore_maliciosa() {
+    perl -MCompress::Zlib -MDigest::SHA=sha1_hex -e '
+        my ($signature, $name) = @ARGV;
+        sub object { my $raw = "$_[0] " . length($_[1]) . "\0$_[1]"; return (sha1_hex($raw), $raw) }
+        my ($blob, $blob_raw) = object("blob", "pwned\n");
+        my ($tree, $tree_raw) = object("tree", "100644 $name\0$blob\n");
+        my ($commit, $commit_raw) = object("commit", "tree $tree\nauthor Malicioso\nmessage Malicioso\ntimestamp 0\n");
+        print "$signature\n$commit refs/heads/malicioso\n\n", compress($commit_raw . $tree_raw . $blob_raw);
+    ' "$(head -1 completo.bundle)" "$2" > "$1"
+}
+arvore_maliciosa pai.bundle ".."
+arvore_maliciosa repositorio.bundle ".minigit"
+arvore_maliciosa vazio.bundle ""
+mkdir arvores
+cd arvores
+"$MINIGIT" init > /dev/null
+"$MINIGIT" bundle verify ../pai.bundle
+echo "Código de saída: $?"
+"$MINIGIT" bundle unbundle ../repositorio.bundle
+echo "Código de saída: $?"
+"$MINIGIT" bundle unbundle ../vazio.bundle
+echo "Código de saída: $?"
+ls .minigit/objects | wc -l
+"$MINIGIT" branch
+cd ..
+"$MINIGIT" clone pai.bundle clone-pai
+echo "Código de saída: $?"
+ls pwned 2>&1 | sed 's/.*: //'
+echo ""
+
+echo "=== Teste concluído ==="
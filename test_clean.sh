@@ -0,0 +1,91 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+export MINIGIT_CONFIG_GLOBAL="$(pwd)/global.config"
+export MINIGIT_CONFIG_NOSYSTEM=1
+
+echo "=== Teste do comando clean ==="
+echo ""
+
+mkdir repo
+cd repo
+
+prepara() {
+    rm -rf build novo src/tmp vazio notas.txt src/rascunho.txt debug.log src/extra.log manter.tmp
+    mkdir -p build novo/sub src/tmp vazio
+    touch build/saida.o novo/a.txt novo/sub/b.txt src/tmp/c.txt notas.txt src/rascunho.txt debug.log src/extra.log manter.tmp
+}
+
+echo "--- 1. Criando o repositório ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Teste"
+mkdir src
+echo "fn main() {}" > src/main.rs
+printf 'build/\n*.log\n' > .gitignore
+"$MINIGIT" add .gitignore src/main.rs
+"$MINIGIT" commit "Primeiro commit"
+prepara
+echo ""
+
+echo "--- 2. Sem -f, -n ou -i (ESPERADO: erro) ---"
+"$MINIGIT" clean
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 3. -n lista só arquivos não rastreados fora de pastas novas (ESPERADO: manter.tmp, notas.txt, src/rascunho.txt) ---"
+"$MINIGIT" clean -n
+echo ""
+
+echo "--- 4. -d inclui pastas não rastreadas inteiras (ESPERADO: novo/, src/tmp/ e vazio/ como pastas) ---"
+"$MINIGIT" clean -n -d
+echo ""
+
+echo "--- 5. -x inclui os ignorados (ESPERADO: build/, debug.log e src/extra.log também) ---"
+"$MINIGIT" clean -n -d -x
+echo ""
+
+echo "--- 6. -X só os ignorados (ESPERADO: build/, debug.log e src/extra.log) ---"
+"$MINIGIT" clean -n -d -X
+echo ""
+
+echo "--- 7. -e protege arquivos, mesmo com -x (ESPERADO: sem manter.tmp nem debug.log) ---"
+"$MINIGIT" clean -n -d -x -e "*.tmp" -e debug.log
+echo ""
+
+echo "--- 8. Pathspec limita a limpeza (ESPERADO: só o que está em src) ---"
+"$MINIGIT" clean -n -d src
+echo ""
+
+echo "--- 9. Pathspec de um arquivo dentro de pasta nova não remove a pasta (ESPERADO: só novo/a.txt) ---"
+"$MINIGIT" clean -n -d novo/a.txt
+echo ""
+
+echo "--- 10. -f remove de fato (ESPERADO: arquivos rastreados, ignorados e .minigit intactos) ---"
+"$MINIGIT" clean -f -d
+find . -path ./.minigit -prune -o -print | sort
+echo ""
+
+echo "--- 11. Interativo: n, ajuda, n, y, q (ESPERADO: remove só src/rascunho.txt) ---"
+prepara
+printf 'n\n?\nn\ny\nq\n' | "$MINIGIT" clean -i
+echo ""
+ls
+echo ""
+
+echo "--- 12. clean.requireForce false dispensa o -f (ESPERADO: remove manter.tmp e notas.txt) ---"
+"$MINIGIT" config clean.requireForce false > /dev/null
+"$MINIGIT" clean
+echo ""
+
+echo "--- 13. Repositórios aninhados nunca são removidos (ESPERADO: sem interno/) ---"
+# O init não aceita repositórios aninhados, então a pasta .minigit é criada diretamente
+mkdir -p interno/.minigit
+touch interno/arquivo.txt
+"$MINIGIT" clean -n -d -x
+echo ""
+
+echo "=== Teste concluído ==="
@@ -0,0 +1,101 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+export MINIGIT_CONFIG_GLOBAL="$(pwd)/global.config"
+export MINIGIT_CONFIG_NOSYSTEM=1
+
+echo "=== Teste da configuração hierárquica ==="
+echo ""
+
+echo "--- 1. Configuração global fora de um repositório ---"
+"$MINIGIT" config --global user.name "Pessoa Global"
+"$MINIGIT" config --global user.email "global@exemplo.com"
+cat global.config
+echo ""
+
+echo "--- 2. Leitura fora de um repositório (ESPERADO: 'Pessoa Global') ---"
+"$MINIGIT" config --get user.name
+echo ""
+
+echo "--- 3. Escrita local fora de um repositório (ESPERADO: erro) ---"
+"$MINIGIT" config user.name "Local"
+echo "Código de saída: $?"
+echo ""
+
+mkdir repo
+cd repo
+
+echo "--- 4. Commit usa o autor global (ESPERADO: 'Pessoa Global <global@exemplo.com>') ---"
+"$MINIGIT" init
+echo "um" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Primeiro commit"
+"$MINIGIT" log | grep "Autor\|Author"
+echo ""
+
+echo "--- 5. Configuração local sobrescreve a global (ESPERADO: 'Pessoa Local') ---"
+"$MINIGIT" config user.name "Pessoa Local"
+"$MINIGIT" config user.name
+"$MINIGIT" config --global --get user.name
+echo ""
+
+echo "--- 6. Seções com subseção e aliases antigos ---"
+"$MINIGIT" config email "local@exemplo.com"
+"$MINIGIT" config branch.master.merge refs/heads/master
+"$MINIGIT" config 'remote.origin.url' "https://exemplo.com/repo"
+cat .minigit/config
+echo ""
+
+echo "--- 7. --list combina os escopos e --local filtra (ESPERADO: global primeiro, depois local) ---"
+"$MINIGIT" config --list
+echo "..."
+"$MINIGIT" config --list --local
+echo ""
+
+echo "--- 8. Valores tipados (ESPERADO: true, 2048, caminho expandido e erro) ---"
+"$MINIGIT" config core.filemode yes
+"$MINIGIT" config --type bool core.filemode
+"$MINIGIT" config --type int core.bigFileThreshold 2k
+"$MINIGIT" config --get core.bigfilethreshold
+"$MINIGIT" config core.excludesFile "~/ignorados"
+HOME=/home/exemplo "$MINIGIT" config --type path core.excludesfile
+"$MINIGIT" config --type int core.bigFileThreshold muito
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 9. Includes (ESPERADO: 'incluido') ---"
+printf '[extra]\n\tvalor = incluido\n' > ../extra.config
+"$MINIGIT" config include.path ../../extra.config
+"$MINIGIT" config extra.valor
+echo ""
+
+echo "--- 10. Configuração da worktree tem precedência sobre a local (ESPERADO: 'Pessoa da Worktree') ---"
+"$MINIGIT" config --worktree user.name "Pessoa da Worktree"
+"$MINIGIT" config user.name
+echo ""
+
+echo "--- 11. Variáveis de ambiente têm a maior precedência (ESPERADO: 'Pessoa do Ambiente') ---"
+MINIGIT_CONFIG_COUNT=1 MINIGIT_CONFIG_KEY_0=user.name MINIGIT_CONFIG_VALUE_0="Pessoa do Ambiente" "$MINIGIT" config user.name
+echo ""
+
+echo "--- 12. --unset e chave inexistente (ESPERADO: 'Pessoa Local' e depois erros) ---"
+"$MINIGIT" config --worktree --unset user.name
+"$MINIGIT" config user.name
+"$MINIGIT" config --unset remote.origin.url
+"$MINIGIT" config --get remote.origin.url
+echo "Código de saída: $?"
+"$MINIGIT" config --unset remote.origin.url
+echo ""
+
+echo "--- 13. Arquivo no formato antigo continua legível (ESPERADO: 'Antigo') ---"
+printf 'username Antigo\nemail antigo@exemplo.com\n' > .minigit/config
+"$MINIGIT" config user.name
+"$MINIGIT" config core.filemode false
+cat .minigit/config
+echo ""
+
+echo "=== Teste concluído ==="
@@ -0,0 +1,77 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste de arquivos executáveis e links simbólicos ==="
+echo ""
+
+echo "--- 1. Criando o repositório com um script, um link e uma pasta ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Teste"
+"$MINIGIT" config email "teste@exemplo.com"
+mkdir scripts
+printf '#!/bin/sh\necho ola\n' > scripts/build.sh
+chmod +x scripts/build.sh
+echo "leia-me" > README
+ln -s README LEIAME
+"$MINIGIT" add .
+"$MINIGIT" commit "Commit inicial"
+echo ""
+
+echo "--- 2. Modos na árvore (ESPERADO: 120000 LEIAME, 100644 README, 100755 scripts/build.sh e 040000 scripts) ---"
+TREE=$("$MINIGIT" log -n 1 --format=%T)
+"$MINIGIT" ls-tree "$TREE"
+"$MINIGIT" cat-file "$TREE"
+echo ""
+
+echo "--- 3. O blob do link guarda o destino (ESPERADO: 'README') ---"
+BLOB=$("$MINIGIT" ls-tree "$TREE" | grep LEIAME | cut -d' ' -f2)
+"$MINIGIT" cat-file "$BLOB"
+echo ""
+
+echo "--- 4. Mudar só o bit de execução aparece no status (ESPERADO: ' M scripts/build.sh') ---"
+chmod -x scripts/build.sh
+"$MINIGIT" status --porcelain
+chmod +x scripts/build.sh
+echo ""
+
+echo "--- 5. Checkout restaura o bit de execução e o link (ESPERADO: executável e link para README) ---"
+"$MINIGIT" switch -c sem-arquivos
+"$MINIGIT" rm scripts/build.sh LEIAME
+"$MINIGIT" commit "Remove o script e o link"
+ls scripts LEIAME 2>&1
+"$MINIGIT" switch master
+test -x scripts/build.sh && echo "scripts/build.sh é executável"
+test -L LEIAME && echo "LEIAME -> $(readlink LEIAME)"
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 6. restore também restaura o modo (ESPERADO: executável e status vazio) ---"
+rm scripts/build.sh LEIAME
+"$MINIGIT" restore .
+test -x scripts/build.sh && echo "scripts/build.sh é executável"
+test -L LEIAME && echo "LEIAME -> $(readlink LEIAME)"
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 7. Links quebrados também são rastreados (ESPERADO: 'A  quebrado') ---"
+ln -s nao-existe quebrado
+"$MINIGIT" add quebrado
+"$MINIGIT" status --porcelain
+"$MINIGIT" commit "Link quebrado"
+echo ""
+
+echo "--- 8. Com core.filemode false, o bit de execução é ignorado (ESPERADO: status vazio e 100755 mantido após o commit) ---"
+"$MINIGIT" config core.filemode false
+chmod -x scripts/build.sh
+"$MINIGIT" status --porcelain
+echo "echo tchau" >> scripts/build.sh
+"$MINIGIT" add scripts/build.sh
+"$MINIGIT" commit "Muda o script" > /dev/null
+"$MINIGIT" ls-tree "$("$MINIGIT" log -n 1 --format=%T)" | grep build.sh
+echo ""
+
+echo "=== Teste concluído ==="
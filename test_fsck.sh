@@ -0,0 +1,91 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do comando fsck e do core.verifyObjects ==="
+echo ""
+
+objeto() {
+    echo ".minigit/objects/${1:0:2}/${1:2}"
+}
+
+echo "--- 1. Criando o histórico ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Teste"
+echo "primeiro" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Primeiro commit" > /dev/null
+"$MINIGIT" branch quebrada
+echo ""
+
+echo "--- 2. Repositório íntegro (ESPERADO: nenhum problema) ---"
+"$MINIGIT" fsck
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 3. Commit de uma branch deletada (ESPERADO: dangling commit; com --unreachable também a árvore e o blob) ---"
+"$MINIGIT" switch -c temporaria > /dev/null
+echo "segundo" > b.txt
+"$MINIGIT" add b.txt
+"$MINIGIT" commit "Segundo commit" > /dev/null
+segundo=$("$MINIGIT" cat-file --batch <<< "HEAD" | sed -n 1p | cut -d' ' -f1)
+"$MINIGIT" switch master > /dev/null
+"$MINIGIT" branch -D temporaria > /dev/null
+"$MINIGIT" fsck | sed -E 's/[0-9a-f]{40}/<hash>/g'
+"$MINIGIT" fsck --unreachable | sed -E 's/[0-9a-f]{40}/<hash>/g'
+"$MINIGIT" fsck --no-dangling
+echo ""
+
+echo "--- 4. JSON estruturado (ESPERADO: object_count e issues com kind 'dangling') ---"
+"$MINIGIT" fsck --json | sed -E 's/[0-9a-f]{40}/<hash>/g'
+echo ""
+
+echo "--- 5. Conteúdo alterado (ESPERADO: hash-mismatch e código 6) ---"
+blob=$("$MINIGIT" cat-file --batch <<< "HEAD:a.txt" | sed -n 1p | cut -d' ' -f1)
+chmod u+w "$(objeto "$blob")"
+printf 'blob 8\0alterado' > "$(objeto "$blob")"
+"$MINIGIT" fsck 2>&1 | sed -E 's/[0-9a-f]{40}/<hash>/g'
+echo "Código de saída: ${PIPESTATUS[0]}"
+echo ""
+
+echo "--- 6. core.verifyObjects recusa o objeto alterado na leitura (ESPERADO: conteúdo lido, depois erro) ---"
+"$MINIGIT" cat-file -p HEAD:a.txt; echo ""
+"$MINIGIT" config core.verifyObjects true > /dev/null
+"$MINIGIT" cat-file -p HEAD:a.txt
+"$MINIGIT" config --unset core.verifyObjects > /dev/null
+echo ""
+
+echo "--- 7. Objeto removido e cabeçalho corrompido (ESPERADO: missing blob, corrupt e a árvore do commit corrompido solta) ---"
+rm -f "$(objeto "$blob")"
+chmod u+w "$(objeto "$segundo")"
+printf 'lixo sem cabeçalho' > "$(objeto "$segundo")"
+"$MINIGIT" fsck 2>&1 | sed -E 's/[0-9a-f]{40}/<hash>/g'
+echo ""
+
+echo "--- 8. Referências quebradas (ESPERADO: bad-ref para a branch e o HEAD) ---"
+echo "nao-e-um-hash" > .minigit/refs/heads/quebrada/index
+echo "0123456789012345678901234567890123456789" > .minigit/HEAD
+"$MINIGIT" fsck 2>&1 | grep "bad-ref\|Erro" | sed -E 's/[0-9a-f]{40}/<hash>/g'
+echo ""
+
+echo "--- 9. Árvore com nomes de entrada inválidos (ESPERADO: bad-tree-entry para '..', '.minigit' e 'a/b', e código 6) ---"
+echo "conteúdo" > nomes.txt
+blob=$("$MINIGIT" hash-object -w nomes.txt | sed -n "1s/.*: //p")
+entradas() {
+    printf '100644 ..\0%s\n100644 .minigit\0%s\n100644 a/b\0%s\n100644 ok.txt\0%s\n' "$blob" "$blob" "$blob" "$blob"
+}
+arvore_crua() {
+    printf 'tree %d\0' "$(entradas | wc -c)"
+    entradas
+}
+arvore=$(arvore_crua | sha1sum | cut -d' ' -f1)
+mkdir -p "$(dirname "$(objeto "$arvore")")"
+arvore_crua > "$(objeto "$arvore")"
+"$MINIGIT" fsck 2>&1 | grep "bad-tree-entry" | sed -E 's/[0-9a-f]{40}/<hash>/g'
+echo "Código de saída: ${PIPESTATUS[0]}"
+echo ""
+
+echo "=== Teste concluído ==="
@@ -0,0 +1,74 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do comando grep ==="
+echo ""
+
+echo "--- 1. Criando o histórico ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Teste"
+mkdir src
+printf 'fn main() {\n    println!("Olá");\n    iniciar();\n}\n' > src/main.rs
+printf 'fn iniciar() {\n    // TODO: configurar\n}\n\nfn parar() {\n    // todo: limpar\n}\n' > src/lib.rs
+printf 'Projeto de teste\nUse iniciar para começar\n' > leiame.txt
+printf 'bin\0ario iniciar\n' > dados.bin
+"$MINIGIT" add src leiame.txt dados.bin
+"$MINIGIT" commit "Primeiro commit" > /dev/null
+sed -i 's/iniciar/comecar/g' src/main.rs src/lib.rs leiame.txt
+"$MINIGIT" add src leiame.txt
+"$MINIGIT" commit "Renomeia iniciar" > /dev/null
+echo "não rastreado iniciar" > solto.txt
+echo ""
+
+echo "--- 2. Busca na worktree (ESPERADO: só arquivos rastreados, sem o binário nem solto.txt) ---"
+"$MINIGIT" grep comecar
+echo ""
+
+echo "--- 3. Busca em revisão antiga sem checkout (ESPERADO: linhas com 'iniciar' prefixadas por HEAD~1) ---"
+"$MINIGIT" grep -n iniciar HEAD~1
+cat src/lib.rs | head -1
+echo ""
+
+echo "--- 4. Expressão regular e -i (ESPERADO: TODO e todo) ---"
+"$MINIGIT" grep -i 'todo: \w+'
+echo ""
+
+echo "--- 5. -w só palavras inteiras (ESPERADO: só leiame.txt, sem 'comecar();') ---"
+"$MINIGIT" grep -w 'Use'
+"$MINIGIT" grep -w 'come'
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 6. -l e -c (ESPERADO: nomes dos arquivos e quantidades) ---"
+"$MINIGIT" grep -l fn
+"$MINIGIT" grep -c fn
+echo ""
+
+echo "--- 7. Contexto com -C, -A e -B (ESPERADO: linhas com '-' e separadores '--') ---"
+"$MINIGIT" grep -n -C 1 todo -i
+"$MINIGIT" grep -A 1 'fn main'
+"$MINIGIT" grep -B 1 -n '^}'  -- src/lib.rs
+echo ""
+
+echo "--- 8. Pathspec (ESPERADO: só src/main.rs) ---"
+"$MINIGIT" grep fn -- src/main.rs
+echo ""
+
+echo "--- 9. --cached lê o índice (ESPERADO: mudança não adicionada só aparece na worktree) ---"
+echo "novo texto" >> leiame.txt
+"$MINIGIT" grep novo
+"$MINIGIT" grep --cached novo
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 10. Expressão inválida e revisão inexistente (ESPERADO: erros) ---"
+"$MINIGIT" grep '(abc'
+echo "Código de saída: $?"
+"$MINIGIT" grep abc nada
+echo ""
+
+echo "=== Teste concluído ==="
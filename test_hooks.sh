@@ -0,0 +1,72 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste dos Ganchos (hooks) ==="
+echo ""
+
+echo "--- 1. Inicializando repositório ---"
+"$MINIGIT" init
+echo "Ganchos de exemplo instalados:"
+ls .minigit/hooks
+echo ""
+
+echo "--- 2. Ativando commit-msg que exige identificador de ticket ---"
+cp .minigit/hooks/commit-msg.sample .minigit/hooks/commit-msg
+chmod +x .minigit/hooks/commit-msg
+
+echo "Conteúdo" > arquivo.txt
+"$MINIGIT" add arquivo.txt
+echo "Commit sem ticket (ESPERADO: abortado):"
+"$MINIGIT" commit "Commit sem ticket"
+echo "Commit com ticket (ESPERADO: sucesso):"
+"$MINIGIT" commit "PROJ-1: Commit com ticket"
+echo ""
+
+echo "--- 3. pre-commit que sempre falha, ignorado com --no-verify ---"
+printf '#!/bin/sh\necho "pre-commit recusou" >&2\nexit 1\n' > .minigit/hooks/pre-commit
+chmod +x .minigit/hooks/pre-commit
+echo "Outro conteúdo" > arquivo.txt
+"$MINIGIT" add arquivo.txt
+echo "Commit com pre-commit falhando (ESPERADO: abortado):"
+"$MINIGIT" commit "PROJ-2: Bloqueado"
+echo "Commit com --no-verify (ESPERADO: sucesso, sem os espaços e linhas em branco no fim da mensagem):"
+"$MINIGIT" commit --no-verify $'Sem verificação  \n\n'
+"$MINIGIT" cat-file -p HEAD | sed -n '/^message/,/^timestamp/p' | sed '$d; s/$/|/'
+rm .minigit/hooks/pre-commit
+echo ""
+
+echo "--- 4. post-checkout recebe os argumentos ---"
+printf '#!/bin/sh\necho "post-checkout: $1 -> $2 ($3)"\n' > .minigit/hooks/post-checkout
+chmod +x .minigit/hooks/post-checkout
+"$MINIGIT" branch develop
+"$MINIGIT" checkout develop
+echo ""
+
+echo "--- 5. pre-merge que falha aborta o merge ---"
+printf '#!/bin/sh\necho "pre-merge recusou $1" >&2\nexit 1\n' > .minigit/hooks/pre-merge
+chmod +x .minigit/hooks/pre-merge
+"$MINIGIT" checkout master
+"$MINIGIT" merge develop
+echo ""
+
+echo "--- 6. rebase --continue não executa os ganchos de commit (ESPERADO: commit sem ticket reaplicado com a mensagem original) ---"
+rm .minigit/hooks/pre-merge
+"$MINIGIT" checkout develop > /dev/null
+echo "develop" > arquivo.txt
+"$MINIGIT" add arquivo.txt
+"$MINIGIT" commit --no-verify "Sem ticket na develop" > /dev/null
+"$MINIGIT" checkout master > /dev/null
+echo "master" > arquivo.txt
+"$MINIGIT" add arquivo.txt
+"$MINIGIT" commit "PROJ-3: Muda o arquivo na master" > /dev/null
+"$MINIGIT" checkout develop > /dev/null
+"$MINIGIT" rebase master > /dev/null
+echo "develop resolvido" > arquivo.txt
+"$MINIGIT" add arquivo.txt
+"$MINIGIT" rebase --continue | sed -E 's/[0-9a-f]{40}/<hash>/'
+"$MINIGIT" log --oneline | sed -n 1p | cut -d' ' -f2-
+echo ""
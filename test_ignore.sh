@@ -0,0 +1,101 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+export MINIGIT_CONFIG_GLOBAL="$(pwd)/global.config"
+export MINIGIT_CONFIG_NOSYSTEM=1
+
+echo "=== Teste das regras de ignore ==="
+echo ""
+
+mkdir repo
+cd repo
+"$MINIGIT" init > /dev/null
+"$MINIGIT" config username "Teste" > /dev/null
+
+mkdir -p build src/gerado docs/interno logs/antigos
+touch a.log importante.log build/saida.o src/main.rs src/gerado/x.rs src/build docs/interno/segredo.txt docs/leia.md
+touch logs/antigos/velho.txt logs/manter.txt "#hash" "!exclama" raiz.tmp src/raiz.tmp notas.bak src/notas.bak
+
+cat > .gitignore <<'EOF'
+# comentário
+*.log
+!importante.log
+build/
+/raiz.tmp
+docs/**/segredo.txt
+logs/*
+!logs/antigos/velho.txt
+\#hash
+\!exclama
+EOF
+cat > src/.gitignore <<'EOF'
+gerado/
+!*.log
+EOF
+
+echo "--- 1. Padrões simples, negação e comentários (ESPERADO: a.log ignorado, importante.log não) ---"
+"$MINIGIT" check-ignore -v -n a.log importante.log
+echo ""
+
+echo "--- 2. Regra só para pastas (ESPERADO: build/saida.o ignorado, src/build não) ---"
+"$MINIGIT" check-ignore -v -n build/saida.o src/build
+echo ""
+
+echo "--- 3. Barra no início ancora na pasta do .gitignore (ESPERADO: só raiz.tmp) ---"
+"$MINIGIT" check-ignore raiz.tmp src/raiz.tmp
+echo ""
+
+echo "--- 4. ** casa com qualquer quantidade de pastas (ESPERADO: docs/interno/segredo.txt) ---"
+"$MINIGIT" check-ignore -v docs/interno/segredo.txt docs/leia.md
+echo ""
+
+echo "--- 5. Não é possível voltar a incluir arquivo de pasta ignorada (ESPERADO: ambos ignorados por logs/*) ---"
+"$MINIGIT" check-ignore -v logs/antigos/velho.txt logs/manter.txt
+echo ""
+
+echo "--- 6. \\# e \\! escapados (ESPERADO: ambos ignorados) ---"
+"$MINIGIT" check-ignore -v "#hash" "!exclama"
+echo ""
+
+echo "--- 7. .gitignore de subpasta tem precedência (ESPERADO: src/gerado/x.rs ignorado e src/x.log não) ---"
+touch src/x.log
+"$MINIGIT" check-ignore -v -n src/gerado/x.rs src/x.log
+echo ""
+
+echo "--- 8. .minigit/info/exclude e core.excludesFile (ESPERADO: notas.bak pelo exclude e src/notas.bak pelo global) ---"
+mkdir -p .minigit/info
+echo "/notas.bak" > .minigit/info/exclude
+echo "*.bak" > ../excludes
+"$MINIGIT" config --global core.excludesFile "$(cd .. && pwd)/excludes" > /dev/null
+"$MINIGIT" check-ignore -v notas.bak src/notas.bak | sed "s|$(cd .. && pwd)|<global>|"
+echo ""
+
+echo "--- 9. Caminhos relativos ao diretório atual (ESPERADO: gerado/x.rs) ---"
+cd src
+"$MINIGIT" check-ignore gerado/x.rs main.rs
+cd ..
+echo ""
+
+echo "--- 10. Nenhum caminho ignorado sai com código 1 (ESPERADO: 1) ---"
+"$MINIGIT" check-ignore src/main.rs
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 11. Arquivos rastreados não são ignorados, exceto com --no-index (ESPERADO: só na segunda) ---"
+"$MINIGIT" add src/main.rs > /dev/null
+echo "main.rs" >> .minigit/info/exclude
+"$MINIGIT" check-ignore src/main.rs
+echo "Código de saída: $?"
+"$MINIGIT" check-ignore -v --no-index src/main.rs
+echo ""
+
+echo "--- 12. Status e add respeitam as regras do worktree sem precisar adicioná-las (ESPERADO: sem build/, logs/, *.bak) ---"
+"$MINIGIT" add . > /dev/null
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "=== Teste concluído ==="
@@ -0,0 +1,77 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do Índice Binário ==="
+echo ""
+
+echo "--- 1. Adicionando arquivo com espaço no nome ---"
+"$MINIGIT" init
+echo "Conteúdo" > "arquivo com espaço.txt"
+echo "Outro" > normal.txt
+"$MINIGIT" add "arquivo com espaço.txt" normal.txt
+"$MINIGIT" commit "C1: Commit inicial"
+echo ""
+
+echo "--- 2. Cabeçalho do índice (ESPERADO: começa com 'MIDX') ---"
+head -c 4 .minigit/index
+echo ""
+echo ""
+
+echo "--- 3. status após o commit (ESPERADO: árvore limpa) ---"
+"$MINIGIT" status
+echo ""
+
+echo "--- 4. Modificando arquivo com o mesmo tamanho (ESPERADO: ' M arquivo com espaço.txt') ---"
+echo "Alterado" > "arquivo com espaço.txt"
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 5. Índice no formato de texto antigo continua sendo lido (ESPERADO: 'M  arquivo com espaço.txt') ---"
+HASH=$("$MINIGIT" hash-object normal.txt | awk '{print $NF}')
+printf "0 33188 %s normal.txt\n" "$HASH" > .minigit/index
+"$MINIGIT" add "arquivo com espaço.txt"
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 6. Índice corrompido (ESPERADO: erro de índice corrompido) ---"
+cp .minigit/index ../index.bak
+printf "X" >> .minigit/index
+"$MINIGIT" status
+echo "Código de saída: $?"
+cp ../index.bak .minigit/index
+rm ../index.bak
+echo ""
+
+echo "--- 7. Conflito de merge registrado no índice ---"
+"$MINIGIT" commit "C2: Altera arquivo"
+"$MINIGIT" branch develop
+echo "Versão master" > normal.txt
+"$MINIGIT" add normal.txt
+"$MINIGIT" commit "C3: Master"
+"$MINIGIT" checkout develop
+echo "Versão develop" > normal.txt
+"$MINIGIT" add normal.txt
+"$MINIGIT" commit "C4: Develop"
+"$MINIGIT" checkout master
+"$MINIGIT" merge develop
+echo ""
+
+echo "--- 8. status com conflito (ESPERADO: 'UU normal.txt') ---"
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 9. merge --continue sem resolver (ESPERADO: erro de conflito) ---"
+"$MINIGIT" merge --continue
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 10. Resolvendo com add e continuando (ESPERADO: commit de merge criado) ---"
+echo "Versão resolvida" > normal.txt
+"$MINIGIT" add normal.txt
+"$MINIGIT" status --porcelain
+"$MINIGIT" merge --continue
+"$MINIGIT" status
@@ -0,0 +1,88 @@
+#!/bin/bash
+
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste das atualizações atômicas com arquivos de lock ==="
+echo ""
+
+echo "--- 1. Repositório com um commit (ESPERADO: nenhum arquivo .lock) ---"
+"$MINIGIT" init > /dev/null
+echo "um" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Primeiro commit" > /dev/null
+find .minigit -name "*.lock"
+echo ""
+
+echo "--- 2. Índice travado por outro processo (ESPERADO: erro claro, código 16, índice intacto) ---"
+touch .minigit/index.lock
+echo "dois" > b.txt
+"$MINIGIT" add b.txt
+echo "Código de saída: $?"
+"$MINIGIT" status
+echo ""
+
+echo "--- 3. Lock removido (ESPERADO: add funciona e não deixa lock para trás) ---"
+rm .minigit/index.lock
+"$MINIGIT" add b.txt
+"$MINIGIT" status
+find .minigit -name "*.lock"
+echo ""
+
+echo "--- 4. Branch travada (ESPERADO: commit recusado com código 16, branch no mesmo commit) ---"
+antes=$(cat .minigit/refs/heads/master/index)
+touch .minigit/refs/heads/master/index.lock
+"$MINIGIT" commit "Segundo commit"
+echo "Código de saída: $?"
+[ "$(cat .minigit/refs/heads/master/index)" = "$antes" ] && echo "branch não mudou"
+rm .minigit/refs/heads/master/index.lock
+echo ""
+
+echo "--- 5. Configuração travada (ESPERADO: erro e valor antigo mantido) ---"
+"$MINIGIT" config user.name "Pessoa"
+touch .minigit/config.lock
+"$MINIGIT" config user.name "Outra Pessoa"
+echo "Código de saída: $?"
+"$MINIGIT" config user.name
+rm .minigit/config.lock
+echo ""
+
+echo "--- 6. Branch movida por outro processo durante o commit (ESPERADO: compare-and-swap recusa, branch fica com o valor do outro processo) ---"
+"$MINIGIT" commit "Segundo commit" > /dev/null
+echo "três" > c.txt
+"$MINIGIT" add c.txt
+printf '#!/bin/sh\necho "%s" > .minigit/refs/heads/master/index\n' "$antes" > .minigit/hooks/pre-commit
+chmod +x .minigit/hooks/pre-commit
+"$MINIGIT" commit "Terceiro commit"
+echo "Código de saída: $?"
+[ "$(cat .minigit/refs/heads/master/index)" = "$antes" ] && echo "branch com o valor do outro processo"
+rm .minigit/hooks/pre-commit
+echo ""
+
+echo "--- 7. Vários add simultâneos (ESPERADO: índice sempre legível, sem locks esquecidos) ---"
+for i in $(seq 1 20); do echo "$i" > "f$i.txt"; done
+for i in $(seq 1 20); do "$MINIGIT" add "f$i.txt" 2> /dev/null & done
+wait
+"$MINIGIT" status > /dev/null && echo "índice legível"
+find .minigit -name "*.lock"
+"$MINIGIT" fsck --no-dangling | sed -E "s/[0-9]+ objetos/N objetos/"
+echo ""
+
+echo "--- 8. mv e restore com o índice travado (ESPERADO: recusados com código 16, nada alterado) ---"
+touch .minigit/index.lock
+"$MINIGIT" mv a.txt movido.txt
+echo "Código de saída: $?"
+ls movido.txt 2>&1 | sed 's/.*: //'
+echo "alterado" > a.txt
+"$MINIGIT" restore a.txt
+echo "Código de saída: $?"
+cat a.txt
+rm .minigit/index.lock
+"$MINIGIT" restore a.txt
+cat a.txt
+echo ""
+
+echo "=== Teste concluído ==="
@@ -0,0 +1,92 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do Histórico (log) ==="
+echo ""
+
+echo "--- 1. Criando histórico com duas branches e um merge ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Ana"
+"$MINIGIT" config email "ana@exemplo.com"
+printf "linha 1\nlinha 2\nlinha 3\n" > arquivo1.txt
+"$MINIGIT" add arquivo1.txt
+"$MINIGIT" commit "C1: Commit inicial"
+"$MINIGIT" branch feature
+"$MINIGIT" checkout feature
+mkdir docs
+echo "Documentação" > docs/leiame.txt
+"$MINIGIT" add docs/leiame.txt
+"$MINIGIT" commit "C2: Adiciona documentação"
+"$MINIGIT" checkout master
+printf "linha 1\nlinha 2 alterada\nlinha 3\n" > arquivo1.txt
+"$MINIGIT" add arquivo1.txt
+"$MINIGIT" commit "C3: Altera arquivo1"
+"$MINIGIT" merge feature
+echo ""
+
+echo "--- 2. log --oneline (ESPERADO: merge, C3, C2 e C1) ---"
+"$MINIGIT" log --oneline
+echo ""
+
+echo "--- 3. log --graph --oneline (ESPERADO: C2 em uma coluna separada) ---"
+"$MINIGIT" log --graph --oneline
+echo ""
+
+echo "--- 4. log -n 2 --format='%h %an <%ae> %s' (ESPERADO: 2 commits de Ana <ana@exemplo.com>) ---"
+"$MINIGIT" log -n 2 --format='%h %an <%ae> %s'
+echo ""
+
+echo "--- 5. log --grep C2 (ESPERADO: apenas C2) ---"
+"$MINIGIT" log --oneline --grep C2
+echo ""
+
+echo "--- 6. log --author Ninguem (ESPERADO: nenhum commit) ---"
+"$MINIGIT" log --oneline --author Ninguem
+echo ""
+
+echo "--- 7. log master..feature (ESPERADO: nada, feature já foi mesclada) e feature..master (ESPERADO: merge e C3) ---"
+"$MINIGIT" log --oneline master..feature
+"$MINIGIT" log --oneline feature..master
+echo ""
+
+echo "--- 8. log HEAD~1 (ESPERADO: C3 e C1) ---"
+"$MINIGIT" log --oneline HEAD~1
+echo ""
+
+echo "--- 9. log -- docs (ESPERADO: apenas C2) ---"
+"$MINIGIT" log --oneline -- docs
+echo ""
+
+echo "--- 10. log --since/--until (ESPERADO: todos os commits, depois nenhum) ---"
+"$MINIGIT" log --oneline --since "1 day ago"
+"$MINIGIT" log --oneline --until "2000-01-01"
+echo ""
+
+echo "--- 11. log --follow após renomear arquivo (ESPERADO: renomeação e C3, C1 com o nome antigo) ---"
+mv arquivo1.txt renomeado.txt
+"$MINIGIT" rm arquivo1.txt
+"$MINIGIT" add renomeado.txt
+"$MINIGIT" commit "C5: Renomeia arquivo1"
+"$MINIGIT" log --oneline --follow -- renomeado.txt
+echo ""
+
+echo "--- 12. log -n 1 --stat -p HEAD~1 (ESPERADO: resumo e diff do merge) ---"
+"$MINIGIT" log -n 1 --stat -p HEAD~1
+echo ""
+
+echo "--- 13. log --all --oneline após novo commit na feature (ESPERADO: inclui C6) ---"
+"$MINIGIT" checkout feature
+echo "Mais documentação" >> docs/leiame.txt
+"$MINIGIT" add docs/leiame.txt
+"$MINIGIT" commit "C6: Amplia documentação"
+"$MINIGIT" checkout master
+"$MINIGIT" log --all --graph --oneline
+echo ""
+
+echo "--- 14. log revisão inexistente (ESPERADO: erro) ---"
+"$MINIGIT" log naoexiste
+echo "Código de saída: $?"
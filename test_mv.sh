@@ -0,0 +1,99 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do comando mv e da detecção de renomeações ==="
+echo ""
+
+echo "--- 1. Criando o repositório ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Teste"
+"$MINIGIT" config email "teste@exemplo.com"
+mkdir -p src docs
+for i in 1 2 3 4 5 6 7 8 9 10; do echo "linha $i" >> src/texto.txt; done
+echo "fn main() {}" > src/main.rs
+echo "Leiame" > docs/leiame.txt
+"$MINIGIT" add .
+"$MINIGIT" commit "Commit inicial"
+echo ""
+
+echo "--- 2. mv de arquivo (ESPERADO: 'R  src/texto.txt -> notas.txt') ---"
+"$MINIGIT" mv src/texto.txt notas.txt
+ls notas.txt
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 3. status legível (ESPERADO: 'renomeado: src/texto.txt -> notas.txt') ---"
+"$MINIGIT" status | grep renomeado
+"$MINIGIT" commit "Renomeia texto"
+echo ""
+
+echo "--- 4. mv para arquivo existente sem -f (ESPERADO: erro, nada muda) ---"
+"$MINIGIT" mv src/main.rs docs/leiame.txt
+echo "Código de saída: $?"
+cat docs/leiame.txt
+echo ""
+
+echo "--- 5. mv com -f (ESPERADO: docs/leiame.txt com 'fn main() {}', sem cópia temporária em docs) ---"
+"$MINIGIT" mv -f src/main.rs docs/leiame.txt
+cat docs/leiame.txt
+ls -A docs
+"$MINIGIT" status --porcelain
+"$MINIGIT" commit "Sobrescreve leiame"
+echo ""
+
+echo "--- 6. mv de diretório para dentro de outro (ESPERADO: 'R  docs/leiame.txt -> arquivo/docs/leiame.txt') ---"
+mkdir arquivo
+"$MINIGIT" mv docs arquivo
+ls arquivo/docs
+"$MINIGIT" status --porcelain
+"$MINIGIT" commit "Move docs"
+echo ""
+
+echo "--- 7. mv de vários arquivos para um não-diretório (ESPERADO: erro) ---"
+echo "a" > a.txt
+echo "b" > b.txt
+"$MINIGIT" add a.txt b.txt
+"$MINIGIT" mv a.txt b.txt c.txt
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 8. mv de arquivo não rastreado (ESPERADO: erro) ---"
+echo "solto" > solto.txt
+"$MINIGIT" mv solto.txt outro.txt
+echo "Código de saída: $?"
+rm solto.txt
+"$MINIGIT" commit "Adiciona a e b"
+echo ""
+
+echo "--- 9. Renomeação com modificação no diff (ESPERADO: 'notas.txt -> anotacoes.txt (renomeado, 95% semelhante)') ---"
+mv notas.txt anotacoes.txt
+echo "linha 11" >> anotacoes.txt
+"$MINIGIT" diff
+echo ""
+
+echo "--- 10. Renomeação semelhante no log --stat (ESPERADO: 'notas.txt -> anotacoes.txt | 1 +') ---"
+"$MINIGIT" add -A
+"$MINIGIT" commit "Renomeia e modifica notas"
+"$MINIGIT" log -n 1 --stat --format="%s"
+echo ""
+
+echo "--- 11. Patch de renomeação (ESPERADO: 'rename from notas.txt' e 'rename to anotacoes.txt') ---"
+"$MINIGIT" log -n 1 -p --format="%s" | grep -E "similarity|rename|^\+linha"
+echo ""
+
+echo "--- 12. Cópia (ESPERADO: 'C  anotacoes.txt -> copia.txt') ---"
+cp anotacoes.txt copia.txt
+"$MINIGIT" add copia.txt
+"$MINIGIT" status --porcelain
+"$MINIGIT" commit "Copia anotacoes"
+echo ""
+
+echo "--- 13. log --follow segue as renomeações (ESPERADO: 3 commits, até 'Commit inicial') ---"
+"$MINIGIT" log --oneline --follow -- anotacoes.txt
+echo ""
+
+echo "=== Teste concluído ==="
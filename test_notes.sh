@@ -0,0 +1,84 @@
+#!/bin/bash
+
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste das notas de commits ==="
+echo ""
+
+echo "--- 1. Repositório com dois commits ---"
+"$MINIGIT" init > /dev/null
+echo "um" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Primeiro commit" > /dev/null
+echo "dois" >> a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Segundo commit" > /dev/null
+primeiro=$("$MINIGIT" log --format "%H" | sed -n 2p)
+segundo=$("$MINIGIT" log --format "%H" | sed -n 1p)
+echo ""
+
+echo "--- 2. Nota no HEAD e no primeiro commit (ESPERADO: o commit não muda, notes show exibe o texto) ---"
+"$MINIGIT" notes add -m "build: ok"
+"$MINIGIT" notes add "$primeiro" -m "build: falhou"
+[ "$("$MINIGIT" log --format "%H" | sed -n 1p)" = "$segundo" ] && echo "HEAD continua em $segundo" | sed -E 's/[0-9a-f]{40}/<hash>/'
+"$MINIGIT" notes show
+"$MINIGIT" notes show HEAD~1
+echo ""
+
+echo "--- 3. Nota repetida (ESPERADO: erro sem -f, substituída com -f) ---"
+"$MINIGIT" notes add -m "outra"
+echo "Código de saída: $?"
+"$MINIGIT" notes add -f -m "build: ok (refeito)"
+"$MINIGIT" notes show
+echo ""
+
+echo "--- 4. Append (ESPERADO: texto acrescentado depois de uma linha em branco) ---"
+"$MINIGIT" notes append -m "revisado-por: Ana"
+"$MINIGIT" notes show
+echo ""
+
+echo "--- 5. list (ESPERADO: '<nota> <commit>' para os dois commits; com commit, só o hash da nota) ---"
+"$MINIGIT" notes list | sed -E 's/[0-9a-f]{40}/<hash>/g'
+"$MINIGIT" notes list | wc -l
+[ "$("$MINIGIT" notes list HEAD)" = "$("$MINIGIT" notes list | grep "$segundo" | cut -d' ' -f1)" ] && echo "notes list HEAD confere"
+echo ""
+
+echo "--- 6. Notas no log e no show (ESPERADO: bloco 'Notas:' abaixo da mensagem; nada no --oneline) ---"
+"$MINIGIT" log | grep -v "^Data:" | sed -E 's/[0-9a-f]{40}/<hash>/'
+"$MINIGIT" log --oneline | cut -d' ' -f2-
+"$MINIGIT" show | sed -n '/Notas:/,/^$/p'
+echo ""
+
+echo "--- 7. Outro namespace com --ref (ESPERADO: separado de 'commits'; log --notes escolhe o namespace) ---"
+"$MINIGIT" notes --ref ci add -m "pipeline #42 verde"
+"$MINIGIT" notes list --ref ci | wc -l
+"$MINIGIT" notes --ref refs/notes/ci show
+"$MINIGIT" log -n 1 --notes ci | sed -n '/Notas/,$p'
+"$MINIGIT" log -n 1 --notes ci --notes commits | grep "^Notas"
+"$MINIGIT" log -n 1 --no-notes | grep -c "^Notas"
+echo ""
+
+echo "--- 8. Histórico das notas (ESPERADO: refs/notes/commits aponta para um commit com 4 versões) ---"
+"$MINIGIT" log --format "%s" "$(cat .minigit/refs/notes/commits/index)"
+"$MINIGIT" fsck --no-dangling
+echo ""
+
+echo "--- 9. Remove (ESPERADO: nota removida; remover de novo falha) ---"
+"$MINIGIT" notes remove "$primeiro" | sed -E 's/[0-9a-f]{40}/<hash>/'
+"$MINIGIT" notes show "$primeiro"
+echo "Código de saída: $?"
+"$MINIGIT" notes remove "$primeiro"
+echo "Código de saída: $?"
+"$MINIGIT" notes list | wc -l
+echo ""
+
+echo "--- 10. Namespace inválido (ESPERADO: erro) ---"
+"$MINIGIT" notes --ref "../fora" list
+echo "Código de saída: $?"
+echo ""
+
+echo "=== Teste concluído ==="
@@ -0,0 +1,120 @@
+#!/bin/bash
+
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do diário de merge e rebase ==="
+echo ""
+
+echo "--- 1. Preparando branches divergentes ---"
+"$MINIGIT" init > /dev/null
+echo "base" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Base" > /dev/null
+"$MINIGIT" branch feature
+"$MINIGIT" switch feature > /dev/null
+echo "f1" > f1.txt
+"$MINIGIT" add f1.txt
+"$MINIGIT" commit "Feature 1" > /dev/null
+echo "feature" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Feature 2 muda a.txt" > /dev/null
+echo "f3" > f3.txt
+"$MINIGIT" add f3.txt
+"$MINIGIT" commit "Feature 3" > /dev/null
+"$MINIGIT" switch master > /dev/null
+echo "m1" > m1.txt
+"$MINIGIT" add m1.txt
+"$MINIGIT" commit "Master 1" > /dev/null
+echo "m2" > m2.txt
+"$MINIGIT" add m2.txt
+"$MINIGIT" commit "Master 2" > /dev/null
+"$MINIGIT" switch feature > /dev/null
+echo ""
+
+echo "--- 2. Rebase para no conflito (ESPERADO: diário com 1 commit feito, 2 restantes e stopped-sha) ---"
+"$MINIGIT" rebase master
+ls .minigit/sequencer
+echo "feitos: $(wc -l < .minigit/sequencer/done), restantes: $(wc -l < .minigit/sequencer/todo)"
+[ "$(cat .minigit/sequencer/stopped-sha)" = "$(cat .minigit/REBASE_HEAD)" ] && echo "REBASE_HEAD = stopped-sha"
+echo ""
+
+echo "--- 3. Status descreve o rebase (ESPERADO: 1 de 3 aplicados, parado por conflitos) ---"
+"$MINIGIT" status
+echo ""
+
+echo "--- 4. Conflito resolvido com a versão da feature (ESPERADO: status sugere rebase --continue) ---"
+echo "feature" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" status | sed -n 3,6p
+echo ""
+
+echo "--- 5. Processo morre entre gravar 'done' e 'todo' (ESPERADO: erro de lock, status mostra 2 de 3 e interrompido) ---"
+touch .minigit/sequencer/todo.lock
+"$MINIGIT" rebase --continue
+echo "Código de saída: $?"
+rm .minigit/sequencer/todo.lock
+"$MINIGIT" status | sed -n 3,6p
+echo ""
+
+echo "--- 6. Retomando (ESPERADO: rebase concluído, diário removido, histórico linear) ---"
+"$MINIGIT" rebase --continue | sed -E 's/[0-9a-f]{40}/<hash>/'
+ls .minigit/sequencer .minigit/REBASE_HEAD .minigit/ORIG_HEAD 2>&1 | sed 's/.*cannot access/não existe:/'
+"$MINIGIT" log --oneline | cut -d' ' -f2-
+cat a.txt
+echo ""
+
+echo "--- 7. Processo morre logo depois de iniciar o rebase (ESPERADO: branch intacta, status sugere continue ou abort) ---"
+"$MINIGIT" switch master > /dev/null
+echo "m3" > m3.txt
+"$MINIGIT" add m3.txt
+"$MINIGIT" commit "Master 3" > /dev/null
+"$MINIGIT" switch feature > /dev/null
+antes=$(cat .minigit/refs/heads/feature/index)
+touch .minigit/refs/heads/feature/index.lock
+"$MINIGIT" rebase master > /dev/null
+echo "Código de saída: $?"
+rm .minigit/refs/heads/feature/index.lock
+[ "$(cat .minigit/refs/heads/feature/index)" = "$antes" ] && echo "branch intacta"
+"$MINIGIT" status | sed -n 3,6p
+echo ""
+
+echo "--- 8. Abortando depois da interrupção (ESPERADO: branch e arquivos de volta, diário removido) ---"
+"$MINIGIT" rebase --abort
+[ "$(cat .minigit/refs/heads/feature/index)" = "$antes" ] && echo "branch no commit original"
+ls m3.txt .minigit/sequencer 2>&1 | sed 's/.*cannot access/não existe:/'
+"$MINIGIT" status | sed -n 1,3p
+echo ""
+
+echo "--- 9. Merge com conflito (ESPERADO: status descreve o merge; abort restaura o índice) ---"
+"$MINIGIT" branch outra
+"$MINIGIT" switch outra > /dev/null
+echo "outra" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Outra muda a.txt" > /dev/null
+"$MINIGIT" switch feature > /dev/null
+echo "de novo a feature" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Feature 4 muda a.txt" > /dev/null
+"$MINIGIT" merge outra | sed -n 1,2p
+"$MINIGIT" status | sed -n 3,6p
+"$MINIGIT" merge --abort
+"$MINIGIT" status --porcelain
+cat a.txt
+echo ""
+
+echo "--- 10. Merge interrompido antes de mover a branch (ESPERADO: merge --continue só termina a atualização) ---"
+touch .minigit/refs/heads/feature/index.lock
+"$MINIGIT" merge master > /dev/null
+echo "Código de saída: $?"
+rm .minigit/refs/heads/feature/index.lock
+"$MINIGIT" status | sed -n 3,5p
+"$MINIGIT" merge --continue | sed -E 's/[0-9a-f]{40}/<hash>/'
+"$MINIGIT" log --oneline | sed -n 1p | cut -d' ' -f2-
+ls m3.txt .minigit/sequencer .minigit/MERGE_HEAD 2>&1 | sed 's/.*cannot access/não existe:/'
+echo ""
+
+echo "=== Teste concluído ==="
@@ -0,0 +1,105 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste de Pathspecs em add e rm ==="
+echo ""
+
+echo "--- 1. Criando arquivos em pastas ---"
+"$MINIGIT" init
+mkdir -p src/modulo docs
+echo "fn main() {}" > src/main.rs
+echo "pub fn a() {}" > src/modulo/a.rs
+echo "pub fn b() {}" > src/modulo/b.rs
+echo "Notas" > src/notas.txt
+echo "Leiame" > docs/leiame.txt
+echo "Raíz" > raiz.txt
+echo ""
+
+echo "--- 2. add com glob e exclusão (ESPERADO: 'A  src/main.rs' e 'A  src/modulo/b.rs') ---"
+"$MINIGIT" add '*.rs' ':(exclude)src/modulo/a.rs'
+"$MINIGIT" status --porcelain | grep "^A"
+echo ""
+
+echo "--- 3. add . dentro de uma pasta (ESPERADO: src/modulo/a.rs e src/notas.txt adicionados) ---"
+cd src
+"$MINIGIT" add .
+cd ..
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 4. add de pathspec inexistente (ESPERADO: erro) ---"
+"$MINIGIT" add naoexiste.txt
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 5. add -A e commit (ESPERADO: todos os arquivos no commit) ---"
+"$MINIGIT" add -A
+"$MINIGIT" commit "C1: Commit inicial"
+"$MINIGIT" log -n 1 --stat --format="%s"
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 6. add -u (ESPERADO: 'M  raiz.txt', 'D  docs/leiame.txt' e '?? novo.txt') ---"
+echo "Raíz alterada" > raiz.txt
+rm docs/leiame.txt
+echo "Novo" > novo.txt
+"$MINIGIT" add -u
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 7. add -p aceitando o primeiro trecho e recusando o segundo ---"
+"$MINIGIT" add novo.txt
+"$MINIGIT" commit "C2: Atualiza arquivos"
+printf "linha 1\nlinha 2\nlinha 3\nlinha 4\nlinha 5\nlinha 6\nlinha 7\nlinha 8\nlinha 9\nlinha 10\n" > novo.txt
+"$MINIGIT" add novo.txt
+"$MINIGIT" commit "C3: Preenche novo.txt"
+printf "linha 1 alterada\nlinha 2\nlinha 3\nlinha 4\nlinha 5\nlinha 6\nlinha 7\nlinha 8\nlinha 9\nlinha 10 alterada\n" > novo.txt
+printf "y\nn\n" | "$MINIGIT" add -p novo.txt
+echo ""
+echo "ESPERADO: 'MM novo.txt' (primeiro trecho no índice, segundo apenas na worktree)"
+"$MINIGIT" status --porcelain
+"$MINIGIT" diff
+echo ""
+
+echo "--- 8. rm de pasta sem -r (ESPERADO: erro) ---"
+"$MINIGIT" rm src/modulo
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 9. rm de arquivo com mudanças (ESPERADO: erro pedindo -f ou --cached) ---"
+"$MINIGIT" rm novo.txt
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 10. rm --cached (ESPERADO: arquivo continua na worktree e aparece como não rastreado) ---"
+"$MINIGIT" rm --cached src/notas.txt
+ls src/notas.txt
+"$MINIGIT" status --porcelain | grep notas
+echo ""
+
+echo "--- 11. rm -r de pasta (ESPERADO: pasta src/modulo apagada) ---"
+"$MINIGIT" rm -r src/modulo
+ls src
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 12. add -p em arquivo sem quebra de linha no final (ESPERADO: 'M  semfim.txt', índice igual à worktree) ---"
+printf "a\nb\nc" > semfim.txt
+"$MINIGIT" add semfim.txt
+"$MINIGIT" commit "C4: Arquivo sem quebra de linha final" > /dev/null
+printf "a alterada\nb\nc" > semfim.txt
+printf "y\n" | "$MINIGIT" add -p semfim.txt > /dev/null
+"$MINIGIT" status --porcelain | grep semfim
+echo ""
+
+echo "--- 13. add -p em arquivo binário (ESPERADO: aviso e ' M dados.bin', mudança só na worktree) ---"
+printf "a\0b\n" > dados.bin
+"$MINIGIT" add dados.bin
+"$MINIGIT" commit "C5: Arquivo binário" > /dev/null
+printf "a\0c\n" > dados.bin
+printf "y\n" | "$MINIGIT" add -p dados.bin
+"$MINIGIT" status --porcelain | grep dados
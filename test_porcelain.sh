@@ -0,0 +1,57 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste das Saídas para Scripts (--porcelain / --json) ==="
+echo ""
+
+echo "--- 1. Inicializando repositório com um commit ---"
+"$MINIGIT" init
+echo "Conteúdo" > arquivo1.txt
+echo "Outro conteúdo" > arquivo2.txt
+"$MINIGIT" add arquivo1.txt arquivo2.txt
+"$MINIGIT" commit "C1: Commit inicial"
+"$MINIGIT" branch develop
+echo ""
+
+echo "--- 2. Criando mudanças no índice e na worktree ---"
+echo "Modificado" > arquivo1.txt
+echo "Novo" > arquivo3.txt
+"$MINIGIT" add arquivo3.txt
+echo "Não rastreado" > arquivo4.txt
+rm arquivo2.txt
+echo ""
+
+echo "--- 3. status --porcelain (ESPERADO: ' M arquivo1.txt', ' D arquivo2.txt', 'A  arquivo3.txt', '?? arquivo4.txt') ---"
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 4. status --json ---"
+"$MINIGIT" status --json
+echo ""
+
+echo "--- 5. branch --porcelain (ESPERADO: '* master <hash>' e '  develop <hash>') ---"
+"$MINIGIT" branch --porcelain
+echo ""
+
+echo "--- 6. log --json ---"
+"$MINIGIT" log --json
+echo ""
+
+echo "--- 7. diff --json ---"
+"$MINIGIT" diff --json
+echo ""
+
+echo "--- 8. Saída redirecionada não contém códigos de cor (ESPERADO: 0) ---"
+"$MINIGIT" status | grep -c $'\x1b'
+echo ""
+
+echo "--- 9. Arquivo removido do índice que continua na worktree (ESPERADO: 'D  arquivo1.txt' e '?? arquivo1.txt') ---"
+"$MINIGIT" rm --cached arquivo1.txt
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "=== Teste Concluído ==="
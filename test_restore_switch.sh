@@ -0,0 +1,102 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste dos comandos restore e switch ==="
+echo ""
+
+echo "--- 1. Criando o repositório ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Teste"
+"$MINIGIT" config email "teste@exemplo.com"
+echo "versão 1" > a.txt
+echo "versão 1" > b.txt
+"$MINIGIT" add .
+"$MINIGIT" commit "Commit inicial"
+echo "versão 2" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" commit "Segunda versão de a"
+echo ""
+
+echo "--- 2. restore descarta mudanças de um arquivo (ESPERADO: 'versão 2' e b.txt ainda modificado) ---"
+echo "lixo" > a.txt
+echo "mudança mantida" > b.txt
+"$MINIGIT" restore a.txt
+cat a.txt
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 3. restore --staged tira do índice (ESPERADO: ' M b.txt') ---"
+"$MINIGIT" add b.txt
+"$MINIGIT" restore --staged b.txt
+"$MINIGIT" status --porcelain
+cat b.txt
+echo ""
+
+echo "--- 4. restore --source (ESPERADO: 'versão 1' e ' M a.txt') ---"
+"$MINIGIT" restore --source HEAD~1 a.txt
+cat a.txt
+"$MINIGIT" status --porcelain | grep a.txt
+echo ""
+
+echo "--- 5. restore --staged --worktree (ESPERADO: status limpo exceto b.txt) ---"
+"$MINIGIT" add a.txt
+"$MINIGIT" restore --staged --worktree a.txt
+cat a.txt
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 6. restore de pathspec inexistente (ESPERADO: erro) ---"
+"$MINIGIT" restore naoexiste.txt
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 7. switch -c leva as mudanças locais (ESPERADO: ' M b.txt' na nova branch) ---"
+echo "não rastreado" > solto.txt
+"$MINIGIT" switch -c feature
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 8. Commit na feature e volta para master (ESPERADO: c.txt removido, solto.txt e b.txt mantidos) ---"
+echo "novo" > c.txt
+"$MINIGIT" add c.txt
+"$MINIGIT" commit "Adiciona c"
+"$MINIGIT" switch master
+ls
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 9. switch com mudança local em arquivo que difere (ESPERADO: erro listando a.txt) ---"
+"$MINIGIT" switch feature
+echo "a na feature" > a.txt
+"$MINIGIT" add a.txt
+"$MINIGIT" restore --staged b.txt
+"$MINIGIT" commit "Muda a na feature"
+"$MINIGIT" switch master
+echo "mudança local" > a.txt
+"$MINIGIT" switch feature
+echo "Código de saída: $?"
+cat a.txt
+echo ""
+
+echo "--- 10. switch para branch inexistente (ESPERADO: erro) ---"
+"$MINIGIT" switch naoexiste
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 11. switch -c para branch existente (ESPERADO: erro) ---"
+"$MINIGIT" switch -c feature
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 12. switch -c com ponto de partida (ESPERADO: branch 'antiga' sem c.txt) ---"
+"$MINIGIT" restore a.txt
+"$MINIGIT" switch -c antiga HEAD~1
+cat a.txt
+ls
+echo ""
+
+echo "=== Teste concluído ==="
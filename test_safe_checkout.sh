@@ -0,0 +1,132 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste do checkout seguro ==="
+echo ""
+
+echo "--- 1. Criando duas branches com arquivos diferentes ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Teste"
+"$MINIGIT" config email "teste@exemplo.com"
+echo "comum" > comum.txt
+echo "master" > diferente.txt
+"$MINIGIT" add .
+"$MINIGIT" commit "Commit inicial"
+"$MINIGIT" branch develop
+"$MINIGIT" checkout develop
+echo "develop" > diferente.txt
+echo "só na develop" > develop.txt
+"$MINIGIT" add .
+"$MINIGIT" commit "Commit na develop"
+"$MINIGIT" checkout master
+echo ""
+
+echo "--- 2. Arquivo não rastreado e mudança local sem conflito são mantidos (ESPERADO: 'develop', ' M comum.txt' e '?? solto.txt') ---"
+echo "não rastreado" > solto.txt
+echo "comum alterado" > comum.txt
+"$MINIGIT" checkout develop
+cat diferente.txt
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 3. Voltando para master remove develop.txt (ESPERADO: sem develop.txt, solto.txt mantido) ---"
+"$MINIGIT" checkout master
+ls
+echo ""
+
+echo "--- 4. Mudança local em arquivo que difere (ESPERADO: erro listando diferente.txt, nada muda) ---"
+echo "mudança local" > diferente.txt
+"$MINIGIT" checkout develop
+echo "Código de saída: $?"
+cat diferente.txt
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 5. Arquivo não rastreado no caminho de um arquivo do destino (ESPERADO: erro listando develop.txt) ---"
+"$MINIGIT" restore diferente.txt
+echo "meu arquivo" > develop.txt
+"$MINIGIT" checkout develop
+echo "Código de saída: $?"
+cat develop.txt
+echo ""
+
+echo "--- 6. checkout -f descarta as mudanças (ESPERADO: 'só na develop', 'develop' e solto.txt mantido) ---"
+"$MINIGIT" checkout -f develop
+cat develop.txt
+cat diferente.txt
+cat comum.txt
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "--- 7. Caminho que troca entre diretório e arquivo (ESPERADO: checkouts sem erro, sem diretório vazio, status limpo) ---"
+"$MINIGIT" checkout -f master > /dev/null
+mkdir caminho
+echo "dentro" > caminho/arquivo
+"$MINIGIT" add caminho/arquivo
+"$MINIGIT" commit "Caminho como diretório" > /dev/null
+"$MINIGIT" branch como-diretorio
+"$MINIGIT" rm caminho/arquivo > /dev/null
+echo "agora é arquivo" > caminho
+"$MINIGIT" add caminho
+"$MINIGIT" commit "Caminho como arquivo" > /dev/null
+"$MINIGIT" checkout como-diretorio
+echo "Código de saída: $?"
+cat caminho/arquivo
+"$MINIGIT" checkout master
+echo "Código de saída: $?"
+cat caminho
+"$MINIGIT" status --porcelain | grep caminho
+"$MINIGIT" checkout como-diretorio > /dev/null
+"$MINIGIT" checkout -f master
+echo "Código de saída: $?"
+cat caminho
+echo ""
+
+echo "--- 8. merge, rebase e --abort mantêm arquivos não rastreados (ESPERADO: 'meu rascunho' após cada operação) ---"
+echo "meu rascunho" > rascunho.txt
+"$MINIGIT" branch avanco
+"$MINIGIT" checkout avanco > /dev/null
+echo "avanço" > avanco.txt
+"$MINIGIT" add avanco.txt
+"$MINIGIT" commit "Avanço" > /dev/null
+"$MINIGIT" checkout master > /dev/null
+"$MINIGIT" merge avanco > /dev/null
+echo "fast-forward: $(cat rascunho.txt)"
+"$MINIGIT" checkout como-diretorio > /dev/null
+echo "lado" > lado.txt
+"$MINIGIT" add lado.txt
+"$MINIGIT" commit "Lado" > /dev/null
+"$MINIGIT" merge master > /dev/null
+echo "three-way: $(cat rascunho.txt) ($(cat avanco.txt))"
+"$MINIGIT" checkout master > /dev/null
+"$MINIGIT" branch rebaseado
+"$MINIGIT" checkout rebaseado > /dev/null
+echo "rebaseado" > rebaseado.txt
+"$MINIGIT" add rebaseado.txt
+"$MINIGIT" commit "Rebaseado" > /dev/null
+"$MINIGIT" checkout master > /dev/null
+echo "principal" > principal.txt
+"$MINIGIT" add principal.txt
+"$MINIGIT" commit "Principal" > /dev/null
+"$MINIGIT" checkout rebaseado > /dev/null
+"$MINIGIT" rebase master > /dev/null
+echo "rebase: $(cat rascunho.txt) ($(cat principal.txt))"
+"$MINIGIT" checkout avanco > /dev/null
+echo "conflito" > avanco.txt
+"$MINIGIT" add avanco.txt
+"$MINIGIT" commit "Conflito" > /dev/null
+"$MINIGIT" checkout master > /dev/null
+echo "outro conflito" > avanco.txt
+"$MINIGIT" add avanco.txt
+"$MINIGIT" commit "Outro conflito" > /dev/null
+"$MINIGIT" merge avanco > /dev/null
+"$MINIGIT" merge --abort > /dev/null
+echo "merge --abort: $(cat rascunho.txt) ($(cat avanco.txt))"
+"$MINIGIT" status --porcelain
+echo ""
+
+echo "=== Teste concluído ==="
@@ -0,0 +1,92 @@
+#!/bin/bash
+MINIGIT="$(pwd)/target/release/minigit"
+
+rm -rf test
+mkdir test
+cd test
+
+echo "=== Teste dos comandos show e cat-file ==="
+echo ""
+
+echo "--- 1. Criando o histórico com um merge com conflito resolvido ---"
+"$MINIGIT" init
+"$MINIGIT" config username "Teste"
+mkdir src
+printf 'um\ndois\ntrês\n' > texto.txt
+echo "fn main() {}" > src/main.rs
+"$MINIGIT" add texto.txt src/main.rs
+"$MINIGIT" commit "Primeiro commit" > /dev/null
+"$MINIGIT" branch outra
+printf 'um\ndois modificado\ntrês\n' > texto.txt
+"$MINIGIT" add texto.txt
+"$MINIGIT" commit "Muda a linha dois" > /dev/null
+"$MINIGIT" switch outra > /dev/null
+printf 'um\ndois da outra\ntrês\n' > texto.txt
+"$MINIGIT" add texto.txt
+"$MINIGIT" commit "Muda a linha dois na outra" > /dev/null
+"$MINIGIT" switch master > /dev/null
+"$MINIGIT" merge outra > /dev/null
+printf 'um\ndois resolvido\ntrês\n' > texto.txt
+"$MINIGIT" add texto.txt
+"$MINIGIT" merge --continue > /dev/null
+echo ""
+
+echo "--- 2. show de um commit comum (ESPERADO: dados do commit e diff com o pai) ---"
+"$MINIGIT" show HEAD~1 | grep -v "^Data:"
+echo ""
+
+echo "--- 3. show de um merge (ESPERADO: diff combinado com dois marcadores por linha) ---"
+"$MINIGIT" show | grep -v "^Data:\|^Merge:"
+echo ""
+
+echo "--- 4. show de uma árvore (ESPERADO: 'src/' e 'texto.txt') ---"
+"$MINIGIT" show HEAD:
+"$MINIGIT" show HEAD~1:src
+echo ""
+
+echo "--- 5. show de um arquivo em uma revisão (ESPERADO: 'dois modificado', depois 'dois' da revisão inicial) ---"
+"$MINIGIT" show HEAD~1:texto.txt
+"$MINIGIT" show HEAD~2:texto.txt
+echo ""
+
+echo "--- 6. show de um arquivo do índice (ESPERADO: conteúdo adicionado, sem a mudança da worktree) ---"
+echo "quatro" >> texto.txt
+"$MINIGIT" show :texto.txt
+"$MINIGIT" restore texto.txt
+echo ""
+
+echo "--- 7. Caminhos e revisões inexistentes (ESPERADO: erros) ---"
+"$MINIGIT" show HEAD:nada.txt
+echo "Código de saída: $?"
+"$MINIGIT" show nada
+echo ""
+
+echo "--- 8. cat-file -t e -s (ESPERADO: commit, tree, blob e o tamanho) ---"
+"$MINIGIT" cat-file -t HEAD
+"$MINIGIT" cat-file -t HEAD:src
+"$MINIGIT" cat-file -t HEAD:texto.txt
+"$MINIGIT" cat-file -s HEAD:texto.txt
+echo ""
+
+echo "--- 9. cat-file -p (ESPERADO: conteúdo do blob e listagem da árvore) ---"
+"$MINIGIT" cat-file -p HEAD:texto.txt
+"$MINIGIT" cat-file -p HEAD: | sed -E 's/[0-9a-f]{40}/<hash>/'
+echo ""
+
+echo "--- 10. cat-file -e (ESPERADO: códigos 0 e 1, sem saída) ---"
+"$MINIGIT" cat-file -e HEAD:texto.txt
+echo "Código de saída: $?"
+"$MINIGIT" cat-file -e 0123456789012345678901234567890123456789
+echo "Código de saída: $?"
+echo ""
+
+echo "--- 11. cat-file --batch (ESPERADO: cabeçalho e conteúdo, 'missing' para o inexistente) ---"
+printf 'HEAD:texto.txt\nnada\nHEAD~2:src/main.rs\n' | "$MINIGIT" cat-file --batch | sed -E 's/[0-9a-f]{40}/<hash>/'
+echo ""
+
+echo "--- 12. cat-file com hash abreviado e formato antigo (ESPERADO: tipo, tamanho e conteúdo) ---"
+hash=$("$MINIGIT" cat-file --batch <<< "HEAD:texto.txt" | sed -n 1p | cut -c1-8)
+"$MINIGIT" cat-file "$hash"
+echo ""
+
+echo "=== Teste concluído ==="